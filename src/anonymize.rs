@@ -0,0 +1,108 @@
+use crate::task::Task;
+use crate::todo_list::TodoList;
+
+/// 生成匿名化的副本，用于附在问题报告中
+///
+/// 任务（包括回收站中的任务）和目标的标题（包括历史记录中的标题）、标签、情境和外部 ID 替换为哈希，相同的原文得到相同的哈希；
+/// ID、父子关系、优先级、状态、日期和循环规则保持不变。
+pub fn anonymize(todo_list: &TodoList) -> TodoList {
+    let mut anonymized = todo_list.clone();
+
+    let ids: Vec<u32> = anonymized.list_tasks().iter().map(|t| t.id).collect();
+    for id in ids {
+        if let Some(task) = anonymized.find_task_mut(id) {
+            anonymize_task(task);
+        }
+    }
+
+    // 回收站中的任务同样可能被恢复，一并处理
+    for trashed in anonymized.trash_mut() {
+        anonymize_task(&mut trashed.task);
+    }
+
+    for goal in anonymized.goals_mut() {
+        goal.title = format!("goal-{}", hash(&goal.title));
+    }
+
+    // 历史记录中保存了当时的标题
+    for event in anonymized.history_mut() {
+        event.title = format!("task-{}", hash(&event.title));
+    }
+
+    anonymized
+}
+
+/// 替换任务的标题、标签、情境和外部 ID
+fn anonymize_task(task: &mut Task) {
+    task.title = format!("task-{}", hash(&task.title));
+    task.tags = task
+        .tags
+        .iter()
+        .map(|tag| format!("tag-{}", hash(tag)))
+        .collect();
+    task.context = task.context.as_deref().map(|context| format!("context-{}", hash(context)));
+    task.todoist_id = task.todoist_id.as_deref().map(hash);
+}
+
+/// 64 位 FNV-1a 哈希（16 位十六进制），结果不随 Rust 版本变化
+fn hash(text: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let value = text
+        .bytes()
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME));
+    format!("{:016x}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_anonymize_preserves_structure() {
+        let mut list = TodoList::new();
+        let parent = list.add_task("给妈妈打电话".to_string(), Priority::High, None);
+        let child = list
+            .add_subtask(parent, "买礼物".to_string(), Priority::Low, None)
+            .unwrap();
+        list.set_tags(parent, vec!["family".to_string()]).unwrap();
+        list.set_tags(child, vec!["family".to_string(), "shopping".to_string()])
+            .unwrap();
+        list.complete_task(child).unwrap();
+        list.add_goal("搬家".to_string(), None);
+        let removed = list.add_task("私人任务".to_string(), Priority::Low, None);
+        list.delete_task(removed).unwrap();
+
+        let anonymized = anonymize(&list);
+        assert_eq!(anonymized.len(), list.len());
+
+        for (original, task) in list.list_tasks().iter().zip(anonymized.list_tasks()) {
+            assert_ne!(task.title, original.title);
+            assert!(task.title.starts_with("task-"));
+            assert_eq!(task.id, original.id);
+            assert_eq!(task.parent_id, original.parent_id);
+            assert_eq!(task.priority, original.priority);
+            assert_eq!(task.completed, original.completed);
+            assert_eq!(task.created_at, original.created_at);
+            assert_eq!(task.tags.len(), original.tags.len());
+        }
+
+        // 相同的标签得到相同的哈希
+        let tasks = anonymized.list_tasks();
+        assert_eq!(tasks[0].tags[0], tasks[1].tags[0]);
+        assert_ne!(tasks[1].tags[0], tasks[1].tags[1]);
+
+        assert!(anonymized.goals()[0].title.starts_with("goal-"));
+        assert!(anonymized.trash()[0].task.title.starts_with("task-"));
+        assert!(!anonymized.history().is_empty());
+        assert!(anonymized.history().iter().all(|event| event.title.starts_with("task-")));
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        assert_eq!(hash(""), "cbf29ce484222325");
+        assert_eq!(hash("a"), "af63dc4c8601ec8c");
+    }
+}
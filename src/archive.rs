@@ -0,0 +1,58 @@
+use crate::task::Task;
+use crate::todo_list::TodoList;
+use std::path::{Path, PathBuf};
+
+/// 没有配置 `archive_after_days` 时，`todo gc` 归档完成超过多少天的任务
+pub const DEFAULT_ARCHIVE_AFTER_DAYS: u32 = 30;
+
+/// 数据文件对应的归档文件（同一目录下，例如 `todos.json` 对应 `todos.archive.json`）
+///
+/// 归档文件总是 JSON 格式的普通数据文件，可以用 `todo --file <归档文件> list` 查看。
+pub fn archive_path(data_path: &Path) -> PathBuf {
+    let file_name = data_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = file_name.strip_suffix(".gz").unwrap_or(&file_name);
+    let stem = name.split_once('.').map_or(name, |(stem, _)| stem);
+    data_path.with_file_name(format!("{}.archive.json", stem))
+}
+
+/// 把任务追加到归档列表，保留原来的 ID；ID 已被占用时（例如重置过列表）分配新的
+///
+/// 不记录历史，归档列表的历史记录保持为空。
+pub fn append(archive: &mut TodoList, tasks: Vec<Task>) {
+    for task in tasks {
+        if archive.find_task(task.id).is_some() {
+            archive.restore_task(task);
+        } else {
+            let (next_id, next_goal_id) = archive.next_ids();
+            archive.set_next_ids(next_id.max(task.id + 1), next_goal_id);
+            archive.put_task(task);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_archive_path() {
+        assert_eq!(archive_path(Path::new("/data/todos.json")), Path::new("/data/todos.archive.json"));
+        assert_eq!(archive_path(Path::new("/data/todos.json.gz")), Path::new("/data/todos.archive.json"));
+        assert_eq!(archive_path(Path::new("todo.txt")), Path::new("todo.archive.json"));
+    }
+
+    #[test]
+    fn test_append_keeps_ids() {
+        let mut archive = TodoList::new();
+        append(&mut archive, vec![Task::new(5, "写周报".to_string(), Priority::Low, None)]);
+        append(&mut archive, vec![Task::new(5, "重置后的任务".to_string(), Priority::Low, None)]);
+
+        assert_eq!(archive[5].title, "写周报");
+        assert_eq!(archive[6].title, "重置后的任务");
+        assert!(archive.history().is_empty());
+    }
+}
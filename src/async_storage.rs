@@ -0,0 +1,135 @@
+use crate::error::{TodoError, TodoResult};
+use crate::storage::{
+    load_from_file_with_format, save_to_file_with_format, FileStorage, MemoryStorage, Storage, StorageFormat,
+};
+use crate::todo_list::TodoList;
+use std::future::Future;
+use std::path::Path;
+
+/// 在 tokio 的阻塞线程池中执行文件操作，不阻塞异步运行时
+async fn blocking<T, F>(operation: F) -> TodoResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> TodoResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(operation)
+        .await
+        .map_err(|err| TodoError::IoError(std::io::Error::other(err)))?
+}
+
+/// `load_from_file` 的异步版本
+///
+/// # 示例
+/// ```
+/// use rust_todo_cli::async_storage::{load_from_file_async, save_to_file_async};
+/// use rust_todo_cli::prelude::*;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let mut list = load_from_file_async("async_example.json").await.unwrap();
+///     list.add_task("写周报".to_string(), Priority::High, None);
+///     save_to_file_async(&list, "async_example.json").await.unwrap();
+/// });
+/// # rust_todo_cli::storage::delete_file("async_example.json").unwrap();
+/// ```
+pub async fn load_from_file_async<P: AsRef<Path>>(path: P) -> TodoResult<TodoList> {
+    load_from_file_with_format_async(path, StorageFormat::Json).await
+}
+
+/// `load_from_file_with_format` 的异步版本
+pub async fn load_from_file_with_format_async<P: AsRef<Path>>(path: P, format: StorageFormat) -> TodoResult<TodoList> {
+    let path = path.as_ref().to_path_buf();
+    blocking(move || load_from_file_with_format(path, format)).await
+}
+
+/// `save_to_file` 的异步版本
+pub async fn save_to_file_async<P: AsRef<Path>>(todo_list: &TodoList, path: P) -> TodoResult<()> {
+    save_to_file_with_format_async(todo_list, path, StorageFormat::Json).await
+}
+
+/// `save_to_file_with_format` 的异步版本，保存的是调用时列表的内容
+pub async fn save_to_file_with_format_async<P: AsRef<Path>>(
+    todo_list: &TodoList,
+    path: P,
+    format: StorageFormat,
+) -> TodoResult<()> {
+    let todo_list = todo_list.contents();
+    let path = path.as_ref().to_path_buf();
+    blocking(move || save_to_file_with_format(&todo_list, path, format)).await
+}
+
+/// `Storage` 的异步版本，返回的 future 可以在多线程运行时中跨线程使用
+pub trait AsyncStorage {
+    /// 加载待办列表，还没有数据时返回空列表
+    fn load(&self) -> impl Future<Output = TodoResult<TodoList>> + Send;
+
+    /// 保存待办列表
+    fn save(&self, todo_list: &TodoList) -> impl Future<Output = TodoResult<()>> + Send;
+}
+
+/// 文件读写、备份和加密都在阻塞线程池中完成
+impl AsyncStorage for FileStorage {
+    async fn load(&self) -> TodoResult<TodoList> {
+        let storage = self.clone();
+        blocking(move || Storage::load(&storage)).await
+    }
+
+    async fn save(&self, todo_list: &TodoList) -> TodoResult<()> {
+        let storage = self.clone();
+        let todo_list = todo_list.contents();
+        blocking(move || Storage::save(&storage, &todo_list)).await
+    }
+}
+
+impl AsyncStorage for MemoryStorage {
+    async fn load(&self) -> TodoResult<TodoList> {
+        Storage::load(self)
+    }
+
+    async fn save(&self, todo_list: &TodoList) -> TodoResult<()> {
+        Storage::save(self, todo_list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    /// 只接受异步存储，确认两种实现都可以通过同一个泛型接口使用
+    async fn add_and_reload<S: AsyncStorage>(storage: &S, title: &str) -> TodoList {
+        let mut list = storage.load().await.unwrap();
+        list.add_task(title.to_string(), Priority::Low, None);
+        storage.save(&list).await.unwrap();
+        storage.load().await.unwrap()
+    }
+
+    #[test]
+    fn test_async_storage() {
+        let dir = std::env::temp_dir().join(format!("todo-async-storage-{}", std::process::id()));
+        let path = dir.join("todo.txt");
+
+        block_on(async {
+            let file = FileStorage::new(&path, StorageFormat::TodoTxt);
+            assert_eq!(add_and_reload(&file, "写周报").await.len(), 1);
+            assert_eq!(add_and_reload(&file, "买牛奶").await.len(), 2);
+
+            let memory = MemoryStorage::new();
+            assert_eq!(add_and_reload(&memory, "写周报").await.len(), 1);
+
+            let list = load_from_file_with_format_async(&path, StorageFormat::TodoTxt).await.unwrap();
+            save_to_file_async(&list, dir.join("todos.json")).await.unwrap();
+            let copy = load_from_file_async(dir.join("todos.json")).await.unwrap();
+            assert_eq!(copy[2].title, "买牛奶");
+        });
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
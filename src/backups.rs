@@ -0,0 +1,209 @@
+use crate::error::{TodoError, TodoResult};
+use crate::storage::{load_from_file_with_format, update_checksum, StorageFormat};
+use crate::todo_list::TodoList;
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 默认保留的备份数量
+pub const DEFAULT_BACKUP_COUNT: usize = 10;
+
+/// 备份文件名中的时间戳格式（UTC，精确到毫秒）
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3f";
+
+/// 备份目录名
+const BACKUP_DIR: &str = "backups";
+
+/// 数据文件的一个备份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// 备份时间戳，例如 `20250101T120000.000`
+    pub timestamp: String,
+    /// 备份文件路径
+    pub path: PathBuf,
+}
+
+/// 数据文件对应的备份目录（数据文件所在目录下的 `backups/`）
+pub fn backup_dir(data_path: &Path) -> PathBuf {
+    data_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(BACKUP_DIR)
+}
+
+/// 将当前数据文件复制到备份目录，并只保留最近的 `keep` 个备份
+///
+/// 数据文件不存在或 `keep` 为 0 时不做任何事。
+pub fn rotate(data_path: &Path, keep: usize) -> TodoResult<Option<PathBuf>> {
+    if keep == 0 || !data_path.exists() {
+        return Ok(None);
+    }
+
+    let (stem, extension) = name_parts(data_path)?;
+    let dir = backup_dir(data_path);
+    fs::create_dir_all(&dir)?;
+
+    let timestamp = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+    let backup_path = dir.join(format!("{}-{}{}", stem, timestamp, extension));
+    if !backup_path.exists() {
+        fs::copy(data_path, &backup_path)?;
+    }
+
+    let backups = list(data_path)?;
+    let excess = backups.len().saturating_sub(keep);
+    for old in &backups[..excess] {
+        fs::remove_file(&old.path)?;
+    }
+
+    Ok(Some(backup_path))
+}
+
+/// 列出数据文件的所有备份，按时间从旧到新排序
+pub fn list(data_path: &Path) -> TodoResult<Vec<Backup>> {
+    let (stem, extension) = name_parts(data_path)?;
+    let dir = backup_dir(data_path);
+
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{}-", stem);
+    let mut backups: Vec<Backup> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let timestamp = name.strip_prefix(&prefix)?.strip_suffix(&extension)?;
+            chrono::NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+
+            Some(Backup {
+                timestamp: timestamp.to_string(),
+                path: entry.path(),
+            })
+        })
+        .collect();
+
+    backups.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(backups)
+}
+
+/// 用指定时间戳的备份覆盖数据文件
+///
+/// 覆盖前会先备份当前的数据文件，恢复操作本身也可以撤销。
+pub fn restore(data_path: &Path, timestamp: &str, keep: usize) -> TodoResult<Backup> {
+    let backup = list(data_path)?
+        .into_iter()
+        .find(|backup| backup.timestamp == timestamp)
+        .ok_or_else(|| TodoError::BackupNotFound(timestamp.to_string()))?;
+
+    // 至少保留一个，确保被覆盖的数据可以找回
+    rotate(data_path, keep.max(1))?;
+    fs::copy(&backup.path, data_path)?;
+    update_checksum(data_path)?;
+
+    Ok(backup)
+}
+
+/// 最近的一个可以正常读取的备份及其内容，没有这样的备份时返回 `None`
+///
+/// 数据文件损坏时用它找回最近的数据。加密的备份无法在这里读取，会被跳过。
+pub fn latest_valid(data_path: &Path, format: StorageFormat) -> TodoResult<Option<(Backup, TodoList)>> {
+    for backup in list(data_path)?.into_iter().rev() {
+        if let Ok(todo_list) = load_from_file_with_format(&backup.path, format) {
+            return Ok(Some((backup, todo_list)));
+        }
+    }
+    Ok(None)
+}
+
+/// 数据文件名拆分为主干和扩展名（含 `.`），例如 `todos` 和 `.json`
+fn name_parts(data_path: &Path) -> TodoResult<(String, String)> {
+    let stem = data_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| TodoError::InvalidPath(data_path.to_path_buf()))?;
+    let extension = data_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| format!(".{}", ext))
+        .unwrap_or_default();
+
+    Ok((stem.to_string(), extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试辅助函数：每个测试使用独立的目录
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = PathBuf::from(format!("test_backups_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // 手动创建指定时间戳的备份
+    fn fake_backup(data_path: &Path, timestamp: &str, content: &str) {
+        let dir = backup_dir(data_path);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("todos-{}.json", timestamp)), content).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_keeps_most_recent() {
+        let dir = test_dir("rotate");
+        let data_path = dir.join("todos.json");
+
+        // 数据文件不存在时不备份
+        assert_eq!(rotate(&data_path, 3).unwrap(), None);
+
+        fs::write(&data_path, "current").unwrap();
+        fake_backup(&data_path, "20200101T000000.000", "old1");
+        fake_backup(&data_path, "20200102T000000.000", "old2");
+        fake_backup(&data_path, "20200103T000000.000", "old3");
+        fs::write(backup_dir(&data_path).join("notes.txt"), "ignored").unwrap();
+
+        let created = rotate(&data_path, 3).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&created).unwrap(), "current");
+
+        let timestamps: Vec<String> = list(&data_path).unwrap().into_iter().map(|b| b.timestamp).collect();
+        assert_eq!(timestamps.len(), 3);
+        assert_eq!(timestamps[0], "20200102T000000.000");
+        assert!(backup_dir(&data_path).join("notes.txt").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rotate_disabled() {
+        let dir = test_dir("disabled");
+        let data_path = dir.join("todos.json");
+        fs::write(&data_path, "current").unwrap();
+
+        assert_eq!(rotate(&data_path, 0).unwrap(), None);
+        assert!(list(&data_path).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restore() {
+        let dir = test_dir("restore");
+        let data_path = dir.join("todos.json");
+        fs::write(&data_path, "current").unwrap();
+        fake_backup(&data_path, "20200101T000000.000", "old");
+
+        let restored = restore(&data_path, "20200101T000000.000", 10).unwrap();
+        assert_eq!(restored.timestamp, "20200101T000000.000");
+        assert_eq!(fs::read_to_string(&data_path).unwrap(), "old");
+
+        // 被覆盖的数据也有备份
+        let backups = list(&data_path).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(fs::read_to_string(&backups[1].path).unwrap(), "current");
+
+        assert!(restore(&data_path, "19990101T000000.000", 10).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::{TodoError, TodoResult};
+use crate::storage::parse_json;
+use crate::todo_list::TodoList;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// 备份包中任务数据的文件名（始终为 JSON，与数据文件格式无关）
+const TASKS_ENTRY: &str = "todos.json";
+
+/// 备份包中配置文件的文件名
+const CONFIG_ENTRY: &str = "config.toml";
+
+/// 迁移到新机器所需的完整状态
+#[derive(Debug)]
+pub struct Bundle {
+    /// 全部任务
+    pub todo_list: TodoList,
+    /// 配置文件原文，没有配置文件时为 `None`
+    pub config: Option<String>,
+}
+
+impl Bundle {
+    /// 备份包中包含的文件名
+    pub fn entries(&self) -> Vec<&'static str> {
+        let mut entries = vec![TASKS_ENTRY];
+        if self.config.is_some() {
+            entries.push(CONFIG_ENTRY);
+        }
+        entries
+    }
+}
+
+/// 将完整状态写入 `.tar.gz` 备份包
+pub fn export_bundle<P: AsRef<Path>>(bundle: &Bundle, path: P) -> TodoResult<()> {
+    write_bundle(bundle, File::create(path)?)
+}
+
+/// 从 `.tar.gz` 备份包读取完整状态
+pub fn import_bundle<P: AsRef<Path>>(path: P) -> TodoResult<Bundle> {
+    read_bundle(File::open(path)?)
+}
+
+/// 将备份包写入 `writer`
+pub fn write_bundle<W: Write>(bundle: &Bundle, writer: W) -> TodoResult<()> {
+    let mut archive = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+
+    let tasks = serde_json::to_string_pretty(&bundle.todo_list)?;
+    append_entry(&mut archive, TASKS_ENTRY, tasks.as_bytes())?;
+
+    if let Some(config) = &bundle.config {
+        append_entry(&mut archive, CONFIG_ENTRY, config.as_bytes())?;
+    }
+
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// 从 `reader` 读取备份包，不认识的文件会被忽略
+pub fn read_bundle<R: Read>(reader: R) -> TodoResult<Bundle> {
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+    let mut todo_list = None;
+    let mut config = None;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+
+        match name.as_str() {
+            TASKS_ENTRY => todo_list = Some(parse_json(&content)?),
+            CONFIG_ENTRY => config = Some(content),
+            _ => {}
+        }
+    }
+
+    let todo_list = todo_list
+        .ok_or_else(|| TodoError::BundleError(format!("missing {}", TASKS_ENTRY)))?;
+
+    Ok(Bundle { todo_list, config })
+}
+
+/// 向归档中添加一个文件
+fn append_entry<W: Write>(archive: &mut tar::Builder<W>, name: &str, data: &[u8]) -> TodoResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+
+    archive.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_round_trip() {
+        let mut list = TodoList::new();
+        let id = list.add_task("写周报".to_string(), Priority::High, None);
+        list.set_tags(id, vec!["work".to_string()]).unwrap();
+
+        let bundle = Bundle {
+            todo_list: list,
+            config: Some("default_priority = \"low\"\n".to_string()),
+        };
+        assert_eq!(bundle.entries(), vec!["todos.json", "config.toml"]);
+
+        let mut data = Vec::new();
+        write_bundle(&bundle, &mut data).unwrap();
+        let restored = read_bundle(data.as_slice()).unwrap();
+
+        assert_eq!(restored.todo_list.len(), 1);
+        assert_eq!(restored.todo_list.list_tasks()[0].tags, vec!["work"]);
+        assert_eq!(restored.config, bundle.config);
+    }
+
+    #[test]
+    fn test_bundle_without_config() {
+        let bundle = Bundle {
+            todo_list: TodoList::new(),
+            config: None,
+        };
+
+        let mut data = Vec::new();
+        write_bundle(&bundle, &mut data).unwrap();
+        let restored = read_bundle(data.as_slice()).unwrap();
+
+        assert!(restored.todo_list.is_empty());
+        assert!(restored.config.is_none());
+    }
+
+    #[test]
+    fn test_missing_tasks_entry() {
+        let mut data = Vec::new();
+        {
+            let mut archive = tar::Builder::new(GzEncoder::new(&mut data, Compression::default()));
+            append_entry(&mut archive, "other.txt", b"hello").unwrap();
+            archive.into_inner().unwrap().finish().unwrap();
+        }
+
+        let result = read_bundle(data.as_slice());
+        assert!(matches!(result, Err(TodoError::BundleError(_))));
+    }
+}
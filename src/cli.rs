@@ -1,4 +1,9 @@
-use clap::{Parser, Subcommand};
+use crate::display::Theme;
+use crate::filter::{SortKey, StatusFilter};
+use crate::report::GroupBy;
+use crate::storage::StorageFormat;
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 /// 命令行待办事项管理器
 #[derive(Parser, Debug)]
@@ -7,6 +12,39 @@ use clap::{Parser, Subcommand};
 #[command(version = "0.1.0")]
 #[command(about = "A simple and elegant CLI todo list manager", long_about = None)]
 pub struct Cli {
+    /// 数据文件格式，覆盖配置文件中的设置（默认 json）
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<StorageFormat>,
+
+    /// 配色主题，覆盖配置文件中的设置
+    #[arg(long, global = true, value_enum)]
+    pub theme: Option<Theme>,
+
+    /// 不使用颜色（也可以设置 NO_COLOR 环境变量）
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// 输出不带颜色和 emoji、只含 ASCII 符号的纯文本（同 --ascii），标准输出不是终端时自动启用
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// 只使用 ASCII 符号，例如用 [x] 代替 ✓、用 due: 代替 📅
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// 使用当前目录下的数据文件，而不是用户数据目录中的文件
+    #[arg(long, global = true)]
+    pub local: bool,
+
+    /// 使用指定的数据文件，例如提交到项目仓库中的任务文件
+    // 参数 ID 与 `import` 的文件参数区分开，否则全局参数会覆盖它
+    #[arg(long = "file", id = "data_file", value_name = "FILE", global = true, conflicts_with = "local")]
+    pub data_file: Option<PathBuf>,
+
+    /// 把配置、数据和会话文件都放在指定目录中（用于测试，不在帮助中显示）
+    #[arg(long, global = true, hide = true, value_name = "DIR", conflicts_with_all = ["local", "data_file"])]
+    pub data_dir: Option<PathBuf>,
+
     /// 子命令
     #[command(subcommand)]
     pub command: Commands,
@@ -17,81 +55,967 @@ pub struct Cli {
 pub enum Commands {
     /// 添加新任务
     #[command(alias = "a")]
+    Add(AddArgs),
+
+    /// 列出任务
+    #[command(alias = "ls")]
+    List(ListArgs),
+
+    /// 标记任务为已完成
+    #[command(alias = "c")]
+    Complete(CompleteArgs),
+
+    /// 设置任务依赖：依赖的任务全部完成前该任务处于阻塞状态
+    Block(BlockArgs),
+
+    /// 移除任务依赖
+    Unblock(UnblockArgs),
+
+    /// 重新打开已完成的任务
+    #[command(alias = "uncomplete")]
+    Reopen(ReopenArgs),
+
+    /// 跳过循环任务的当前周期
+    Skip(SkipArgs),
+
+    /// 推迟任务：修改截止日期
+    Postpone(PostponeArgs),
+
+    /// 修改任务的优先级
+    #[command(alias = "pri")]
+    Prioritize(PrioritizeArgs),
+
+    /// 修改任务的标题、优先级、截止日期、标签或情境
+    Edit(EditArgs),
+
+    /// 删除任务
+    #[command(alias = "d")]
+    Delete(DeleteArgs),
+
+    /// 按标题搜索任务（包括已完成的任务）
+    Search(SearchArgs),
+
+    /// 只输出符合条件的任务数量，供脚本和 shell 提示符使用
+    Count(CountArgs),
+
+    /// 列出一段时间内完成的任务，适合站会前回顾
+    Done(DoneArgs),
+
+    /// 复制任务（标题、优先级、标签、情境、预估耗时），作为新的未完成任务
+    #[command(alias = "dup")]
+    Duplicate(DuplicateArgs),
+
+    /// 从回收站恢复已删除的任务
+    Restore(RestoreArgs),
+
+    /// 管理回收站（删除的任务先移到这里）
+    Trash(TrashArgs),
+
+    /// 显示任务详情
+    #[command(alias = "s")]
+    Show(ShowArgs),
+
+    /// 为任务运行一个番茄钟，结束后记录到任务上，按 Ctrl+C 放弃
+    Pomodoro(PomodoroArgs),
+
+    /// 清除所有已完成的任务
+    Clear(ClearArgs),
+
+    /// 把完成已久的任务移到归档文件（数据文件旁的 `*.archive.json`）
+    Gc(GcArgs),
+
+    /// 删除整个列表（任务、目标、历史记录和回收站），需要输入 reset 确认，之前会先备份
+    Reset(ResetArgs),
+
+    /// 撤销上一个修改了数据的命令
+    Undo(UndoArgs),
+
+    /// 重做上一个被撤销的命令
+    Redo(RedoArgs),
+
+    /// 专注模式：在指定时间前只显示带有某个标签的任务，不带参数时显示当前状态
+    Focus(FocusArgs),
+
+    /// 逐个整理收件箱中的任务（没有标签和截止日期的任务）
+    #[command(alias = "review")]
+    Triage(TriageArgs),
+
+    /// 检查数据问题（例如子任务晚于父任务截止）
+    Doctor(DoctorArgs),
+
+    /// 按截止时间列出今天或最近一周要做的任务，之前过期的任务排在最前
+    Agenda(AgendaArgs),
+
+    /// 以看板形式并排显示待办和已完成的任务
+    Board(BoardArgs),
+
+    /// 显示月历，每天标出到期的任务数
+    #[command(alias = "cal")]
+    Calendar(CalendarArgs),
+
+    /// 显示统计：完成率、最近一周每天完成数、平均完成耗时和各优先级的任务数
+    Stats(StatsArgs),
+
+    /// 生成报告
+    Report(ReportArgs),
+
+    /// 从其他工具导入任务
+    Import(ImportArgs),
+
+    /// 按任务 UUID 把另一份数据文件合并进来（例如另一台机器上的副本），逐个字段保留较新的修改
+    Merge(MergeArgs),
+
+    /// 在临时目录中用示例数据运行命令，不读取也不修改自己的数据和配置
+    ///
+    /// 例如 `todo demo board`，不指定命令时列出所有示例任务。
+    Demo(DemoArgs),
+
+    /// 交互模式：逐行输入子命令（例如 `add 买牛奶 -p high`），输入 exit 或按 Ctrl+D 退出
+    Shell(ShellArgs),
+
+    /// 生成 shell 补全脚本，例如 `todo completions bash > ~/.local/share/bash-completion/completions/todo`
+    Completions(CompletionsArgs),
+
+    /// 生成 roff 格式的 man page（供打包使用）
+    #[command(hide = true)]
+    Man(ManArgs),
+
+    /// 列出未完成任务的 ID 和标题（供补全脚本使用）
+    #[command(name = "__ids", hide = true)]
+    Ids(IdsArgs),
+
+    /// 导出完整状态（任务和配置）用于迁移，导出匿名化的任务用于报告问题，或导出 HTML 状态报告
+    Export(ExportArgs),
+
+    /// 管理目标并查看进度
+    Goal(GoalArgs),
+
+    /// 管理任务模板
+    Template(TemplateArgs),
+
+    /// 管理自动备份
+    Backup(BackupArgs),
+
+    /// 把数据文件转换为另一种格式，写入旁边的同名文件（例如 todos.bin），原文件保留
+    Convert(ConvertArgs),
+
+    /// 加密数据文件（口令从 TODO_PASSPHRASE 环境变量读取，未设置时提示输入）
+    #[cfg(feature = "encryption")]
+    Encrypt(EncryptArgs),
+
+    /// 解密数据文件，恢复为明文保存
+    #[cfg(feature = "encryption")]
+    Decrypt(DecryptArgs),
+
+    /// 为过期和即将到期的任务发送桌面通知（适合由 cron 或 systemd timer 定期运行）
+    #[cfg(feature = "notify")]
+    Notify(NotifyArgs),
+
+    /// 运行本地 REST API 服务（GET/POST/PATCH/DELETE /tasks），供手机快捷指令或网页前端使用
+    #[cfg(feature = "server")]
+    Serve(ServeArgs),
+
+    /// 与远端服务同步
+    Sync(SyncArgs),
+}
+
+/// 支持的同步目标
+#[derive(Subcommand, Debug)]
+pub enum SyncTarget {
+    /// 通过数据文件所在的 git 仓库同步：提交本地修改，再与远端拉取合并、推送
+    Git {
+        /// 远端名（默认读取配置中的 git_remote，否则为 origin）
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// 与 Todoist 双向同步（Token 从配置中的 todoist.token 或 TODOIST_API_TOKEN 环境变量读取）
+    #[cfg(feature = "todoist")]
+    Todoist,
+
+    /// 通过中继服务器与其他设备同步，交换的内容在本地端到端加密
+    #[cfg(feature = "relay")]
+    Relay {
+        /// 房间 ID，需要同步的设备使用同一个房间
+        #[arg(long)]
+        room: String,
+
+        /// 共享密钥，用于加密和解密交换的内容
+        #[arg(long)]
+        key: String,
+
+        /// 中继服务器地址（默认读取 TODO_RELAY_URL 环境变量）
+        #[arg(long, value_name = "URL")]
+        server: Option<String>,
+    },
+
+    /// 与配置文件中 [remote] 指定的 WebDAV 或 S3 存储同步，本地数据文件作为离线缓存
+    #[cfg(feature = "remote")]
+    Remote,
+}
+
+/// 目标操作
+#[derive(Subcommand, Debug)]
+pub enum GoalAction {
+    /// 添加新目标
     Add {
-        /// 任务标题
+        /// 目标标题
         title: String,
-        
-        /// 优先级:  high (h), medium (m), low (l)
-        #[arg(short, long, default_value = "medium")]
-        priority: String,
-        
+
         /// 截止日期 (格式: YYYY-MM-DD)
         #[arg(short, long)]
         due: Option<String>,
     },
 
-    /// 列出任务
-    #[command(alias = "ls")]
-    List {
-        /// 筛选选项
-        #[arg(value_enum)]
-        filter: Option<ListFilter>,
+    /// 显示所有目标的进度，指定 ID 时同时列出关联的任务
+    Show {
+        /// 目标 ID
+        id: Option<u32>,
     },
 
-    /// 标记任务为已完成
-    #[command(alias = "c")]
-    Complete {
+    /// 将任务关联到目标
+    Link {
         /// 任务 ID
-        id: u32,
+        task: u32,
+
+        /// 目标 ID
+        goal: u32,
     },
 
-    /// 删除任务
-    #[command(alias = "d")]
-    Delete {
+    /// 取消任务与目标的关联
+    Unlink {
         /// 任务 ID
-        id: u32,
+        task: u32,
     },
+}
 
-    /// 显示任务详情
-    #[command(alias = "s")]
-    Show {
-        /// 任务 ID
-        id: u32,
+/// 备份操作
+#[derive(Subcommand, Debug)]
+pub enum BackupAction {
+    /// 列出所有备份
+    #[command(alias = "ls")]
+    List,
+
+    /// 用指定的备份覆盖数据文件（当前数据会先备份）
+    Restore {
+        /// 备份时间戳，见 `todo backup list`
+        timestamp: String,
     },
+}
 
-    /// 清除所有已完成的任务
-    Clear {
-        /// 跳过确认提示
-        #[arg(short, long)]
-        force: bool,
+/// 回收站操作
+#[derive(Subcommand, Debug)]
+pub enum TrashAction {
+    /// 列出回收站中的任务
+    #[command(alias = "ls")]
+    List,
+
+    /// 永久删除回收站中的任务
+    Empty {
+        /// 只删除在这之前删除的任务，例如 30d、2w 或 YYYY-MM-DD
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+    },
+}
+
+/// 模板操作
+#[derive(Subcommand, Debug)]
+pub enum TemplateAction {
+    /// 把已有任务保存为模板（优先级、标签、情境、预估耗时、重复周期、相对截止日期，子任务作为清单）
+    Save {
+        /// 模板名称，已存在时覆盖
+        name: String,
+
+        /// 作为模板的任务 ID
+        #[arg(long, value_name = "ID")]
+        from: u32,
+    },
+
+    /// 列出所有模板
+    #[command(alias = "ls")]
+    List,
+
+    /// 删除模板
+    #[command(alias = "rm")]
+    Delete {
+        /// 模板名称
+        name: String,
     },
 }
 
-/// 列表筛选选项
-#[derive(clap::ValueEnum, Clone, Debug)]
-pub enum ListFilter {
-    /// 所有任务
-    All,
-    /// 待办任务（未完成）
-    Pending,
-    /// 已完成任务
-    Completed,
-    /// 过期任务
-    Overdue,
+/// 支持的导入来源
+#[derive(Subcommand, Debug)]
+pub enum ImportSource {
+    /// 导入 Taskwarrior 的 JSON 导出文件 (`task export > file.json`)
+    Taskwarrior {
+        /// 导出文件路径
+        file: PathBuf,
+    },
+
+    /// 导入 Markdown 清单中的 `- [ ]` 和 `- [x]` 项，缩进的项作为子任务
+    Markdown {
+        /// Markdown 文件路径
+        file: PathBuf,
+    },
+
+    /// 从 `todo export --bundle` 生成的备份包恢复完整状态（会替换现有任务和配置）
+    Bundle {
+        /// 备份包路径
+        file: PathBuf,
+    },
+}
+
+/// `add` 命令的参数
+#[derive(Args, Debug, Default)]
+pub struct AddArgs {
+    /// 任务标题
+    #[arg(required_unless_present = "stdin")]
+    pub title: Option<String>,
+
+    /// 从标准输入读取任务标题，每行一个（空行忽略），其他选项应用到所有任务
+    #[arg(long, conflicts_with = "title")]
+    pub stdin: bool,
+    
+    /// 优先级:  high (h), medium (m), low (l)，默认 medium
+    #[arg(short, long)]
+    pub priority: Option<String>,
+    
+    /// 截止日期 (格式: YYYY-MM-DD)
+    #[arg(short, long)]
+    pub due: Option<String>,
+
+    /// 标签（可重复指定），例如 -t work -t urgent
+    #[arg(short, long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// 情境，例如 home、work（可以带 `@`）
+    #[arg(short, long, value_name = "CONTEXT")]
+    pub context: Option<String>,
+
+    /// 作为指定任务的子任务添加
+    #[arg(long, value_name = "ID")]
+    pub parent: Option<u32>,
+
+    /// 子任务沿用父任务的截止日期
+    #[arg(long, requires = "parent", conflicts_with = "due")]
+    pub inherit_due: bool,
+
+    /// 预估耗时，例如 30m、2h、1h30m
+    #[arg(short, long)]
+    pub estimate: Option<String>,
+
+    /// 重复周期: daily, weekly, monthly 或 RRULE（例如 "FREQ=WEEKLY;BYDAY=MO,WE"），需要同时指定截止日期
+    #[arg(long, requires = "due")]
+    pub every: Option<String>,
+
+    /// 关联到指定目标
+    #[arg(long, value_name = "GOAL_ID")]
+    pub goal: Option<u32>,
+
+    /// 使用模板填充未指定的字段，并把模板的清单添加为子任务
+    #[arg(long, value_name = "NAME")]
+    pub template: Option<String>,
+}
+
+/// `list` 命令的参数
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// 按状态筛选
+    #[arg(value_enum)]
+    pub status: Option<StatusFilter>,
+
+    /// 只显示指定优先级的任务
+    #[arg(short, long)]
+    pub priority: Option<String>,
+
+    /// 只显示截止日期早于指定日期的任务 (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    pub due_before: Option<String>,
+
+    /// 只显示截止日期晚于指定日期的任务 (YYYY-MM-DD)，与 --due-before 一起指定时间窗口
+    #[arg(long, value_name = "DATE")]
+    pub due_after: Option<String>,
+
+    /// 只显示带有指定标签的任务，例如 work 或 #work
+    #[arg(short, long)]
+    pub tag: Option<String>,
+
+    /// 只显示指定情境的任务，例如 home 或 @home
+    #[arg(long, value_name = "CONTEXT")]
+    pub context: Option<String>,
+
+    /// 查询表达式，例如 "priority:high AND (tag:work OR overdue)"
+    #[arg(short, long)]
+    pub query: Option<String>,
+
+    /// 分组显示，每组带有小标题和任务数
+    #[arg(short, long, value_enum, value_name = "FIELD")]
+    pub group_by: Option<GroupBy>,
+
+    /// 排序方式
+    #[arg(short, long, value_enum, default_value_t = SortKey::Id)]
+    pub sort: SortKey,
+
+    /// 最多显示多少个任务
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// 跳过前多少个任务（与 --limit 一起用于分页）
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub offset: usize,
+
+    /// 隐藏被未完成的依赖阻塞的任务
+    #[arg(long)]
+    pub hide_blocked: bool,
+}
+
+/// `complete` 命令的参数
+#[derive(Args, Debug)]
+pub struct CompleteArgs {
+    /// 任务 ID
+    #[arg(required_unless_present = "pattern")]
+    pub id: Option<u32>,
+
+    /// 按标题片段（不区分大小写）选择唯一的未完成任务
+    #[arg(long = "match", value_name = "TEXT", conflicts_with = "id")]
+    pub pattern: Option<String>,
+
+    /// 即使依赖的任务尚未完成也标记为完成
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+/// `block` 命令的参数
+#[derive(Args, Debug)]
+pub struct BlockArgs {
+    /// 被阻塞的任务 ID
+    pub id: u32,
+
+    /// 依赖的任务 ID，可以指定多个
+    #[arg(long = "on", value_name = "ID", required = true, num_args = 1..)]
+    pub on: Vec<u32>,
+}
+
+/// `unblock` 命令的参数
+#[derive(Args, Debug)]
+pub struct UnblockArgs {
+    /// 被阻塞的任务 ID
+    pub id: u32,
+
+    /// 不再依赖的任务 ID，可以指定多个
+    #[arg(long = "on", value_name = "ID", required = true, num_args = 1..)]
+    pub on: Vec<u32>,
+}
+
+/// `reopen` 命令的参数
+#[derive(Args, Debug)]
+pub struct ReopenArgs {
+    /// 任务 ID
+    pub id: u32,
+}
+
+/// `skip` 命令的参数
+#[derive(Args, Debug)]
+pub struct SkipArgs {
+    /// 任务 ID
+    pub id: u32,
+}
+
+/// `postpone` 命令的参数
+#[derive(Args, Debug)]
+pub struct PostponeArgs {
+    /// 任务 ID
+    pub id: u32,
+
+    /// 新的截止日期 YYYY-MM-DD，或相对原截止日期的推迟量，例如 +1d、+2w（没有截止日期时从今天算起）
+    #[arg(allow_hyphen_values = true)]
+    pub to: String,
+}
+
+/// `prioritize` 命令的参数
+#[derive(Args, Debug)]
+pub struct PrioritizeArgs {
+    /// 任务 ID
+    pub id: u32,
+
+    /// 新的优先级: high (h), medium (m), low (l)
+    pub priority: String,
+}
+
+/// `edit` 命令的参数
+#[derive(Args, Debug, Default)]
+#[command(group(ArgGroup::new("changes").required(true).multiple(true).args(
+    ["title", "priority", "due", "no_due", "tags", "no_tags", "context", "no_context"]
+)))]
+pub struct EditArgs {
+    /// 任务 ID
+    pub id: u32,
+
+    /// 新的标题
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// 新的优先级: high (h), medium (m), low (l)
+    #[arg(short, long)]
+    pub priority: Option<String>,
+
+    /// 新的截止日期 (格式: YYYY-MM-DD)
+    #[arg(short, long, conflicts_with = "no_due")]
+    pub due: Option<String>,
+
+    /// 清除截止日期
+    #[arg(long)]
+    pub no_due: bool,
+
+    /// 用这些标签替换原有的标签（可重复指定）
+    #[arg(short, long = "tag", value_name = "TAG", conflicts_with = "no_tags")]
+    pub tags: Option<Vec<String>>,
+
+    /// 清除所有标签
+    #[arg(long)]
+    pub no_tags: bool,
+
+    /// 新的情境，例如 home、work（可以带 `@`）
+    #[arg(short, long, value_name = "CONTEXT", conflicts_with = "no_context")]
+    pub context: Option<String>,
+
+    /// 清除情境
+    #[arg(long)]
+    pub no_context: bool,
+}
+
+/// `delete` 命令的参数
+#[derive(Args, Debug)]
+pub struct DeleteArgs {
+    /// 任务 ID
+    #[arg(required_unless_present = "pattern")]
+    pub id: Option<u32>,
+
+    /// 按标题片段（不区分大小写）选择唯一的任务
+    #[arg(long = "match", value_name = "TEXT", conflicts_with = "id")]
+    pub pattern: Option<String>,
+
+    /// 连同所有子任务一起删除
+    #[arg(long, conflicts_with = "orphan")]
+    pub cascade: bool,
+
+    /// 只删除该任务，子任务提升为顶层任务
+    #[arg(long)]
+    pub orphan: bool,
+}
+
+/// `search` 命令的参数
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// 标题片段（不区分大小写），使用 --regex 时为正则表达式
+    pub pattern: String,
+
+    /// 把 PATTERN 作为正则表达式匹配（区分大小写，可以用 (?i) 忽略大小写）
+    #[arg(short, long)]
+    pub regex: bool,
+}
+
+/// `count` 命令的参数
+#[derive(Args, Debug)]
+pub struct CountArgs {
+    /// 按状态筛选（默认统计所有任务）
+    #[arg(value_enum)]
+    pub status: Option<StatusFilter>,
+
+    /// 只统计指定优先级的任务
+    #[arg(short, long)]
+    pub priority: Option<String>,
+
+    /// 只统计带有指定标签的任务，例如 work 或 #work
+    #[arg(short, long)]
+    pub tag: Option<String>,
+
+    /// 只统计指定情境的任务，例如 home 或 @home
+    #[arg(long, value_name = "CONTEXT")]
+    pub context: Option<String>,
+
+    /// 查询表达式，与 `todo list --query` 相同
+    #[arg(short, long)]
+    pub query: Option<String>,
+}
+
+/// `done` 命令的参数
+#[derive(Args, Debug)]
+pub struct DoneArgs {
+    /// 起始时间：today、yesterday、最近一段时间（例如 7d、24h）或日期 YYYY-MM-DD
+    #[arg(long, default_value = "today")]
+    pub since: String,
+}
+
+/// `duplicate` 命令的参数
+#[derive(Args, Debug)]
+pub struct DuplicateArgs {
+    /// 任务 ID
+    pub id: u32,
+
+    /// 新任务的截止日期 (格式: YYYY-MM-DD)，不指定时没有截止日期
+    #[arg(short, long)]
+    pub due: Option<String>,
+}
+
+/// `restore` 命令的参数
+#[derive(Args, Debug)]
+pub struct RestoreArgs {
+    /// 任务 ID，见 `todo trash list`
+    pub id: u32,
+}
+
+/// `trash` 命令的参数
+#[derive(Args, Debug)]
+pub struct TrashArgs {
+    /// 回收站操作
+    #[command(subcommand)]
+    pub action: TrashAction,
+}
+
+/// `show` 命令的参数
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// 任务 ID
+    pub id: u32,
+
+    /// 实时显示到截止日期的倒计时，按 Ctrl+C 退出
+    #[arg(long)]
+    pub live: bool,
+
+    /// 同时显示任务的历史记录（新建、修改、完成、改期）
+    #[arg(long)]
+    pub history: bool,
+}
+
+/// `pomodoro` 命令的参数
+#[derive(Args, Debug)]
+pub struct PomodoroArgs {
+    /// 任务 ID
+    pub id: u32,
+
+    /// 时长（分钟）
+    #[arg(short, long, default_value_t = 25, value_parser = clap::value_parser!(u32).range(1..=240))]
+    pub minutes: u32,
+
+    /// 结束时发送桌面通知
+    #[cfg(feature = "notify")]
+    #[arg(long)]
+    pub notify: bool,
+}
+
+/// `clear` 命令的参数
+#[derive(Args, Debug)]
+pub struct ClearArgs {
+    /// 跳过确认提示
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+/// `gc` 命令的参数
+#[derive(Args, Debug)]
+pub struct GcArgs {
+    /// 归档在这之前完成的任务，例如 30d、2w 或 YYYY-MM-DD（默认为配置的 archive_after_days 或 30 天）
+    #[arg(long, value_name = "AGE")]
+    pub older_than: Option<String>,
+
+    /// 只列出会归档的任务，不做修改
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// `reset` 命令的参数
+#[derive(Args, Debug)]
+pub struct ResetArgs {
+    /// 跳过确认提示（仍会备份）
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+/// `undo` 命令的参数（没有参数）
+#[derive(Args, Debug)]
+pub struct UndoArgs {}
+
+/// `redo` 命令的参数（没有参数）
+#[derive(Args, Debug)]
+pub struct RedoArgs {}
+
+/// `triage` 命令的参数（没有参数）
+#[derive(Args, Debug)]
+pub struct TriageArgs {}
+
+/// `__ids` 命令的参数（没有参数）
+#[derive(Args, Debug)]
+pub struct IdsArgs {}
+
+/// `encrypt` 命令的参数（没有参数）
+#[cfg(feature = "encryption")]
+#[derive(Args, Debug)]
+pub struct EncryptArgs {}
+
+/// `decrypt` 命令的参数（没有参数）
+#[cfg(feature = "encryption")]
+#[derive(Args, Debug)]
+pub struct DecryptArgs {}
+
+/// `shell` 命令的参数（没有参数）
+#[derive(Args, Debug)]
+pub struct ShellArgs {}
+
+/// `focus` 命令的参数
+#[derive(Args, Debug)]
+pub struct FocusArgs {
+    /// 只显示带有该标签的任务
+    #[arg(long, conflicts_with = "off")]
+    pub tag: Option<String>,
+
+    /// 专注结束时间 HH:MM（已过时表示明天），不指定时一直有效
+    #[arg(long, requires = "tag")]
+    pub until: Option<String>,
+
+    /// 关闭专注模式
+    #[arg(long)]
+    pub off: bool,
+}
+
+/// `doctor` 命令的参数
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// 自动修复可以修复的问题
+    #[arg(long)]
+    pub fix: bool,
+}
+
+/// `agenda` 命令的参数
+#[derive(Args, Debug)]
+pub struct AgendaArgs {
+    /// 时间范围
+    #[arg(value_enum, default_value_t = AgendaRange::Today)]
+    pub range: AgendaRange,
+}
+
+/// `board` 命令的参数
+#[derive(Args, Debug)]
+pub struct BoardArgs {
+    /// 看板总宽度，默认使用终端宽度（`COLUMNS`，未设置时为 80）
+    #[arg(long)]
+    pub width: Option<usize>,
+}
+
+/// `calendar` 命令的参数
+#[derive(Args, Debug)]
+pub struct CalendarArgs {
+    /// 月份 YYYY-MM，默认本月
+    pub month: Option<String>,
+}
+
+/// `stats` 命令的参数
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// 输出 JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `report` 命令的参数
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("kind").required(true).args(["changelog", "week"])))]
+pub struct ReportArgs {
+    /// 列出期间内新建、完成、改期和删除的任务
+    #[arg(long)]
+    pub changelog: bool,
+
+    /// 周报：最近 7 天完成、新增和仍然过期的任务，适合粘贴到状态更新中
+    #[arg(long)]
+    pub week: bool,
+
+    /// 变更记录的起始时间：最近一段时间（例如 7d、2w、24h）或日期 YYYY-MM-DD
+    #[arg(long, default_value = "7d", conflicts_with = "week")]
+    pub since: String,
+
+    /// 周报中任务的分组方式
+    #[arg(long, value_enum, default_value_t = GroupBy::Priority, conflicts_with = "changelog")]
+    pub group_by: GroupBy,
+
+    /// 输出 Markdown 而不是纯文本
+    #[arg(long)]
+    pub markdown: bool,
+}
+
+/// `import` 命令的参数
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// 只显示导入报告，不修改数据文件
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// 导入来源
+    #[command(subcommand)]
+    pub source: ImportSource,
+}
+
+/// `merge` 命令的参数
+#[derive(Args, Debug)]
+pub struct MergeArgs {
+    /// 要合并的数据文件（与当前数据文件使用同样的格式）
+    pub file: PathBuf,
+
+    /// 两份文件共同的上一个版本，指定后可以识别只在一边修改的字段和被删除的任务
+    #[arg(long)]
+    pub base: Option<PathBuf>,
+}
+
+/// `demo` 命令的参数
+#[derive(Args, Debug)]
+pub struct DemoArgs {
+    /// 要运行的命令及其参数
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_name = "COMMAND")]
+    pub args: Vec<String>,
+}
+
+/// `completions` 命令的参数
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// 目标 shell
+    #[arg(value_enum)]
+    pub shell: clap_complete::Shell,
+}
+
+/// `man` 命令的参数
+#[derive(Args, Debug)]
+pub struct ManArgs {
+    /// 为每个子命令生成 man page 并写入该目录（例如 todo.1、todo-add.1），不指定时把主页面输出到标准输出
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+}
+
+/// `export` 命令的参数
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("mode").required(true).args(["bundle", "anonymize", "html"])))]
+pub struct ExportArgs {
+    /// 备份包路径，例如 backup.tar.gz
+    #[arg(long)]
+    pub bundle: Option<PathBuf>,
+
+    /// 导出标题和标签替换为哈希的任务 JSON，保留结构、日期和数量
+    #[arg(long)]
+    pub anonymize: bool,
+
+    /// 导出独立的 HTML 页面（可按列排序的任务表格，过期任务高亮），便于分享给不用命令行的人
+    #[arg(long)]
+    pub html: bool,
+
+    /// 匿名导出或 HTML 导出的输出文件，默认输出到终端
+    #[arg(short, long, conflicts_with = "bundle")]
+    pub output: Option<PathBuf>,
+}
+
+/// `goal` 命令的参数
+#[derive(Args, Debug)]
+pub struct GoalArgs {
+    /// 目标操作
+    #[command(subcommand)]
+    pub action: GoalAction,
+}
+
+/// `template` 命令的参数
+#[derive(Args, Debug)]
+pub struct TemplateArgs {
+    /// 模板操作
+    #[command(subcommand)]
+    pub action: TemplateAction,
+}
+
+/// `backup` 命令的参数
+#[derive(Args, Debug)]
+pub struct BackupArgs {
+    /// 备份操作
+    #[command(subcommand)]
+    pub action: BackupAction,
+}
+
+/// `convert` 命令的参数
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// 目标格式
+    #[arg(value_enum)]
+    pub to: StorageFormat,
+}
+
+/// `notify` 命令的参数
+#[cfg(feature = "notify")]
+#[derive(Args, Debug)]
+pub struct NotifyArgs {
+    /// 在多长时间内到期算即将到期，例如 30m、2h
+    #[arg(long, default_value = "1h")]
+    pub within: String,
+
+    /// 持续运行，定期检查，每个任务的每种提醒只发送一次
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// 持续运行时的检查间隔，例如 5m
+    #[arg(long, default_value = "5m", requires = "daemon")]
+    pub interval: String,
+}
+
+/// `serve` 命令的参数
+#[cfg(feature = "server")]
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// 监听的端口
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// 监听的地址，默认只接受本机的请求；改为 0.0.0.0 时建议同时设置 --token
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// 要求请求带上 `Authorization: Bearer <TOKEN>`（默认读取 TODO_API_TOKEN 环境变量）
+    #[arg(long)]
+    pub token: Option<String>,
+}
+
+/// `sync` 命令的参数
+#[derive(Args, Debug)]
+pub struct SyncArgs {
+    /// 同步目标
+    #[command(subcommand)]
+    pub target: SyncTarget,
+}
+
+/// 日程的时间范围
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgendaRange {
+    /// 今天
+    Today,
+    /// 从今天起的 7 天
+    Week,
+}
+
+impl AgendaRange {
+    /// 范围包含的天数
+    pub fn days(self) -> i64 {
+        match self {
+            AgendaRange::Today => 1,
+            AgendaRange::Week => 7,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_cli_add_command() {
         let cli = Cli::parse_from(vec! ["todo", "add", "测试任务", "--priority", "high"]);
         
         match cli.command {
-            Commands:: Add { title, priority, ..  } => {
-                assert_eq!(title, "测试任务");
-                assert_eq!(priority, "high");
+            Commands::Add(AddArgs { title, priority, .. }) => {
+                assert_eq!(title.as_deref(), Some("测试任务"));
+                assert_eq!(priority.as_deref(), Some("high"));
             }
             _ => panic!("Expected Add command"),
         }
@@ -102,33 +1026,527 @@ mod tests {
         let cli = Cli::parse_from(vec! ["todo", "list", "pending"]);
         
         match cli.command {
-            Commands::List { filter } => {
-                assert!(filter.is_some());
+            Commands::List(ListArgs { status, priority, context, .. }) => {
+                assert_eq!(status, Some(StatusFilter::Pending));
+                assert!(priority.is_none());
+                assert!(context.is_none());
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_list_combined_filters() {
+        let cli = Cli::parse_from(vec![
+            "todo", "list", "pending", "--priority", "high", "--due-before", "2025-07-01", "--tag", "work",
+        ]);
+
+        match cli.command {
+            Commands::List(ListArgs { status, priority, due_before, tag, context, .. }) => {
+                assert_eq!(status, Some(StatusFilter::Pending));
+                assert_eq!(priority.as_deref(), Some("high"));
+                assert_eq!(due_before.as_deref(), Some("2025-07-01"));
+                assert_eq!(tag.as_deref(), Some("work"));
+                assert!(context.is_none());
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_list_group_by() {
+        let cli = Cli::parse_from(vec!["todo", "list", "--group-by", "due-week"]);
+        match cli.command {
+            Commands::List(ListArgs { group_by, .. }) => assert_eq!(group_by, Some(GroupBy::DueWeek)),
+            _ => panic!("Expected List command"),
+        }
+        assert!(Cli::try_parse_from(vec!["todo", "list", "--group-by", "color"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_list_pagination() {
+        let cli = Cli::parse_from(vec!["todo", "list", "--sort", "due", "--limit", "5", "--offset", "10"]);
+        match cli.command {
+            Commands::List(ListArgs { sort, limit, offset, .. }) => {
+                assert_eq!(sort, SortKey::Due);
+                assert_eq!(limit, Some(5));
+                assert_eq!(offset, 10);
+            }
+            _ => panic!("Expected List command"),
+        }
+
+        let cli = Cli::parse_from(vec!["todo", "list"]);
+        match cli.command {
+            Commands::List(ListArgs { sort, limit, offset, .. }) => {
+                assert_eq!(sort, SortKey::Id);
+                assert!(limit.is_none());
+                assert_eq!(offset, 0);
             }
             _ => panic!("Expected List command"),
         }
     }
 
+    #[test]
+    fn test_cli_demo_command() {
+        let cli = Cli::parse_from(vec!["todo", "demo", "list", "--tag", "work"]);
+        match cli.command {
+            Commands::Demo(DemoArgs { args }) => assert_eq!(args, ["list", "--tag", "work"]),
+            _ => panic!("Expected Demo command"),
+        }
+
+        let cli = Cli::parse_from(vec!["todo", "demo"]);
+        assert!(matches!(cli.command, Commands::Demo(DemoArgs { args }) if args.is_empty()));
+    }
+
     #[test]
     fn test_cli_complete_command() {
         let cli = Cli::parse_from(vec!["todo", "complete", "42"]);
         
         match cli.command {
-            Commands::Complete { id } => {
-                assert_eq!(id, 42);
+            Commands::Complete(CompleteArgs { id, pattern, .. }) => {
+                assert_eq!(id, Some(42));
+                assert!(pattern.is_none());
             }
             _ => panic!("Expected Complete command"),
         }
     }
 
+    #[test]
+    fn test_cli_match_by_title() {
+        let cli = Cli::parse_from(vec!["todo", "complete", "--match", "groceries"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Complete(CompleteArgs { id: None, pattern: Some(pattern), .. }) if pattern == "groceries"
+        ));
+
+        let cli = Cli::parse_from(vec!["todo", "delete", "--match", "milk", "--cascade"]);
+        assert!(matches!(cli.command, Commands::Delete(DeleteArgs { id: None, pattern: Some(_), cascade: true, .. })));
+
+        // ID 和 --match 必须且只能指定一个
+        assert!(Cli::try_parse_from(vec!["todo", "complete"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "complete", "1", "--match", "milk"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_completions_command() {
+        let cli = Cli::parse_from(vec!["todo", "completions", "zsh"]);
+        assert!(matches!(cli.command, Commands::Completions(CompletionsArgs { shell: clap_complete::Shell::Zsh })));
+        assert!(Cli::try_parse_from(vec!["todo", "completions", "tcsh"]).is_err());
+
+        let cli = Cli::parse_from(vec!["todo", "man", "--out-dir", "target/man"]);
+        assert!(matches!(cli.command, Commands::Man(ManArgs { out_dir: Some(dir) }) if dir == Path::new("target/man")));
+
+        let cli = Cli::parse_from(vec!["todo", "__ids"]);
+        assert!(matches!(cli.command, Commands::Ids(_)));
+    }
+
     #[test]
     fn test_cli_alias() {
         // 测试 'a' 别名
         let cli = Cli::parse_from(vec!["todo", "a", "任务"]);
-        assert!(matches!(cli.command, Commands::Add { .. }));
+        assert!(matches!(cli.command, Commands::Add(_)));
         
         // 测试 'ls' 别名
         let cli = Cli::parse_from(vec!["todo", "ls"]);
-        assert!(matches!(cli.command, Commands::List { ..  }));
+        assert!(matches!(cli.command, Commands::List(ListArgs { ..  })));
+    }
+
+    #[test]
+    fn test_cli_prioritize_command() {
+        let cli = Cli::parse_from(vec!["todo", "pri", "7", "h"]);
+
+        match cli.command {
+            Commands::Prioritize(PrioritizeArgs { id, priority }) => {
+                assert_eq!(id, 7);
+                assert_eq!(priority, "h");
+            }
+            _ => panic!("Expected Prioritize command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_goal_command() {
+        let cli = Cli::parse_from(vec!["todo", "goal", "add", "Ship v1", "--due", "2025-09-01"]);
+        match cli.command {
+            Commands::Goal(GoalArgs { action: GoalAction::Add { title, due } }) => {
+                assert_eq!(title, "Ship v1");
+                assert_eq!(due.as_deref(), Some("2025-09-01"));
+            }
+            _ => panic!("Expected goal add command"),
+        }
+
+        let cli = Cli::parse_from(vec!["todo", "goal", "link", "3", "1"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Goal(GoalArgs { action: GoalAction::Link { task: 3, goal: 1 } })
+        ));
+    }
+
+    #[test]
+    fn test_cli_postpone_command() {
+        let cli = Cli::parse_from(vec!["todo", "postpone", "4", "+1d"]);
+
+        match cli.command {
+            Commands::Postpone(PostponeArgs { id, to }) => {
+                assert_eq!(id, 4);
+                assert_eq!(to, "+1d");
+            }
+            _ => panic!("Expected Postpone command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_focus_command() {
+        let cli = Cli::parse_from(vec!["todo", "focus", "--tag", "deepwork", "--until", "17:00"]);
+        match cli.command {
+            Commands::Focus(FocusArgs { tag, until, off }) => {
+                assert_eq!(tag.as_deref(), Some("deepwork"));
+                assert_eq!(until.as_deref(), Some("17:00"));
+                assert!(!off);
+            }
+            _ => panic!("Expected Focus command"),
+        }
+
+        assert!(Cli::try_parse_from(vec!["todo", "focus", "--tag", "x", "--off"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "focus", "--until", "17:00"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_agenda() {
+        let cli = Cli::parse_from(vec!["todo", "agenda", "week"]);
+        assert!(matches!(cli.command, Commands::Agenda(AgendaArgs { range: AgendaRange::Week })));
+
+        let cli = Cli::parse_from(vec!["todo", "agenda"]);
+        assert!(matches!(cli.command, Commands::Agenda(AgendaArgs { range: AgendaRange::Today })));
+
+        assert!(Cli::try_parse_from(vec!["todo", "agenda", "month"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_board() {
+        let cli = Cli::parse_from(vec!["todo", "board", "--width", "120"]);
+        assert!(matches!(cli.command, Commands::Board(BoardArgs { width: Some(120) })));
+
+        let cli = Cli::parse_from(vec!["todo", "board"]);
+        assert!(matches!(cli.command, Commands::Board(BoardArgs { width: None })));
+    }
+
+    #[test]
+    fn test_cli_calendar() {
+        let cli = Cli::parse_from(vec!["todo", "calendar", "2025-03"]);
+        assert!(matches!(cli.command, Commands::Calendar(CalendarArgs { month: Some(ref m) }) if m == "2025-03"));
+
+        let cli = Cli::parse_from(vec!["todo", "cal"]);
+        assert!(matches!(cli.command, Commands::Calendar(CalendarArgs { month: None })));
+    }
+
+    #[test]
+    fn test_cli_report_week() {
+        let cli = Cli::parse_from(vec!["todo", "report", "--week", "--group-by", "tag", "--markdown"]);
+        match cli.command {
+            Commands::Report(ReportArgs { changelog, week, group_by, markdown, .. }) => {
+                assert!(!changelog);
+                assert!(week);
+                assert_eq!(group_by, GroupBy::Tag);
+                assert!(markdown);
+            }
+            _ => panic!("Expected Report command"),
+        }
+
+        assert!(Cli::try_parse_from(vec!["todo", "report"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "report", "--changelog", "--week"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "report", "--week", "--since", "2w"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "report", "--changelog", "--group-by", "tag"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "report", "--changelog"]).is_ok());
+    }
+
+    #[test]
+    fn test_cli_show_live() {
+        let cli = Cli::parse_from(vec!["todo", "show", "3", "--live"]);
+        assert!(matches!(cli.command, Commands::Show(ShowArgs { id: 3, live: true, history: false })));
+
+        let cli = Cli::parse_from(vec!["todo", "s", "3"]);
+        assert!(matches!(cli.command, Commands::Show(ShowArgs { id: 3, live: false, history: false })));
+
+        let cli = Cli::parse_from(vec!["todo", "show", "3", "--history"]);
+        assert!(matches!(cli.command, Commands::Show(ShowArgs { id: 3, history: true, .. })));
+    }
+
+    #[test]
+    fn test_cli_add_from_stdin() {
+        let cli = Cli::parse_from(vec!["todo", "add", "--stdin", "-t", "inbox"]);
+        assert!(matches!(cli.command, Commands::Add(AddArgs { title: None, stdin: true, .. })));
+
+        assert!(Cli::try_parse_from(vec!["todo", "add"]).is_err());
+        assert!(Cli::try_parse_from(vec!["todo", "add", "任务", "--stdin"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_templates() {
+        let cli = Cli::parse_from(vec!["todo", "template", "save", "release", "--from", "4"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Template(TemplateArgs { action: TemplateAction::Save { ref name, from: 4 } }) if name == "release"
+        ));
+
+        let cli = Cli::parse_from(vec!["todo", "add", "发布 v2", "--template", "release"]);
+        assert!(matches!(cli.command, Commands::Add(AddArgs { template: Some(ref name), .. }) if name == "release"));
+    }
+
+    #[test]
+    fn test_cli_dependencies() {
+        let cli = Cli::parse_from(vec!["todo", "block", "3", "--on", "1", "2"]);
+        assert!(matches!(cli.command, Commands::Block(BlockArgs { id: 3, on }) if on == [1, 2]));
+        assert!(Cli::try_parse_from(vec!["todo", "block", "3"]).is_err());
+
+        let cli = Cli::parse_from(vec!["todo", "complete", "3", "--force"]);
+        assert!(matches!(cli.command, Commands::Complete(CompleteArgs { id: Some(3), force: true, .. })));
+
+        let cli = Cli::parse_from(vec!["todo", "list", "--hide-blocked"]);
+        assert!(matches!(cli.command, Commands::List(ListArgs { hide_blocked: true, .. })));
+    }
+
+    #[test]
+    fn test_cli_pomodoro() {
+        let cli = Cli::parse_from(vec!["todo", "pomodoro", "3"]);
+        assert!(matches!(cli.command, Commands::Pomodoro(PomodoroArgs { id: 3, minutes: 25, .. })));
+
+        let cli = Cli::parse_from(vec!["todo", "pomodoro", "3", "--minutes", "50"]);
+        assert!(matches!(cli.command, Commands::Pomodoro(PomodoroArgs { id: 3, minutes: 50, .. })));
+
+        assert!(Cli::try_parse_from(vec!["todo", "pomodoro", "3", "-m", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_add_tags() {
+        let cli = Cli::parse_from(vec!["todo", "add", "任务", "-t", "work", "--tag", "#urgent"]);
+
+        match cli.command {
+            Commands::Add(AddArgs { tags, priority, .. }) => {
+                assert_eq!(tags, vec!["work", "#urgent"]);
+                assert!(priority.is_none());
+            }
+            _ => panic!("Expected Add command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_context() {
+        let cli = Cli::parse_from(vec!["todo", "add", "买牛奶", "-c", "@errands"]);
+        assert!(matches!(cli.command, Commands::Add(AddArgs { context: Some(ref c), .. }) if c == "@errands"));
+
+        let cli = Cli::parse_from(vec!["todo", "list", "pending", "--context", "home"]);
+        assert!(matches!(cli.command, Commands::List(ListArgs { context: Some(ref c), .. }) if c == "home"));
+    }
+
+    #[test]
+    fn test_cli_add_recurring_requires_due() {
+        let cli = Cli::parse_from(vec!["todo", "add", "周报", "--every", "weekly", "--due", "2025-01-03"]);
+        assert!(matches!(cli.command, Commands::Add(AddArgs { every: Some(_), .. })));
+
+        let result = Cli::try_parse_from(vec!["todo", "add", "周报", "--every", "weekly"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_inherit_due_requires_parent() {
+        let cli = Cli::parse_from(vec!["todo", "add", "子任务", "--parent", "1", "--inherit-due"]);
+        assert!(matches!(cli.command, Commands::Add(AddArgs { inherit_due: true, .. })));
+
+        assert!(Cli::try_parse_from(vec!["todo", "add", "任务", "--inherit-due"]).is_err());
+        assert!(Cli::try_parse_from(vec![
+            "todo", "add", "任务", "--parent", "1", "--inherit-due", "--due", "2025-01-01"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_cli_delete_policy_flags() {
+        let cli = Cli::parse_from(vec!["todo", "delete", "3", "--cascade"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Delete(DeleteArgs { id: Some(3), pattern: None, cascade: true, orphan: false })
+        ));
+
+        let result = Cli::try_parse_from(vec!["todo", "delete", "3", "--cascade", "--orphan"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_output_flags() {
+        let cli = Cli::parse_from(vec!["todo", "list", "--plain"]);
+        assert!(cli.plain && !cli.no_color);
+
+        let cli = Cli::parse_from(vec!["todo", "--no-color", "list"]);
+        assert!(cli.no_color && !cli.plain);
+
+        let cli = Cli::parse_from(vec!["todo", "stats", "--ascii"]);
+        assert!(cli.ascii);
+    }
+
+    #[test]
+    fn test_cli_global_format() {
+        let cli = Cli::parse_from(vec!["todo", "list", "--format", "todotxt"]);
+        assert_eq!(cli.format, Some(StorageFormat::TodoTxt));
+
+        let cli = Cli::parse_from(vec!["todo", "list"]);
+        assert_eq!(cli.format, None);
+        assert!(!cli.local);
+
+        let cli = Cli::parse_from(vec!["todo", "add", "任务", "--local"]);
+        assert!(cli.local);
+    }
+
+    #[test]
+    fn test_cli_global_file() {
+        let cli = Cli::parse_from(vec!["todo", "list", "--file", "project/todos.json"]);
+        assert_eq!(cli.data_file, Some(PathBuf::from("project/todos.json")));
+
+        let cli = Cli::parse_from(vec!["todo", "--file", "tasks.json", "complete", "1"]);
+        assert_eq!(cli.data_file, Some(PathBuf::from("tasks.json")));
+
+        let cli = Cli::parse_from(vec!["todo", "list"]);
+        assert!(cli.data_file.is_none());
+
+        let result = Cli::try_parse_from(vec!["todo", "list", "--file", "a.json", "--local"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_data_dir() {
+        let cli = Cli::parse_from(vec!["todo", "--data-dir", "/tmp/todo-test", "list"]);
+        assert_eq!(cli.data_dir, Some(PathBuf::from("/tmp/todo-test")));
+
+        let result = Cli::try_parse_from(vec!["todo", "list", "--data-dir", "d", "--file", "a.json"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_import_taskwarrior() {
+        let cli = Cli::parse_from(vec!["todo", "import", "taskwarrior", "export.json"]);
+        assert!(cli.data_file.is_none());
+
+        match cli.command {
+            Commands::Import(ImportArgs {
+                dry_run,
+                source: ImportSource::Taskwarrior { file },
+            }) => {
+                assert!(!dry_run);
+                assert_eq!(file, PathBuf::from("export.json"));
+            }
+            _ => panic!("Expected Import command"),
+        }
+
+        let cli = Cli::parse_from(vec!["todo", "import", "taskwarrior", "export.json", "--dry-run"]);
+        assert!(matches!(cli.command, Commands::Import(ImportArgs { dry_run: true, .. })));
+
+        let cli = Cli::parse_from(vec!["todo", "import", "markdown", "notes.md"]);
+        assert!(matches!(cli.command, Commands::Import(ImportArgs { source: ImportSource::Markdown { .. }, .. })));
+    }
+
+    #[test]
+    fn test_cli_bundle() {
+        let cli = Cli::parse_from(vec!["todo", "export", "--bundle", "backup.tar.gz"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Export(ExportArgs { bundle: Some(bundle), anonymize: false, .. }) if bundle == Path::new("backup.tar.gz")
+        ));
+
+        let cli = Cli::parse_from(vec!["todo", "import", "bundle", "backup.tar.gz"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Import(ImportArgs { source: ImportSource::Bundle { .. }, .. })
+        ));
+
+        assert!(Cli::try_parse_from(vec!["todo", "export"]).is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_cli_encryption() {
+        let cli = Cli::parse_from(vec!["todo", "encrypt"]);
+        assert!(matches!(cli.command, Commands::Encrypt(_)));
+
+        let cli = Cli::parse_from(vec!["todo", "--local", "decrypt"]);
+        assert!(matches!(cli.command, Commands::Decrypt(_)));
+    }
+
+    #[test]
+    fn test_cli_convert() {
+        let cli = Cli::parse_from(vec!["todo", "convert", "binary"]);
+        assert!(matches!(cli.command, Commands::Convert(ConvertArgs { to: StorageFormat::Binary })));
+        assert!(Cli::try_parse_from(vec!["todo", "convert", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_backup() {
+        let cli = Cli::parse_from(vec!["todo", "backup", "list"]);
+        assert!(matches!(cli.command, Commands::Backup(BackupArgs { action: BackupAction::List })));
+
+        let cli = Cli::parse_from(vec!["todo", "backup", "restore", "20250101T120000.000"]);
+        match cli.command {
+            Commands::Backup(BackupArgs {
+                action: BackupAction::Restore { timestamp },
+            }) => assert_eq!(timestamp, "20250101T120000.000"),
+            _ => panic!("Expected Backup Restore command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_trash() {
+        let cli = Cli::parse_from(vec!["todo", "trash", "ls"]);
+        assert!(matches!(cli.command, Commands::Trash(TrashArgs { action: TrashAction::List })));
+
+        let cli = Cli::parse_from(vec!["todo", "trash", "empty", "--older-than", "30d"]);
+        match cli.command {
+            Commands::Trash(TrashArgs {
+                action: TrashAction::Empty { older_than },
+            }) => assert_eq!(older_than.as_deref(), Some("30d")),
+            _ => panic!("Expected Trash Empty command"),
+        }
+
+        let cli = Cli::parse_from(vec!["todo", "gc", "--older-than", "2w", "--dry-run"]);
+        assert!(matches!(cli.command, Commands::Gc(GcArgs { older_than: Some(age), dry_run: true }) if age == "2w"));
+
+        let cli = Cli::parse_from(vec!["todo", "reset", "--force"]);
+        assert!(matches!(cli.command, Commands::Reset(ResetArgs { force: true })));
+
+        let cli = Cli::parse_from(vec!["todo", "dup", "2", "--due", "2099-01-05"]);
+        assert!(matches!(cli.command, Commands::Duplicate(DuplicateArgs { id: 2, due: Some(_) })));
+
+        let cli = Cli::parse_from(vec!["todo", "done", "--since", "yesterday"]);
+        assert!(matches!(cli.command, Commands::Done(DoneArgs { since }) if since == "yesterday"));
+        let cli = Cli::parse_from(vec!["todo", "done"]);
+        assert!(matches!(cli.command, Commands::Done(DoneArgs { since }) if since == "today"));
+
+        let cli = Cli::parse_from(vec!["todo", "search", "--regex", "^Fix .*prod"]);
+        assert!(matches!(cli.command, Commands::Search(SearchArgs { pattern, regex: true }) if pattern == "^Fix .*prod"));
+
+        let cli = Cli::parse_from(vec!["todo", "count", "pending", "--tag", "work"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Count(CountArgs { status: Some(StatusFilter::Pending), tag: Some(tag), .. }) if tag == "work"
+        ));
+
+        let cli = Cli::parse_from(vec!["todo", "restore", "3"]);
+        assert!(matches!(cli.command, Commands::Restore(RestoreArgs { id: 3 })));
+    }
+
+    #[test]
+    fn test_cli_export_anonymize() {
+        let cli = Cli::parse_from(vec!["todo", "export", "--anonymize", "-o", "bug.json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Export(ExportArgs { bundle: None, anonymize: true, html: false, output: Some(_) })
+        ));
+
+        let cli = Cli::parse_from(vec!["todo", "export", "--html", "-o", "status.html"]);
+        assert!(matches!(cli.command, Commands::Export(ExportArgs { html: true, output: Some(_), .. })));
+
+        let result = Cli::try_parse_from(vec!["todo", "export", "--anonymize", "--bundle", "b.tar.gz"]);
+        assert!(result.is_err());
+
+        let result = Cli::try_parse_from(vec!["todo", "export", "--bundle", "b.tar.gz", "-o", "x.json"]);
+        assert!(result.is_err());
     }
 }
@@ -0,0 +1,3304 @@
+use clap::{CommandFactory, Parser};
+use crate::{
+    cli::{
+        AddArgs, AgendaArgs, AgendaRange, BackupAction, BackupArgs, BlockArgs, BoardArgs, CalendarArgs, ClearArgs, Cli,
+        Commands, CompleteArgs, CompletionsArgs, ConvertArgs, CountArgs, DeleteArgs, DemoArgs, DoctorArgs, DoneArgs,
+        DuplicateArgs, EditArgs, ExportArgs, FocusArgs, GcArgs, GoalAction, GoalArgs, IdsArgs, ImportArgs, ImportSource,
+        ListArgs, ManArgs, MergeArgs, PomodoroArgs, PostponeArgs, PrioritizeArgs, RedoArgs, ReopenArgs, ReportArgs,
+        ResetArgs, RestoreArgs, SearchArgs, ShellArgs, ShowArgs, SkipArgs, StatsArgs, SyncArgs, TemplateAction,
+        TemplateArgs, TrashAction, TrashArgs, TriageArgs, UnblockArgs, UndoArgs,
+    },
+    config::{set_data_dir, Config},
+    display::*,
+    dates::{end_of_day, local_date, start_of_day, to_local, today},
+    demo, doctor, html,
+    error::TodoError,
+    filter::{ListFilter, SortKey, StatusFilter},
+    anonymize::anonymize,
+    archive, backups,
+    bundle::{export_bundle, import_bundle, Bundle},
+    hooks::{history_hooks, Hooks},
+    i18n::{set_locale, Locale},
+    journal::JournalStorage,
+    import::{import_markdown, import_taskwarrior},
+    report::{self, GroupBy},
+    session::{Focus, Session},
+    stats,
+    storage::{
+        backup_file, compress_file, default_data_path, gzip_path, is_gzip_path, env_data_path, file_exists, parse_with_format, resolve_data_path,
+        save_to_file_with_format, FileStorage, Storage, StorageFormat,
+    },
+    task::{Pomodoro, Priority, Recurrence, Task},
+    template::{Template, Templates},
+    todo_list::{DeletePolicy, TodoList},
+    undo::UndoStack,
+    triage,
+};
+#[cfg(feature = "encryption")]
+use crate::cli::{DecryptArgs, EncryptArgs};
+#[cfg(feature = "notify")]
+use crate::cli::NotifyArgs;
+#[cfg(feature = "server")]
+use crate::cli::ServeArgs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// 当前使用的数据文件及其格式
+pub(crate) struct DataFile {
+    path: PathBuf,
+    format: StorageFormat,
+    /// 保存前自动备份时保留的数量
+    backup_count: usize,
+    /// 变更日志（配置了 `journal = true` 且使用 JSON 格式时）
+    journal: Option<JournalStorage>,
+    /// 加密口令（加载加密的数据文件后设置，保存时重新加密）
+    #[cfg(feature = "encryption")]
+    passphrase: std::cell::RefCell<Option<String>>,
+    /// 配置的 Webhook
+    #[cfg(feature = "webhooks")]
+    webhooks: Vec<crate::config::WebhookConfig>,
+    /// 钩子脚本，没有任何脚本时为 `None`
+    hooks: Option<Hooks>,
+    /// 上次加载或保存时历史记录的长度，保存时据此找出新产生的事件
+    history_len: std::cell::Cell<usize>,
+    /// 上次加载或保存时的任务，用于把被删除的任务交给 on-delete 钩子
+    saved_tasks: std::cell::RefCell<Vec<Task>>,
+    /// 当前命令是否保存过数据，命令修改了数据后才自动归档
+    saved: std::cell::Cell<bool>,
+}
+
+impl DataFile {
+    /// 加载待办列表；配置了 Webhook 时顺便通知新过期的任务
+    fn load(&self) -> Result<TodoList, TodoError> {
+        let mut todo_list = self.read()?;
+        self.mark_saved(&todo_list);
+
+        // 撤销记录损坏时不影响正常使用
+        if self.journal().is_none() {
+            match self.read_undo() {
+                Ok(stack) => todo_list.set_undo_stack(stack),
+                Err(err) => print_warning(&format!("Ignoring unreadable undo history: {}", err)),
+            }
+        }
+
+        #[cfg(feature = "webhooks")]
+        if !self.webhooks.is_empty() {
+            if let Err(err) = self.announce_overdue(&todo_list) {
+                print_warning(&err.to_string());
+            }
+        }
+
+        Ok(todo_list)
+    }
+
+    /// 记下与数据文件一致的状态，下次保存时与它比较找出新的事件
+    fn mark_saved(&self, todo_list: &TodoList) {
+        self.history_len.set(todo_list.history().len());
+        if self.hooks.is_some() {
+            self.saved_tasks.replace(todo_list.list_tasks().to_vec());
+        }
+    }
+
+    /// 读取数据文件，数据文件已加密时先读取口令
+    fn read(&self) -> Result<TodoList, TodoError> {
+        #[cfg(feature = "encryption")]
+        if self.passphrase.borrow().is_none() && crate::storage::is_encrypted_file(&self.path)? {
+            // 重新加载时（例如 notify --daemon）沿用已输入的口令，口令错误时忘掉它
+            self.passphrase.replace(Some(read_passphrase(false)?));
+            return self.storage().load().inspect_err(|_| {
+                self.passphrase.replace(None);
+            });
+        }
+
+        self.storage().load().or_else(|err| self.recover(err))
+    }
+
+    /// 数据文件损坏时改用最近一个可以读取的备份，损坏的文件复制一份留作排查
+    ///
+    /// 损坏的文件在下次保存时才会被替换，在此之前每次加载都会给出提示。
+    fn recover(&self, err: TodoError) -> Result<TodoList, TodoError> {
+        if !err.is_corruption() {
+            return Err(err);
+        }
+        let Some((backup, todo_list)) = backups::latest_valid(&self.path, self.format)? else {
+            return Err(err);
+        };
+
+        let mut damaged = self.path.as_os_str().to_owned();
+        damaged.push(".corrupt");
+        backup_file(&self.path, &damaged)?;
+        print_warning(&format!(
+            "{} could not be read ({}). Loaded backup {} instead; the damaged file was copied to {}",
+            self.path.display(),
+            err.to_string().trim_start_matches("❌ "),
+            backup.timestamp,
+            PathBuf::from(damaged).display()
+        ));
+        Ok(todo_list)
+    }
+
+    /// 数据文件的存储后端，命令处理只通过它读写数据
+    fn storage(&self) -> Box<dyn Storage + '_> {
+        if let Some(journal) = self.journal() {
+            return Box::new(journal);
+        }
+
+        let storage = FileStorage::new(&self.path, self.format).with_backups(self.backup_count);
+        #[cfg(feature = "encryption")]
+        let storage = storage.with_passphrase(self.passphrase.borrow().clone());
+        Box::new(storage)
+    }
+
+    /// 正在使用的变更日志；数据文件加密时不使用日志
+    fn journal(&self) -> Option<&JournalStorage> {
+        #[cfg(feature = "encryption")]
+        if self.passphrase.borrow().is_some() {
+            return None;
+        }
+
+        self.journal.as_ref()
+    }
+
+    /// 把变更日志合并回数据文件，直接读写数据文件的操作（备份恢复、git 同步等）之前调用
+    fn compact(&self, todo_list: &TodoList) -> Result<(), TodoError> {
+        match self.journal() {
+            Some(journal) if journal.journal_path().exists() => journal.compact(todo_list),
+            _ => Ok(()),
+        }
+    }
+
+    /// 保存待办列表，然后为本次新建、完成和删除的任务运行钩子脚本和 Webhook
+    fn save(&self, todo_list: &TodoList) -> Result<(), TodoError> {
+        self.write(todo_list)?;
+        // 撤销记录保存的是完整快照，使用变更日志时不保存，否则每次保存仍要写入整个列表
+        if self.journal().is_none() {
+            if let Err(err) = self.write_undo(&todo_list.undo_stack()) {
+                print_warning(&format!("Could not save undo history: {}", err));
+            }
+        }
+
+        // 保存成功后才通知，失败只给出警告，不影响命令本身
+        if let Some(hooks) = &self.hooks {
+            let saved_tasks = self.saved_tasks.borrow();
+            for (event, task) in history_hooks(todo_list, self.history_len.get(), &saved_tasks) {
+                if let Err(err) = hooks.run(event, &task) {
+                    print_warning(&err.to_string());
+                }
+            }
+        }
+
+        #[cfg(feature = "webhooks")]
+        if !self.webhooks.is_empty() {
+            let payloads = crate::webhook::history_payloads(todo_list, self.history_len.get());
+            for err in crate::webhook::deliver(&self.webhooks, &payloads) {
+                print_warning(&err.to_string());
+            }
+        }
+
+        self.mark_saved(todo_list);
+        self.saved.set(true);
+        Ok(())
+    }
+
+    /// 写入数据文件，写入前先备份旧的数据文件
+    fn write(&self, todo_list: &TodoList) -> Result<(), TodoError> {
+        self.storage().save(todo_list)
+    }
+
+    /// 读取数据文件的撤销记录，数据文件加密时撤销记录也是加密的
+    fn read_undo(&self) -> Result<UndoStack, TodoError> {
+        let path = UndoStack::path_for(&self.path);
+
+        #[cfg(feature = "encryption")]
+        if let Some(passphrase) = self.passphrase.borrow().as_deref() {
+            return UndoStack::load_encrypted(&path, passphrase);
+        }
+
+        UndoStack::load_from(&path)
+    }
+
+    /// 保存撤销记录
+    fn write_undo(&self, stack: &UndoStack) -> Result<(), TodoError> {
+        let path = UndoStack::path_for(&self.path);
+
+        #[cfg(feature = "encryption")]
+        if let Some(passphrase) = self.passphrase.borrow().as_deref() {
+            return stack.save_encrypted(&path, passphrase);
+        }
+
+        stack.save_to(&path)
+    }
+
+    /// 把在 `before` 之前完成的任务移到归档文件，返回归档的任务
+    ///
+    /// 先写入归档文件再保存数据文件，中途失败时任务最多在两边各有一份，不会丢失。
+    fn archive(&self, todo_list: &mut TodoList, before: chrono::DateTime<chrono::Utc>) -> Result<Vec<Task>, TodoError> {
+        let tasks = todo_list.archive_completed(before);
+        if tasks.is_empty() {
+            return Ok(tasks);
+        }
+
+        // 归档文件与数据文件使用同一个口令加密
+        let storage = FileStorage::new(archive::archive_path(&self.path), StorageFormat::Json);
+        #[cfg(feature = "encryption")]
+        let storage = storage.with_passphrase(self.passphrase.borrow().clone());
+        let mut archived = storage.load()?;
+        archive::append(&mut archived, tasks.clone());
+        storage.save(&archived)?;
+
+        self.save(todo_list)?;
+        Ok(tasks)
+    }
+
+    /// 通知还没有通知过的过期任务
+    #[cfg(feature = "webhooks")]
+    fn announce_overdue(&self, todo_list: &TodoList) -> Result<(), TodoError> {
+        use crate::webhook::{deliver, OverdueState};
+
+        let Some(path) = OverdueState::default_path() else {
+            return Ok(());
+        };
+        let mut state = OverdueState::load_from(&path)?;
+        let payloads = state.newly_overdue(todo_list, chrono::Utc::now());
+        if payloads.is_empty() {
+            return Ok(());
+        }
+        // 先记下再发送，发送失败时不会每次运行命令都重试
+        state.save_to(&path)?;
+        for err in deliver(&self.webhooks, &payloads) {
+            print_warning(&err.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 命令执行时可以访问的状态：数据文件（保存时运行钩子脚本和 Webhook）、配置和已加载的待办列表
+///
+/// 命令行、交互模式（`todo shell`）和 REST API 都通过它执行命令，输出设置在创建时应用。
+pub struct Context {
+    pub(crate) data_file: DataFile,
+    pub(crate) config: Config,
+    pub(crate) todo_list: TodoList,
+}
+
+/// 命令处理器，每个子命令的参数类型各实现一个
+///
+/// 除了 `handle`，其余方法描述 [`Context::dispatch`] 在执行命令前后要做的事，默认适用于大多数命令。
+pub trait CommandHandler {
+    /// 命令是否需要读取数据文件（补全脚本和 man page 不需要）
+    fn reads_data(&self) -> bool {
+        true
+    }
+
+    /// 是否在演示用的临时目录中运行
+    fn uses_demo_data(&self) -> bool {
+        false
+    }
+
+    /// 执行前是否记下撤销点（撤销和重做本身不记）
+    fn records_undo(&self) -> bool {
+        true
+    }
+
+    /// 命令修改了数据之后是否可以顺便自动归档完成已久的任务
+    fn auto_archives(&self) -> bool {
+        true
+    }
+
+    /// 输出是否供程序读取，这时不输出提醒和提示
+    fn machine_readable(&self) -> bool {
+        false
+    }
+
+    /// 执行命令
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError>;
+}
+
+/// 主要运行逻辑
+pub fn run(cli: Cli) -> Result<(), TodoError> {
+    let mut ctx = Context::load(&cli)?;
+    ctx.dispatch(cli.command, command_label())
+}
+
+impl Context {
+    /// 应用配置和全局参数，加载数据文件
+    pub fn load(cli: &Cli) -> Result<Self, TodoError> {
+        load_context(cli)
+    }
+
+    /// 重新读取数据文件，使其他进程做的修改生效（REST API 处理每个请求之前调用）
+    #[cfg(feature = "server")]
+    pub(crate) fn reload(&mut self) -> Result<(), TodoError> {
+        self.todo_list = self.data_file.load()?;
+        Ok(())
+    }
+
+    /// 执行一个命令，`label` 是撤销记录中显示的命令
+    pub fn dispatch(&mut self, command: Commands, label: String) -> Result<(), TodoError> {
+        if !command.reads_data() {
+            return command.handle(self);
+        }
+
+        // 记下命令执行前的内容，命令修改了数据时可以撤销
+        if command.records_undo() {
+            self.todo_list.checkpoint(label);
+        }
+
+        // 严重过期提醒（输出供程序读取时跳过）
+        if self.config.alert_bell && !command.machine_readable() {
+            let config = &self.config;
+            print_critical_alert(&self.todo_list.critical_tasks(|task| config.critical_overdue(task)));
+        }
+
+        let auto_archives = command.auto_archives();
+        self.data_file.saved.set(false);
+        command.handle(self)?;
+
+        // 配置了自动归档时，命令修改了数据之后顺便归档完成已久的任务；
+        // 只读的命令不会改动数据文件，归档与命令属于同一条撤销记录
+        if let Some(days) = self.config.archive_after_days.filter(|_| auto_archives && self.data_file.saved.get()) {
+            let before = chrono::Utc::now() - chrono::Duration::days(days.into());
+            let archived = self.data_file.archive(&mut self.todo_list, before)?;
+            if !archived.is_empty() && !output_redirected() {
+                print_info(&format!(
+                    "Archived {} task(s) completed more than {} day(s) ago",
+                    archived.len(),
+                    days
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 撤销记录中显示的命令：从子命令开始的命令行参数，例如 `add 写周报 --priority high`
+fn command_label() -> String {
+    let command = Cli::command();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let start = args
+        .iter()
+        .position(|arg| command.find_subcommand(arg).is_some())
+        .unwrap_or(0);
+    args[start..].join(" ")
+}
+
+/// 标准输出是否被重定向到文件或管道（设置了 CLICOLOR_FORCE 时视为终端）
+fn output_redirected() -> bool {
+    use std::io::IsTerminal;
+
+    !io::stdout().is_terminal() && std::env::var_os("CLICOLOR_FORCE").is_none_or(|value| value == "0")
+}
+
+/// 应用配置和全局参数，加载数据文件
+fn load_context(cli: &Cli) -> Result<Context, TodoError> {
+    // 演示模式使用临时目录，其中只有重新生成的示例数据
+    let demo = cli.command.uses_demo_data();
+    if demo {
+        set_data_dir(demo::demo_dir());
+    } else if let Some(dir) = &cli.data_dir {
+        set_data_dir(dir.clone());
+    }
+
+    // 加载用户配置并应用显示设置（补全脚本和 man page 不读取配置）
+    let config = if cli.command.reads_data() { Config::load()? } else { Config::default() };
+    apply_palette(Palette {
+        theme: if cli.no_color { Theme::Monochrome } else { cli.theme.unwrap_or(config.theme) },
+        colors: config.colors.clone(),
+    });
+    // 纯文本只含 ASCII 符号，`| cat -A` 或其他程序处理时不会遇到 ✓ 和框线
+    let plain = cli.plain || output_redirected();
+    set_plain(plain);
+    set_ascii(cli.ascii || config.ascii || plain);
+    set_accessible(config.accessible);
+    set_locale(config.locale.unwrap_or_else(Locale::from_env));
+    if let Some(date_format) = &config.date_format {
+        set_date_format(date_format);
+    }
+    if let Some(time_format) = &config.time_format {
+        set_time_format(time_format);
+    }
+
+    // 数据文件：--file 指定的文件优先，--local 使用当前目录（旧版行为），
+    // 否则使用 TODO_FILE 环境变量、配置的路径或用户数据目录
+    let format = cli.format.or(config.format).unwrap_or_default();
+    let local_path = PathBuf::from(format.file_name());
+    let configured_path = config.data_file_path();
+    let path = if demo {
+        default_data_path(format)
+    } else if let Some(file) = &cli.data_file {
+        file.clone()
+    } else if cli.local {
+        local_path.clone()
+    } else if cli.data_dir.is_some() {
+        default_data_path(format)
+    } else {
+        resolve_data_path(format, configured_path.clone())
+    };
+    // 配置了压缩时使用 `.gz` 文件，--file 指定的文件按原样使用
+    let path = if config.compress && !demo && cli.data_file.is_none() {
+        compressed_data_path(path)?
+    } else {
+        path
+    };
+    let backup_count = if demo { 0 } else { config.backup_count.unwrap_or(backups::DEFAULT_BACKUP_COUNT) };
+    let data_file = DataFile {
+        journal: (config.journal && !demo && format == StorageFormat::Json)
+            .then(|| JournalStorage::new(&path).with_backups(backup_count)),
+        path,
+        format,
+        backup_count,
+        #[cfg(feature = "encryption")]
+        passphrase: Default::default(),
+        #[cfg(feature = "webhooks")]
+        webhooks: config.webhooks.clone(),
+        hooks: if demo { None } else { Hooks::default_dir().map(Hooks::new).filter(Hooks::any) },
+        history_len: Default::default(),
+        saved_tasks: Default::default(),
+        saved: Default::default(),
+    };
+
+    if !cli.command.reads_data() {
+        return Ok(Context { data_file, config, todo_list: TodoList::new() });
+    }
+
+    if demo {
+        save_to_file_with_format(&demo::sample_list(chrono::Utc::now()), &data_file.path, data_file.format)?;
+        print_info(&format!("Demo mode: using sample data in {}", data_file.path.display()));
+    }
+
+    // 提示从旧版位置迁移
+    if !demo
+        && cli.data_file.is_none()
+        && cli.data_dir.is_none()
+        && !cli.local
+        && configured_path.is_none()
+        && env_data_path().is_none()
+        && !file_exists(&data_file.path)
+        && file_exists(&local_path)
+    {
+        print_info(&format!(
+            "Found {} in the current directory. Use --local to keep using it, or move it to {}",
+            local_path.display(),
+            data_file.path.display()
+        ));
+    }
+
+    // 加载现有的待办列表
+    let todo_list = data_file.load()?;
+
+    Ok(Context { data_file, config, todo_list })
+}
+
+/// 对子命令的参数执行同一段代码，新增子命令时只需要在这里加一行
+macro_rules! with_args {
+    ($command:expr, $args:ident => $body:expr) => {
+        match $command {
+            Commands::Add($args) => $body,
+            Commands::List($args) => $body,
+            Commands::Complete($args) => $body,
+            Commands::Block($args) => $body,
+            Commands::Unblock($args) => $body,
+            Commands::Reopen($args) => $body,
+            Commands::Skip($args) => $body,
+            Commands::Postpone($args) => $body,
+            Commands::Prioritize($args) => $body,
+            Commands::Edit($args) => $body,
+            Commands::Delete($args) => $body,
+            Commands::Search($args) => $body,
+            Commands::Count($args) => $body,
+            Commands::Done($args) => $body,
+            Commands::Duplicate($args) => $body,
+            Commands::Restore($args) => $body,
+            Commands::Trash($args) => $body,
+            Commands::Show($args) => $body,
+            Commands::Pomodoro($args) => $body,
+            Commands::Clear($args) => $body,
+            Commands::Gc($args) => $body,
+            Commands::Reset($args) => $body,
+            Commands::Undo($args) => $body,
+            Commands::Redo($args) => $body,
+            Commands::Focus($args) => $body,
+            Commands::Triage($args) => $body,
+            Commands::Doctor($args) => $body,
+            Commands::Agenda($args) => $body,
+            Commands::Board($args) => $body,
+            Commands::Calendar($args) => $body,
+            Commands::Stats($args) => $body,
+            Commands::Report($args) => $body,
+            Commands::Import($args) => $body,
+            Commands::Merge($args) => $body,
+            Commands::Demo($args) => $body,
+            Commands::Shell($args) => $body,
+            Commands::Completions($args) => $body,
+            Commands::Man($args) => $body,
+            Commands::Ids($args) => $body,
+            Commands::Export($args) => $body,
+            Commands::Goal($args) => $body,
+            Commands::Template($args) => $body,
+            Commands::Backup($args) => $body,
+            Commands::Convert($args) => $body,
+            #[cfg(feature = "encryption")]
+            Commands::Encrypt($args) => $body,
+            #[cfg(feature = "encryption")]
+            Commands::Decrypt($args) => $body,
+            #[cfg(feature = "notify")]
+            Commands::Notify($args) => $body,
+            #[cfg(feature = "server")]
+            Commands::Serve($args) => $body,
+            Commands::Sync($args) => $body,
+        }
+    };
+}
+
+impl CommandHandler for Commands {
+    fn reads_data(&self) -> bool {
+        with_args!(self, args => args.reads_data())
+    }
+
+    fn uses_demo_data(&self) -> bool {
+        with_args!(self, args => args.uses_demo_data())
+    }
+
+    fn records_undo(&self) -> bool {
+        with_args!(self, args => args.records_undo())
+    }
+
+    fn auto_archives(&self) -> bool {
+        with_args!(self, args => args.auto_archives())
+    }
+
+    fn machine_readable(&self) -> bool {
+        with_args!(self, args => args.machine_readable())
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        // 根据命令交给对应的处理器
+        with_args!(self, args => args.handle(ctx))
+    }
+}
+
+impl CommandHandler for AddArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_add(&ctx.data_file, &ctx.config, &mut ctx.todo_list, self)
+    }
+}
+
+impl CommandHandler for ListArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let ListArgs {
+            status,
+            priority,
+            due_before,
+            due_after,
+            tag,
+            context,
+            query,
+            group_by,
+            sort,
+            limit,
+            offset,
+            hide_blocked,
+        } = self;
+
+        let filter = ListFilter {
+            status: status.or(ctx.config.default_list).unwrap_or_default(),
+            priority: priority.as_deref().map(parse_priority).transpose()?,
+            due_before: due_before.as_deref().map(parse_date).transpose()?.map(local_date),
+            due_after: due_after.as_deref().map(parse_date).transpose()?.map(local_date),
+            tag: tag.map(|tag| tag.trim().trim_start_matches('#').to_string()),
+            context: context.as_deref().map(normalize_context),
+            query: query.as_deref().map(str::parse).transpose()?,
+        };
+        let session = Session::load()?;
+        let focus = session.active_focus(chrono::Utc::now());
+        let view = ListView { hide_blocked, sort, offset, limit, group_by };
+        handle_list(&ctx.todo_list, &filter, &view, focus);
+        Ok(())
+    }
+}
+
+impl CommandHandler for CompleteArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let id = resolve_task_id(&ctx.todo_list, self.id, self.pattern.as_deref(), false)?;
+        handle_complete(&ctx.data_file, &mut ctx.todo_list, id, self.force)
+    }
+}
+
+impl CommandHandler for BlockArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_block(&ctx.data_file, &mut ctx.todo_list, self.id, &self.on)
+    }
+}
+
+impl CommandHandler for UnblockArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_unblock(&ctx.data_file, &mut ctx.todo_list, self.id, &self.on)
+    }
+}
+
+impl CommandHandler for ReopenArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_reopen(&ctx.data_file, &mut ctx.todo_list, self.id)
+    }
+}
+
+impl CommandHandler for SkipArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_skip(&ctx.data_file, &mut ctx.todo_list, self.id)
+    }
+}
+
+impl CommandHandler for PostponeArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_postpone(&ctx.data_file, &ctx.config, &mut ctx.todo_list, self.id, &self.to)
+    }
+}
+
+impl CommandHandler for PrioritizeArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_prioritize(&ctx.data_file, &mut ctx.todo_list, self.id, &self.priority)
+    }
+}
+
+impl CommandHandler for EditArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_edit(&ctx.data_file, &mut ctx.todo_list, self)
+    }
+}
+
+impl CommandHandler for DeleteArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let id = resolve_task_id(&ctx.todo_list, self.id, self.pattern.as_deref(), true)?;
+        handle_delete(&ctx.data_file, &mut ctx.todo_list, id, self.cascade, self.orphan)
+    }
+}
+
+impl CommandHandler for SearchArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_search(&ctx.todo_list, &self.pattern, self.regex)
+    }
+}
+
+impl CommandHandler for CountArgs {
+    fn machine_readable(&self) -> bool {
+        true
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let CountArgs { status, priority, tag, context, query } = self;
+
+        let filter = ListFilter {
+            status: status.unwrap_or_default(),
+            priority: priority.as_deref().map(parse_priority).transpose()?,
+            tag: tag.map(|tag| tag.trim().trim_start_matches('#').to_string()),
+            context: context.as_deref().map(normalize_context),
+            query: query.as_deref().map(str::parse).transpose()?,
+            ..ListFilter::default()
+        };
+        println!("{}", ctx.todo_list.filter_tasks(&filter).len());
+        Ok(())
+    }
+}
+
+impl CommandHandler for DoneArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_done(&ctx.todo_list, &self.since)
+    }
+}
+
+impl CommandHandler for DuplicateArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_duplicate(&ctx.data_file, &mut ctx.todo_list, self.id, self.due.as_deref())
+    }
+}
+
+impl CommandHandler for RestoreArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_restore(&ctx.data_file, &mut ctx.todo_list, self.id)
+    }
+}
+
+impl CommandHandler for TrashArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_trash(&ctx.data_file, &mut ctx.todo_list, self.action)
+    }
+}
+
+impl CommandHandler for ShowArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_show(&ctx.todo_list, self.id, self.live, self.history)
+    }
+}
+
+impl CommandHandler for PomodoroArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        #[cfg(feature = "notify")]
+        let notify = self.notify;
+        #[cfg(not(feature = "notify"))]
+        let notify = false;
+        handle_pomodoro(&ctx.data_file, &mut ctx.todo_list, self.id, self.minutes, notify)
+    }
+}
+
+impl CommandHandler for ClearArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_clear(&ctx.data_file, &mut ctx.todo_list, self.force)
+    }
+}
+
+impl CommandHandler for GcArgs {
+    fn auto_archives(&self) -> bool {
+        false
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_gc(&ctx.data_file, &ctx.config, &mut ctx.todo_list, self.older_than.as_deref(), self.dry_run)
+    }
+}
+
+impl CommandHandler for ResetArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_reset(&ctx.data_file, &mut ctx.todo_list, self.force)
+    }
+}
+
+impl CommandHandler for UndoArgs {
+    fn records_undo(&self) -> bool {
+        false
+    }
+
+    fn auto_archives(&self) -> bool {
+        false
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_undo(ctx)
+    }
+}
+
+impl CommandHandler for RedoArgs {
+    fn records_undo(&self) -> bool {
+        false
+    }
+
+    fn auto_archives(&self) -> bool {
+        false
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_redo(ctx)
+    }
+}
+
+/// 处理撤销
+fn handle_undo(ctx: &mut Context) -> Result<(), TodoError> {
+    match ctx.todo_list.undo() {
+        Some(label) => {
+            ctx.data_file.save(&ctx.todo_list)?;
+            print_success(&format!("Undid `{}`", label));
+        }
+        None => print_info("Nothing to undo"),
+    }
+    Ok(())
+}
+
+/// 处理重做
+fn handle_redo(ctx: &mut Context) -> Result<(), TodoError> {
+    match ctx.todo_list.redo() {
+        Some(label) => {
+            ctx.data_file.save(&ctx.todo_list)?;
+            print_success(&format!("Redid `{}`", label));
+        }
+        None => print_info("Nothing to redo"),
+    }
+    Ok(())
+}
+
+impl CommandHandler for TriageArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_triage(&ctx.data_file, &ctx.config, &mut ctx.todo_list)
+    }
+}
+
+impl CommandHandler for FocusArgs {
+    fn handle(self, _ctx: &mut Context) -> Result<(), TodoError> {
+        handle_focus(self.tag, self.until.as_deref(), self.off)
+    }
+}
+
+impl CommandHandler for DoctorArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_doctor(&ctx.data_file, &mut ctx.todo_list, self.fix)
+    }
+}
+
+impl CommandHandler for AgendaArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let session = Session::load()?;
+        let focus = session.active_focus(chrono::Utc::now());
+        handle_agenda(&ctx.todo_list, self.range, focus);
+        Ok(())
+    }
+}
+
+impl CommandHandler for BoardArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let session = Session::load()?;
+        let focus = session.active_focus(chrono::Utc::now());
+        handle_board(&ctx.todo_list, self.width.unwrap_or_else(terminal_width), focus);
+        Ok(())
+    }
+}
+
+impl CommandHandler for CalendarArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_calendar(&ctx.todo_list, self.month.as_deref())
+    }
+}
+
+impl CommandHandler for StatsArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_stats(&ctx.config, &ctx.todo_list, self.json)
+    }
+}
+
+impl CommandHandler for ReportArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        if self.week {
+            handle_weekly_review(&ctx.todo_list, self.group_by, self.markdown);
+            Ok(())
+        } else {
+            handle_report(&ctx.todo_list, &self.since, self.markdown)
+        }
+    }
+}
+
+impl CommandHandler for ImportArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        match self.source {
+            ImportSource::Bundle { file } => {
+                ctx.data_file.compact(&ctx.todo_list)?;
+                handle_import_bundle(&ctx.data_file, &file, self.dry_run)
+            }
+            source => handle_import(&ctx.data_file, &mut ctx.todo_list, source, self.dry_run),
+        }
+    }
+}
+
+impl CommandHandler for MergeArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_merge(&ctx.data_file, &mut ctx.todo_list, &self.file, self.base.as_deref())
+    }
+}
+
+impl CommandHandler for DemoArgs {
+    fn uses_demo_data(&self) -> bool {
+        true
+    }
+
+    /// 在示例数据上运行指定的命令，不指定时列出所有任务
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        if self.args.is_empty() {
+            handle_list(&ctx.todo_list, &ListFilter::default(), &ListView::default(), None);
+            return Ok(());
+        }
+
+        let demo_cli = Cli::try_parse_from(std::iter::once("todo".to_string()).chain(self.args))
+            .unwrap_or_else(|err| err.exit());
+        demo_cli.command.handle(ctx)
+    }
+}
+
+impl CommandHandler for ShellArgs {
+    fn records_undo(&self) -> bool {
+        false
+    }
+
+    fn auto_archives(&self) -> bool {
+        false
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_shell(ctx, io::stdin().lock())
+    }
+}
+
+/// 交互模式：逐行读取子命令，与命令行使用同一个 [`Context`] 执行，每个命令各有一条撤销记录
+///
+/// 全局参数（例如 `--file`）在启动时已经生效，行内指定的会被忽略。
+fn handle_shell(ctx: &mut Context, mut input: impl io::BufRead) -> Result<(), TodoError> {
+    print_info("Type a command such as `add Buy milk` or `list`, `help` for all commands, `exit` to quit");
+    loop {
+        print!("todo> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
+        let words = match split_command_line(&line) {
+            Ok(words) => words,
+            Err(message) => {
+                print_error(&message);
+                continue;
+            }
+        };
+
+        match words.first().map(String::as_str) {
+            None => continue,
+            Some("exit" | "quit") => return Ok(()),
+            Some("shell") => {
+                print_error("Already in the shell");
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        // clap 的帮助和用法错误直接显示，不退出交互模式
+        let cli = match Cli::try_parse_from(std::iter::once("todo".to_string()).chain(words)) {
+            Ok(cli) => cli,
+            Err(err) => {
+                let _ = err.print();
+                continue;
+            }
+        };
+        // 演示模式要换用临时目录，只能在启动时选择
+        if cli.command.uses_demo_data() {
+            print_error("Run `todo demo` outside the shell");
+            continue;
+        }
+        if let Err(err) = ctx.dispatch(cli.command, line.trim().to_string()) {
+            print_error(&err.to_string());
+        }
+    }
+}
+
+/// 按空白拆分一行命令，单引号和双引号内的空白不拆分，反斜杠转义下一个字符
+fn split_command_line(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (Some('\''), c) => word.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let escaped = chars.next().ok_or("Unfinished escape at the end of the line")?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (_, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return Err("Unclosed quote".to_string());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+impl CommandHandler for CompletionsArgs {
+    fn reads_data(&self) -> bool {
+        false
+    }
+
+    fn handle(self, _ctx: &mut Context) -> Result<(), TodoError> {
+        print_completions(self.shell);
+        Ok(())
+    }
+}
+
+impl CommandHandler for ManArgs {
+    fn reads_data(&self) -> bool {
+        false
+    }
+
+    fn handle(self, _ctx: &mut Context) -> Result<(), TodoError> {
+        handle_man(self.out_dir.as_deref())
+    }
+}
+
+impl CommandHandler for IdsArgs {
+    fn machine_readable(&self) -> bool {
+        true
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_ids(ctx)
+    }
+}
+
+/// 列出未完成任务的 ID 和标题，每行一个，供补全脚本使用
+fn handle_ids(ctx: &mut Context) -> Result<(), TodoError> {
+    for task in ctx.todo_list.list_pending_tasks() {
+        println!("{}\t{}", task.id, task.title);
+    }
+    Ok(())
+}
+
+impl CommandHandler for ExportArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        match self.bundle {
+            Some(bundle) => handle_export_bundle(&ctx.todo_list, &bundle),
+            None if self.html => handle_export_html(&ctx.todo_list, self.output.as_deref()),
+            None => handle_export_anonymized(&ctx.todo_list, self.output.as_deref()),
+        }
+    }
+}
+
+impl CommandHandler for GoalArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_goal(&ctx.data_file, &mut ctx.todo_list, self.action)
+    }
+}
+
+impl CommandHandler for TemplateArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_template(&ctx.todo_list, self.action)
+    }
+}
+
+impl CommandHandler for BackupArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_backup(&ctx.data_file, &ctx.todo_list, self.action)
+    }
+}
+
+impl CommandHandler for ConvertArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_convert(&ctx.data_file, &ctx.todo_list, self.to)
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl CommandHandler for EncryptArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_encrypt(&ctx.data_file, &ctx.todo_list)
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl CommandHandler for DecryptArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_decrypt(&ctx.data_file, &ctx.todo_list)
+    }
+}
+
+#[cfg(feature = "notify")]
+impl CommandHandler for NotifyArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        let interval = self.daemon.then_some(self.interval.as_str());
+        handle_notify(&ctx.data_file, &ctx.todo_list, &self.within, interval)
+    }
+}
+
+#[cfg(feature = "server")]
+impl CommandHandler for ServeArgs {
+    fn auto_archives(&self) -> bool {
+        false
+    }
+
+    fn records_undo(&self) -> bool {
+        false
+    }
+
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_serve(ctx, &self.host, self.port, self.token)
+    }
+}
+
+impl CommandHandler for SyncArgs {
+    fn handle(self, ctx: &mut Context) -> Result<(), TodoError> {
+        handle_sync(&ctx.data_file, &ctx.config, &mut ctx.todo_list, self.target)
+    }
+}
+
+/// 按任务 ID 补全的子命令（含别名）
+const ID_SUBCOMMANDS: &str = "complete c delete d duplicate dup edit show s skip postpone prioritize pri";
+
+/// 输出 shell 补全脚本
+///
+/// bash 和 fish 额外补全任务 ID：在上面的子命令后按 Tab 时调用 `todo __ids` 列出未完成的任务。
+fn print_completions(shell: clap_complete::Shell) {
+    use clap_complete::Shell;
+
+    let mut stdout = io::stdout();
+    clap_complete::generate(shell, &mut Cli::command(), "todo", &mut stdout);
+
+    match shell {
+        Shell::Bash => {
+            let subcommands = ID_SUBCOMMANDS.replace(' ', "|");
+            println!(
+                r#"
+_todo_with_ids() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    if [[ $COMP_CWORD -ge 2 ]]; then
+        case "${{COMP_WORDS[COMP_CWORD-1]}}" in
+            {subcommands})
+                COMPREPLY=( $(compgen -W "$(todo __ids 2>/dev/null | cut -f1)" -- "$cur") )
+                return 0
+                ;;
+        esac
+    fi
+    _todo "$@"
+}}
+complete -F _todo_with_ids -o bashdefault -o default todo"#
+            );
+        }
+        Shell::Fish => {
+            println!(
+                "complete -c todo -n \"__fish_seen_subcommand_from {}\" -f -a \"(todo __ids 2>/dev/null)\"",
+                ID_SUBCOMMANDS
+            );
+        }
+        _ => {}
+    }
+}
+
+/// 生成 man page：指定目录时为每个子命令各写一个文件，否则把主页面输出到标准输出
+fn handle_man(out_dir: Option<&Path>) -> Result<(), TodoError> {
+    let command = Cli::command();
+
+    match out_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            clap_mangen::generate_to(command, dir)?;
+            print_success(&format!("Man pages written to {}", dir.display()));
+        }
+        None => clap_mangen::Man::new(command).render(&mut io::stdout())?,
+    }
+
+    Ok(())
+}
+
+/// 确定命令要操作的任务：直接给出的 ID，或 `--match` 的标题片段唯一匹配的任务
+fn resolve_task_id(
+    todo_list: &TodoList,
+    id: Option<u32>,
+    pattern: Option<&str>,
+    include_completed: bool,
+) -> Result<u32, TodoError> {
+    match id {
+        Some(id) => Ok(id),
+        None => Ok(todo_list.match_task(pattern.unwrap_or_default(), include_completed)?.id),
+    }
+}
+
+/// 处理添加任务
+fn handle_add(
+    data_file: &DataFile,
+    config: &Config,
+    todo_list: &mut TodoList,
+    args: AddArgs,
+) -> Result<(), TodoError> {
+    let ids = add_tasks(data_file, config, todo_list, args)?;
+
+    // 显示成功消息
+    match ids[..] {
+        [] => {}
+        [id] => print_success(&format!(
+            "Task added successfully!  (ID: {})",
+            id
+        )),
+        _ => {
+            let id_list: Vec<String> = ids.iter().map(u32::to_string).collect();
+            print_success(&format!("{} tasks added successfully!  (IDs: {})", ids.len(), id_list.join(", ")));
+        }
+    }
+
+    // 显示任务详情，批量添加时列成一张表格
+    if let [id] = ids[..] {
+        if let Some(task) = todo_list.find_task(id) {
+            print_blank_line();
+            print_task_detail(todo_list, task);
+        }
+    } else if !ids.is_empty() {
+        print_blank_line();
+        let tasks: Vec<&Task> = ids.iter().filter_map(|&id| todo_list.find_task(id)).collect();
+        print_task_rows(todo_list, &tasks);
+    }
+
+    Ok(())
+}
+
+/// 添加任务并保存，返回新任务的 ID（REST API 新建任务时也使用它）
+pub(crate) fn add_tasks(
+    data_file: &DataFile,
+    config: &Config,
+    todo_list: &mut TodoList,
+    args: AddArgs,
+) -> Result<Vec<u32>, TodoError> {
+    let AddArgs {
+        title,
+        stdin,
+        priority: priority_str,
+        due: due_str,
+        tags,
+        context,
+        parent,
+        inherit_due,
+        estimate,
+        every,
+        goal,
+        template,
+    } = args;
+
+    let titles = if stdin {
+        read_titles(io::stdin().lock())?
+    } else {
+        title.into_iter().map(|title| title.trim().to_string()).collect()
+    };
+    if titles.is_empty() {
+        print_info("No task titles on stdin, nothing added");
+        return Ok(Vec::new());
+    }
+    if titles.iter().any(String::is_empty) {
+        return Err(TodoError::Custom("Task title must not be empty".to_string()));
+    }
+
+    // 先检查父任务和目标是否存在，避免任务已添加后才报错
+    if let Some(parent_id) = parent {
+        todo_list.find_task(parent_id).ok_or(TodoError::TaskNotFound(parent_id))?;
+    }
+    if let Some(goal_id) = goal {
+        todo_list.find_goal(goal_id).ok_or(TodoError::GoalNotFound(goal_id))?;
+    }
+
+    // 模板填充命令行中没有指定的字段
+    let template = match &template {
+        Some(name) => Some(Templates::load()?.get(name)?.clone()),
+        None => None,
+    };
+
+    let mut tags = normalize_tags(tags);
+    if let Some(template) = &template {
+        for tag in template.tags.iter().rev() {
+            if !tags.contains(tag) {
+                tags.insert(0, tag.clone());
+            }
+        }
+    }
+    let priority_str = priority_str.or_else(|| template.as_ref().map(|template| template.priority.to_string()));
+    let context = context.or_else(|| template.as_ref().and_then(|template| template.context.clone()));
+
+    // 未指定的字段使用标签配置的默认值
+    let tag_defaults = config.tag_defaults(&tags);
+    let mut applied_defaults = Vec::new();
+    let default_priority = tag_defaults.and_then(|(_, defaults)| defaults.priority.clone());
+    let priority_str = match (priority_str, default_priority) {
+        (Some(priority), _) => priority,
+        (None, Some(priority)) => {
+            applied_defaults.push("priority");
+            priority
+        }
+        (None, None) => config
+            .default_priority
+            .clone()
+            .unwrap_or_else(|| "medium".to_string()),
+    };
+    let default_context = tag_defaults.and_then(|(_, defaults)| defaults.context.clone());
+    let context = match (context, default_context) {
+        (Some(context), _) => Some(context),
+        (None, Some(context)) => {
+            applied_defaults.push("context");
+            Some(context)
+        }
+        (None, None) => None,
+    };
+
+    // 解析优先级
+    let priority = parse_priority(&priority_str)?;
+
+    // 解析预估耗时
+    let estimate_minutes = estimate
+        .as_deref()
+        .map(parse_estimate)
+        .transpose()?
+        .or_else(|| template.as_ref().and_then(|template| template.estimate_minutes));
+
+    // 解析重复周期
+    let recurrence = every
+        .map(|every| {
+            Recurrence::from_str(&every).map_err(|reason| {
+                if every.contains('=') {
+                    TodoError::InvalidRRule(reason)
+                } else {
+                    TodoError::InvalidRecurrence(every)
+                }
+            })
+        })
+        .transpose()?
+        .or_else(|| template.as_ref().and_then(|template| template.recurrence));
+
+    // 解析截止日期
+    let mut due_date = if let Some(date_str) = due_str {
+        Some(parse_date(&date_str)?)
+    } else {
+        template.as_ref().and_then(|template| template.due_date(chrono::Utc::now()))
+    };
+
+    // 子任务可以沿用父任务的截止日期
+    let parent_due = parent
+        .and_then(|parent_id| todo_list.find_task(parent_id))
+        .and_then(|task| task.due_date);
+    if inherit_due {
+        due_date = parent_due;
+    }
+
+    let context = context.as_deref().map(normalize_context).filter(|c| !c.is_empty());
+
+    // 任何一个任务添加失败时，已添加的任务也一并撤销
+    let ids = todo_list.transaction(|txn| {
+        let mut ids = Vec::with_capacity(titles.len());
+        for title in titles {
+            // 一次构建好所有字段，历史记录中只有一条新建，没有随后的修改
+            let mut task = Task::new(0, title, priority, due_date);
+            task.tags = tags.clone();
+            task.context = context.clone();
+            task.recurrence = recurrence;
+            task.estimate_minutes = estimate_minutes;
+            task.goal_id = goal;
+            task.parent_id = parent;
+            let id = txn.insert_task(task);
+            ids.push(id);
+            if let Some((tag, _)) = tag_defaults.filter(|_| !applied_defaults.is_empty()) {
+                txn.record_defaults_applied(id, tag, &applied_defaults);
+            }
+
+            // 模板的清单逐项添加为子任务
+            if let Some(template) = &template {
+                for item in &template.checklist {
+                    txn.add_subtask(id, item.clone(), priority, due_date)?;
+                }
+            }
+        }
+        Ok(ids)
+    })?;
+
+    if let Some(due) = due_date {
+        warn_if_over_capacity(config, todo_list, due);
+    }
+
+    if let (Some(due), Some(parent_due)) = (due_date, parent_due) {
+        if due > parent_due {
+            print_warning(&format!(
+                "Subtask is due after its parent (parent due {})",
+                format_date(&parent_due)
+            ));
+        }
+    }
+
+    // 保存到文件（批量添加时也只保存一次）
+    data_file.save(todo_list)?;
+
+    if let Some((tag, _)) = tag_defaults.filter(|_| !applied_defaults.is_empty()) {
+        print_info(&format!(
+            "Applied defaults from #{}: {}",
+            tag,
+            applied_defaults.join(", ")
+        ));
+    }
+
+    Ok(ids)
+}
+
+/// 读取任务标题，每行一个，去掉两端空白并忽略空行
+fn read_titles(reader: impl io::BufRead) -> io::Result<Vec<String>> {
+    let mut titles = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let title = line.trim();
+        if !title.is_empty() {
+            titles.push(title.to_string());
+        }
+    }
+    Ok(titles)
+}
+
+/// `todo list` 的显示选项：隐藏被阻塞的任务、排序、分页和分组
+#[derive(Default)]
+struct ListView {
+    hide_blocked: bool,
+    sort: SortKey,
+    offset: usize,
+    limit: Option<usize>,
+    group_by: Option<GroupBy>,
+}
+
+/// 处理列出任务
+fn handle_list(todo_list: &TodoList, filter: &ListFilter, view: &ListView, focus: Option<&Focus>) {
+    let title = match filter.status {
+        StatusFilter::All => "📋 All Tasks",
+        StatusFilter::Pending => "⏳ Pending Tasks",
+        StatusFilter::Completed => "✅ Completed Tasks",
+        StatusFilter::Overdue => "⚠️  Overdue Tasks",
+        StatusFilter::Today => "📅 Due Today",
+        StatusFilter::Week => "📅 Due This Week",
+    };
+    let mut tasks = todo_list.filter_tasks(filter);
+
+    // 专注模式下只显示匹配的任务
+    if let Some(focus) = focus {
+        tasks.retain(|task| focus.matches(task));
+    }
+    if view.hide_blocked {
+        tasks.retain(|task| !todo_list.is_blocked(task.id));
+    }
+
+    view.sort.sort(&mut tasks);
+    let total = tasks.len();
+    let tasks: Vec<&Task> = tasks
+        .into_iter()
+        .skip(view.offset)
+        .take(view.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    match view.group_by {
+        Some(group_by) => print_grouped_tasks(todo_list, &tasks, title, group_by),
+        None => print_tasks(todo_list, &tasks, title),
+    }
+    if !tasks.is_empty() && tasks.len() < total {
+        print_info(&format!(
+            "Showing tasks {}-{} of {}",
+            view.offset + 1,
+            view.offset + tasks.len(),
+            total
+        ));
+    }
+
+    if let Some(focus) = focus {
+        print_info(&format!("{} (`todo focus --off` to show everything)", describe_focus(focus)));
+    }
+
+    // 显示统计信息
+    println!();
+    print_summary(&stats::compute_now(todo_list));
+}
+
+/// 去掉标签开头的 `#` 和两端空白，忽略空标签
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    tags.into_iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// 去掉情境开头的 `@` 和两端空白
+fn normalize_context(context: &str) -> String {
+    context.trim().trim_start_matches('@').to_string()
+}
+
+/// 处理专注模式
+fn handle_focus(tag: Option<String>, until: Option<&str>, off: bool) -> Result<(), TodoError> {
+    let mut session = Session::load()?;
+
+    if off {
+        session.focus = None;
+        session.save()?;
+        print_success("Focus mode off");
+        return Ok(());
+    }
+
+    let Some(tag) = tag else {
+        match session.active_focus(chrono::Utc::now()) {
+            Some(focus) => print_info(&describe_focus(focus)),
+            None => print_info("Focus mode is off"),
+        }
+        return Ok(());
+    };
+
+    let focus = Focus {
+        tag: tag.trim_start_matches('#').to_string(),
+        until: until.map(parse_until).transpose()?,
+    };
+    print_success(&describe_focus(&focus));
+    session.focus = Some(focus);
+    session.save()?;
+
+    Ok(())
+}
+
+/// 描述专注模式，例如 `Focusing on #deepwork until 17:00`
+fn describe_focus(focus: &Focus) -> String {
+    match focus.until {
+        Some(until) => format!(
+            "Focusing on #{} until {}",
+            focus.tag,
+            until.with_timezone(&chrono::Local).format("%H:%M")
+        ),
+        None => format!("Focusing on #{}", focus.tag),
+    }
+}
+
+/// 解析专注结束时间 HH:MM（本地时间），今天已过时取明天的同一时间
+fn parse_until(until_str: &str) -> Result<chrono::DateTime<chrono::Utc>, TodoError> {
+    use chrono::{Local, NaiveTime};
+
+    let invalid = || TodoError::InvalidTime(until_str.to_string());
+    let time = NaiveTime::parse_from_str(until_str.trim(), "%H:%M").map_err(|_| invalid())?;
+
+    let now = Local::now();
+    let mut date = now.date_naive();
+    if time <= now.time() {
+        date = date.succ_opt().ok_or_else(invalid)?;
+    }
+
+    date.and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|until| until.with_timezone(&chrono::Utc))
+        .ok_or_else(invalid)
+}
+
+/// 处理重新打开任务
+fn handle_reopen(data_file: &DataFile, todo_list: &mut TodoList, id: u32) -> Result<(), TodoError> {
+    if todo_list.find_task(id).is_some_and(|task| !task.completed) {
+        print_info(&format!("Task {} is not completed", id));
+        return Ok(());
+    }
+
+    todo_list.reopen_task(id)?;
+    data_file.save(todo_list)?;
+
+    print_success(&format!("Task {} reopened", id));
+    if let Some(task) = todo_list.find_task(id) {
+        print_blank_line();
+        print_task(todo_list, task);
+    }
+    Ok(())
+}
+
+/// 处理完成任务
+fn handle_complete(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    id: u32,
+    force: bool,
+) -> Result<(), TodoError> {
+    // 检查任务是否已经完成
+    if let Some(task) = todo_list.find_task(id) {
+        if task.completed {
+            print_info(&format!("Task {} is already completed", id));
+            return Ok(());
+        }
+    }
+
+    // 依赖的任务未完成时拒绝，除非指定 --force
+    let blockers: Vec<u32> = todo_list.blockers(id).iter().map(|task| task.id).collect();
+    if !blockers.is_empty() {
+        if !force {
+            return Err(TodoError::TaskBlocked(id, blockers));
+        }
+        print_warning(&format!("Task {} still has unfinished dependencies", id));
+    }
+
+    // 完成任务
+    let next_id = todo_list.complete_task(id)?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!("Task {} marked as completed!", id));
+
+    // 显示更新后的任务
+    if let Some(task) = todo_list.find_task(id) {
+        print_blank_line();
+        print_task(todo_list, task);
+    }
+
+    // 循环任务显示下一次实例
+    if let Some(task) = next_id.and_then(|next_id| todo_list.find_task(next_id)) {
+        print_info("Next occurrence created:");
+        print_task(todo_list, task);
+    }
+
+    Ok(())
+}
+
+/// 处理设置任务依赖
+fn handle_block(data_file: &DataFile, todo_list: &mut TodoList, id: u32, on: &[u32]) -> Result<(), TodoError> {
+    todo_list.transaction(|txn| on.iter().try_for_each(|&dependency| txn.add_dependency(id, dependency)))?;
+
+    data_file.save(todo_list)?;
+
+    let ids: Vec<String> = on.iter().map(u32::to_string).collect();
+    print_success(&format!("Task {} now depends on {}", id, ids.join(", ")));
+    if let Some(task) = todo_list.find_task(id) {
+        print_task_detail(todo_list, task);
+    }
+
+    Ok(())
+}
+
+/// 处理移除任务依赖
+fn handle_unblock(data_file: &DataFile, todo_list: &mut TodoList, id: u32, on: &[u32]) -> Result<(), TodoError> {
+    let mut removed = Vec::new();
+    for &dependency in on {
+        if todo_list.remove_dependency(id, dependency)? {
+            removed.push(dependency.to_string());
+        }
+    }
+
+    if removed.is_empty() {
+        print_info(&format!("Task {} has no such dependencies", id));
+        return Ok(());
+    }
+
+    data_file.save(todo_list)?;
+    print_success(&format!("Task {} no longer depends on {}", id, removed.join(", ")));
+
+    Ok(())
+}
+
+/// 处理跳过循环任务的当前周期
+fn handle_skip(data_file: &DataFile, todo_list: &mut TodoList, id: u32) -> Result<(), TodoError> {
+    let next_due = todo_list.skip_occurrence(id)?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!(
+        "Skipped this occurrence of task {}. Next due: {}",
+        id,
+        format_date(&next_due)
+    ));
+
+    Ok(())
+}
+
+/// 处理推迟任务
+fn handle_postpone(
+    data_file: &DataFile,
+    config: &Config,
+    todo_list: &mut TodoList,
+    id: u32,
+    to: &str,
+) -> Result<(), TodoError> {
+    let current = todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?.due_date;
+    let new_due = parse_postpone(current, to)?;
+    todo_list.set_due_date(id, Some(new_due))?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    let from = current.map_or("none".to_string(), |due| format_date(&due));
+    print_success(&format!(
+        "Task {} postponed: {} → {}",
+        id,
+        from,
+        format_date(&new_due)
+    ));
+    warn_if_over_capacity(config, todo_list, new_due);
+
+    Ok(())
+}
+
+/// 解析推迟目标：日期 YYYY-MM-DD，或 `+Nd` / `+Nw`（相对原截止日期，没有时相对今天结束）
+fn parse_postpone(
+    current: Option<chrono::DateTime<chrono::Utc>>,
+    to: &str,
+) -> Result<chrono::DateTime<chrono::Utc>, TodoError> {
+    use chrono::Duration;
+
+    let invalid = || TodoError::InvalidPostpone(to.to_string());
+    let input = to.trim().to_lowercase();
+
+    let Some(shift) = input.strip_prefix('+') else {
+        return parse_date(&input).map_err(|_| invalid());
+    };
+
+    let unit = shift.chars().last().ok_or_else(invalid)?;
+    let amount = shift[..shift.len() - unit.len_utf8()]
+        .parse::<u32>()
+        .map_err(|_| invalid())?;
+
+    let shift = match unit {
+        'd' => Duration::days(amount.into()),
+        'w' => Duration::weeks(amount.into()),
+        _ => return Err(invalid()),
+    };
+
+    let base = match current {
+        Some(due) => due,
+        None => end_of_day(today()),
+    };
+
+    Ok(base + shift)
+}
+
+/// 截止日期当天到期任务的预估耗时超过每日可用时间时给出警告
+fn warn_if_over_capacity(config: &Config, todo_list: &TodoList, due: chrono::DateTime<chrono::Utc>) {
+    let Some(capacity) = config.daily_capacity_minutes() else {
+        return;
+    };
+
+    let planned = todo_list.estimated_minutes_on(local_date(due));
+    if planned > capacity {
+        print_warning(&format!(
+            "{} {} now has {} of estimated work, over your daily capacity of {}",
+            to_local(due).format("%a"),
+            format_date(&due),
+            format_minutes(planned),
+            format_minutes(capacity)
+        ));
+    }
+}
+
+/// 处理修改优先级
+fn handle_prioritize(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    id: u32,
+    priority_str: &str,
+) -> Result<(), TodoError> {
+    let priority = parse_priority(priority_str)?;
+
+    let old_priority = todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?.priority;
+    todo_list.set_priority(id, priority)?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!(
+        "Task {} priority changed: {} → {}",
+        id, old_priority, priority
+    ));
+
+    Ok(())
+}
+
+/// 处理修改任务：先检查所有字段，全部有效后再一起修改
+fn handle_edit(data_file: &DataFile, todo_list: &mut TodoList, args: EditArgs) -> Result<(), TodoError> {
+    let EditArgs { id, title, priority, due, no_due, tags, no_tags, context, no_context } = args;
+    todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+
+    let title = title.map(|title| title.trim().to_string());
+    if title.as_deref().is_some_and(str::is_empty) {
+        return Err(TodoError::Custom("Task title must not be empty".to_string()));
+    }
+    let priority = priority.as_deref().map(parse_priority).transpose()?;
+    let due = match due {
+        Some(due) => Some(Some(parse_date(&due)?)),
+        None => no_due.then_some(None),
+    };
+    let tags = if no_tags { Some(Vec::new()) } else { tags.map(normalize_tags) };
+    let context = match context {
+        Some(context) => Some(Some(normalize_context(&context)).filter(|context| !context.is_empty())),
+        None => no_context.then_some(None),
+    };
+
+    todo_list.transaction(|txn| {
+        if let Some(title) = title {
+            txn.set_title(id, title)?;
+        }
+        if let Some(priority) = priority {
+            txn.set_priority(id, priority)?;
+        }
+        if let Some(due) = due {
+            txn.set_due_date(id, due)?;
+        }
+        if let Some(tags) = tags {
+            txn.set_tags(id, tags)?;
+        }
+        if let Some(context) = context {
+            txn.set_context(id, context)?;
+        }
+        Ok(())
+    })?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!("Task {} updated", id));
+    if let Some(task) = todo_list.find_task(id) {
+        print_blank_line();
+        print_task_detail(todo_list, task);
+    }
+
+    Ok(())
+}
+
+/// 处理删除任务
+fn handle_delete(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    id: u32,
+    cascade: bool,
+    orphan: bool,
+) -> Result<(), TodoError> {
+    // 先获取任务信息用于显示
+    let task_title = todo_list
+        .find_task(id)
+        .map(|t| t.title.clone())
+        .ok_or(TodoError::TaskNotFound(id))?;
+
+    // 确定子任务的处理方式，没有指定标志时询问用户
+    let policy = if cascade {
+        DeletePolicy::Cascade
+    } else if orphan {
+        DeletePolicy::Orphan
+    } else {
+        let child_count = todo_list.children(id).len();
+        if child_count == 0 {
+            DeletePolicy::Abort
+        } else {
+            match prompt_delete_policy(id, child_count)? {
+                Some(policy) => policy,
+                None => {
+                    print_info("Operation cancelled");
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    // 删除任务（所有修改一次性完成）
+    let deleted = todo_list.delete_task_with_policy(id, policy)?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    if deleted.len() > 1 {
+        print_success(&format!(
+            "Task {} '{}' and {} subtask(s) deleted!",
+            id,
+            task_title,
+            deleted.len() - 1
+        ));
+    } else {
+        print_success(&format!("Task {} '{}' deleted!", id, task_title));
+    }
+    print_info(&format!("Moved to the trash. Undo with `todo restore {}`", id));
+
+    Ok(())
+}
+
+/// 询问如何处理被删除任务的子任务，返回 `None` 表示取消
+fn prompt_delete_policy(id: u32, child_count: usize) -> Result<Option<DeletePolicy>, TodoError> {
+    print_warning(&format!(
+        "Task {} has {} subtask(s). [c]ascade, [o]rphan or [A]bort? ",
+        id, child_count
+    ));
+    print!("> ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let policy = match input.trim().to_lowercase().as_str() {
+        "c" | "cascade" => Some(DeletePolicy::Cascade),
+        "o" | "orphan" => Some(DeletePolicy::Orphan),
+        _ => None,
+    };
+
+    Ok(policy)
+}
+
+/// 处理显示任务详情
+fn handle_show(todo_list: &TodoList, id: u32, live: bool, history: bool) -> Result<(), TodoError> {
+    let task = todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+
+    print_task_detail(todo_list, task);
+    if history {
+        print_task_history(&todo_list.task_history(id));
+    }
+
+    if live {
+        let Some(due) = task.due_date else {
+            print_info("Task has no due date, nothing to count down to");
+            return Ok(());
+        };
+
+        // 读屏软件无法跟踪原地刷新的内容，输出被重定向时也无法原地刷新，只输出一次
+        if is_accessible() {
+            println!("Time left: {}.", format_countdown(due - chrono::Utc::now()));
+            return Ok(());
+        }
+        if is_plain() {
+            println!("Time left: {}", format_countdown(due - chrono::Utc::now()));
+            return Ok(());
+        }
+
+        // 原地刷新，直到被 Ctrl+C 中断
+        loop {
+            let prefix = if is_ascii() { "Time left:" } else { "⏳" };
+            print!("\r\x1b[2K{} {}", prefix, format_countdown(due - chrono::Utc::now()));
+            io::stdout().flush()?;
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理番茄钟：倒计时结束后把这次番茄钟记录到任务上
+///
+/// 中途按 Ctrl+C 放弃时进程直接退出，不会记录。
+fn handle_pomodoro(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    id: u32,
+    minutes: u32,
+    notify: bool,
+) -> Result<(), TodoError> {
+    let task = todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+    print_info(&format!(
+        "Pomodoro started: {} on task {} ({}), press Ctrl+C to abandon",
+        format_minutes(minutes),
+        id,
+        task.title
+    ));
+
+    let started_at = chrono::Utc::now();
+    let end = started_at + chrono::Duration::minutes(i64::from(minutes));
+
+    // 读屏软件和重定向的输出无法跟踪原地刷新的内容，只在结束时输出
+    if is_accessible() || is_plain() {
+        if let Ok(remaining) = (end - chrono::Utc::now()).to_std() {
+            std::thread::sleep(remaining);
+        }
+    } else {
+        let prefix = if is_ascii() { "Time left:" } else { "🍅" };
+        loop {
+            let remaining = end - chrono::Utc::now();
+            if remaining <= chrono::Duration::zero() {
+                break;
+            }
+            print!("\r\x1b[2K{} {}", prefix, format_countdown(remaining));
+            io::stdout().flush()?;
+            std::thread::sleep(std::time::Duration::from_secs(1).min(remaining.to_std().unwrap_or_default()));
+        }
+        println!();
+    }
+
+    // 倒计时期间其他命令可能修改过数据文件，重新加载后再记录
+    *todo_list = data_file.load()?;
+    todo_list.log_pomodoro(id, Pomodoro { started_at, minutes })?;
+    data_file.save(todo_list)?;
+
+    let task = todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+    print_success(&format!(
+        "Pomodoro done! Logged {} on task {} ({} so far)",
+        format_minutes(minutes),
+        id,
+        task.pomodoros.len()
+    ));
+
+    if notify {
+        #[cfg(feature = "notify")]
+        crate::notify::send_pomodoro_done(task, minutes)?;
+    }
+
+    Ok(())
+}
+
+/// 处理清除已完成任务
+fn handle_clear(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    force: bool,
+) -> Result<(), TodoError> {
+    let completed_tasks = todo_list.list_completed_tasks();
+
+    if completed_tasks.is_empty() {
+        print_info("No completed tasks to clear");
+        return Ok(());
+    }
+
+    // 如果没有 force 标志，请求确认
+    if !force {
+        print_warning(&format!(
+            "About to delete {} completed task(s). Are you sure? (y/N): ",
+            completed_tasks.len()
+        ));
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+
+        if ! input.trim().eq_ignore_ascii_case("y") {
+            print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    // 收集要删除的任务 ID
+    let ids_to_delete: Vec<u32> = completed_tasks. iter().map(|t| t.id).collect();
+
+    // 删除所有已完成的任务，失败时一个也不删除
+    todo_list.transaction(|txn| ids_to_delete.iter().try_for_each(|&id| txn.delete_task(id)))?;
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!(
+        "Cleared {} completed task(s)!",
+        ids_to_delete. len()
+    ));
+
+    Ok(())
+}
+
+/// 处理整理收件箱
+fn handle_triage(
+    data_file: &DataFile,
+    config: &Config,
+    todo_list: &mut TodoList,
+) -> Result<(), TodoError> {
+    let inbox: Vec<u32> = triage::inbox(todo_list).iter().map(|t| t.id).collect();
+
+    if inbox.is_empty() {
+        print_info("Inbox is empty, nothing to triage");
+        return Ok(());
+    }
+
+    let mut accepted = 0;
+    for id in inbox {
+        let Some(task) = todo_list.find_task(id) else {
+            continue;
+        };
+
+        println!();
+        print_task(todo_list, task);
+
+        let Some(suggestion) = triage::suggest(todo_list, task) else {
+            print_info("No similar tasks found, skipping");
+            continue;
+        };
+
+        print_info(&format!("Suggested: {}", describe_suggestion(&suggestion)));
+        print!("Accept? [y]es / [n]o / [q]uit > ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => {
+                triage::apply(todo_list, id, &suggestion)?;
+                if let Some(due) = suggestion.due_date() {
+                    warn_if_over_capacity(config, todo_list, due);
+                }
+                accepted += 1;
+            }
+            "q" | "quit" => break,
+            _ => {}
+        }
+    }
+
+    if accepted > 0 {
+        // 保存到文件
+        data_file.save(todo_list)?;
+    }
+
+    println!();
+    print_success(&format!("Triaged {} task(s)!", accepted));
+
+    Ok(())
+}
+
+/// 描述整理建议，例如 `#work, priority High, due in 3 day(s) (based on 2, 5)`
+fn describe_suggestion(suggestion: &triage::Suggestion) -> String {
+    let mut parts = Vec::new();
+
+    if !suggestion.tags.is_empty() {
+        let tags: Vec<String> = suggestion.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        parts.push(tags.join(" "));
+    }
+    if let Some(priority) = suggestion.priority {
+        parts.push(format!("priority {}", priority));
+    }
+    if let Some(days) = suggestion.due_in_days {
+        parts.push(format!("due in {} day(s)", days));
+    }
+
+    let based_on: Vec<String> = suggestion.based_on.iter().map(|id| id.to_string()).collect();
+    format!("{} (based on {})", parts.join(", "), based_on.join(", "))
+}
+
+/// 处理数据检查
+fn handle_doctor(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    fix: bool,
+) -> Result<(), TodoError> {
+    let health = doctor::check_file(&data_file.path, data_file.format)?;
+    report_file_health(&data_file.path, &health);
+    let issues = doctor::check(todo_list);
+
+    if issues.is_empty() && health.is_healthy() {
+        print_success("No problems found!");
+        return Ok(());
+    }
+
+    for issue in &issues {
+        print_warning(&issue.to_string());
+    }
+
+    if fix {
+        let fixed = doctor::fix(todo_list, &issues);
+
+        // 保存到文件，同时用已加载的内容（数据文件损坏时是备份中的内容）替换数据文件
+        data_file.save(todo_list)?;
+
+        print_success(&format!("Fixed {} problem(s)!", fixed + usize::from(!health.is_healthy())));
+    } else {
+        print_info("Run 'todo doctor --fix' to fix these problems");
+    }
+
+    Ok(())
+}
+
+/// 显示数据文件的检查结果
+fn report_file_health(path: &Path, health: &doctor::FileHealth) {
+    let Some(size) = health.size else {
+        print_info(&format!("Data file {} does not exist yet", path.display()));
+        return;
+    };
+
+    match (&health.error, health.checksum_ok) {
+        (Some(error), _) => print_warning(&format!(
+            "Data file {} is damaged: {}",
+            path.display(),
+            error.trim_start_matches("❌ ")
+        )),
+        (None, Some(false)) => print_warning(&format!(
+            "Data file {} does not match its checksum; it was changed outside todo or is damaged",
+            path.display()
+        )),
+        (None, checksum_ok) => {
+            let verified = match (health.encrypted, checksum_ok) {
+                (true, _) => "encrypted",
+                (false, Some(true)) => "checksum verified",
+                (false, _) => "no checksum yet",
+            };
+            print_info(&format!("Data file {}: {} bytes, {}", path.display(), size, verified));
+        }
+    }
+
+    match &health.latest_valid_backup {
+        Some(backup) => print_info(&format!(
+            "{} backup(s), latest readable backup: {}",
+            health.backups, backup.timestamp
+        )),
+        None => print_info(&format!("{} backup(s), none readable", health.backups)),
+    }
+}
+
+/// 处理导入任务
+fn handle_import(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    source: ImportSource,
+    dry_run: bool,
+) -> Result<(), TodoError> {
+    let (report, source_name) = match source {
+        ImportSource::Taskwarrior { file } => {
+            let content = std::fs::read_to_string(&file)?;
+            (import_taskwarrior(todo_list, &content)?, "Taskwarrior")
+        }
+        ImportSource::Markdown { file } => {
+            let content = std::fs::read_to_string(&file)?;
+            (import_markdown(todo_list, &content)?, "Markdown")
+        }
+        ImportSource::Bundle { .. } => unreachable!("bundles are restored by handle_import_bundle"),
+    };
+
+    for (title, reason) in &report.skipped {
+        print_info(&format!("Skipped '{}': {}", title, reason));
+    }
+
+    let summary = format!(
+        "{} created, {} updated, {} skipped",
+        report.created.len(),
+        report.updated.len(),
+        report.skipped.len()
+    );
+
+    if dry_run {
+        print_warning(&format!(
+            "Dry run: {} from {}. The data file was not changed.",
+            summary, source_name
+        ));
+        return Ok(());
+    }
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!("Imported from {}: {}!", source_name, summary));
+
+    Ok(())
+}
+
+/// 处理合并另一份数据文件
+fn handle_merge(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    file: &Path,
+    base: Option<&Path>,
+) -> Result<(), TodoError> {
+    let read = |path: &Path| -> Result<TodoList, TodoError> {
+        parse_with_format(&std::fs::read(path)?, data_file.format)
+    };
+    let other = read(file)?;
+    let base = base.map(read).transpose()?;
+
+    let report = todo_list.merge(&other, base.as_ref());
+
+    // 保存到文件
+    data_file.save(todo_list)?;
+
+    print_success(&format!(
+        "Merged {}: {} added, {} updated, {} removed",
+        file.display(),
+        report.added.len(),
+        report.updated.len(),
+        report.removed.len()
+    ));
+
+    Ok(())
+}
+
+/// 压缩保存时的数据文件路径；还只有未压缩的数据文件时先把它压缩（原文件备份为 `.bak` 后删除）
+fn compressed_data_path(path: PathBuf) -> Result<PathBuf, TodoError> {
+    let compressed = gzip_path(&path);
+    if compressed == path || file_exists(&compressed) || !file_exists(&path) {
+        return Ok(compressed);
+    }
+
+    // 加密的文件识别不了压缩后的文件头，不自动压缩
+    #[cfg(feature = "encryption")]
+    if crate::storage::is_encrypted_file(&path)? {
+        print_warning(&format!("{} is encrypted and was not compressed", path.display()));
+        return Ok(path);
+    }
+
+    compress_file(&path, &compressed)?;
+    backup_existing(&path)?;
+    std::fs::remove_file(&path)?;
+    print_info(&format!("Compressed {} to {}", path.display(), compressed.display()));
+    Ok(compressed)
+}
+
+/// 处理数据文件格式转换：写入另一种格式的文件，原文件保留
+fn handle_convert(data_file: &DataFile, todo_list: &TodoList, to: StorageFormat) -> Result<(), TodoError> {
+    if to == data_file.format {
+        print_info(&format!("{} is already in {} format", data_file.path.display(), to.name()));
+        return Ok(());
+    }
+
+    // 默认文件名换成目标格式的默认文件名，其他文件名只换扩展名；压缩的文件转换后仍然压缩
+    let compressed = is_gzip_path(&data_file.path);
+    let path = if compressed { data_file.path.with_extension("") } else { data_file.path.clone() };
+    let is_default_name = path.file_name() == Some(data_file.format.file_name().as_ref());
+    let target = if is_default_name {
+        path.with_file_name(to.file_name())
+    } else {
+        path.with_extension(Path::new(to.file_name()).extension().unwrap_or_default())
+    };
+    let target = if compressed { gzip_path(target) } else { target };
+
+    backup_existing(&target)?;
+    let storage = FileStorage::new(&target, to);
+    #[cfg(feature = "encryption")]
+    let storage = storage.with_passphrase(data_file.passphrase.borrow().clone());
+    storage.save(todo_list)?;
+
+    print_success(&format!("Converted {} task(s) to {}: {}", todo_list.len(), to.name(), target.display()));
+    if to == StorageFormat::TodoTxt {
+        print_warning("todo.txt keeps only the fields it supports; history, goals and other details are not converted.");
+    }
+    print_info(&format!(
+        "Use it with `--format {0}`, or set `format = \"{0}\"` in the config file.",
+        to.name()
+    ));
+
+    Ok(())
+}
+
+/// 处理备份管理
+fn handle_backup(data_file: &DataFile, todo_list: &TodoList, action: BackupAction) -> Result<(), TodoError> {
+    match action {
+        BackupAction::List => {
+            let backups = backups::list(&data_file.path)?;
+
+            if backups.is_empty() {
+                print_info(&format!(
+                    "No backups in {}",
+                    backups::backup_dir(&data_file.path).display()
+                ));
+                return Ok(());
+            }
+
+            print_heading("💾 Backups (newest first)");
+            print_rule();
+            for backup in backups.iter().rev() {
+                println!("  {}  {}", paint(&backup.timestamp, Role::Accent), backup.path.display());
+            }
+        }
+        BackupAction::Restore { timestamp } => {
+            data_file.compact(todo_list)?;
+            let backup = backups::restore(&data_file.path, &timestamp, data_file.backup_count)?;
+            print_success(&format!(
+                "Restored {} from backup {}",
+                data_file.path.display(),
+                backup.timestamp
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 删除整个列表，之前先备份数据文件
+fn handle_reset(data_file: &DataFile, todo_list: &mut TodoList, force: bool) -> Result<(), TodoError> {
+    let (tasks, goals, trashed) = (todo_list.len(), todo_list.goals().len(), todo_list.trash().len());
+    if tasks == 0 && goals == 0 && trashed == 0 && todo_list.history().is_empty() {
+        print_info("The list is already empty");
+        return Ok(());
+    }
+
+    // 输入完整的单词才确认，避免随手按下 y
+    if !force {
+        print_warning(&format!(
+            "This permanently deletes {} task(s), {} goal(s), {} task(s) in the trash and all history.",
+            tasks, goals, trashed
+        ));
+        print!("Type 'reset' to confirm: ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if input.trim() != "reset" {
+            print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    // 保存时的自动备份会保留重置前的数据；关闭了自动备份时单独备份一份
+    data_file.compact(todo_list)?;
+    if data_file.backup_count == 0 {
+        backups::rotate(&data_file.path, 1)?;
+    }
+
+    todo_list.reset();
+    data_file.save(todo_list)?;
+
+    print_success(&format!("Deleted {} task(s) and {} goal(s)", tasks, goals));
+    if let Some(backup) = backups::list(&data_file.path)?.last() {
+        let undo = if data_file.journal().is_none() { "Undo with `todo undo`, or restore" } else { "Restore" };
+        print_info(&format!("{} the backup with `todo backup restore {}`", undo, backup.timestamp));
+    }
+    Ok(())
+}
+
+/// 把完成已久的任务移到归档文件
+fn handle_gc(
+    data_file: &DataFile,
+    config: &Config,
+    todo_list: &mut TodoList,
+    older_than: Option<&str>,
+    dry_run: bool,
+) -> Result<(), TodoError> {
+    let before = match older_than {
+        Some(age) => parse_since(age)?,
+        None => {
+            let days = config.archive_after_days.unwrap_or(archive::DEFAULT_ARCHIVE_AFTER_DAYS);
+            chrono::Utc::now() - chrono::Duration::days(days.into())
+        }
+    };
+    let archive_path = archive::archive_path(&data_file.path);
+
+    let tasks = if dry_run {
+        todo_list.clone().archive_completed(before)
+    } else {
+        data_file.archive(todo_list, before)?
+    };
+    if tasks.is_empty() {
+        print_info(&format!("No tasks completed before {} to archive", format_date(&before)));
+        return Ok(());
+    }
+
+    if dry_run {
+        print_tasks(todo_list, &tasks.iter().collect::<Vec<_>>(), "🗄  Would archive");
+        print_info(&format!("Run without --dry-run to move them to {}", archive_path.display()));
+    } else {
+        print_success(&format!("Archived {} task(s) to {}", tasks.len(), archive_path.display()));
+        print_info(&format!("Browse them with `todo --file {} list`", archive_path.display()));
+    }
+    Ok(())
+}
+
+/// 列出 `since` 之后完成的任务
+fn handle_done(todo_list: &TodoList, since: &str) -> Result<(), TodoError> {
+    let tasks = todo_list.completed_since(parse_since(since)?);
+    if tasks.is_empty() {
+        print_info(&format!("Nothing completed since {}", since));
+        return Ok(());
+    }
+
+    print_tasks(todo_list, &tasks, &format!("✅ Done since {}", since));
+    Ok(())
+}
+
+/// 按标题搜索任务
+fn handle_search(todo_list: &TodoList, pattern: &str, regex: bool) -> Result<(), TodoError> {
+    let tasks = todo_list.search(pattern, regex)?;
+    print_tasks(todo_list, &tasks, &format!("🔍 Search: {}", pattern));
+    Ok(())
+}
+
+/// 复制任务
+fn handle_duplicate(data_file: &DataFile, todo_list: &mut TodoList, id: u32, due: Option<&str>) -> Result<(), TodoError> {
+    let due_date = due.map(parse_date).transpose()?;
+    let copy = todo_list.duplicate_task(id, due_date)?;
+    data_file.save(todo_list)?;
+
+    let task = &todo_list[copy];
+    print_success(&format!("Task {} duplicated as task {}: '{}'", id, copy, task.title));
+    if let Some(due) = &task.due_date {
+        print_info(&format!("Due: {}", format_date(due)));
+    }
+    Ok(())
+}
+
+/// 从回收站恢复任务
+fn handle_restore(data_file: &DataFile, todo_list: &mut TodoList, id: u32) -> Result<(), TodoError> {
+    let restored = todo_list.restore_from_trash(id)?;
+    data_file.save(todo_list)?;
+
+    let title = &todo_list[restored].title;
+    if restored == id {
+        print_success(&format!("Task {} '{}' restored!", id, title));
+    } else {
+        print_success(&format!("Task {} '{}' restored as task {}!", id, title, restored));
+    }
+    Ok(())
+}
+
+/// 处理回收站操作
+fn handle_trash(data_file: &DataFile, todo_list: &mut TodoList, action: TrashAction) -> Result<(), TodoError> {
+    match action {
+        TrashAction::List => {
+            if todo_list.trash().is_empty() {
+                print_info("The trash is empty");
+                return Ok(());
+            }
+
+            print_heading("🗑  Trash (newest first)");
+            print_rule();
+            for trashed in todo_list.trash().iter().rev() {
+                println!(
+                    "  {}  {}  deleted {}",
+                    paint(&format!("[{}]", trashed.task.id), Role::Accent),
+                    trashed.task.title,
+                    format_datetime(&trashed.deleted_at)
+                );
+            }
+            print_info("Restore a task with `todo restore <id>`");
+        }
+        TrashAction::Empty { older_than } => {
+            let before = older_than.as_deref().map(parse_since).transpose()?;
+            let removed = todo_list.empty_trash(before);
+            if removed == 0 {
+                print_info("Nothing to remove from the trash");
+                return Ok(());
+            }
+
+            data_file.save(todo_list)?;
+            print_success(&format!("Permanently deleted {} task(s) from the trash", removed));
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理模板操作
+fn handle_template(todo_list: &TodoList, action: TemplateAction) -> Result<(), TodoError> {
+    let mut templates = Templates::load()?;
+
+    match action {
+        TemplateAction::Save { name, from } => {
+            let task = todo_list.find_task(from).ok_or(TodoError::TaskNotFound(from))?;
+            let replaced = templates.insert(name.clone(), Template::from_task(todo_list, task));
+            templates.save()?;
+
+            let verb = if replaced.is_some() { "updated" } else { "saved" };
+            print_success(&format!("Template '{}' {} from task {}", name, verb, from));
+            print_info(&format!("Use it with `todo add <title> --template {}`", name));
+        }
+        TemplateAction::List => {
+            if templates.iter().next().is_none() {
+                print_info("No templates yet. Save one with `todo template save <name> --from <id>`");
+                return Ok(());
+            }
+
+            print_heading("📐 Templates");
+            print_rule();
+            for (name, template) in templates.iter() {
+                println!("  {}  {}", paint(name, Role::Accent), describe_template(template));
+            }
+        }
+        TemplateAction::Delete { name } => {
+            templates.remove(&name)?;
+            templates.save()?;
+            print_success(&format!("Template '{}' deleted", name));
+        }
+    }
+
+    Ok(())
+}
+
+/// 模板内容的一行摘要，例如 `High, #release, due in 3 day(s), 2 checklist item(s)`
+fn describe_template(template: &Template) -> String {
+    let mut parts = vec![template.priority.to_string()];
+    parts.extend(template.tags.iter().map(|tag| format!("#{}", tag)));
+    if let Some(context) = &template.context {
+        parts.push(format!("@{}", context));
+    }
+    if let Some(minutes) = template.estimate_minutes {
+        parts.push(format_minutes(minutes));
+    }
+    if let Some(recurrence) = template.recurrence {
+        parts.push(recurrence.to_string());
+    }
+    if let Some(days) = template.due_in_days {
+        parts.push(format!("due in {} day(s)", days));
+    }
+    if !template.checklist.is_empty() {
+        parts.push(format!("{} checklist item(s)", template.checklist.len()));
+    }
+    parts.join(", ")
+}
+
+/// 处理加密数据文件
+#[cfg(feature = "encryption")]
+fn handle_encrypt(data_file: &DataFile, todo_list: &TodoList) -> Result<(), TodoError> {
+    if data_file.passphrase.borrow().is_some() {
+        print_info(&format!("{} is already encrypted", data_file.path.display()));
+        return Ok(());
+    }
+
+    data_file.passphrase.replace(Some(read_passphrase(true)?));
+    data_file.save(todo_list)?;
+
+    print_success(&format!("Encrypted {}", data_file.path.display()));
+    print_warning(&format!(
+        "Backups made before encryption are still plaintext. Delete them from {} if they contain sensitive data.",
+        backups::backup_dir(&data_file.path).display()
+    ));
+
+    Ok(())
+}
+
+/// 处理解密数据文件
+#[cfg(feature = "encryption")]
+fn handle_decrypt(data_file: &DataFile, todo_list: &TodoList) -> Result<(), TodoError> {
+    if data_file.passphrase.take().is_none() {
+        print_info(&format!("{} is not encrypted", data_file.path.display()));
+        return Ok(());
+    }
+
+    data_file.save(todo_list)?;
+    print_success(&format!("Decrypted {}", data_file.path.display()));
+
+    Ok(())
+}
+
+/// 读取加密口令：优先使用 TODO_PASSPHRASE 环境变量，否则在终端提示输入
+///
+/// `confirm` 为 true 时要求输入两次（设置新口令时）。
+#[cfg(feature = "encryption")]
+fn read_passphrase(confirm: bool) -> Result<String, TodoError> {
+    if let Some(passphrase) = std::env::var("TODO_PASSPHRASE").ok().filter(|p| !p.is_empty()) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("Passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(TodoError::EncryptionError("passphrase must not be empty".to_string()));
+    }
+
+    if confirm && rpassword::prompt_password("Confirm passphrase: ")? != passphrase {
+        return Err(TodoError::EncryptionError("passphrases do not match".to_string()));
+    }
+
+    Ok(passphrase)
+}
+
+/// 处理导出备份包
+fn handle_export_bundle(todo_list: &TodoList, path: &Path) -> Result<(), TodoError> {
+    let config = match Config::default_path() {
+        Some(config_path) if file_exists(&config_path) => Some(std::fs::read_to_string(config_path)?),
+        _ => None,
+    };
+
+    let bundle = Bundle { todo_list: todo_list.clone(), config };
+    export_bundle(&bundle, path)?;
+
+    print_success(&format!(
+        "Exported {} task(s) to {} ({})",
+        bundle.todo_list.len(),
+        path.display(),
+        bundle.entries().join(", ")
+    ));
+
+    Ok(())
+}
+
+/// 处理匿名导出，未指定输出文件时打印到终端
+fn handle_export_anonymized(todo_list: &TodoList, output: Option<&Path>) -> Result<(), TodoError> {
+    let json = serde_json::to_string_pretty(&anonymize(todo_list))?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, json)?;
+            print_success(&format!(
+                "Exported {} anonymized task(s) to {}",
+                todo_list.len(),
+                path.display()
+            ));
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// 处理 HTML 导出，未指定输出文件时打印到终端
+fn handle_export_html(todo_list: &TodoList, output: Option<&Path>) -> Result<(), TodoError> {
+    let page = html::render_html(todo_list, chrono::Utc::now());
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, page)?;
+            print_success(&format!("Exported {} task(s) to {}", todo_list.len(), path.display()));
+        }
+        None => print!("{}", page),
+    }
+
+    Ok(())
+}
+
+/// 处理从备份包恢复
+///
+/// 被替换的数据文件和配置文件会先备份为同名的 `.bak` 文件。
+fn handle_import_bundle(data_file: &DataFile, path: &Path, dry_run: bool) -> Result<(), TodoError> {
+    let bundle = import_bundle(path)?;
+    let config_path = Config::default_path();
+
+    if dry_run {
+        print_warning(&format!(
+            "Dry run: {} task(s){} would replace the current data. Nothing was changed.",
+            bundle.todo_list.len(),
+            if bundle.config.is_some() { " and the config file" } else { "" }
+        ));
+        return Ok(());
+    }
+
+    backup_existing(&data_file.path)?;
+    data_file.save(&bundle.todo_list)?;
+
+    if let (Some(config), Some(config_path)) = (&bundle.config, &config_path) {
+        backup_existing(config_path)?;
+        if let Some(dir) = config_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(config_path, config)?;
+    }
+
+    print_success(&format!(
+        "Restored {} task(s) from {}!",
+        bundle.todo_list.len(),
+        path.display()
+    ));
+
+    Ok(())
+}
+
+/// 文件存在时备份为同目录下的 `<文件名>.bak`
+fn backup_existing(path: &Path) -> Result<(), TodoError> {
+    if !file_exists(path) {
+        return Ok(());
+    }
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    backup_file(path, &backup)?;
+    print_info(&format!("Backed up {} to {}", path.display(), PathBuf::from(backup).display()));
+
+    Ok(())
+}
+
+/// 处理桌面通知，`interval` 不为空时持续运行
+#[cfg(feature = "notify")]
+fn handle_notify(
+    data_file: &DataFile,
+    todo_list: &TodoList,
+    within: &str,
+    interval: Option<&str>,
+) -> Result<(), TodoError> {
+    use crate::notify;
+    use std::collections::HashSet;
+
+    let window = chrono::Duration::minutes(parse_estimate(within)?.into());
+
+    let Some(interval) = interval else {
+        let reminders = notify::reminders(todo_list, window, chrono::Utc::now());
+        for reminder in &reminders {
+            notify::send(reminder)?;
+        }
+        print_info(&format!("Sent {} notification(s)", reminders.len()));
+        return Ok(());
+    };
+
+    let interval = std::time::Duration::from_secs(u64::from(parse_estimate(interval)?) * 60);
+    print_info("Watching for due tasks, press Ctrl+C to stop");
+
+    // 已发送过的提醒（任务 UUID 和类型），避免重复打扰
+    let mut sent = HashSet::new();
+    loop {
+        // 每次重新加载，以便看到其他命令做出的修改
+        let todo_list = data_file.load()?;
+        for reminder in notify::reminders(&todo_list, window, chrono::Utc::now()) {
+            if sent.insert((reminder.task_uuid.clone(), reminder.kind)) {
+                notify::send(&reminder)?;
+            }
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// 处理运行 REST API 服务
+#[cfg(feature = "server")]
+fn handle_serve(ctx: &mut Context, host: &str, port: u16, token: Option<String>) -> Result<(), TodoError> {
+    use crate::server::{serve, API_TOKEN_ENV};
+
+    let token = token.or_else(|| std::env::var(API_TOKEN_ENV).ok());
+    let address = format!("{}:{}", host, port);
+
+    print_info(&format!(
+        "Serving {} at http://{}/tasks{} (Ctrl+C to stop)",
+        ctx.data_file.path.display(),
+        address,
+        if token.is_some() { " with token authentication" } else { "" }
+    ));
+    // 请求交给命令处理器执行，终端上只显示警告和错误
+    set_quiet(true);
+    serve(ctx, &address, token.as_deref())
+}
+
+/// 处理与远端服务同步
+fn handle_sync(
+    data_file: &DataFile,
+    config: &Config,
+    todo_list: &mut TodoList,
+    target: crate::cli::SyncTarget,
+) -> Result<(), TodoError> {
+    use crate::cli::SyncTarget;
+
+    match target {
+        SyncTarget::Git { remote } => {
+            use crate::git_sync::{sync_git, DEFAULT_REMOTE};
+
+            let remote = remote
+                .or_else(|| config.git_remote.clone())
+                .unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+            data_file.compact(todo_list)?;
+            let report = sync_git(&data_file.path, data_file.format, &remote, "Update tasks")?;
+
+            // 拉取后数据文件可能已经变化
+            *todo_list = data_file.load()?;
+
+            let committed = if report.committed {
+                "local changes committed"
+            } else {
+                "nothing to commit"
+            };
+            if !report.has_remote {
+                print_success(&format!(
+                    "Git sync: {}; remote '{}' not configured, skipped pull and push",
+                    committed, remote
+                ));
+            } else {
+                let pulled = match &report.resolved {
+                    Some(merge) => format!(
+                        "merged with '{}' by task ({} added, {} updated)",
+                        remote,
+                        merge.added.len(),
+                        merge.updated.len()
+                    ),
+                    None if report.pulled => format!("pulled from '{}'", remote),
+                    None => format!("'{}' already up to date", remote),
+                };
+                print_success(&format!("Git sync: {}; {}; pushed", committed, pulled));
+            }
+        }
+
+        #[cfg(feature = "todoist")]
+        SyncTarget::Todoist => {
+            use crate::todoist::{sync_todoist, TodoistClient};
+
+            let client = TodoistClient::from_config(&config.todoist)?;
+            let report = sync_todoist(todo_list, &client)?;
+
+            // 保存到文件
+            data_file.save(todo_list)?;
+
+            print_success(&format!(
+                "Synced with Todoist: {} pushed, {} pulled, {} updated locally, {} updated remotely, {} closed remotely, {} completed locally",
+                report.pushed,
+                report.pulled,
+                report.updated,
+                report.updated_remote,
+                report.closed_remote,
+                report.completed_local
+            ));
+        }
+
+        #[cfg(feature = "relay")]
+        SyncTarget::Relay { room, key, server } => {
+            use crate::relay::{sync_relay, RelayClient, RelayState};
+
+            let client = match server {
+                Some(server) => RelayClient::new(server),
+                None => RelayClient::from_env()?,
+            };
+            let state_path = RelayState::default_path(&room)
+                .ok_or_else(|| TodoError::Custom("Could not determine the state directory".to_string()))?;
+            let mut state = RelayState::load_from(&state_path)?;
+            let report = sync_relay(todo_list, &mut state, &client, &room, &key)?;
+
+            // 先保存任务，再记录同步状态，保存失败时下次会重新推送
+            data_file.save(todo_list)?;
+            state.save_to(&state_path)?;
+
+            print_success(&format!(
+                "Synced through relay room '{}': {} message(s) received, {} change(s) applied, {} change(s) sent",
+                room, report.received, report.applied, report.sent
+            ));
+        }
+
+        #[cfg(feature = "remote")]
+        SyncTarget::Remote => {
+            use crate::remote::{open, sync_remote};
+
+            let remote = config
+                .remote
+                .as_ref()
+                .ok_or_else(|| TodoError::ConfigError("no [remote] section in config.toml".to_string()))?;
+            let store = open(remote)?;
+            let report = sync_remote(todo_list, store.as_ref(), data_file.format)?;
+
+            // 保存到文件
+            data_file.save(todo_list)?;
+
+            if report.created {
+                print_success(&format!("Uploaded {} task(s) to {}", todo_list.len(), store.location()));
+            } else {
+                print_success(&format!(
+                    "Synced with {}: {} added, {} updated, {}",
+                    store.location(),
+                    report.merged.added.len(),
+                    report.merged.updated.len(),
+                    if report.uploaded { "changes uploaded" } else { "nothing to upload" }
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理目标操作
+fn handle_goal(
+    data_file: &DataFile,
+    todo_list: &mut TodoList,
+    action: GoalAction,
+) -> Result<(), TodoError> {
+    match action {
+        GoalAction::Add { title, due } => {
+            let due_date = due.as_deref().map(parse_date).transpose()?;
+            let id = todo_list.add_goal(title.clone(), due_date);
+            data_file.save(todo_list)?;
+            print_success(&format!("Goal {} '{}' added!", id, title));
+        }
+
+        GoalAction::Show { id: None } => print_goals(todo_list),
+
+        GoalAction::Show { id: Some(id) } => {
+            let goal = todo_list.find_goal(id).ok_or(TodoError::GoalNotFound(id))?;
+            print_goal_detail(todo_list, goal);
+        }
+
+        GoalAction::Link { task, goal } => {
+            todo_list.set_goal(task, Some(goal))?;
+            data_file.save(todo_list)?;
+            print_success(&format!("Task {} linked to goal {}", task, goal));
+        }
+
+        GoalAction::Unlink { task } => {
+            todo_list.set_goal(task, None)?;
+            data_file.save(todo_list)?;
+            print_success(&format!("Task {} unlinked from its goal", task));
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理日程
+fn handle_agenda(todo_list: &TodoList, range: AgendaRange, focus: Option<&Focus>) {
+    use chrono::{DateTime, Duration, Utc};
+
+    let today = today();
+    let start = start_of_day(today);
+    let end = start + Duration::days(range.days());
+
+    let mut carried_over = todo_list.tasks_due_between(DateTime::<Utc>::MIN_UTC, start);
+    let mut due = todo_list.tasks_due_between(start, end);
+    let title = match range {
+        AgendaRange::Today => "📆 Today",
+        AgendaRange::Week => "📆 This Week",
+    };
+
+    // 专注模式下只显示匹配的任务
+    if let Some(focus) = focus {
+        carried_over.retain(|task| focus.matches(task));
+        due.retain(|task| focus.matches(task));
+    }
+    print_agenda(todo_list, &carried_over, &due, title, today);
+
+    if let Some(focus) = focus {
+        print_info(&format!("{} (`todo focus --off` to show everything)", describe_focus(focus)));
+    }
+}
+
+/// 处理看板
+fn handle_board(todo_list: &TodoList, width: usize, focus: Option<&Focus>) {
+    // 任务目前只有待办和已完成两种状态
+    let mut columns = [
+        BoardColumn { title: "Pending", tasks: todo_list.list_pending_tasks() },
+        BoardColumn { title: "Done", tasks: todo_list.list_completed_tasks() },
+    ];
+
+    // 专注模式下只显示匹配的任务
+    if let Some(focus) = focus {
+        for column in &mut columns {
+            column.tasks.retain(|task| focus.matches(task));
+        }
+    }
+    print_board(&columns, width);
+
+    if let Some(focus) = focus {
+        print_info(&format!("{} (`todo focus --off` to show everything)", describe_focus(focus)));
+    }
+}
+
+/// 处理月历
+fn handle_calendar(todo_list: &TodoList, month: Option<&str>) -> Result<(), TodoError> {
+    let today = today();
+    let month = match month {
+        Some(month) => parse_month(month)?,
+        None => today,
+    };
+
+    print_calendar(todo_list, month, today);
+    Ok(())
+}
+
+/// 解析 YYYY-MM 格式的月份，返回该月第一天
+fn parse_month(month_str: &str) -> Result<chrono::NaiveDate, TodoError> {
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", month_str.trim()), "%Y-%m-%d")
+        .map_err(|_| TodoError::InvalidMonth(month_str.to_string()))
+}
+
+/// 处理统计
+fn handle_stats(config: &Config, todo_list: &TodoList, json: bool) -> Result<(), TodoError> {
+    let stats = stats::compute_now(todo_list);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        print_stats(&stats);
+        warn_if_over_capacity(config, todo_list, chrono::Utc::now());
+    }
+
+    Ok(())
+}
+
+/// 处理变更报告
+fn handle_report(todo_list: &TodoList, since: &str, markdown: bool) -> Result<(), TodoError> {
+    let changelog = report::changelog(todo_list, parse_since(since)?);
+
+    if markdown {
+        print!("{}", changelog.to_markdown());
+    } else {
+        print!("{}", changelog.to_text());
+    }
+
+    Ok(())
+}
+
+/// 处理周报
+fn handle_weekly_review(todo_list: &TodoList, group_by: report::GroupBy, markdown: bool) {
+    let review = report::weekly_review(todo_list, chrono::Utc::now(), group_by);
+
+    if markdown {
+        print!("{}", review.to_markdown());
+    } else {
+        print!("{}", review.to_text());
+    }
+}
+
+/// 解析报告的起始时间：`7d`、`2w`、`24h` 表示最近一段时间，也可以是 `today`、`yesterday`
+/// 或日期 YYYY-MM-DD（当天开始）
+fn parse_since(since_str: &str) -> Result<chrono::DateTime<chrono::Utc>, TodoError> {
+    use chrono::{Duration, NaiveDate, Utc};
+
+    let invalid = || TodoError::InvalidPeriod(since_str.to_string());
+    let input = since_str.trim().to_lowercase();
+
+    let today = today();
+    match input.as_str() {
+        "today" => return Ok(start_of_day(today)),
+        "yesterday" => return Ok(start_of_day(today - Duration::days(1))),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(start_of_day(date));
+    }
+
+    let unit = input.chars().last().ok_or_else(invalid)?;
+    let amount = input[..input.len() - unit.len_utf8()]
+        .parse::<u32>()
+        .map_err(|_| invalid())?;
+
+    let period = match unit {
+        'h' => Duration::hours(amount.into()),
+        'd' => Duration::days(amount.into()),
+        'w' => Duration::weeks(amount.into()),
+        _ => return Err(invalid()),
+    };
+
+    Ok(Utc::now() - period)
+}
+
+/// 解析优先级（high/medium/low 或 h/m/l）
+fn parse_priority(priority_str: &str) -> Result<Priority, TodoError> {
+    Priority::from_str(priority_str).map_err(|_| TodoError::InvalidPriority(priority_str.to_string()))
+}
+
+/// 解析日期字符串（格式：YYYY-MM-DD）
+fn parse_date(date_str: &str) -> Result<chrono::DateTime<chrono:: Utc>, TodoError> {
+    // 也接受带时间的 RFC 3339 格式，例如 REST API 的调用方传入的 2025-01-31T09:00:00Z
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(date_str) {
+        return Ok(datetime.with_timezone(&chrono::Utc));
+    }
+
+    let naive_date = chrono::NaiveDate:: parse_from_str(date_str, "%Y-%m-%d")
+        .map_err(|_| TodoError::InvalidDateFormat(date_str.to_string()))?;
+
+    // 本地时区当天结束，保存为 UTC
+    Ok(end_of_day(naive_date))
+}
+
+/// 解析预估耗时（例如 30m、2h、1h30m，纯数字视为分钟）
+fn parse_estimate(estimate_str: &str) -> Result<u32, TodoError> {
+    let invalid = || TodoError::InvalidEstimate(estimate_str.to_string());
+    let input = estimate_str.trim().to_lowercase();
+
+    if let Ok(minutes) = input.parse::<u32>() {
+        return Ok(minutes);
+    }
+
+    let (hours, rest) = match input.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<u32>().map_err(|_| invalid())?, rest),
+        None => (0, input.as_str()),
+    };
+
+    let minutes = match rest.strip_suffix('m') {
+        Some(minutes) => minutes.parse::<u32>().map_err(|_| invalid())?,
+        None if rest.is_empty() => 0,
+        None => return Err(invalid()),
+    };
+
+    let total = hours * 60 + minutes;
+    if total == 0 {
+        return Err(invalid());
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+impl Context {
+    /// 测试用的上下文：默认配置，空列表保存到 `path`（JSON 格式，不备份，没有钩子）
+    pub(crate) fn for_test(path: PathBuf) -> Self {
+        Context {
+            data_file: DataFile {
+                path,
+                format: StorageFormat::Json,
+                backup_count: 0,
+                journal: None,
+                #[cfg(feature = "encryption")]
+                passphrase: Default::default(),
+                #[cfg(feature = "webhooks")]
+                webhooks: Vec::new(),
+                hooks: None,
+                history_len: Default::default(),
+                saved_tasks: Default::default(),
+                saved: Default::default(),
+            },
+            config: Config::default(),
+            todo_list: TodoList::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let result = parse_date("2025-12-31");
+        assert!(result.is_ok());
+
+        let date = result.unwrap();
+        assert_eq!(local_date(date).to_string(), "2025-12-31");
+        assert_eq!(to_local(date).format("%H:%M:%S").to_string(), "23:59:59");
+    }
+
+    #[test]
+    fn test_parse_invalid_date() {
+        let result = parse_date("invalid-date");
+        assert!(result. is_err());
+
+        if let Err(TodoError::InvalidDateFormat(msg)) = result {
+            assert_eq!(msg, "invalid-date");
+        } else {
+            panic!("Expected InvalidDateFormat error");
+        }
+    }
+
+    #[test]
+    fn test_read_titles_skips_blank_lines() {
+        let input = "买牛奶\n\n  写周报  \r\n   \n开会";
+        assert_eq!(read_titles(input.as_bytes()).unwrap(), ["买牛奶", "写周报", "开会"]);
+    }
+
+    #[test]
+    fn test_parse_estimate() {
+        assert_eq!(parse_estimate("30m").unwrap(), 30);
+        assert_eq!(parse_estimate("2h").unwrap(), 120);
+        assert_eq!(parse_estimate("1h30m").unwrap(), 90);
+        assert_eq!(parse_estimate("45").unwrap(), 45);
+
+        assert!(matches!(parse_estimate("soon"), Err(TodoError::InvalidEstimate(_))));
+        assert!(parse_estimate("0m").is_err());
+        assert!(parse_estimate("1h30").is_err());
+    }
+
+    #[test]
+    fn test_parse_postpone() {
+        let due = parse_date("2025-01-31").unwrap();
+
+        let next_day = parse_postpone(Some(due), "+1d").unwrap();
+        assert_eq!(local_date(next_day).to_string(), "2025-02-01");
+
+        let two_weeks = parse_postpone(Some(due), "+2w").unwrap();
+        assert_eq!(local_date(two_weeks).to_string(), "2025-02-14");
+
+        let date = parse_postpone(Some(due), "2025-03-01").unwrap();
+        assert_eq!(date, parse_date("2025-03-01").unwrap());
+
+        let tomorrow = parse_postpone(None, "+1d").unwrap();
+        assert_eq!(local_date(tomorrow), today().succ_opt().unwrap());
+
+        assert!(matches!(parse_postpone(Some(due), "+1x"), Err(TodoError::InvalidPostpone(_))));
+        assert!(matches!(parse_postpone(Some(due), "+"), Err(TodoError::InvalidPostpone(_))));
+        assert!(matches!(parse_postpone(None, "tomorrow"), Err(TodoError::InvalidPostpone(_))));
+    }
+
+    #[test]
+    fn test_parse_until() {
+        let now = chrono::Utc::now();
+        let until = parse_until("17:00").unwrap();
+        assert!(until > now && until - now <= chrono::Duration::hours(25));
+        assert_eq!(until.with_timezone(&chrono::Local).format("%H:%M").to_string(), "17:00");
+
+        assert!(matches!(parse_until("5pm"), Err(TodoError::InvalidTime(_))));
+    }
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!(parse_month("2025-03").unwrap().to_string(), "2025-03-01");
+        assert!(matches!(parse_month("2025-13"), Err(TodoError::InvalidMonth(_))));
+        assert!(matches!(parse_month("march"), Err(TodoError::InvalidMonth(_))));
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let week_ago = parse_since("7d").unwrap();
+        assert_eq!((chrono::Utc::now() - week_ago).num_days(), 7);
+        assert_eq!(local_date(parse_since("1w").unwrap()), local_date(week_ago));
+
+        let date = parse_since("2025-01-01").unwrap();
+        assert_eq!(to_local(date).format("%Y-%m-%dT%H:%M:%S").to_string(), "2025-01-01T00:00:00");
+
+        let today = parse_since("Today").unwrap();
+        assert_eq!(local_date(today), super::today());
+        assert_eq!(today - parse_since("yesterday").unwrap(), chrono::Duration::days(1));
+
+        assert!(matches!(parse_since("7x"), Err(TodoError::InvalidPeriod(_))));
+        assert!(matches!(parse_since("d"), Err(TodoError::InvalidPeriod(_))));
+        assert!(matches!(parse_since(""), Err(TodoError::InvalidPeriod(_))));
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(split_command_line("  add 写周报 -p high \n").unwrap(), ["add", "写周报", "-p", "high"]);
+        assert_eq!(split_command_line(r#"add "写 周报" -t 'a b'"#).unwrap(), ["add", "写 周报", "-t", "a b"]);
+        assert_eq!(split_command_line(r#"add It\'s "say \"hi\"" ''"#).unwrap(), ["add", "It's", r#"say "hi""#, ""]);
+        assert!(split_command_line("").unwrap().is_empty());
+        assert!(split_command_line("add \"写周报").is_err());
+        assert!(split_command_line("add \\").is_err());
+    }
+
+    #[test]
+    fn test_shell_shares_the_context() {
+        let dir = std::env::temp_dir().join(format!("todo-shell-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut ctx = Context::for_test(dir.join("todos.json"));
+
+        let input = "add 写周报\nedit 1 --title '写月报' -t work\nadd ''\nundo\nquit\n";
+        handle_shell(&mut ctx, input.as_bytes()).unwrap();
+
+        // 每个命令各有一条撤销记录，撤销只撤回最后一个修改
+        let task = ctx.todo_list.find_task(1).unwrap();
+        assert_eq!(task.title, "写周报");
+        assert!(task.tags.is_empty());
+        assert_eq!(ctx.todo_list.len(), 1);
+        assert_eq!(ctx.data_file.load().unwrap().find_task(1).unwrap().title, "写周报");
+    }
+
+    #[test]
+    fn test_parse_date_wrong_format() {
+        let result = parse_date("12/31/2025");
+        assert!(result.is_err());
+    }
+}
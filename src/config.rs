@@ -0,0 +1,517 @@
+use crate::filter::StatusFilter;
+use crate::display::{Role, Theme, ThemeColor};
+use crate::error::{TodoError, TodoResult};
+use crate::i18n::Locale;
+use crate::storage::StorageFormat;
+use crate::task::Task;
+use chrono::format::{Item, StrftimeItems};
+use directories::{BaseDirs, ProjectDirs};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 配置文件名
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// 用户配置（`~/.config/todo/config.toml`）
+///
+/// 所有字段都是可选的，命令行参数优先于配置。
+///
+/// # 示例
+/// ```toml
+/// default_priority = "low"
+/// data_file = "~/Dropbox/todos.json"
+/// format = "json"
+/// compress = true
+/// date_format = "%d/%m/%Y"
+/// time_format = "%H:%M"
+/// locale = "zh-CN"
+/// theme = "monochrome"
+/// accessible = true
+/// ascii = true
+/// default_list = "pending"
+/// backup_count = 20
+/// archive_after_days = 30
+/// journal = true
+/// alert_bell = true
+/// critical_overdue_hours = 48
+/// daily_capacity_hours = 6.5
+/// git_remote = "origin"
+///
+/// [colors]
+/// high = "magenta"
+/// tag = "#00afaf"
+///
+/// [tags.work]
+/// priority = "high"
+/// context = "office"
+/// critical_overdue_hours = 4
+///
+/// [todoist]
+/// token = "0123456789abcdef"
+///
+/// [remote]
+/// type = "webdav"
+/// url = "https://dav.example.com/todo/todos.json"
+/// username = "me"
+///
+/// [[webhooks]]
+/// url = "https://hooks.slack.com/services/T000/B000/XXXX"
+/// events = ["completed", "overdue"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 新任务的默认优先级: high, medium, low
+    pub default_priority: Option<String>,
+    /// 数据文件路径（支持 `~/` 开头）
+    pub data_file: Option<PathBuf>,
+    /// 数据文件格式: json（默认）, todotxt, binary
+    pub format: Option<StorageFormat>,
+    /// 用 gzip 压缩数据文件（路径加上 `.gz`，`--file` 指定的文件除外）
+    pub compress: bool,
+    /// 日期显示格式（chrono 格式），例如 `%d/%m/%Y`
+    pub date_format: Option<String>,
+    /// 详情和历史中时间的显示格式（chrono 格式），默认 `%H:%M:%S`
+    pub time_format: Option<String>,
+    /// 界面语言: en, zh-CN（默认根据 LC_ALL、LC_MESSAGES 或 LANG 环境变量判断）
+    pub locale: Option<Locale>,
+    /// 配色主题: default, high-contrast, deuteranopia-safe, monochrome
+    pub theme: Theme,
+    /// 覆盖主题中各角色的颜色，键为角色名（high、overdue、tag、heading 等）
+    pub colors: HashMap<Role, ThemeColor>,
+    /// 读屏友好模式：不使用颜色、emoji 和框线，改用明确的文字标签
+    pub accessible: bool,
+    /// 只使用 ASCII 符号，用于无法显示 emoji 和框线的终端
+    pub ascii: bool,
+    /// 不带筛选条件运行 `todo list` 时使用的筛选
+    pub default_list: Option<StatusFilter>,
+    /// 保存前自动备份时保留的数量，0 表示不备份（默认 10）
+    pub backup_count: Option<usize>,
+    /// 完成超过多少天的任务自动移到归档文件（修改了数据的命令执行完后检查），也是 `todo gc` 的默认值
+    pub archive_after_days: Option<u32>,
+    /// 把修改追加到变更日志而不是每次重写整个数据文件（只对 JSON 格式生效），适合很大的列表
+    pub journal: bool,
+    /// 有任务严重过期时响铃并显示醒目的提示
+    pub alert_bell: bool,
+    /// 过期超过多少小时算严重过期（默认 24）
+    pub critical_overdue_hours: Option<u32>,
+    /// 每天可用于任务的小时数，设置截止日期时当天的预估耗时超过该值会提示
+    pub daily_capacity_hours: Option<f64>,
+    /// `todo sync git` 推送和拉取的远端（默认 origin）
+    pub git_remote: Option<String>,
+    /// 按标签设置的默认值，键为标签名（不含 `#`）
+    pub tags: HashMap<String, TagDefaults>,
+    /// `todo sync todoist` 的设置（需要启用 todoist feature）
+    pub todoist: TodoistConfig,
+    /// `todo sync remote` 使用的远程存储（需要启用 remote feature）
+    pub remote: Option<RemoteConfig>,
+    /// 任务事件发生时接收通知的 Webhook（需要启用 webhooks feature）
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// 带有某个标签的新任务使用的默认值
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct TagDefaults {
+    /// 默认优先级: high, medium, low
+    pub priority: Option<String>,
+    /// 默认情境（不含 `@`）
+    pub context: Option<String>,
+    /// 带有该标签的任务过期超过多少小时算严重过期
+    pub critical_overdue_hours: Option<u32>,
+}
+
+/// Todoist 同步的设置
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TodoistConfig {
+    /// API Token，不设置时从 `TODOIST_API_TOKEN` 环境变量读取
+    pub token: Option<String>,
+}
+
+/// 远程存储的位置，密码和密钥从环境变量读取，不写在配置文件中
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum RemoteConfig {
+    /// WebDAV 上的一个文件，密码从 `TODO_WEBDAV_PASSWORD` 读取
+    Webdav {
+        /// 文件的完整地址
+        url: String,
+        /// 用户名，不设置时不使用认证
+        #[serde(default)]
+        username: Option<String>,
+    },
+    /// S3（或兼容 S3 的服务）上的一个对象，密钥从 `AWS_ACCESS_KEY_ID` 和 `AWS_SECRET_ACCESS_KEY` 读取
+    S3 {
+        bucket: String,
+        /// 对象键（默认 todos.json）
+        #[serde(default = "default_s3_key")]
+        key: String,
+        /// 区域（默认 us-east-1）
+        #[serde(default = "default_s3_region")]
+        region: String,
+        /// 服务地址，默认为 AWS 在该区域的地址；MinIO 等兼容服务需要设置
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+}
+
+/// 一个 Webhook：事件发生时向 `url` POST 一条 JSON 消息
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// 订阅的事件，默认订阅全部
+    #[serde(default = "WebhookEvent::all")]
+    pub events: Vec<WebhookEvent>,
+}
+
+/// Webhook 可以订阅的任务事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// 新建任务
+    Added,
+    /// 完成任务
+    Completed,
+    /// 任务过期（每个截止日期只通知一次）
+    Overdue,
+}
+
+impl WebhookEvent {
+    /// 所有事件
+    pub fn all() -> Vec<Self> {
+        vec![Self::Added, Self::Completed, Self::Overdue]
+    }
+}
+
+fn default_s3_key() -> String {
+    "todos.json".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// 本工具在各平台上的标准目录（配置、数据等）
+pub(crate) fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "todo")
+}
+
+/// `--data-dir` 指定的目录（程序启动时设置一次）
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 把配置、数据和会话文件都放到指定目录中，而不是各平台的标准目录，只有第一次调用生效
+///
+/// 用于端到端测试等需要与用户数据完全隔离的场景。
+pub fn set_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+/// `--data-dir` 指定的目录
+pub(crate) fn data_dir_override() -> Option<&'static Path> {
+    DATA_DIR.get().map(PathBuf::as_path)
+}
+
+/// 默认的严重过期阈值（小时）
+pub const DEFAULT_CRITICAL_OVERDUE_HOURS: u32 = 24;
+
+impl Config {
+    /// 默认配置文件路径（Linux 下为 `~/.config/todo/config.toml`）
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(dir) = data_dir_override() {
+            return Some(dir.join(CONFIG_FILE_NAME));
+        }
+        project_dirs().map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+    }
+
+    /// 从默认位置加载配置，文件不存在时返回默认配置
+    pub fn load() -> TodoResult<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 从指定文件加载配置，文件不存在时返回默认配置
+    pub fn load_from<P: AsRef<Path>>(path: P) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !path_ref.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path_ref)?;
+        let config: Config = toml::from_str(&content).map_err(|err| {
+            TodoError::ConfigError(format!("{}: {}", path_ref.display(), err.message()))
+        })?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 检查无法在反序列化时发现的错误
+    fn validate(&self) -> TodoResult<()> {
+        // 无效的格式字符串会让 chrono 在格式化时 panic，提前报错
+        for (key, format) in [("date_format", &self.date_format), ("time_format", &self.time_format)] {
+            if let Some(format) = format {
+                if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                    return Err(TodoError::ConfigError(format!("invalid {} '{}'", key, format)));
+                }
+            }
+        }
+
+        if let Some(hours) = self.daily_capacity_hours {
+            if !(hours > 0.0 && hours <= 24.0) {
+                return Err(TodoError::ConfigError(format!(
+                    "daily_capacity_hours must be between 0 and 24, got {}",
+                    hours
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 配置的数据文件路径，`~/` 开头时展开为用户主目录
+    pub fn data_file_path(&self) -> Option<PathBuf> {
+        let path = self.data_file.as_ref()?;
+
+        match (path.strip_prefix("~"), BaseDirs::new()) {
+            (Ok(rest), Some(dirs)) => Some(dirs.home_dir().join(rest)),
+            _ => Some(path.clone()),
+        }
+    }
+
+    /// 任务的严重过期阈值：优先使用标签上配置的阈值，其次是全局阈值
+    pub fn critical_overdue(&self, task: &Task) -> chrono::Duration {
+        let hours = task
+            .tags
+            .iter()
+            .find_map(|tag| self.tags.get(tag)?.critical_overdue_hours)
+            .or(self.critical_overdue_hours)
+            .unwrap_or(DEFAULT_CRITICAL_OVERDUE_HOURS);
+        chrono::Duration::hours(i64::from(hours))
+    }
+
+    /// 每天的可用时间（分钟），未配置时返回 `None`
+    pub fn daily_capacity_minutes(&self) -> Option<u32> {
+        self.daily_capacity_hours.map(|hours| (hours * 60.0).round() as u32)
+    }
+
+    /// 查找第一个配置了默认值的标签，返回标签名和对应的默认值
+    pub fn tag_defaults(&self, tags: &[String]) -> Option<(&str, &TagDefaults)> {
+        tags.iter().find_map(|tag| {
+            self.tags
+                .get_key_value(tag.trim_start_matches('#'))
+                .map(|(name, defaults)| (name.as_str(), defaults))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_parse_tag_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            [tags.work]
+            priority = "high"
+            context = "office"
+
+            [tags.home]
+            "#,
+        )
+        .unwrap();
+
+        let tags = vec!["misc".to_string(), "work".to_string()];
+        let (tag, defaults) = config.tag_defaults(&tags).unwrap();
+        assert_eq!(tag, "work");
+        assert_eq!(defaults.priority.as_deref(), Some("high"));
+        assert_eq!(defaults.context.as_deref(), Some("office"));
+
+        let (_, defaults) = config.tag_defaults(&["home".to_string()]).unwrap();
+        assert!(defaults.priority.is_none() && defaults.context.is_none());
+
+        assert!(config.tag_defaults(&["other".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_parse_general_settings() {
+        let config: Config = toml::from_str(
+            r#"
+            default_priority = "low"
+            data_file = "~/todos.json"
+            format = "binary"
+            compress = true
+            date_format = "%d/%m/%Y"
+            locale = "zh"
+            theme = "monochrome"
+            accessible = true
+            ascii = true
+            default_list = "pending"
+            backup_count = 3
+            journal = true
+            alert_bell = true
+            critical_overdue_hours = 48
+            daily_capacity_hours = 6.5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.default_priority.as_deref(), Some("low"));
+        assert_eq!(config.daily_capacity_minutes(), Some(390));
+        assert_eq!(config.locale, Some(Locale::ZhCn));
+        assert_eq!(config.theme, Theme::Monochrome);
+        assert!(config.accessible);
+        assert!(config.ascii);
+        assert!(matches!(config.default_list, Some(StatusFilter::Pending)));
+        assert_eq!(config.backup_count, Some(3));
+        assert_eq!(config.format, Some(StorageFormat::Binary));
+        assert!(config.compress);
+        assert!(config.journal);
+        assert!(config.alert_bell);
+        assert!(config.validate().is_ok());
+
+        let path = config.data_file_path().unwrap();
+        assert!(path.ends_with("todos.json"));
+        assert!(!path.starts_with("~"));
+    }
+
+    #[test]
+    fn test_critical_overdue_threshold() {
+        let config: Config = toml::from_str(
+            r#"
+            critical_overdue_hours = 48
+
+            [tags.work]
+            critical_overdue_hours = 4
+            "#,
+        )
+        .unwrap();
+
+        let mut task = Task::new(1, "任务".to_string(), Priority::High, None);
+        assert_eq!(config.critical_overdue(&task), chrono::Duration::hours(48));
+
+        task.tags = vec!["home".to_string(), "work".to_string()];
+        assert_eq!(config.critical_overdue(&task), chrono::Duration::hours(4));
+
+        let default = Config::default();
+        assert_eq!(default.critical_overdue(&task), chrono::Duration::hours(24));
+    }
+
+    #[test]
+    fn test_invalid_date_format() {
+        let config = Config {
+            date_format: Some("%Q".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(config.validate(), Err(TodoError::ConfigError(_))));
+
+        let config = Config {
+            time_format: Some("%H:%".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(config.validate(), Err(TodoError::ConfigError(msg)) if msg.contains("time_format")));
+    }
+
+    #[test]
+    fn test_invalid_daily_capacity() {
+        let config = Config {
+            daily_capacity_hours: Some(0.0),
+            ..Default::default()
+        };
+        assert!(matches!(config.validate(), Err(TodoError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let config: Config = toml::from_str(
+            r##"
+            [colors]
+            high = "magenta"
+            heading = "bright-cyan"
+            tag = "#00afaf"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.colors[&Role::High], ThemeColor(colored::Color::Magenta));
+        assert_eq!(config.colors[&Role::Heading], ThemeColor(colored::Color::BrightCyan));
+        assert_eq!(config.colors[&Role::Tag], ThemeColor(colored::Color::TrueColor { r: 0, g: 175, b: 175 }));
+
+        // 未知的角色或颜色都会报错
+        assert!(toml::from_str::<Config>("[colors]\nhigh = \"mauve\"").is_err());
+        assert!(toml::from_str::<Config>("[colors]\nurgent = \"red\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_remote() {
+        let config: Config = toml::from_str(
+            r#"
+            [remote]
+            type = "s3"
+            bucket = "my-todos"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.remote,
+            Some(RemoteConfig::S3 {
+                bucket: "my-todos".to_string(),
+                key: "todos.json".to_string(),
+                region: "us-east-1".to_string(),
+                endpoint: None,
+            })
+        );
+
+        assert!(toml::from_str::<Config>("[remote]\ntype = \"ftp\"").is_err());
+        assert!(toml::from_str::<Config>("[remote]\ntype = \"webdav\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_webhooks() {
+        let config: Config = toml::from_str(
+            r#"
+            [[webhooks]]
+            url = "https://example.com/a"
+
+            [[webhooks]]
+            url = "https://example.com/b"
+            events = ["completed"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.webhooks[0].events, WebhookEvent::all());
+        assert_eq!(config.webhooks[1].events, [WebhookEvent::Completed]);
+
+        assert!(toml::from_str::<Config>("[[webhooks]]\nurl = \"x\"\nevents = [\"deleted\"]").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let config = Config::load_from("test_missing_config.toml").unwrap();
+        assert!(config.tags.is_empty());
+        assert!(config.default_priority.is_none());
+        assert_eq!(config.theme, Theme::Default);
+        assert!(!config.accessible);
+        assert!(!config.alert_bell);
+    }
+
+    #[test]
+    fn test_load_invalid_file() {
+        let path = "test_invalid_config.toml";
+        fs::write(path, "tags = 42").unwrap();
+
+        let result = Config::load_from(path);
+        assert!(matches!(result, Err(TodoError::ConfigError(_))));
+
+        let _ = fs::remove_file(path);
+    }
+}
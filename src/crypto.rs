@@ -0,0 +1,98 @@
+use crate::error::{TodoError, TodoResult};
+use crate::storage::ENCRYPTED_MAGIC;
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// 派生密钥使用的盐长度
+const SALT_LEN: usize = 16;
+
+/// ChaCha20-Poly1305 的 nonce 长度
+const NONCE_LEN: usize = 12;
+
+/// 用口令加密数据
+///
+/// 输出格式：文件头 `TODOENC1` + 盐 + nonce + 密文。
+/// 密钥由 Argon2id 从口令和随机盐派生，每次加密都使用新的盐和 nonce。
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> TodoResult<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt)
+        .map_err(|err| TodoError::EncryptionError(err.to_string()))?;
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| TodoError::EncryptionError("encryption failed".to_string()))?;
+
+    let mut output = Vec::with_capacity(ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(ENCRYPTED_MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// 用口令解密 `encrypt` 生成的数据，口令错误或数据被篡改时返回错误
+pub fn decrypt(data: &[u8], passphrase: &str) -> TodoResult<Vec<u8>> {
+    let body = data
+        .strip_prefix(ENCRYPTED_MAGIC)
+        .filter(|body| body.len() >= SALT_LEN + NONCE_LEN)
+        .ok_or_else(|| TodoError::EncryptionError("not an encrypted data file".to_string()))?;
+
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| TodoError::EncryptionError("wrong passphrase or corrupted file".to_string()))
+}
+
+/// 用 Argon2id 从口令派生 256 位密钥
+fn derive_key(passphrase: &str, salt: &[u8]) -> TodoResult<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| TodoError::EncryptionError(err.to_string()))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let encrypted = encrypt(b"secret todo", "passphrase").unwrap();
+
+        assert!(encrypted.starts_with(ENCRYPTED_MAGIC));
+        assert_eq!(decrypt(&encrypted, "passphrase").unwrap(), b"secret todo");
+    }
+
+    #[test]
+    fn test_fresh_salt_and_nonce() {
+        let a = encrypt(b"same", "passphrase").unwrap();
+        let b = encrypt(b"same", "passphrase").unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let encrypted = encrypt(b"secret todo", "passphrase").unwrap();
+        assert!(matches!(
+            decrypt(&encrypted, "wrong"),
+            Err(TodoError::EncryptionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampered_data() {
+        let mut encrypted = encrypt(b"secret todo", "passphrase").unwrap();
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0x01;
+
+        assert!(decrypt(&encrypted, "passphrase").is_err());
+        assert!(decrypt(b"plain json", "passphrase").is_err());
+    }
+}
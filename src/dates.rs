@@ -0,0 +1,92 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// 时间在本地时区的日期和时间
+///
+/// 数据中的时间都以 UTC 保存，显示和按日期计算（今天、某天到期等）时先转换为本地时间。
+pub fn to_local(at: DateTime<Utc>) -> DateTime<FixedOffset> {
+    to_offset(at, &Local)
+}
+
+/// 时间在本地时区的日期
+pub fn local_date(at: DateTime<Utc>) -> NaiveDate {
+    to_local(at).date_naive()
+}
+
+/// 本地时区的今天
+pub fn today() -> NaiveDate {
+    local_date(Utc::now())
+}
+
+/// 本地时区某一天的开始（00:00:00）
+pub fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    from_local(date.and_time(NaiveTime::MIN))
+}
+
+/// 本地时区某一天的结束（23:59:59），只指定了日期的截止日期都使用这个时间
+pub fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    from_local(date.and_time(last_second()))
+}
+
+/// 本地时间转换为 UTC；夏令时切换时重复的时间取较早的一个，不存在的时间按 UTC 处理
+pub fn from_local(datetime: NaiveDateTime) -> DateTime<Utc> {
+    from_offset(datetime, &Local)
+}
+
+/// 一天的最后一秒
+fn last_second() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN)
+}
+
+/// 时间在时区 `tz` 中的日期和时间
+fn to_offset<Tz: TimeZone>(at: DateTime<Utc>, tz: &Tz) -> DateTime<FixedOffset> {
+    at.with_timezone(tz).fixed_offset()
+}
+
+/// 时区 `tz` 中的时间转换为 UTC，规则见 [`from_local`]
+fn from_offset<Tz: TimeZone>(datetime: NaiveDateTime, tz: &Tz) -> DateTime<Utc> {
+    tz.from_local_datetime(&datetime)
+        .earliest()
+        .map_or_else(|| datetime.and_utc(), |local| local.with_timezone(&Utc))
+}
+
+/// 本地时区中的某个时间，供测试构造与时区无关的数据
+#[cfg(test)]
+pub(crate) fn local_time(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+    let date = NaiveDate::from_ymd_opt(year, month, day).expect("valid date");
+    from_local(date.and_hms_opt(hour, minute, second).expect("valid time"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_boundaries() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let end = end_of_day(date);
+        assert_eq!(local_date(end), date);
+        assert_eq!(local_date(start_of_day(date)), date);
+        assert_eq!(end - start_of_day(date), chrono::Duration::seconds(86_399));
+    }
+
+    #[test]
+    fn test_day_boundaries_in_other_timezones() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let utc = |text: &str| DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc);
+
+        // UTC-5：当天结束已经是 UTC 的第二天
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        let end = from_offset(date.and_time(last_second()), &new_york);
+        assert_eq!(end, utc("2025-06-02T04:59:59Z"));
+        assert_eq!(to_offset(end, &new_york).date_naive(), date);
+        assert_eq!(to_offset(utc("2025-06-01T04:59:59Z"), &new_york).date_naive(), date.pred_opt().unwrap());
+
+        // UTC+13：当天开始还是 UTC 的前一天
+        let tonga = FixedOffset::east_opt(13 * 3600).unwrap();
+        let start = from_offset(date.and_time(NaiveTime::MIN), &tonga);
+        assert_eq!(start, utc("2025-05-31T11:00:00Z"));
+        assert_eq!(to_offset(start, &tonga).date_naive(), date);
+        assert_eq!(to_offset(utc("2025-06-01T10:59:59Z"), &tonga).date_naive(), date);
+        assert_eq!(to_offset(utc("2025-06-01T11:00:00Z"), &tonga).date_naive(), date.succ_opt().unwrap());
+    }
+}
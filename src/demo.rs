@@ -0,0 +1,111 @@
+use crate::dates::{end_of_day, local_date};
+use crate::task::{Priority, Recurrence, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Duration, Utc};
+use std::path::PathBuf;
+
+/// 演示数据所在的目录（系统临时目录下），每次运行 `todo demo` 都会重新生成
+pub fn demo_dir() -> PathBuf {
+    std::env::temp_dir().join("todo-demo")
+}
+
+/// 生成演示用的示例任务，日期都相对于 `now`
+///
+/// 覆盖各个视图需要的数据：过期、今天和本周到期、已完成（含完成时间）、
+/// 子任务、循环任务、预估耗时、标签、情境和目标。
+pub fn sample_list(now: DateTime<Utc>) -> TodoList {
+    let mut list = TodoList::new();
+    let goal = list.add_goal("Ship the Q3 review".to_string(), Some(day(now, 5)));
+
+    let mut sample = |title: &str, priority: Priority, due: Option<DateTime<Utc>>, tags: &[&str], context: Option<&str>| {
+        let mut task = Task::new(0, title.to_string(), priority, due);
+        task.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        task.context = context.map(str::to_string);
+        task.created_at = now - Duration::days(6);
+        list.restore_task(task)
+    };
+
+    // 过期
+    sample("Renew passport", Priority::High, Some(day(now, -3)), &["personal"], Some("errands"));
+    sample("Pay electricity bill", Priority::Medium, Some(day(now, -1)), &["home", "bills"], None);
+
+    // 今天和本周到期
+    let report = sample("Prepare quarterly report", Priority::High, Some(day(now, 0)), &["work"], Some("office"));
+    let figures = sample("Collect sales figures", Priority::Medium, Some(day(now, 0)), &["work"], Some("office"));
+    let summary = sample("Draft executive summary", Priority::High, Some(day(now, 0)), &["work"], Some("office"));
+    let standup = sample("Team standup", Priority::Medium, Some(day(now, 0)), &["work"], Some("office"));
+    let groceries = sample("Buy groceries", Priority::Low, Some(day(now, 1)), &["home"], Some("errands"));
+    let plants = sample("Water the plants", Priority::Low, Some(day(now, 2)), &["home"], Some("home"));
+    let reading = sample("Read chapter 8 of the Rust book", Priority::Low, Some(day(now, 4)), &["learning"], None);
+    sample("Plan weekend hike", Priority::Low, Some(day(now, 6)), &["personal"], None);
+
+    // 没有截止日期
+    sample("Book dentist appointment", Priority::Low, None, &["health"], Some("phone"));
+    sample("Sort out the garage", Priority::Low, None, &[], Some("home"));
+
+    // 最近完成
+    let expenses = sample("Submit expense report", Priority::Medium, Some(day(now, -1)), &["work"], None);
+    let call = sample("Call mom", Priority::Medium, None, &["personal"], Some("phone"));
+    let bike = sample("Fix bike tire", Priority::Low, Some(day(now, -4)), &["home"], None);
+
+    for (id, parent) in [(figures, report), (summary, report)] {
+        set(&mut list, id, |task| task.parent_id = Some(parent));
+    }
+    for id in [report, figures, summary] {
+        set(&mut list, id, |task| task.goal_id = Some(goal));
+    }
+    for (id, minutes) in [(figures, 30), (summary, 45), (standup, 15), (groceries, 40), (reading, 60)] {
+        set(&mut list, id, |task| task.estimate_minutes = Some(minutes));
+    }
+    set(&mut list, standup, |task| task.recurrence = Some(Recurrence::Daily));
+    set(&mut list, plants, |task| task.recurrence = Some(Recurrence::Weekly));
+    for (id, days_ago) in [(figures, 0), (expenses, 1), (call, 2), (bike, 4)] {
+        set(&mut list, id, |task| {
+            task.completed = true;
+            task.completed_at = Some(now - Duration::days(days_ago) - Duration::hours(2));
+        });
+    }
+
+    list
+}
+
+/// 距离 `now` 所在日期 `days` 天的那一天结束时（与命令行中 YYYY-MM-DD 格式的截止日期一致）
+fn day(now: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+    end_of_day(local_date(now) + Duration::days(days))
+}
+
+fn set(list: &mut TodoList, id: u32, update: impl FnOnce(&mut Task)) {
+    if let Some(task) = list.find_task_mut(id) {
+        update(task);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doctor;
+
+    #[test]
+    fn test_sample_list_covers_every_view() {
+        let now = Utc::now();
+        let list = sample_list(now);
+        let today = crate::dates::local_date(now);
+
+        assert!(doctor::check(&list).is_empty());
+        assert!(!list.overdue_tasks().is_empty());
+        assert!(!list.tasks_due_on(today).is_empty());
+        assert!(!list.tasks_due_between(now, now + Duration::days(7)).is_empty());
+        assert!(list.list_tasks().iter().any(|task| task.recurrence.is_some()));
+        assert!(list.list_tasks().iter().any(|task| task.context.is_some()));
+        assert!(list.list_tasks().iter().any(|task| !list.children(task.id).is_empty()));
+
+        let completed = list.list_completed_tasks();
+        assert!(completed.len() >= 3);
+        assert!(completed
+            .iter()
+            .all(|task| task.completed_at.is_some_and(|at| now - at < Duration::days(7))));
+
+        let progress = list.goal_progress(list.goals()[0].id);
+        assert!(progress.completed > 0 && progress.completed < progress.total);
+    }
+}
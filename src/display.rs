@@ -1,116 +1,1833 @@
+use crate::dates::{local_date, to_local, today};
+use crate::goal::{Goal, Progress};
+use crate::history::{Event, EventKind};
+use crate::i18n::{t, tf};
+use crate::report::{group_tasks, GroupBy};
+use crate::stats::Stats;
 use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
 use colored::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
-/// 格式化单个任务的显示
-pub fn format_task(task: &Task) -> String {
-    let status = if task.completed {
-        "✓". green().bold()
-    } else {
-        "○".yellow()
+/// 默认日期显示格式
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 默认时间显示格式
+pub const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+/// 日期显示格式（程序启动时根据配置设置一次）
+static DATE_FORMAT: OnceLock<String> = OnceLock::new();
+
+/// 时间显示格式（程序启动时根据配置设置一次）
+static TIME_FORMAT: OnceLock<String> = OnceLock::new();
+
+/// 当前的配色（程序启动时根据配置设置一次）
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// 配色主题
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// 默认彩色输出
+    #[default]
+    Default,
+    /// 高对比度：只使用明亮的颜色，不使用暗淡文字
+    HighContrast,
+    /// 红绿色盲友好：用蓝色和橙色代替红色和绿色（Okabe-Ito 配色）
+    DeuteranopiaSafe,
+    /// 不使用颜色
+    Monochrome,
+}
+
+/// 输出内容的语义角色，由主题决定具体颜色
+///
+/// 配置文件的 `[colors]` 表使用小写的角色名作为键，例如 `high = "magenta"`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 成功、已完成
+    Success,
+    /// 错误
+    Error,
+    /// 警告、待办
+    Warning,
+    /// 提示信息
+    Info,
+    /// 需要突出的值（ID、日期、数量）
+    Accent,
+    /// 过期
+    Overdue,
+    /// 标签
+    Tag,
+    /// 情境（`@home` 等）
+    Context,
+    /// 高优先级
+    High,
+    /// 中优先级
+    Medium,
+    /// 低优先级
+    Low,
+    /// 次要信息
+    Muted,
+    /// 标题
+    Heading,
+}
+
+impl Theme {
+    /// 角色在该主题下的颜色，`None` 表示不着色
+    fn color(self, role: Role) -> Option<Color> {
+        // Okabe-Ito 配色中对红绿色盲可区分的颜色
+        const BLUE: Color = Color::TrueColor { r: 0, g: 114, b: 178 };
+        const SKY_BLUE: Color = Color::TrueColor { r: 86, g: 180, b: 233 };
+        const ORANGE: Color = Color::TrueColor { r: 230, g: 159, b: 0 };
+        const VERMILLION: Color = Color::TrueColor { r: 213, g: 94, b: 0 };
+        const PURPLE: Color = Color::TrueColor { r: 204, g: 121, b: 167 };
+        const YELLOW: Color = Color::TrueColor { r: 240, g: 228, b: 66 };
+
+        let color = match (self, role) {
+            (Theme::Monochrome, _) | (_, Role::Muted | Role::Heading) => return None,
+
+            (Theme::Default, Role::Success) => Color::Green,
+            (Theme::Default, Role::Error | Role::Overdue | Role::High) => Color::Red,
+            (Theme::Default, Role::Warning | Role::Medium) => Color::Yellow,
+            (Theme::Default, Role::Info | Role::Accent) => Color::Cyan,
+            (Theme::Default, Role::Tag) => Color::Magenta,
+            (Theme::Default, Role::Context) => Color::BrightBlue,
+            (Theme::Default, Role::Low) => Color::Blue,
+
+            (Theme::HighContrast, Role::Success) => Color::BrightGreen,
+            (Theme::HighContrast, Role::Error | Role::Overdue | Role::High) => Color::BrightRed,
+            (Theme::HighContrast, Role::Warning | Role::Medium) => Color::BrightYellow,
+            (Theme::HighContrast, Role::Info | Role::Low) => Color::BrightCyan,
+            (Theme::HighContrast, Role::Accent) => Color::BrightWhite,
+            (Theme::HighContrast, Role::Tag) => Color::BrightMagenta,
+            (Theme::HighContrast, Role::Context) => Color::BrightBlue,
+
+            (Theme::DeuteranopiaSafe, Role::Success) => BLUE,
+            (Theme::DeuteranopiaSafe, Role::Error | Role::Overdue | Role::High) => VERMILLION,
+            (Theme::DeuteranopiaSafe, Role::Warning | Role::Medium) => ORANGE,
+            (Theme::DeuteranopiaSafe, Role::Info | Role::Accent | Role::Low) => SKY_BLUE,
+            (Theme::DeuteranopiaSafe, Role::Tag) => PURPLE,
+            (Theme::DeuteranopiaSafe, Role::Context) => YELLOW,
+        };
+        Some(color)
+    }
+
+    /// 按角色为文字着色
+    pub fn paint(self, text: &str, role: Role) -> ColoredString {
+        Palette::from(self).paint(text, role)
+    }
+}
+
+/// 配置文件中的颜色：颜色名（`magenta`、`bright red`）或 `#rrggbb`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeColor(pub Color);
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid color '{}' (use a name like \"magenta\" or \"bright red\", or #rrggbb)", s);
+
+        if let Some(hex) = s.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|part| u8::from_str_radix(part, 16).ok());
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(ThemeColor(Color::TrueColor { r, g, b })),
+                _ => Err(invalid()),
+            };
+        }
+
+        s.replace(['-', '_'], " ").parse().map(ThemeColor).map_err(|_| invalid())
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 配色：主题加上用户在配置中为各角色指定的颜色
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    pub theme: Theme,
+    /// 优先于主题的颜色，单色主题下忽略
+    pub colors: HashMap<Role, ThemeColor>,
+}
+
+impl From<Theme> for Palette {
+    fn from(theme: Theme) -> Self {
+        Palette { theme, colors: HashMap::new() }
+    }
+}
+
+impl Palette {
+    /// 角色的颜色，`None` 表示不着色
+    fn color(&self, role: Role) -> Option<Color> {
+        if self.theme == Theme::Monochrome {
+            return None;
+        }
+        match self.colors.get(&role) {
+            Some(color) => Some(color.0),
+            None => self.theme.color(role),
+        }
+    }
+
+    /// 按角色为文字着色
+    pub fn paint(&self, text: &str, role: Role) -> ColoredString {
+        match self.color(role) {
+            Some(color) => text.color(color),
+            // 高对比度主题下次要信息也保持正常亮度
+            None if role == Role::Muted && self.theme != Theme::HighContrast => text.dimmed(),
+            None => text.normal(),
+        }
+    }
+}
+
+/// 应用配色，只有第一次调用生效
+pub fn apply_palette(palette: Palette) {
+    if palette.theme == Theme::Monochrome {
+        colored::control::set_override(false);
+    }
+    let _ = PALETTE.set(palette);
+}
+
+/// 按当前配色为文字着色，所有终端输出都应通过它选择颜色
+pub fn paint(text: &str, role: Role) -> ColoredString {
+    match PALETTE.get() {
+        Some(palette) => palette.paint(text, role),
+        None => Theme::default().paint(text, role),
+    }
+}
+
+/// 设置日期显示格式（chrono 格式字符串），只有第一次调用生效
+pub fn set_date_format(format: &str) {
+    let _ = DATE_FORMAT.set(format.to_string());
+}
+
+/// 设置时间显示格式（chrono 格式字符串），只有第一次调用生效
+pub fn set_time_format(format: &str) {
+    let _ = TIME_FORMAT.set(format.to_string());
+}
+
+/// 按配置的格式显示日期（本地时区）
+pub fn format_date(date: &DateTime<Utc>) -> String {
+    let format = DATE_FORMAT.get().map(String::as_str).unwrap_or(DEFAULT_DATE_FORMAT);
+    to_local(*date).format(format).to_string()
+}
+
+/// 按配置的格式显示日期和时间（本地时区）
+pub fn format_datetime(date: &DateTime<Utc>) -> String {
+    let format = TIME_FORMAT.get().map(String::as_str).unwrap_or(DEFAULT_TIME_FORMAT);
+    format!("{} {}", format_date(date), to_local(*date).format(format))
+}
+
+/// 是否使用读屏友好的输出（程序启动时根据配置设置一次）
+static ACCESSIBLE: OnceLock<bool> = OnceLock::new();
+
+/// 启用读屏友好的输出：不使用颜色、框线和 emoji，改用明确的文字标签
+pub fn set_accessible(enabled: bool) {
+    let _ = ACCESSIBLE.set(enabled);
+    if enabled {
+        colored::control::set_override(false);
+    }
+}
+
+/// 当前是否使用读屏友好的输出
+pub fn is_accessible() -> bool {
+    ACCESSIBLE.get().copied().unwrap_or(false)
+}
+
+/// 去掉 emoji 和装饰符号，并合并多余的空白，供读屏友好的输出使用
+pub fn plain_text(text: &str) -> String {
+    let is_symbol = |c: char| {
+        matches!(c as u32,
+            0x2190..=0x2BFF     // 箭头、技术符号、方框、几何图形、杂项符号
+            | 0xFE00..=0xFE0F   // 变体选择符
+            | 0x1F000..=0x1FAFF // emoji
+        )
     };
 
-    let priority_str = match task.priority {
-        Priority::High => "HIGH". red().bold(),
-        Priority:: Medium => "MED".yellow(),
-        Priority::Low => "LOW".blue(),
+    text.chars()
+        .filter(|c| !is_symbol(*c))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 是否输出不带样式的纯文本（程序启动时设置一次）
+static PLAIN: OnceLock<bool> = OnceLock::new();
+
+/// 输出不带颜色和 emoji 的纯文本，适合重定向到文件或交给 grep 处理
+pub fn set_plain(enabled: bool) {
+    let _ = PLAIN.set(enabled);
+    if enabled {
+        colored::control::set_override(false);
+    }
+}
+
+/// 当前是否输出纯文本
+pub fn is_plain() -> bool {
+    PLAIN.get().copied().unwrap_or(false)
+}
+
+/// 去掉 emoji 和紧随其后的空格，保留 ✓、○、↳ 和框线等普通符号，不改变其余排版（`to_ascii` 先替换这些符号再调用）
+pub fn strip_emoji(text: &str) -> String {
+    let is_emoji = |c: char| {
+        matches!(c as u32,
+            0x23E9..=0x23FA     // ⏱ ⏳ 等
+            | 0x2600..=0x26FF   // ⚠ 等杂项符号
+            | 0x2705            // ✅
+            | 0x274C            // ❌
+            | 0xFE0F            // emoji 变体选择符
+            | 0x1F000..=0x1FAFF // emoji
+        )
     };
 
-    let title = if task.completed {
-        task.title.strikethrough().dimmed()
+    let mut out = String::with_capacity(text.len());
+    let mut after_emoji = false;
+    for c in text.chars() {
+        if is_emoji(c) {
+            after_emoji = true;
+        } else if !(after_emoji && c == ' ') {
+            after_emoji = false;
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// 是否不输出命令的结果和提示（REST API 执行命令时开启，警告和错误照常输出）
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// 开启或关闭安静模式
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+/// 当前是否处于安静模式
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// 是否只输出 ASCII 符号（程序启动时设置一次）
+static ASCII: OnceLock<bool> = OnceLock::new();
+
+/// 用 ASCII 字符代替 emoji、✓ 和框线，用于无法显示这些符号的终端
+pub fn set_ascii(enabled: bool) {
+    let _ = ASCII.set(enabled);
+}
+
+/// 当前是否只输出 ASCII 符号
+pub fn is_ascii() -> bool {
+    ASCII.get().copied().unwrap_or(false)
+}
+
+/// 把输出中的符号换成 ASCII 字符，没有对应字符的 emoji 直接去掉，任务标题等用户输入的文字不变
+///
+/// 框线和进度条的替换字符宽度相同，不影响对齐。
+pub fn to_ascii(text: &str) -> String {
+    let replaced: String = text
+        .chars()
+        .map(|c| match c {
+            '✓' => "[x]",
+            '○' => "[ ]",
+            '✗' => "x",
+            '📅' => "due:",
+            '⏱' => "est:",
+            '🔁' => "repeats:",
+            '📋' => "*",
+            '⚠' => "!",
+            '↳' => "^",
+            '→' => "->",
+            '─' => "-",
+            '═' => "=",
+            '│' => "|",
+            '█' => "#",
+            '░' => ".",
+            '…' => "~",
+            '\u{FE0F}' => "",
+            _ => return c.to_string(),
+        }
+        .to_string())
+        .collect();
+    strip_emoji(&replaced)
+}
+
+/// 所有终端输出在打印前都经过这里，ASCII 模式（纯文本模式也会开启）下替换符号、去掉 emoji
+fn for_output(text: String) -> String {
+    if is_ascii() {
+        to_ascii(&text)
     } else {
-        task.title.normal()
-    };
+        text
+    }
+}
+
+/// 相对今天描述日期，例如 `today`、`tomorrow`、`in 3 days`，一周以外显示日期
+pub fn describe_date(date: &DateTime<Utc>, today: chrono::NaiveDate) -> String {
+    match (local_date(*date) - today).num_days() {
+        0 => t("today").to_string(),
+        1 => t("tomorrow").to_string(),
+        -1 => t("yesterday").to_string(),
+        days @ 2..=6 => tf("in {} days", &[&days]),
+        days @ -6..=-2 => tf("{} days ago", &[&-days]),
+        _ => format_date(date),
+    }
+}
+
+/// 格式化标题（前面空一行）
+fn format_heading(title: &str) -> String {
+    if is_accessible() {
+        format!("\n{}.\n", plain_text(title))
+    } else {
+        format!("\n{}\n", paint(title, Role::Heading).bold().underline())
+    }
+}
+
+/// 打印标题
+pub fn print_heading(title: &str) {
+    print!("{}", for_output(format_heading(title)));
+}
+
+/// 格式化分隔线（读屏友好的输出中省略）
+fn format_rule() -> String {
+    if is_accessible() {
+        String::new()
+    } else {
+        format!("{}\n", paint(&"─".repeat(60), Role::Muted))
+    }
+}
+
+/// 打印分隔线（读屏友好的输出中省略）
+pub fn print_rule() {
+    print!("{}", for_output(format_rule()));
+}
+
+/// 以完整句子描述单个任务，供读屏友好的输出使用
+///
+/// 例如 `Task 3: Write report. Status: pending. Priority: high. Due: tomorrow. Tags: work.`
+pub fn describe_task(task: &Task) -> String {
+    let mut parts = vec![
+        tf("Task {}: {}", &[&task.id, &task.title]),
+        tf("Status: {}", &[&t(if task.completed { "completed" } else { "pending" })]),
+        tf("Priority: {}", &[&task.priority.to_string().to_lowercase()]),
+    ];
 
-    let due_info = if let Some(due) = task.due_date {
-        let due_str = due.format("%Y-%m-%d").to_string();
+    if let Some(due) = task.due_date {
+        let when = describe_date(&due, today());
         if task.is_overdue() {
-            format!(" 📅 {}", due_str. red())
+            parts.push(tf("Due: {}, overdue", &[&when]));
         } else {
-            format!(" 📅 {}", due_str. cyan())
+            parts.push(tf("Due: {}", &[&when]));
         }
+    }
+    if !task.tags.is_empty() {
+        parts.push(tf("Tags: {}", &[&task.tags.join(", ")]));
+    }
+    if let Some(context) = &task.context {
+        parts.push(tf("Context: {}", &[context]));
+    }
+    if let Some(parent_id) = task.parent_id {
+        parts.push(tf("Subtask of task {}", &[&parent_id]));
+    }
+    if let Some(minutes) = task.estimate_minutes {
+        parts.push(tf("Estimate: {}", &[&format_minutes(minutes)]));
+    }
+    if let Some(recurrence) = task.recurrence {
+        parts.push(tf("Repeats: {}", &[&recurrence]));
+    }
+
+    parts.iter().map(|part| format!("{}.", part)).collect::<Vec<_>>().join(" ")
+}
+
+/// 格式化分钟数，例如 `1h30m`、`45m`
+pub fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+/// 格式化倒计时，例如 `2d 03:14:07 left`，已过期时为 `overdue by 00:05:00`
+pub fn format_countdown(remaining: chrono::Duration) -> String {
+    let seconds = remaining.num_seconds().abs();
+    let (days, hours, minutes, secs) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60,
+    );
+
+    let clock = if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, secs)
     } else {
-        String::new()
+        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
     };
 
+    if remaining < chrono::Duration::zero() {
+        tf("overdue by {}", &[&clock])
+    } else {
+        tf("{} left", &[&clock])
+    }
+}
+
+/// 格式化目标进度条，例如 `[██████░░░░] 3/5 (60%)`
+pub fn format_progress(progress: Progress) -> String {
+    const WIDTH: usize = 10;
+
+    if is_accessible() {
+        return format!(
+            "{} of {} tasks done ({:.0}%)",
+            progress.completed,
+            progress.total,
+            progress.fraction() * 100.0
+        );
+    }
+
+    let filled = (progress.fraction() * WIDTH as f64).round() as usize;
+
     format!(
-        "{} [{}] {} | {}{}",
-        status,
-        format! ("{:3}", task.id).cyan(),
-        priority_str,
-        title,
-        due_info
+        "[{}{}] {}/{} ({:.0}%)",
+        "█".repeat(filled),
+        "░".repeat(WIDTH - filled),
+        progress.completed,
+        progress.total,
+        progress.fraction() * 100.0
     )
 }
 
-/// 打印任务列表
-pub fn print_tasks(tasks: &[&Task], title: &str) {
+/// 父任务的子任务汇总信息和阻塞信息（都没有时为空字符串）
+fn format_rollup(todo_list: &TodoList, task: &Task) -> String {
+    let mut out = match todo_list.subtask_estimate_minutes(task.id) {
+        Some(minutes) if is_accessible() => format!(" Subtask estimate: {}.", format_minutes(minutes)),
+        Some(minutes) => format!(
+            " {}",
+            paint(&format!("({})", tf("subtasks {}", &[&format!("⏱ {}", format_minutes(minutes))])), Role::Muted)
+        ),
+        None => String::new(),
+    };
+    if let Some(blockers) = format_blocker_ids(todo_list, task) {
+        if is_accessible() {
+            out.push_str(&format!(" Blocked by tasks {}.", blockers));
+        } else {
+            out.push_str(&format!(" {}", paint(&format!("({})", tf("blocked by {}", &[&blockers])), Role::Muted)));
+        }
+    }
+    out
+}
+
+/// 任务未完成的依赖 ID，例如 `2, 5`（没有阻塞时为 `None`）
+fn format_blocker_ids(todo_list: &TodoList, task: &Task) -> Option<String> {
+    let blockers: Vec<String> = todo_list.blockers(task.id).iter().map(|blocker| blocker.id.to_string()).collect();
+    (!blockers.is_empty()).then(|| blockers.join(", "))
+}
+
+/// 格式化次要信息
+fn format_muted(text: &str) -> String {
+    if is_accessible() {
+        format!("{}\n", plain_text(text))
+    } else {
+        format!("{}\n", paint(text, Role::Muted))
+    }
+}
+
+/// 渲染任务列表：对齐成 ID、优先级、标题、截止日期和标签几列，标题按 `width` 截断
+pub fn render_tasks(todo_list: &TodoList, tasks: &[&Task], title: &str, width: usize) -> String {
     if tasks.is_empty() {
-        println!("{}", "📭 No tasks found. ".dimmed());
+        return format_muted(&format!("📭 {} ", t("No tasks found.")));
+    }
+
+    let mut out = String::new();
+    out.push_str(&format_heading(title));
+
+    // 读屏软件逐行朗读，不使用表格
+    if is_accessible() {
+        out.push_str(&render_task_rows(todo_list, tasks, width));
+        out.push_str(&format!("{}\n", tf("{} task(s)", &[&tasks.len()])));
+        return out;
+    }
+
+    let table = render_task_table(todo_list, tasks, width);
+    let rule = format!("{}\n", paint(&"─".repeat(table.width), Role::Muted));
+    out.push_str(&rule);
+    out.push_str(&table.text);
+    out.push_str(&rule);
+    out.push_str(&format!("{}\n", tf("{} task(s)", &[&paint(&tasks.len().to_string(), Role::Accent).bold()])));
+    out
+}
+
+/// 按分组渲染任务列表：每组一个小标题（含任务数）和一张表格
+pub fn render_grouped_tasks(
+    todo_list: &TodoList,
+    tasks: &[&Task],
+    title: &str,
+    group_by: GroupBy,
+    width: usize,
+) -> String {
+    if tasks.is_empty() {
+        return format_muted(&format!("📭 {} ", t("No tasks found.")));
+    }
+
+    let mut out = format_heading(title);
+    for (label, group) in group_tasks(tasks, group_by) {
+        out.push_str(&format_subheading(&format!("{} ({})", label, group.len())));
+        out.push_str(&render_task_rows(todo_list, &group, width));
+    }
+
+    out.push('\n');
+    out.push_str(&format!("{}\n", tf("{} task(s)", &[&paint(&tasks.len().to_string(), Role::Accent).bold()])));
+    out
+}
+
+/// 打印分组的任务列表
+pub fn print_grouped_tasks(todo_list: &TodoList, tasks: &[&Task], title: &str, group_by: GroupBy) {
+    print!("{}", for_output(render_grouped_tasks(todo_list, tasks, title, group_by, terminal_width())));
+}
+
+/// 渲染几个任务：与任务列表相同的列，只有表头和任务行（读屏友好的输出中每个任务一句话）
+pub fn render_task_rows(todo_list: &TodoList, tasks: &[&Task], width: usize) -> String {
+    if is_accessible() {
+        return tasks
+            .iter()
+            .map(|task| format!("{}{}\n", describe_task(task), format_rollup(todo_list, task)))
+            .collect();
+    }
+    render_task_table(todo_list, tasks, width).text
+}
+
+/// 渲染好的表格和它的宽度（列数）
+struct Table {
+    text: String,
+    width: usize,
+}
+
+/// 任务表格：先按纯文本补齐每一格再上色，避免颜色代码影响对齐
+fn render_task_table(todo_list: &TodoList, tasks: &[&Task], width: usize) -> Table {
+    const SEPARATOR: &str = "  ";
+    const MIN_TITLE_WIDTH: usize = 10;
+    const MAX_TAGS_WIDTH: usize = 24;
+
+    let (done_symbol, pending_symbol) = if is_ascii() { ("[x]", "[ ]") } else { ("✓", "○") };
+
+    struct Row {
+        status: &'static str,
+        id: String,
+        priority: &'static str,
+        title: String,
+        due: String,
+        tags: String,
+    }
+
+    let rows: Vec<Row> = tasks
+        .iter()
+        .map(|task| {
+            // 子任务在标题前标出父任务，预估耗时、循环和子任务汇总附在标题后
+            let mut title = match task.parent_id {
+                Some(parent_id) => format!("↳{} {}", parent_id, task.title),
+                None => task.title.clone(),
+            };
+            let extras: Vec<String> = [
+                task.estimate_minutes.map(format_minutes),
+                task.recurrence.map(|recurrence| recurrence.to_string()),
+                todo_list
+                    .subtask_estimate_minutes(task.id)
+                    .map(|minutes| format!("subtasks {}", format_minutes(minutes))),
+                format_blocker_ids(todo_list, task).map(|blockers| format!("blocked by {}", blockers)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if !extras.is_empty() {
+                title.push_str(&format!(" ({})", extras.join(", ")));
+            }
+
+            let tags = task
+                .context
+                .iter()
+                .map(|context| format!("@{}", context))
+                .chain(task.tags.iter().map(|tag| format!("#{}", tag)))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Row {
+                status: if task.completed { done_symbol } else { pending_symbol },
+                id: task.id.to_string(),
+                priority: match task.priority {
+                    Priority::High => "HIGH",
+                    Priority::Medium => "MED",
+                    Priority::Low => "LOW",
+                },
+                title,
+                due: task.due_date.as_ref().map(format_date).unwrap_or_default(),
+                tags,
+            }
+        })
+        .collect();
+
+    let column_width = |header: &str, cell: fn(&Row) -> &str| {
+        rows.iter().map(|row| display_width(cell(row))).chain([display_width(header)]).max().unwrap_or(0)
+    };
+    let status_width = display_width(done_symbol);
+    let id_width = column_width("ID", |row| &row.id);
+    let priority_width = column_width(t("Pri"), |row| row.priority);
+    let due_width = column_width(t("Due"), |row| &row.due);
+    let tags_width = column_width(t("Tags"), |row| &row.tags).min(MAX_TAGS_WIDTH);
+
+    // 没有任何任务设置截止日期或标签时省略该列
+    let show_due = rows.iter().any(|row| !row.due.is_empty());
+    let show_tags = rows.iter().any(|row| !row.tags.is_empty());
+
+    let separator = display_width(SEPARATOR);
+    let fixed = status_width
+        + separator
+        + id_width
+        + separator
+        + priority_width
+        + separator
+        + if show_due { separator + due_width } else { 0 }
+        + if show_tags { separator + tags_width } else { 0 };
+    let title_width = column_width(t("Title"), |row| &row.title).min(width.saturating_sub(fixed).max(MIN_TITLE_WIDTH));
+
+    // 每行最后一个非空的格不补齐，避免行尾多余的空格
+    let fit = |text: &str, width: usize, last: bool| {
+        let cell = fit_width(text, width);
+        if last {
+            cell.trim_end().to_string()
+        } else {
+            cell
+        }
+    };
+
+    let mut header = vec![
+        " ".repeat(status_width),
+        format!("{:>width$}", "ID", width = id_width),
+        fit(t("Pri"), priority_width, false),
+        fit(t("Title"), title_width, !show_due && !show_tags),
+    ];
+    if show_due {
+        header.push(fit(t("Due"), due_width, !show_tags));
+    }
+    if show_tags {
+        header.push(t("Tags").to_string());
+    }
+    let mut text = format!("{}\n", paint(&header.join(SEPARATOR), Role::Heading).bold());
+
+    for (row, task) in rows.iter().zip(tasks) {
+        let has_tags = !row.tags.is_empty();
+        let has_due = !row.due.is_empty() || (show_due && has_tags);
+
+        let status = if task.completed {
+            paint(row.status, Role::Success).bold()
+        } else {
+            paint(row.status, Role::Warning)
+        };
+        let priority = fit(row.priority, priority_width, false);
+        let priority = match task.priority {
+            Priority::High => paint(&priority, Role::High).bold(),
+            Priority::Medium => paint(&priority, Role::Medium),
+            Priority::Low => paint(&priority, Role::Low),
+        };
+        let title = fit(&row.title, title_width, !has_due && !has_tags);
+        let title = if task.completed {
+            paint(&title, Role::Muted).strikethrough()
+        } else {
+            title.normal()
+        };
+
+        let mut cells = vec![
+            status.to_string(),
+            paint(&format!("{:>width$}", row.id, width = id_width), Role::Accent).to_string(),
+            priority.to_string(),
+            title.to_string(),
+        ];
+        if has_due {
+            let role = if task.is_overdue() { Role::Overdue } else { Role::Accent };
+            cells.push(paint(&fit(&row.due, due_width, !has_tags), role).to_string());
+        }
+        if has_tags {
+            cells.push(paint(&fit(&row.tags, tags_width, true), Role::Tag).to_string());
+        }
+        text.push_str(&format!("{}\n", cells.join(SEPARATOR)));
+    }
+
+    Table { text, width: fixed + title_width }
+}
+
+/// 打印任务列表
+pub fn print_tasks(todo_list: &TodoList, tasks: &[&Task], title: &str) {
+    if is_quiet() {
         return;
     }
+    print!("{}", for_output(render_tasks(todo_list, tasks, title, terminal_width())));
+}
 
-    println!("\n{}", title. bold().underline());
-    println!("{}", "─".repeat(60).dimmed());
-    
-    for task in tasks {
-        println!("{}", format_task(task));
+/// 打印几个任务（表头和任务行），例如刚完成或批量添加的任务
+pub fn print_task_rows(todo_list: &TodoList, tasks: &[&Task]) {
+    if is_quiet() {
+        return;
     }
-    
-    println!("{}", "─".repeat(60).dimmed());
-    println!("{} task(s)", tasks.len().to_string().cyan().bold());
+    print!("{}", for_output(render_task_rows(todo_list, tasks, terminal_width())));
 }
 
-/// 打印单个任务的详细信息
-pub fn print_task_detail(task: &Task) {
-    println!("\n{}", "Task Details". bold().underline());
-    println!("{}", "─".repeat(60).dimmed());
+/// 打印单个任务：只有一行的任务表格
+pub fn print_task(todo_list: &TodoList, task: &Task) {
+    print_task_rows(todo_list, &[task]);
+}
+
+/// 格式化详情中的一个字段，例如 `Status: Pending`
+fn format_field(label: &'static str, value: impl std::fmt::Display) -> String {
+    let label = t(label);
+    if is_accessible() {
+        format!("{}: {}.\n", label, plain_text(&value.to_string()))
+    } else {
+        format!("{}: {}\n", label.bold(), value)
+    }
+}
+
+/// 渲染单个任务的详细信息
+pub fn render_task_detail(todo_list: &TodoList, task: &Task) -> String {
+    let mut out = String::new();
+    out.push_str(&format_heading(t("Task Details")));
+    out.push_str(&format_rule());
     
-    println!("{}:  {}", "ID".bold(), task.id. to_string().cyan());
-    println!("{}: {}", "Title".bold(), task.title);
+    out.push_str(&format_field("ID", paint(&task.id.to_string(), Role::Accent)));
+    out.push_str(&format_field("UUID", paint(&task.uuid, Role::Muted)));
+    if let Some(uid) = &task.uid {
+        out.push_str(&format_field("UID", paint(uid, Role::Muted)));
+    }
+    out.push_str(&format_field("Title", &task.title));
     
     let status = if task.completed {
-        "Completed ✓".green()
+        paint(t("Completed ✓"), Role::Success)
     } else {
-        "Pending ○".yellow()
+        paint(t("Pending ○"), Role::Warning)
     };
-    println!("{}: {}", "Status". bold(), status);
+    out.push_str(&format_field("Status", status));
     
     let priority_str = match task.priority {
-        Priority::High => "High".red(),
-        Priority::Medium => "Medium".yellow(),
-        Priority::Low => "Low".blue(),
+        Priority::High => paint(t("High"), Role::High),
+        Priority::Medium => paint(t("Medium"), Role::Medium),
+        Priority::Low => paint(t("Low"), Role::Low),
     };
-    println!("{}: {}", "Priority".bold(), priority_str);
+    out.push_str(&format_field("Priority", priority_str));
     
-    println!("{}: {}", "Created". bold(), 
-        task.created_at. format("%Y-%m-%d %H:%M:%S").to_string().dimmed());
+    out.push_str(&format_field("Created", paint(&format_datetime(&task.created_at), Role::Muted)));
+    if let Some(completed_at) = task.completed_at {
+        out.push_str(&format_field("Completed", paint(&format_datetime(&completed_at), Role::Muted)));
+    }
     
     if let Some(due) = task.due_date {
-        let due_str = due.format("%Y-%m-%d %H:%M:%S").to_string();
-        let display = if task.is_overdue() {
-            format!("{} {}", due_str, "(OVERDUE!)".red().bold())
+        let due_str = if is_accessible() {
+            describe_date(&due, today())
+        } else {
+            format_datetime(&due)
+        };
+        let display = if task.is_overdue() && is_accessible() {
+            tf("{}, overdue", &[&due_str])
+        } else if task.is_overdue() {
+            format!("{} {}", due_str, paint(t("(OVERDUE!)"), Role::Overdue).bold())
         } else {
-            due_str.cyan().to_string()
+            paint(&due_str, Role::Accent).to_string()
         };
-        println!("{}: {}", "Due Date".bold(), display);
+        out.push_str(&format_field("Due Date", display));
     } else {
-        println!("{}:  {}", "Due Date".bold(), "None".dimmed());
+        out.push_str(&format_field("Due Date", paint(t("None"), Role::Muted)));
+    }
+
+    if let Some(minutes) = task.estimate_minutes {
+        out.push_str(&format_field("Estimate", paint(&format_minutes(minutes), Role::Accent)));
+    }
+
+    if let Some(minutes) = todo_list.subtask_estimate_minutes(task.id) {
+        out.push_str(&format_field("Subtask Estimate", paint(&format_minutes(minutes), Role::Accent)));
+    }
+
+    if !task.pomodoros.is_empty() {
+        let minutes = task.pomodoros.iter().map(|pomodoro| pomodoro.minutes).sum();
+        let summary = format!("{} ({})", task.pomodoros.len(), format_minutes(minutes));
+        out.push_str(&format_field("Pomodoros", paint(&summary, Role::Accent)));
+    }
+
+    if let Some(recurrence) = task.recurrence {
+        out.push_str(&format_field("Repeats", paint(&recurrence.to_string(), Role::Accent)));
+    }
+
+    if !task.skipped.is_empty() {
+        out.push_str(&format_field("Skipped", paint(&task.skipped.len().to_string(), Role::Muted)));
+    }
+
+    if let Some(parent_id) = task.parent_id {
+        out.push_str(&format_field("Parent", paint(&parent_id.to_string(), Role::Accent)));
+    }
+
+    if !task.depends_on.is_empty() {
+        let dependencies: Vec<String> = task
+            .depends_on
+            .iter()
+            .map(|&id| match todo_list.find_task(id) {
+                Some(dependency) if dependency.completed => tf("{} (done)", &[&id]),
+                _ => id.to_string(),
+            })
+            .collect();
+        let role = if todo_list.is_blocked(task.id) { Role::Warning } else { Role::Accent };
+        out.push_str(&format_field("Depends On", paint(&dependencies.join(", "), role)));
+    }
+
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        out.push_str(&format_field("Tags", paint(&tags.join(" "), Role::Tag)));
+    }
+
+    if let Some(context) = &task.context {
+        out.push_str(&format_field("Context", paint(&format!("@{}", context), Role::Context)));
+    }
+
+    if let Some(goal) = task.goal_id.and_then(|id| todo_list.find_goal(id)) {
+        out.push_str(&format_field("Goal", format!("{} {}", paint(&format!("[{}]", goal.id), Role::Accent), goal.title)));
     }
     
-    println!("{}", "─".repeat(60).dimmed());
+    out.push_str(&format_rule());
+    out
+}
+
+/// 打印单个任务的详细信息
+pub fn print_task_detail(todo_list: &TodoList, task: &Task) {
+    if is_quiet() {
+        return;
+    }
+    print!("{}", for_output(render_task_detail(todo_list, task)));
+}
+
+/// 一条历史记录的简短描述，例如 `priority: medium → high`
+pub fn describe_event(kind: &EventKind) -> String {
+    let show = |value: Option<&str>| value.unwrap_or(t("none")).to_string();
+    match kind {
+        EventKind::Added => t("created").to_string(),
+        EventKind::Completed => t("completed").to_string(),
+        EventKind::Reopened => t("reopened").to_string(),
+        EventKind::Rescheduled { from, to } => tf(
+            "due date: {} → {}",
+            &[
+                &show(from.map(|date| format_date(&date)).as_deref()),
+                &show(to.map(|date| format_date(&date)).as_deref()),
+            ],
+        ),
+        EventKind::Skipped { from, to } => tf(
+            "skipped: {} → {}",
+            &[&show(from.map(|date| format_date(&date)).as_deref()), &format_date(to)],
+        ),
+        EventKind::DefaultsApplied { tag, fields } => tf("defaults from #{}: {}", &[tag, &fields.join(", ")]),
+        EventKind::Edited { field, from, to } => {
+            format!("{}: {} → {}", field, show(from.as_deref()), show(to.as_deref()))
+        }
+        EventKind::Deleted => t("deleted").to_string(),
+        EventKind::Restored => t("restored from the trash").to_string(),
+    }
+}
+
+/// 渲染任务的历史记录（按时间顺序）
+pub fn render_task_history(events: &[&Event]) -> String {
+    let mut out = format_heading(t("History"));
+    out.push_str(&format_rule());
+
+    if events.is_empty() {
+        out.push_str(&format_muted(t("No recorded changes.")));
+    }
+    for event in events {
+        out.push_str(&format!(
+            "{}  {}\n",
+            paint(&format_datetime(&event.at), Role::Muted),
+            describe_event(&event.kind)
+        ));
+    }
+
+    out.push_str(&format_rule());
+    out
+}
+
+/// 打印任务的历史记录
+pub fn print_task_history(events: &[&Event]) {
+    print!("{}", for_output(render_task_history(events)));
+}
+
+/// 格式化单个目标及其进度
+fn format_goal(todo_list: &TodoList, goal: &Goal) -> String {
+    let progress = todo_list.goal_progress(goal.id);
+    let progress_str = if progress.total > 0 && progress.completed == progress.total {
+        paint(&format_progress(progress), Role::Success)
+    } else {
+        format_progress(progress).normal()
+    };
+
+    let due_info = match goal.due_date {
+        Some(due) => format!(" 📅 {}", paint(&format_date(&due), Role::Accent)),
+        None => String::new(),
+    };
+
+    format!(
+        "[{}] {} {}{}",
+        paint(&format!("{:3}", goal.id), Role::Accent),
+        progress_str,
+        goal.title.bold(),
+        due_info
+    )
+}
+
+/// 渲染所有目标的进度
+pub fn render_goals(todo_list: &TodoList) -> String {
+    if todo_list.goals().is_empty() {
+        return format_muted("🎯 No goals yet. ");
+    }
+
+    let mut out = String::new();
+
+    out.push_str(&format_heading("🎯 Goals"));
+    out.push_str(&format_rule());
+
+    for goal in todo_list.goals() {
+        out.push_str(&format!("{}\n", format_goal(todo_list, goal)));
+    }
+
+    out.push_str(&format_rule());
+    out
+}
+
+/// 打印所有目标的进度
+pub fn print_goals(todo_list: &TodoList) {
+    print!("{}", for_output(render_goals(todo_list)));
+}
+
+/// 渲染单个目标的进度和关联的任务
+pub fn render_goal_detail(todo_list: &TodoList, goal: &Goal, width: usize) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("\n{}\n", format_goal(todo_list, goal)));
+    out.push_str(&format_rule());
+
+    let tasks = todo_list.goal_tasks(goal.id);
+    if tasks.is_empty() {
+        out.push_str(&format_muted("No linked tasks. Use `todo goal link <task> <goal>`"));
+    } else {
+        out.push_str(&render_task_rows(todo_list, &tasks, width));
+    }
+
+    out.push_str(&format_rule());
+    out
+}
+
+/// 打印单个目标的进度和关联的任务
+pub fn print_goal_detail(todo_list: &TodoList, goal: &Goal) {
+    print!("{}", for_output(render_goal_detail(todo_list, goal, terminal_width())));
+}
+
+/// 渲染简要统计（任务数量）
+pub fn render_summary(stats: &Stats) -> String {
+    let mut out = String::new();
+    if is_accessible() {
+        out.push_str(&format!("{}:\n", t("Statistics")));
+    } else {
+        out.push_str(&format!("{}\n", paint(&format!("📊 {}", t("Statistics")), Role::Heading).bold()));
+    }
+    out.push_str(&format!("  {}\n", tf("Total:      {}", &[&paint(&stats.total.to_string(), Role::Accent)])));
+    out.push_str(&format!("  {}\n", tf("Pending:   {}", &[&paint(&stats.pending.to_string(), Role::Warning)])));
+    out.push_str(&format!("  {}\n", tf("Completed: {}", &[&paint(&stats.completed.to_string(), Role::Success)])));
+
+    if stats.overdue > 0 {
+        out.push_str(&format!(
+            "  {}\n",
+            tf("Overdue:   {}", &[&paint(&stats.overdue.to_string(), Role::Overdue).bold()])
+        ));
+    }
+    out
+}
+
+/// 打印简要统计（任务数量）
+pub fn print_summary(stats: &Stats) {
+    print!("{}", for_output(render_summary(stats)));
+}
+
+/// 格式化小时数，超过一天时以天为单位，例如 `5.5 hours`、`2.3 days`
+pub fn format_hours(hours: f64) -> String {
+    if hours >= 24.0 {
+        tf("{:.1} days", &[&(hours / 24.0)])
+    } else {
+        tf("{:.1} hours", &[&hours])
+    }
+}
+
+/// 格式化统计中的小标题
+fn format_subheading(title: &str) -> String {
+    if is_accessible() {
+        format!("\n{}:\n", title)
+    } else {
+        format!("\n{}\n", paint(title, Role::Heading).bold())
+    }
+}
+
+/// 渲染完整统计
+pub fn render_stats(stats: &Stats) -> String {
+    let mut out = render_summary(stats);
+    out.push_str(&format!(
+        "  {}: {}\n",
+        t("Completion rate"),
+        paint(&format!("{:.0}%", stats.completion_rate * 100.0), Role::Accent)
+    ));
+
+    let average = match stats.average_hours_to_complete {
+        Some(hours) => paint(&format_hours(hours), Role::Accent),
+        None => paint("n/a", Role::Muted),
+    };
+    out.push_str(&format!("  {}: {}\n", t("Average time to complete"), average));
+    out.push_str(&format!(
+        "  {}: {}\n",
+        t("Streak"),
+        tf(
+            "{} day(s) in a row (best {})",
+            &[&paint(&stats.streak.current.to_string(), Role::Accent), &stats.streak.best]
+        )
+    ));
+    out.push_str(&format!(
+        "  {}: {}\n",
+        t("Estimated work"),
+        tf(
+            "{} due today, {} pending in total",
+            &[
+                &paint(&format_minutes(stats.estimated_minutes_today), Role::Accent),
+                &paint(&format_minutes(stats.estimated_minutes_pending), Role::Accent),
+            ]
+        )
+    ));
+
+    out.push_str(&format_subheading(t("Completed per day")));
+    for day in &stats.completed_per_day {
+        if is_accessible() {
+            out.push_str(&format!("  {}: {} completed.\n", day.date.format("%a %m-%d"), day.completed));
+            continue;
+        }
+        out.push_str(&format!(
+            "  {} {} {}\n",
+            day.date.format("%a %m-%d"),
+            paint(&"█".repeat(day.completed), Role::Success),
+            day.completed
+        ));
+    }
+
+    out.push_str(&format_subheading(t("By priority")));
+    for count in &stats.by_priority {
+        let label = match count.priority {
+            Priority::High => paint("High  ", Role::High),
+            Priority::Medium => paint("Medium", Role::Medium),
+            Priority::Low => paint("Low   ", Role::Low),
+        };
+        out.push_str(&format!("  {} {}\n", label, tf("{} total, {} pending", &[&count.total, &count.pending])));
+    }
+    out
+}
+
+/// 打印完整统计
+pub fn print_stats(stats: &Stats) {
+    print!("{}", for_output(render_stats(stats)));
+}
+
+/// 格式化月历网格（周一开始），每天显示到期的未完成任务数，有过期任务的日期标红
+///
+/// `month` 可以是该月的任意一天
+pub fn format_calendar(todo_list: &TodoList, month: NaiveDate, today: NaiveDate) -> String {
+    const CELL_WIDTH: usize = 6;
+
+    let mut out: String = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+        .iter()
+        .map(|day| format!("{:<width$}", day, width = CELL_WIDTH))
+        .collect::<String>()
+        .bold()
+        .to_string();
+    out.push('\n');
+
+    let first = month.with_day(1).unwrap_or(month);
+    out.push_str(&" ".repeat(CELL_WIDTH * first.weekday().num_days_from_monday() as usize));
+
+    for date in first.iter_days().take_while(|date| date.month() == first.month()) {
+        let due = todo_list.tasks_due_on(date);
+        let count = if due.is_empty() { String::new() } else { format!("({})", due.len()) };
+        let cell = format!("{:>2}{:<width$}", date.day(), count, width = CELL_WIDTH - 2);
+
+        // 先补齐宽度再上色，避免颜色代码影响对齐
+        let cell = if due.iter().any(|task| task.is_overdue()) {
+            paint(&cell, Role::Overdue).bold()
+        } else if date == today {
+            cell.reversed()
+        } else if !due.is_empty() {
+            paint(&cell, Role::Accent)
+        } else {
+            cell.normal()
+        };
+        out.push_str(&cell.to_string());
+
+        if date.weekday() == Weekday::Sun {
+            out.push('\n');
+        }
+    }
+
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// 渲染月历
+pub fn render_calendar(todo_list: &TodoList, month: NaiveDate, today: NaiveDate) -> String {
+    let title = month.format("%B %Y").to_string();
+
+    // 读屏软件无法按网格朗读，只列出有任务到期的日期
+    if is_accessible() {
+        let mut out = format_heading(&title);
+        let first = month.with_day(1).unwrap_or(month);
+        let mut any = false;
+        for date in first.iter_days().take_while(|date| date.month() == first.month()) {
+            let due = todo_list.tasks_due_on(date);
+            if due.is_empty() {
+                continue;
+            }
+            let overdue = if due.iter().any(|task| task.is_overdue()) { ", overdue" } else { "" };
+            out.push_str(&format!("{}: {} task(s) due{}.\n", date.format("%A %B %-d"), due.len(), overdue));
+            any = true;
+        }
+        if !any {
+            out.push_str(&format!("{}\n", t("No tasks due this month.")));
+        }
+        return out;
+    }
+
+    let mut out = format_heading(&format!("📅 {}", title));
+    out.push_str(&format_calendar(todo_list, month, today));
+    out
+}
+
+/// 打印月历
+pub fn print_calendar(todo_list: &TodoList, month: NaiveDate, today: NaiveDate) {
+    print!("{}", for_output(render_calendar(todo_list, month, today)));
+}
+
+/// 渲染日程：先列出之前过期的任务，再按天分组列出范围内到期的任务，每组一张表格
+pub fn render_agenda(
+    todo_list: &TodoList,
+    carried_over: &[&Task],
+    due: &[&Task],
+    title: &str,
+    today: NaiveDate,
+    width: usize,
+) -> String {
+    if carried_over.is_empty() && due.is_empty() {
+        return format_muted(&format!("🎉 {} ", t("Nothing due.")));
+    }
+
+    let mut out = format_heading(title);
+    out.push_str(&format_rule());
+
+    if !carried_over.is_empty() {
+        out.push_str(&format_subheading(t("Overdue")));
+        out.push_str(&render_task_rows(todo_list, carried_over, width));
+    }
+
+    // `due` 按截止日期排序，相邻的同一天的任务放在一组
+    let mut days: Vec<(NaiveDate, Vec<&Task>)> = Vec::new();
+    for &task in due {
+        let Some(day) = task.due_date.map(local_date) else {
+            continue;
+        };
+        match days.last_mut() {
+            Some((current, tasks)) if *current == day => tasks.push(task),
+            _ => days.push((day, vec![task])),
+        }
+    }
+    for (day, tasks) in days {
+        let label = match (day - today).num_days() {
+            0 => t("Today").to_string(),
+            1 => t("Tomorrow").to_string(),
+            _ => day.format("%A %m-%d").to_string(),
+        };
+        out.push_str(&format_subheading(&label));
+        out.push_str(&render_task_rows(todo_list, &tasks, width));
+    }
+
+    out.push_str(&format_rule());
+    out
+}
+
+/// 打印日程
+pub fn print_agenda(todo_list: &TodoList, carried_over: &[&Task], due: &[&Task], title: &str, today: NaiveDate) {
+    print!("{}", for_output(render_agenda(todo_list, carried_over, due, title, today, terminal_width())));
+}
+
+/// 未设置 `COLUMNS` 时假定的终端宽度
+pub const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+/// 终端宽度（读取 `COLUMNS` 环境变量）
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|&width| width > 0)
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// 字符在终端中占用的列数：中日韩文字和 emoji 占两列，变体选择符不占列
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0xFE00..=0xFE0F | 0x200B..=0x200F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// 文字在终端中占用的列数
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// 把文字截断或补齐到恰好 `width` 列，截断时以 `…` 结尾
+pub fn fit_width(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+
+    if display_width(text) > width {
+        for c in text.chars() {
+            if used + char_width(c) + 1 > width {
+                break;
+            }
+            out.push(c);
+            used += char_width(c);
+        }
+        if width > 0 {
+            out.push('…');
+            used += 1;
+        }
+    } else {
+        out.push_str(text);
+        used = display_width(text);
+    }
+
+    out.push_str(&" ".repeat(width.saturating_sub(used)));
+    out
+}
+
+/// 看板中的一列
+pub struct BoardColumn<'a> {
+    /// 列标题，例如 `Pending`
+    pub title: &'a str,
+    pub tasks: Vec<&'a Task>,
+}
+
+/// 渲染看板：各列并排显示，按 `width` 平均分配列宽
+pub fn render_board(columns: &[BoardColumn], width: usize) -> String {
+    const SEPARATOR: &str = " │ ";
+    const MIN_COLUMN_WIDTH: usize = 12;
+
+    // 读屏软件无法按列朗读，逐列列出任务
+    if is_accessible() {
+        let mut out = String::new();
+        for column in columns {
+            out.push_str(&format!("\n{} column, {} task(s).\n", column.title, column.tasks.len()));
+            for task in &column.tasks {
+                out.push_str(&format!("{}\n", describe_task(task)));
+            }
+        }
+        return out;
+    }
+
+    if columns.is_empty() {
+        return String::new();
+    }
+
+    let separators = display_width(SEPARATOR) * (columns.len() - 1);
+    let column_width = (width.saturating_sub(separators) / columns.len()).max(MIN_COLUMN_WIDTH);
+    let join = |cells: Vec<String>| format!("{}\n", cells.join(&paint(SEPARATOR, Role::Muted).to_string()));
+
+    let mut out = join(
+        columns
+            .iter()
+            .map(|column| {
+                let header = format!("{} ({})", column.title, column.tasks.len());
+                fit_width(&header, column_width).bold().to_string()
+            })
+            .collect(),
+    );
+    out.push_str(&join(
+        columns
+            .iter()
+            .map(|_| paint(&"─".repeat(column_width), Role::Muted).to_string())
+            .collect(),
+    ));
+
+    let rows = columns.iter().map(|column| column.tasks.len()).max().unwrap_or(0);
+    for row in 0..rows {
+        let cells = columns
+            .iter()
+            .map(|column| match column.tasks.get(row) {
+                // 先补齐宽度再上色，避免颜色代码影响对齐
+                Some(task) => {
+                    let cell = fit_width(&format!("[{}] {}", task.id, task.title), column_width);
+                    let role = if task.completed {
+                        Role::Muted
+                    } else if task.is_overdue() {
+                        Role::Overdue
+                    } else {
+                        match task.priority {
+                            Priority::High => Role::High,
+                            Priority::Medium => Role::Medium,
+                            Priority::Low => Role::Low,
+                        }
+                    };
+                    paint(&cell, role).to_string()
+                }
+                None => " ".repeat(column_width),
+            })
+            .collect();
+        out.push_str(&join(cells));
+    }
+
+    out
+}
+
+/// 打印看板
+pub fn print_board(columns: &[BoardColumn], width: usize) {
+    print!("{}", for_output(render_board(columns, width)));
+}
+
+/// 提示消息的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// 操作成功
+    Success,
+    /// 错误（输出到标准错误）
+    Error,
+    /// 警告
+    Warning,
+    /// 提示信息
+    Info,
+}
+
+/// 格式化一行提示消息（不含换行）
+pub fn format_message(kind: MessageKind, message: &str) -> String {
+    if is_accessible() {
+        let label = match kind {
+            MessageKind::Success => t("Success"),
+            MessageKind::Error => t("Error"),
+            MessageKind::Warning => t("Warning"),
+            MessageKind::Info => t("Info"),
+        };
+        return format!("{}: {}", label, plain_text(message));
+    }
+
+    let (symbol, role) = match (kind, is_ascii()) {
+        (MessageKind::Success, false) => ("✓", Role::Success),
+        (MessageKind::Error, false) => ("✗", Role::Error),
+        (MessageKind::Warning, false) => ("⚠", Role::Warning),
+        (MessageKind::Info, false) => ("ℹ", Role::Info),
+        (MessageKind::Success, true) => ("[ok]", Role::Success),
+        (MessageKind::Error, true) => ("[error]", Role::Error),
+        (MessageKind::Warning, true) => ("[warn]", Role::Warning),
+        (MessageKind::Info, true) => ("[info]", Role::Info),
+    };
+    // 信息消息的正文保持默认颜色
+    let text = if kind == MessageKind::Info { message.normal() } else { paint(message, role) };
+    format!("{} {}", paint(symbol, role).bold(), text)
 }
 
 /// 打印成功消息
 pub fn print_success(message: &str) {
-    println!("{} {}", "✓".green().bold(), message.green());
+    if is_quiet() {
+        return;
+    }
+    println!("{}", for_output(format_message(MessageKind::Success, message)));
 }
 
 /// 打印错误消息
 pub fn print_error(message: &str) {
-    eprintln!("{} {}", "✗".red().bold(), message.red());
+    eprintln!("{}", for_output(format_message(MessageKind::Error, message)));
+}
+
+/// 打印警告消息
+pub fn print_warning(message: &str) {
+    println!("{}", for_output(format_message(MessageKind::Warning, message)));
+}
+
+/// 渲染严重过期提醒的横幅（不依赖颜色）
+pub fn render_critical_alert(tasks: &[&Task]) -> String {
+    if is_accessible() {
+        let mut out = format!("{}\n", tf("Alert: {} task(s) critically overdue.", &[&tasks.len()]));
+        for task in tasks {
+            out.push_str(&format!("  Task {}: {}.\n", task.id, task.title));
+        }
+        return out;
+    }
+
+    let rule = format!("{}\n", paint(&"═".repeat(60), Role::Overdue).bold());
+    let banner = format!("🚨 {} 🚨", tf("{} TASK(S) CRITICALLY OVERDUE", &[&tasks.len()]));
+    let mut out = rule.clone();
+    out.push_str(&format!("{}\n", paint(&banner, Role::Overdue).bold()));
+    for task in tasks {
+        out.push_str(&format!("  {} {}\n", format!("[{}]", task.id).bold(), task.title.bold()));
+    }
+    out.push_str(&rule);
+    out
+}
+
+/// 打印严重过期提醒：响铃并显示醒目的横幅
+pub fn print_critical_alert(tasks: &[&Task]) {
+    if tasks.is_empty() {
+        return;
+    }
+
+    print!("\x07{}", for_output(render_critical_alert(tasks)));
 }
 
 /// 打印信息消息
 pub fn print_info(message: &str) {
-    println!("{} {}", "ℹ". cyan().bold(), message);
+    if is_quiet() {
+        return;
+    }
+    println!("{}", for_output(format_message(MessageKind::Info, message)));
+}
+
+/// 打印空行，分隔消息和随后的任务
+pub fn print_blank_line() {
+    if !is_quiet() {
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::local_time;
+    use chrono::Duration;
+
+    /// 快照测试使用的固定数据，日期远离当前时间以保证输出稳定
+    fn sample_list() -> TodoList {
+        let at = |year, month, day| local_time(year, month, day, 23, 59, 59);
+
+        let mut list = TodoList::new();
+        let report = list.add_task("写周报".to_string(), Priority::High, Some(at(2099, 1, 5)));
+        let rent = list.add_task("交房租".to_string(), Priority::Medium, Some(at(2020, 1, 1)));
+        let milk = list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.set_estimate(report, Some(90)).unwrap();
+        list.set_recurrence(rent, Some(crate::task::Recurrence::Monthly)).unwrap();
+        list.complete_task(milk).unwrap();
+
+        let goal = list.add_goal("发布 v1".to_string(), Some(at(2099, 2, 1)));
+        list.set_goal(report, Some(goal)).unwrap();
+        list.set_goal(milk, Some(goal)).unwrap();
+
+        let task = list.find_task_mut(report).unwrap();
+        task.tags = vec!["work".to_string()];
+        task.context = Some("office".to_string());
+        task.uuid = "00000000-0000-4000-8000-000000000001".to_string();
+        task.created_at = local_time(2025, 1, 1, 9, 30, 0);
+
+        list
+    }
+
+    /// 快照测试比较纯文本输出（快照文件在 `src/snapshots/` 中，修改输出后用 `cargo insta review` 更新）
+    fn without_color() {
+        colored::control::set_override(false);
+    }
+
+    #[test]
+    fn test_render_tasks_snapshot() {
+        without_color();
+        let list = sample_list();
+        let tasks: Vec<&Task> = list.list_tasks().iter().collect();
+
+        insta::assert_snapshot!("tasks", render_tasks(&list, &tasks, "📋 All Tasks", 80));
+        assert_eq!(render_tasks(&list, &[], "📋 All Tasks", 80), "📭 No tasks found. \n");
+    }
+
+    #[test]
+    fn test_render_tasks_truncates_long_titles() {
+        without_color();
+        let mut list = TodoList::new();
+        list.add_task("一个非常非常非常长的任务标题".to_string(), Priority::Low, None);
+        list.add_task("short".to_string(), Priority::High, None);
+        let tasks: Vec<&Task> = list.list_tasks().iter().collect();
+
+        let output = render_tasks(&list, &tasks, "Tasks", 24);
+        let rows: Vec<&str> = output.lines().skip(3).take(3).collect();
+        assert_eq!(rows, ["   ID  Pri   Title", "○   1  LOW   一个非常非…", "○   2  HIGH  short"]);
+    }
+
+    #[test]
+    fn test_render_task_detail_snapshot() {
+        without_color();
+        let list = sample_list();
+
+        insta::assert_snapshot!("task_detail", render_task_detail(&list, &list.list_tasks()[0]));
+    }
+
+    #[test]
+    fn test_describe_event() {
+        let due = local_time(2025, 1, 5, 23, 59, 59);
+        assert_eq!(
+            describe_event(&EventKind::Rescheduled { from: None, to: Some(due) }),
+            "due date: none → 2025-01-05"
+        );
+        let edited = EventKind::Edited {
+            field: "priority".to_string(),
+            from: Some("low".to_string()),
+            to: Some("high".to_string()),
+        };
+        assert_eq!(describe_event(&edited), "priority: low → high");
+        assert_eq!(describe_event(&EventKind::Added), "created");
+    }
+
+    #[test]
+    fn test_render_goals_snapshot() {
+        without_color();
+        let list = sample_list();
+
+        insta::assert_snapshot!("goals", render_goals(&list));
+        insta::assert_snapshot!("goal_detail", render_goal_detail(&list, &list.goals()[0], 80));
+    }
+
+    #[test]
+    fn test_render_stats_snapshot() {
+        without_color();
+        let mut list = sample_list();
+        let milk = list.find_task_mut(3).unwrap();
+        milk.created_at = local_time(2025, 1, 13, 12, 0, 0);
+        milk.completed_at = Some(local_time(2025, 1, 14, 12, 0, 0));
+        let stats = crate::stats::compute(&list, NaiveDate::from_ymd_opt(2025, 1, 15).unwrap());
+
+        insta::assert_snapshot!("stats", render_stats(&stats));
+    }
+
+    #[test]
+    fn test_render_calendar_snapshot() {
+        without_color();
+        let list = sample_list();
+        let month = NaiveDate::from_ymd_opt(2099, 1, 1).unwrap();
+
+        insta::assert_snapshot!("calendar", render_calendar(&list, month, NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()));
+    }
+
+    #[test]
+    fn test_render_agenda_snapshot() {
+        without_color();
+        let list = sample_list();
+        let report = list.find_task(1).unwrap();
+        let rent = list.find_task(2).unwrap();
+        let today = NaiveDate::from_ymd_opt(2099, 1, 4).unwrap();
+
+        insta::assert_snapshot!("agenda", render_agenda(&list, &[rent], &[report], "📆 This Week", today, 80));
+        assert_eq!(render_agenda(&list, &[], &[], "📆 Today", today, 80), "🎉 Nothing due. \n");
+    }
+
+    #[test]
+    fn test_fit_width() {
+        assert_eq!(display_width("写周报"), 6);
+        assert_eq!(display_width("⚠️ ok"), 4);
+        assert_eq!(fit_width("abc", 5), "abc  ");
+        assert_eq!(fit_width("abcdef", 4), "abc…");
+        assert_eq!(fit_width("写周报和月报", 7), "写周报…");
+        assert_eq!(fit_width("写周报和月报", 8), "写周报… ");
+    }
+
+    #[test]
+    fn test_render_board_snapshot() {
+        without_color();
+        let list = sample_list();
+        let columns = [
+            BoardColumn { title: "Pending", tasks: list.list_pending_tasks() },
+            BoardColumn { title: "Done", tasks: list.list_completed_tasks() },
+        ];
+
+        insta::assert_snapshot!("board", render_board(&columns, 33));
+    }
+
+    #[test]
+    fn test_messages_snapshot() {
+        without_color();
+        let list = sample_list();
+
+        assert_eq!(format_message(MessageKind::Success, "Task added"), "✓ Task added");
+        assert_eq!(format_message(MessageKind::Error, "Not found"), "✗ Not found");
+        assert_eq!(format_message(MessageKind::Warning, "Careful"), "⚠ Careful");
+        assert_eq!(format_message(MessageKind::Info, "Nothing to do"), "ℹ Nothing to do");
+
+        insta::assert_snapshot!("critical_alert", render_critical_alert(&list.overdue_tasks()));
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        let remaining = Duration::days(2) + Duration::hours(3) + Duration::minutes(14) + Duration::seconds(7);
+        assert_eq!(format_countdown(remaining), "2d 03:14:07 left");
+        assert_eq!(format_countdown(Duration::seconds(59)), "00:00:59 left");
+        assert_eq!(format_countdown(Duration::minutes(-5)), "overdue by 00:05:00");
+    }
+
+    const ALL_ROLES: [Role; 12] = [
+        Role::Success,
+        Role::Error,
+        Role::Warning,
+        Role::Info,
+        Role::Accent,
+        Role::Overdue,
+        Role::Tag,
+        Role::Context,
+        Role::High,
+        Role::Medium,
+        Role::Low,
+        Role::Muted,
+    ];
+
+    #[test]
+    fn test_deuteranopia_safe_avoids_red_and_green() {
+        for role in ALL_ROLES {
+            let color = Theme::DeuteranopiaSafe.paint("x", role).fgcolor;
+            assert!(!matches!(
+                color,
+                Some(Color::Red | Color::Green | Color::BrightRed | Color::BrightGreen)
+            ));
+        }
+        assert_ne!(
+            Theme::DeuteranopiaSafe.paint("x", Role::Success).fgcolor,
+            Theme::DeuteranopiaSafe.paint("x", Role::Error).fgcolor
+        );
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        assert_eq!(Theme::Default.paint("x", Role::Error).fgcolor, Some(Color::Red));
+        assert_eq!(Theme::HighContrast.paint("x", Role::Error).fgcolor, Some(Color::BrightRed));
+        for role in ALL_ROLES {
+            assert_eq!(Theme::Monochrome.paint("x", role).fgcolor, None);
+        }
+
+        // 高对比度主题不使用暗淡文字
+        assert_ne!(Theme::Default.paint("x", Role::Muted).style, Style::default());
+        assert_eq!(Theme::HighContrast.paint("x", Role::Muted).style, Style::default());
+    }
+
+    #[test]
+    fn test_palette_overrides_theme_colors() {
+        let palette = Palette {
+            theme: Theme::Default,
+            colors: HashMap::from([(Role::High, ThemeColor(Color::Magenta))]),
+        };
+        assert_eq!(palette.paint("x", Role::High).fgcolor, Some(Color::Magenta));
+        // 没有覆盖的角色使用主题颜色
+        assert_eq!(palette.paint("x", Role::Low).fgcolor, Some(Color::Blue));
+
+        // 单色主题下忽略覆盖
+        let palette = Palette { theme: Theme::Monochrome, ..palette };
+        assert_eq!(palette.paint("x", Role::High).fgcolor, None);
+    }
+
+    #[test]
+    fn test_parse_theme_color() {
+        assert_eq!("bright_red".parse(), Ok(ThemeColor(Color::BrightRed)));
+        assert_eq!("#E69F00".parse(), Ok(ThemeColor(Color::TrueColor { r: 230, g: 159, b: 0 })));
+        assert!("#E69F0".parse::<ThemeColor>().is_err());
+        assert!("mauve".parse::<ThemeColor>().is_err());
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(plain_text("📭 No tasks found. "), "No tasks found.");
+        assert_eq!(plain_text("⚠️  Overdue Tasks"), "Overdue Tasks");
+        assert_eq!(plain_text("Done ✓"), "Done");
+        assert_eq!(plain_text("买牛奶 #home"), "买牛奶 #home");
+    }
+
+    #[test]
+    fn test_describe_date() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+        let at = |day| local_time(2025, 1, day, 12, 0, 0);
+        assert_eq!(describe_date(&at(10), today), "today");
+        assert_eq!(describe_date(&at(11), today), "tomorrow");
+        assert_eq!(describe_date(&at(9), today), "yesterday");
+        assert_eq!(describe_date(&at(13), today), "in 3 days");
+        assert_eq!(describe_date(&at(7), today), "3 days ago");
+        assert_eq!(describe_date(&at(20), today), "2025-01-20");
+    }
+
+    #[test]
+    fn test_describe_task() {
+        let due = Utc::now() + Duration::days(1);
+        let mut task = Task::new(3, "写周报".to_string(), Priority::High, Some(due));
+        task.tags = vec!["work".to_string()];
+        assert_eq!(
+            describe_task(&task),
+            "Task 3: 写周报. Status: pending. Priority: high. Due: tomorrow. Tags: work."
+        );
+
+        task.due_date = Some(Utc::now() - Duration::days(1));
+        task.tags.clear();
+        assert!(describe_task(&task).ends_with("Due: yesterday, overdue."));
+    }
+
+    #[test]
+    fn test_format_hours() {
+        assert_eq!(format_hours(5.5), "5.5 hours");
+        assert_eq!(format_hours(54.0), "2.2 days");
+    }
+
+    #[test]
+    fn test_format_calendar() {
+        let mut list = TodoList::new();
+        let due = |day| local_time(2025, 1, day, 23, 59, 59);
+        list.add_task("交房租".to_string(), Priority::High, Some(due(5)));
+        list.add_task("写周报".to_string(), Priority::Low, Some(due(5)));
+        let done = list.add_task("已完成".to_string(), Priority::Low, Some(due(20)));
+        list.complete_task(done).unwrap();
+
+        let month = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        let calendar = format_calendar(&list, month, month);
+        let lines: Vec<&str> = calendar.lines().collect();
+
+        // 2025 年 1 月 1 日是周三，共 5 周
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1].starts_with(&" ".repeat(12)));
+        assert!(calendar.contains(" 5(2) "));
+        assert!(calendar.contains("20    "));
+        assert!(lines[5].contains("31"));
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        assert_eq!(strip_emoji("✅ Task added (ID: 1)"), "Task added (ID: 1)");
+        assert_eq!(strip_emoji("✗ ❌ Task not found"), "✗ Task not found");
+        assert_eq!(strip_emoji("写周报 #work 📅 2099-01-05 ⏱️ 1h30m"), "写周报 #work 2099-01-05 1h30m");
+        // 状态符号和表格边框不是 emoji，保留
+        assert_eq!(strip_emoji("✓ ○ ↳ │"), "✓ ○ ↳ │");
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("✓ [  1] HIGH | 写周报 📅 2099-01-05 ⏱️ 1h30m"), "[x] [  1] HIGH | 写周报 due: 2099-01-05 est: 1h30m");
+        assert_eq!(to_ascii("📋 All Tasks"), "* All Tasks");
+        assert_eq!(to_ascii("🎯 Goals"), "Goals");
+        assert_eq!(to_ascii("[██░░] │ ──"), "[##..] | --");
+        assert!(to_ascii("⚠️  Overdue ↳3 → 🚨").is_ascii());
+    }
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(format_progress(Progress { completed: 3, total: 5 }), "[██████░░░░] 3/5 (60%)");
+        assert_eq!(format_progress(Progress { completed: 0, total: 0 }), "[░░░░░░░░░░] 0/0 (0%)");
+    }
 }
@@ -0,0 +1,269 @@
+use crate::backups::{self, Backup};
+use crate::display::format_date;
+use crate::error::TodoResult;
+use crate::storage::{is_encrypted_file, load_from_file_with_format, verify_checksum, StorageFormat};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// `doctor` 检查发现的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// 子任务的截止日期晚于父任务
+    ChildDueAfterParent {
+        child: u32,
+        parent: u32,
+        child_due: DateTime<Utc>,
+        parent_due: DateTime<Utc>,
+    },
+    /// 子任务指向的父任务不存在
+    MissingParent { child: u32, parent: u32 },
+    /// 任务依赖的任务不存在
+    MissingDependency { task: u32, dependency: u32 },
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::ChildDueAfterParent {
+                child,
+                parent,
+                child_due,
+                parent_due,
+            } => write!(
+                f,
+                "Task {} is due {} but its parent {} is due {}",
+                child,
+                format_date(child_due),
+                parent,
+                format_date(parent_due)
+            ),
+            Issue::MissingParent { child, parent } => {
+                write!(f, "Task {} refers to missing parent {}", child, parent)
+            }
+            Issue::MissingDependency { task, dependency } => {
+                write!(f, "Task {} depends on missing task {}", task, dependency)
+            }
+        }
+    }
+}
+
+/// 检查待办列表中的数据问题
+///
+/// 已完成的子任务不参与截止日期检查。
+pub fn check(todo_list: &TodoList) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for task in todo_list.list_tasks() {
+        for &dependency in &task.depends_on {
+            if todo_list.find_task(dependency).is_none() {
+                issues.push(Issue::MissingDependency {
+                    task: task.id,
+                    dependency,
+                });
+            }
+        }
+
+        let Some(parent_id) = task.parent_id else {
+            continue;
+        };
+
+        let Some(parent) = todo_list.find_task(parent_id) else {
+            issues.push(Issue::MissingParent {
+                child: task.id,
+                parent: parent_id,
+            });
+            continue;
+        };
+
+        if let (Some(child_due), Some(parent_due)) = (task.due_date, parent.due_date) {
+            if !task.completed && child_due > parent_due {
+                issues.push(Issue::ChildDueAfterParent {
+                    child: task.id,
+                    parent: parent_id,
+                    child_due,
+                    parent_due,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// 修复可以自动修复的问题，返回修复的数量
+///
+/// - 截止日期晚于父任务的子任务，截止日期收紧为父任务的截止日期
+/// - 父任务不存在的子任务提升为顶层任务
+/// - 移除对不存在的任务的依赖
+pub fn fix(todo_list: &mut TodoList, issues: &[Issue]) -> usize {
+    let mut fixed = 0;
+
+    for issue in issues {
+        match issue {
+            Issue::ChildDueAfterParent {
+                child, parent_due, ..
+            } => {
+                if let Some(task) = todo_list.find_task_mut(*child) {
+                    task.due_date = Some(*parent_due);
+                    task.touch();
+                    fixed += 1;
+                }
+            }
+            Issue::MissingParent { child, .. } => {
+                if let Some(task) = todo_list.find_task_mut(*child) {
+                    task.parent_id = None;
+                    task.touch();
+                    fixed += 1;
+                }
+            }
+            Issue::MissingDependency { task, dependency } => {
+                if todo_list.remove_dependency(*task, *dependency).unwrap_or(false) {
+                    fixed += 1;
+                }
+            }
+        }
+    }
+
+    fixed
+}
+
+/// 数据文件的健康状况
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHealth {
+    /// 文件大小（字节），文件还不存在时为 `None`
+    pub size: Option<u64>,
+    /// 文件已加密（需要口令，不检查内容）
+    pub encrypted: bool,
+    /// 内容无法解析时的错误
+    pub error: Option<String>,
+    /// 内容与保存时记下的校验和是否一致，没有校验和时为 `None`
+    pub checksum_ok: Option<bool>,
+    /// 备份数量
+    pub backups: usize,
+    /// 最近一个可以正常读取的备份
+    pub latest_valid_backup: Option<Backup>,
+}
+
+impl FileHealth {
+    /// 内容可以解析且校验和没有不一致
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none() && self.checksum_ok != Some(false)
+    }
+}
+
+/// 检查数据文件本身：能否解析、校验和是否一致，以及有没有可用的备份
+pub fn check_file(path: &Path, format: StorageFormat) -> TodoResult<FileHealth> {
+    let size = path.metadata().ok().map(|metadata| metadata.len());
+    let encrypted = is_encrypted_file(path)?;
+    let error = if size.is_some() && !encrypted {
+        load_from_file_with_format(path, format).err().map(|err| err.to_string())
+    } else {
+        None
+    };
+    let checksum_ok = if size.is_some() { verify_checksum(path)? } else { None };
+
+    Ok(FileHealth {
+        size,
+        encrypted,
+        error,
+        checksum_ok,
+        backups: backups::list(path)?.len(),
+        latest_valid_backup: backups::latest_valid(path, format)?.map(|(backup, _)| backup),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use chrono::Duration;
+
+    #[test]
+    fn test_child_due_after_parent() {
+        let mut list = TodoList::new();
+        let parent_due = Utc::now();
+        let parent = list.add_task("发布".to_string(), Priority::High, Some(parent_due));
+        let late = list
+            .add_subtask(parent, "写文档".to_string(), Priority::Low, Some(parent_due + Duration::days(2)))
+            .unwrap();
+        list.add_subtask(parent, "写代码".to_string(), Priority::Low, Some(parent_due - Duration::days(2)))
+            .unwrap();
+
+        let issues = check(&list);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], Issue::ChildDueAfterParent { child, .. } if child == late));
+
+        assert_eq!(fix(&mut list, &issues), 1);
+        assert_eq!(list.find_task(late).unwrap().due_date, Some(parent_due));
+        assert!(check(&list).is_empty());
+    }
+
+    #[test]
+    fn test_completed_child_is_ignored() {
+        let mut list = TodoList::new();
+        let parent_due = Utc::now();
+        let parent = list.add_task("发布".to_string(), Priority::High, Some(parent_due));
+        let child = list
+            .add_subtask(parent, "写文档".to_string(), Priority::Low, Some(parent_due + Duration::days(2)))
+            .unwrap();
+        list.complete_task(child).unwrap();
+
+        assert!(check(&list).is_empty());
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let mut list = TodoList::new();
+        let id = list.add_task("孤儿".to_string(), Priority::Low, None);
+        list.find_task_mut(id).unwrap().parent_id = Some(42);
+
+        let issues = check(&list);
+        assert_eq!(issues, vec![Issue::MissingParent { child: id, parent: 42 }]);
+
+        fix(&mut list, &issues);
+        assert_eq!(list.find_task(id).unwrap().parent_id, None);
+    }
+
+    #[test]
+    fn test_missing_dependency() {
+        let mut list = TodoList::new();
+        let id = list.add_task("部署".to_string(), Priority::Low, None);
+        list.find_task_mut(id).unwrap().depends_on = vec![42];
+
+        let issues = check(&list);
+        assert_eq!(issues, vec![Issue::MissingDependency { task: id, dependency: 42 }]);
+
+        assert_eq!(fix(&mut list, &issues), 1);
+        assert!(list.find_task(id).unwrap().depends_on.is_empty());
+    }
+
+    #[test]
+    fn test_check_file() {
+        use std::fs;
+
+        let dir = std::env::temp_dir().join(format!("todo-doctor-{}", std::process::id()));
+        let path = dir.join("todos.json");
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(check_file(&path, StorageFormat::Json).unwrap().size, None);
+
+        let mut list = TodoList::new();
+        list.add_task("写周报".to_string(), Priority::High, None);
+        crate::storage::save_to_file(&list, &path).unwrap();
+        let health = check_file(&path, StorageFormat::Json).unwrap();
+        assert!(health.is_healthy());
+        assert_eq!(health.checksum_ok, Some(true));
+
+        // 保存时备份，然后损坏数据文件
+        backups::rotate(&path, 3).unwrap();
+        fs::write(&path, "{\"tasks\": [").unwrap();
+        let health = check_file(&path, StorageFormat::Json).unwrap();
+        assert!(!health.is_healthy());
+        assert!(health.error.is_some());
+        assert_eq!(health.checksum_ok, Some(false));
+        assert_eq!(health.backups, 1);
+        assert!(health.latest_valid_backup.is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,9 @@
+use crate::i18n::{t, tf};
 use std::fmt;
 
 /// 自定义错误类型
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum TodoError {
     /// 任务未找到
     TaskNotFound(u32),
@@ -9,10 +11,68 @@ pub enum TodoError {
     IoError(std::io::Error),
     /// JSON 序列化/反序列化错误
     SerdeError(serde_json:: Error),
+    /// 没有标题匹配的任务
+    NoMatchingTask(String),
+    /// 有多个标题匹配的任务（匹配文字，候选任务的 ID 和标题）
+    AmbiguousMatch(String, Vec<(u32, String)>),
+    /// 目标未找到
+    GoalNotFound(u32),
+    /// 回收站中没有该任务
+    NotInTrash(u32),
+    /// 模板未找到
+    TemplateNotFound(String),
     /// 无效的优先级
     InvalidPriority(String),
     /// 无效的日期格式
     InvalidDateFormat(String),
+    /// 无效的月份
+    InvalidMonth(String),
+    /// 无效的时间
+    InvalidTime(String),
+    /// 无效的预估耗时
+    InvalidEstimate(String),
+    /// 无效的查询表达式
+    InvalidQuery(String),
+    /// 无效的正则表达式（表达式，原因）
+    InvalidRegex(String, String),
+    /// 无效的推迟目标
+    InvalidPostpone(String),
+    /// 无效的时间段
+    InvalidPeriod(String),
+    /// 无效的重复周期
+    InvalidRecurrence(String),
+    /// 无效的 RRULE 重复规则（原因）
+    InvalidRRule(String),
+    /// 任务不是循环任务
+    NotRecurring(u32),
+    /// 循环任务的重复规则已经结束，没有下一次
+    RecurrenceEnded(u32),
+    /// 任务存在子任务，无法直接删除（任务 ID，子任务数量）
+    TaskHasChildren(u32, usize),
+    /// 添加依赖会形成环（任务 ID，依赖的任务 ID）
+    DependencyCycle(u32, u32),
+    /// 任务依赖的任务尚未完成（任务 ID，未完成的依赖 ID）
+    TaskBlocked(u32, Vec<u32>),
+    /// 配置文件无效
+    ConfigError(String),
+    /// 与远端服务同步失败
+    SyncError(String),
+    /// 发送桌面通知失败
+    NotificationError(String),
+    /// 备份包无效
+    BundleError(String),
+    /// 数据文件的版本比当前程序支持的更新
+    UnsupportedVersion(u32),
+    /// 数据文件的结构无效
+    InvalidDataFile(String),
+    /// 数据文件路径无效（没有文件名）
+    InvalidPath(std::path::PathBuf),
+    /// 文件不存在
+    FileNotFound(std::path::PathBuf),
+    /// 指定时间戳的备份不存在
+    BackupNotFound(String),
+    /// 加密或解密失败
+    EncryptionError(String),
     /// 其他自定义错误
     Custom(String),
 }
@@ -22,27 +82,144 @@ impl fmt::Display for TodoError {
     fn fmt(&self, f: &mut fmt:: Formatter<'_>) -> fmt::Result {
         match self {
             TodoError::TaskNotFound(id) => {
-                write!(f, "❌ Task with ID {} not found", id)
+                write!(f, "❌ {}", tf("Task with ID {} not found", &[&id]))
+            }
+            TodoError::NoMatchingTask(pattern) => {
+                write!(f, "❌ {}", tf("No task matches '{}'", &[&pattern]))
+            }
+            TodoError::AmbiguousMatch(pattern, candidates) => {
+                write!(f, "❌ {}", tf("'{}' matches {} tasks:", &[&pattern, &candidates.len()]))?;
+                for (id, title) in candidates {
+                    write!(f, "\n  [{}] {}", id, title)?;
+                }
+                write!(f, "\n{}", t("Use a more specific text or the task ID"))
+            }
+            TodoError::GoalNotFound(id) => {
+                write!(f, "❌ {}", tf("Goal with ID {} not found", &[&id]))
+            }
+            TodoError::NotInTrash(id) => {
+                write!(f, "❌ {}", tf("Task {} is not in the trash. See `todo trash list`", &[&id]))
+            }
+            TodoError::TemplateNotFound(name) => {
+                write!(f, "❌ {}", tf("Template '{}' not found. See `todo template list`", &[&name]))
             }
             TodoError::IoError(err) => {
-                write!(f, "❌ File operation failed: {}", err)
+                write!(f, "❌ {}", tf("File operation failed: {}", &[&err]))
             }
             TodoError::SerdeError(err) => {
-                write!(f, "❌ JSON parsing failed: {}", err)
+                write!(f, "❌ {}", tf("JSON parsing failed: {}", &[&err]))
             }
             TodoError::InvalidPriority(priority) => {
-                write!(f, "❌ Invalid priority '{}'.  Use:  high, medium, or low", priority)
+                write!(f, "❌ {}", tf("Invalid priority '{}'.  Use:  high, medium, or low", &[&priority]))
             }
             TodoError::InvalidDateFormat(date) => {
-                write!(f, "❌ Invalid date format '{}'. Expected:  YYYY-MM-DD", date)
+                write!(f, "❌ {}", tf("Invalid date format '{}'. Expected:  YYYY-MM-DD", &[&date]))
+            }
+            TodoError::InvalidQuery(msg) => {
+                write!(f, "❌ {}", tf("Invalid query: {}", &[&msg]))
+            }
+            TodoError::InvalidRegex(pattern, reason) => {
+                write!(f, "❌ {}", tf("Invalid regular expression '{}': {}", &[&pattern, &reason]))
+            }
+            TodoError::InvalidMonth(month) => {
+                write!(f, "❌ {}", tf("Invalid month '{}'. Expected: YYYY-MM", &[&month]))
+            }
+            TodoError::InvalidTime(time) => {
+                write!(f, "❌ {}", tf("Invalid time '{}'. Expected: HH:MM", &[&time]))
+            }
+            TodoError::InvalidEstimate(estimate) => {
+                write!(f, "❌ {}", tf("Invalid estimate '{}'. Expected e.g. 30m, 2h or 1h30m", &[&estimate]))
+            }
+            TodoError::InvalidPostpone(to) => {
+                write!(f, "❌ {}", tf("Invalid postpone target '{}'. Expected YYYY-MM-DD or e.g. +1d, +2w", &[&to]))
+            }
+            TodoError::InvalidPeriod(period) => {
+                write!(f, "❌ {}", tf("Invalid period '{}'. Expected e.g. today, yesterday, 7d, 2w, 24h or YYYY-MM-DD", &[&period]))
+            }
+            TodoError::InvalidRecurrence(recurrence) => {
+                write!(
+                    f,
+                    "❌ {}",
+                    tf(
+                        "Invalid recurrence '{}'. Use: daily, weekly, monthly, or an RRULE such as FREQ=WEEKLY;BYDAY=MO,WE",
+                        &[&recurrence]
+                    )
+                )
+            }
+            TodoError::InvalidRRule(reason) => {
+                write!(f, "❌ {}", tf("Invalid RRULE: {}", &[&reason]))
+            }
+            TodoError::NotRecurring(id) => {
+                write!(f, "❌ {}", tf("Task {} is not a recurring task", &[&id]))
+            }
+            TodoError::RecurrenceEnded(id) => {
+                write!(f, "❌ {}", tf("Task {} has no further occurrences: its repeat rule has ended", &[&id]))
+            }
+            TodoError::TaskHasChildren(id, count) => {
+                write!(f, "❌ {}", tf("Task {} has {} subtask(s). Use --cascade or --orphan", &[&id, &count]))
+            }
+            TodoError::DependencyCycle(id, on) => {
+                write!(f, "❌ {}", tf("Task {} cannot depend on task {}: that would create a cycle", &[&id, &on]))
+            }
+            TodoError::TaskBlocked(id, blockers) => {
+                let blockers: Vec<String> = blockers.iter().map(u32::to_string).collect();
+                write!(
+                    f,
+                    "❌ {}",
+                    tf(
+                        "Task {} is blocked by unfinished task(s) {}. Use --force to complete it anyway",
+                        &[&id, &blockers.join(", ")]
+                    )
+                )
+            }
+            TodoError::ConfigError(msg) => {
+                write!(f, "❌ {}", tf("Invalid config: {}", &[&msg]))
+            }
+            TodoError::SyncError(msg) => {
+                write!(f, "❌ {}", tf("Sync failed: {}", &[&msg]))
+            }
+            TodoError::NotificationError(msg) => {
+                write!(f, "❌ {}", tf("Failed to send notification: {}", &[&msg]))
+            }
+            TodoError::BundleError(msg) => {
+                write!(f, "❌ {}", tf("Invalid bundle: {}", &[&msg]))
+            }
+            TodoError::UnsupportedVersion(version) => {
+                write!(f, "❌ {}", tf("Data file version {} is newer than supported. Please upgrade todo", &[&version]))
+            }
+            TodoError::InvalidDataFile(msg) => {
+                write!(f, "❌ {}", tf("Invalid data file: {}", &[&msg]))
+            }
+            TodoError::InvalidPath(path) => {
+                write!(f, "❌ {}", tf("Invalid data file path '{}'", &[&path.display()]))
+            }
+            TodoError::FileNotFound(path) => {
+                write!(f, "❌ {}", tf("File '{}' does not exist", &[&path.display()]))
+            }
+            TodoError::BackupNotFound(timestamp) => {
+                write!(f, "❌ {}", tf("No backup with timestamp '{}'. See `todo backup list`", &[&timestamp]))
+            }
+            TodoError::EncryptionError(msg) => {
+                write!(f, "❌ {}", tf("Encryption error: {}", &[&msg]))
             }
             TodoError::Custom(msg) => {
-                write!(f, "❌ Error: {}", msg)
+                write!(f, "❌ {}", tf("Error: {}", &[&msg]))
             }
         }
     }
 }
 
+impl TodoError {
+    /// 是否是数据文件内容损坏导致的错误（无法解析），而不是文件无法访问或口令错误
+    pub fn is_corruption(&self) -> bool {
+        match self {
+            TodoError::SerdeError(_) | TodoError::InvalidDataFile(_) => true,
+            TodoError::IoError(err) => err.kind() == std::io::ErrorKind::InvalidData,
+            _ => false,
+        }
+    }
+}
+
 /// 实现标准库的 Error trait
 impl std::error::Error for TodoError {
     fn source(&self) -> Option<&(dyn std:: error::Error + 'static)> {
@@ -98,6 +275,15 @@ mod tests {
         assert!(error_msg.contains("Something went wrong"));
     }
 
+    #[test]
+    fn test_file_errors() {
+        let error = TodoError::InvalidPath(std::path::PathBuf::from("/"));
+        assert!(error.to_string().contains("Invalid data file path '/'"));
+
+        let error = TodoError::BackupNotFound("20250101-000000".to_string());
+        assert!(error.to_string().contains("20250101-000000"));
+    }
+
     #[test]
     fn test_error_conversion_from_io() {
         let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
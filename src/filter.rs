@@ -0,0 +1,250 @@
+use crate::dates::{local_date, today};
+use crate::query::Query;
+use crate::task::{Priority, Task};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+
+/// 按完成状态筛选
+#[derive(clap::ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusFilter {
+    /// 所有任务
+    #[default]
+    All,
+    /// 待办任务（未完成）
+    Pending,
+    /// 已完成任务
+    Completed,
+    /// 过期任务
+    Overdue,
+    /// 今天（本地日期）到期的未完成任务
+    Today,
+    /// 本周（周一到周日）到期的未完成任务
+    Week,
+}
+
+impl StatusFilter {
+    /// 任务是否处于该状态
+    pub fn matches(self, task: &Task) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Pending => !task.completed,
+            StatusFilter::Completed => task.completed,
+            StatusFilter::Overdue => task.is_overdue(),
+            StatusFilter::Today => !task.completed && task.due_date.is_some_and(|due| local_date(due) == today()),
+            StatusFilter::Week => {
+                let today = today();
+                let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+                !task.completed
+                    && task
+                        .due_date
+                        .is_some_and(|due| (monday..monday + Duration::weeks(1)).contains(&local_date(due)))
+            }
+        }
+    }
+}
+
+/// 列表排序方式
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// 按 ID（创建顺序）
+    #[default]
+    Id,
+    /// 按截止日期，最早的在前，没有截止日期的排在最后
+    Due,
+    /// 按优先级，高优先级在前
+    Priority,
+    /// 按紧急程度（综合优先级、截止日期和创建至今的时间），最紧急的在前
+    Urgency,
+}
+
+impl SortKey {
+    /// 按该方式排序，相同时保持原有顺序
+    pub fn sort(self, tasks: &mut [&Task]) {
+        match self {
+            SortKey::Id => tasks.sort_by_key(|task| task.id),
+            SortKey::Due => tasks.sort_by_key(|task| (task.due_date.is_none(), task.due_date)),
+            SortKey::Priority => tasks.sort_by_key(|task| match task.priority {
+                Priority::High => 0,
+                Priority::Medium => 1,
+                Priority::Low => 2,
+            }),
+            SortKey::Urgency => {
+                let now = Utc::now();
+                tasks.sort_by(|a, b| b.urgency(now).total_cmp(&a.urgency(now)));
+            }
+        }
+    }
+}
+
+/// 列表筛选条件，所有设置了的条件必须同时满足
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListFilter {
+    pub status: StatusFilter,
+    pub priority: Option<Priority>,
+    /// 截止日期早于这一天（不含当天），没有截止日期的任务不匹配
+    pub due_before: Option<NaiveDate>,
+    /// 截止日期晚于这一天（不含当天），没有截止日期的任务不匹配
+    pub due_after: Option<NaiveDate>,
+    /// 标签名（不含 `#`，不区分大小写）
+    pub tag: Option<String>,
+    /// 情境名（不含 `@`，不区分大小写）
+    pub context: Option<String>,
+    /// 查询表达式
+    pub query: Option<Query>,
+}
+
+impl ListFilter {
+    /// 任务是否满足所有条件
+    pub fn matches(&self, task: &Task) -> bool {
+        self.status.matches(task)
+            && self.priority.is_none_or(|priority| task.priority == priority)
+            && self
+                .due_before
+                .is_none_or(|date| task.due_date.is_some_and(|due| local_date(due) < date))
+            && self
+                .due_after
+                .is_none_or(|date| task.due_date.is_some_and(|due| local_date(due) > date))
+            && self
+                .tag
+                .as_deref()
+                .is_none_or(|tag| task.tags.iter().any(|task_tag| task_tag.eq_ignore_ascii_case(tag)))
+            && self.context.as_deref().is_none_or(|context| {
+                task.context
+                    .as_deref()
+                    .is_some_and(|task_context| task_context.eq_ignore_ascii_case(context))
+            })
+            && self.query.as_ref().is_none_or(|query| query.matches(task))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::local_time;
+
+    fn task(id: u32, priority: Priority, due: Option<(i32, u32, u32)>, tags: &[&str]) -> Task {
+        let due = due.map(|(y, m, d)| local_time(y, m, d, 12, 0, 0));
+        let mut task = Task::new(id, format!("任务 {}", id), priority, due);
+        task.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn test_default_matches_everything() {
+        let filter = ListFilter::default();
+        assert!(filter.matches(&task(1, Priority::Low, None, &[])));
+    }
+
+    #[test]
+    fn test_criteria_are_combined_with_and() {
+        let filter = ListFilter {
+            status: StatusFilter::Pending,
+            priority: Some(Priority::High),
+            due_before: NaiveDate::from_ymd_opt(2025, 7, 1),
+            due_after: None,
+            tag: Some("work".to_string()),
+            context: None,
+            query: None,
+        };
+
+        assert!(filter.matches(&task(1, Priority::High, Some((2025, 6, 30)), &["Work"])));
+        // 任意一个条件不满足都不匹配
+        assert!(!filter.matches(&task(2, Priority::Low, Some((2025, 6, 30)), &["work"])));
+        assert!(!filter.matches(&task(3, Priority::High, Some((2025, 7, 1)), &["work"])));
+        assert!(!filter.matches(&task(4, Priority::High, None, &["work"])));
+        assert!(!filter.matches(&task(5, Priority::High, Some((2025, 6, 30)), &["home"])));
+
+        let mut done = task(6, Priority::High, Some((2025, 6, 30)), &["work"]);
+        done.completed = true;
+        assert!(!filter.matches(&done));
+    }
+
+    #[test]
+    fn test_query_is_combined_with_other_criteria() {
+        let filter = ListFilter {
+            status: StatusFilter::Pending,
+            query: Some("tag:work OR tag:home".parse().unwrap()),
+            ..ListFilter::default()
+        };
+
+        assert!(filter.matches(&task(1, Priority::Low, None, &["home"])));
+        assert!(!filter.matches(&task(2, Priority::Low, None, &["garden"])));
+
+        let mut done = task(3, Priority::Low, None, &["work"]);
+        done.completed = true;
+        assert!(!filter.matches(&done));
+    }
+
+    #[test]
+    fn test_sort_by_due_puts_undated_last() {
+        let undated = task(1, Priority::High, None, &[]);
+        let later = task(2, Priority::Low, Some((2025, 7, 2)), &[]);
+        let sooner = task(3, Priority::Low, Some((2025, 7, 1)), &[]);
+        let mut tasks = vec![&undated, &later, &sooner];
+
+        SortKey::Due.sort(&mut tasks);
+        assert_eq!(tasks.iter().map(|task| task.id).collect::<Vec<_>>(), [3, 2, 1]);
+
+        SortKey::Priority.sort(&mut tasks);
+        assert_eq!(tasks.iter().map(|task| task.id).collect::<Vec<_>>(), [1, 3, 2]);
+
+        // 两个过期很久的任务比没有截止日期的高优先级任务更紧急
+        SortKey::Urgency.sort(&mut tasks);
+        assert_eq!(tasks.iter().map(|task| task.id).collect::<Vec<_>>(), [3, 2, 1]);
+    }
+
+    #[test]
+    fn test_due_window() {
+        let filter = ListFilter {
+            due_after: NaiveDate::from_ymd_opt(2025, 6, 30),
+            due_before: NaiveDate::from_ymd_opt(2025, 7, 4),
+            ..ListFilter::default()
+        };
+
+        // 两端的日期都不包含在内
+        assert!(!filter.matches(&task(1, Priority::Low, Some((2025, 6, 30)), &[])));
+        assert!(filter.matches(&task(2, Priority::Low, Some((2025, 7, 1)), &[])));
+        assert!(filter.matches(&task(3, Priority::Low, Some((2025, 7, 3)), &[])));
+        assert!(!filter.matches(&task(4, Priority::Low, Some((2025, 7, 4)), &[])));
+        assert!(!filter.matches(&task(5, Priority::Low, None, &[])));
+    }
+
+    #[test]
+    fn test_today_and_week() {
+        let today = today();
+        let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+        let due = |date: NaiveDate| Some((date.year(), date.month(), date.day()));
+
+        let due_today = task(1, Priority::Low, due(today), &[]);
+        let start_of_week = task(2, Priority::Low, due(monday), &[]);
+        let end_of_week = task(3, Priority::Low, due(monday + Duration::days(6)), &[]);
+        let next_week = task(4, Priority::Low, due(monday + Duration::days(7)), &[]);
+        let mut done = task(5, Priority::Low, due(today), &[]);
+        done.completed = true;
+
+        assert!(StatusFilter::Today.matches(&due_today));
+        assert!(!StatusFilter::Today.matches(&next_week));
+        assert!(!StatusFilter::Today.matches(&done));
+        assert!(!StatusFilter::Today.matches(&task(6, Priority::Low, None, &[])));
+
+        for task in [&due_today, &start_of_week, &end_of_week] {
+            assert!(StatusFilter::Week.matches(task), "task {} is due this week", task.id);
+        }
+        assert!(!StatusFilter::Week.matches(&next_week));
+        assert!(!StatusFilter::Week.matches(&done));
+    }
+
+    #[test]
+    fn test_context_filter() {
+        let filter = ListFilter {
+            context: Some("home".to_string()),
+            ..ListFilter::default()
+        };
+
+        let mut at_home = task(1, Priority::Low, None, &[]);
+        at_home.context = Some("Home".to_string());
+        assert!(filter.matches(&at_home));
+        assert!(!filter.matches(&task(2, Priority::Low, None, &[])));
+    }
+}
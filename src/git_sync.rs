@@ -0,0 +1,310 @@
+use crate::error::{TodoError, TodoResult};
+use crate::merge::MergeReport;
+use crate::storage::{checksum_path, parse_with_format, serialize_for_path, write_data_file, StorageFormat};
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// 没有配置时使用的远端名
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/// Git 同步结果
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GitSyncReport {
+    /// 是否提交了本地修改
+    pub committed: bool,
+    /// 远端是否存在（不存在时只在本地提交）
+    pub has_remote: bool,
+    /// 是否合并了远端的新提交
+    pub pulled: bool,
+    /// 两边都有新提交时三方合并数据文件的结果
+    pub resolved: Option<MergeReport>,
+}
+
+/// 通过数据文件所在的 git 仓库同步
+///
+/// 1. 提交数据文件和它的校验和文件的本地修改（目录中的其他文件不受影响）
+/// 2. 远端存在时拉取远端分支：能快进时直接快进；两边都有新提交时不依赖按行合并 JSON，
+///    而是以共同祖先为基础按任务 UUID 三方合并两边的数据文件（见 [`crate::todo_list::TodoList::merge`]），
+///    其他文件冲突时放弃合并并报错
+/// 3. 推送到远端
+pub fn sync_git(data_path: &Path, format: StorageFormat, remote: &str, message: &str) -> TodoResult<GitSyncReport> {
+    // 以 `-` 开头的远端名会被 git fetch / push 当作选项
+    if remote.is_empty() || remote.starts_with('-') {
+        return Err(TodoError::SyncError(format!("invalid git remote name '{}'", remote)));
+    }
+
+    let dir = match data_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = data_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| TodoError::SyncError(format!("invalid data file path {}", data_path.display())))?;
+
+    if !git(dir, &["rev-parse", "--is-inside-work-tree"])?.status.success() {
+        return Err(TodoError::SyncError(format!(
+            "{} is not a git repository. Run `git init` there first",
+            dir.display()
+        )));
+    }
+
+    let mut report = GitSyncReport::default();
+
+    // 1. 提交本地修改，校验和文件一起提交，拉取后两者仍然一致
+    if data_path.exists() {
+        let files = tracked_files(data_path, file_name);
+        let with_files = |args: &[&'static str]| -> Vec<&str> {
+            args.iter().copied().chain(files.iter().map(String::as_str)).collect()
+        };
+        git_ok(dir, &with_files(&["add", "--"]))?;
+        if !git(dir, &with_files(&["diff", "--cached", "--quiet", "--"]))?.status.success() {
+            let mut commit = vec!["commit", "--quiet", "-m", message, "--"];
+            commit.extend(files.iter().map(String::as_str));
+            git_ok(dir, &commit)?;
+            report.committed = true;
+        }
+    }
+
+    if !git(dir, &["remote", "get-url", remote])?.status.success() {
+        return Ok(report);
+    }
+    report.has_remote = true;
+
+    let branch = git_ok(dir, &["symbolic-ref", "--short", "HEAD"])?;
+    let tracking = format!("{}/{}", remote, branch);
+
+    // 2. 拉取并合并，远端还没有这个分支时直接推送
+    git_ok(dir, &["fetch", "--quiet", remote])?;
+    if git(dir, &["rev-parse", "--verify", "--quiet", &tracking])?.status.success() {
+        let head = current_head(dir)?;
+        let is_ancestor = |ancestor: &str, of: &str| -> TodoResult<bool> {
+            Ok(git(dir, &["merge-base", "--is-ancestor", ancestor, of])?.status.success())
+        };
+
+        // 新克隆的空仓库还没有提交，直接快进到远端分支
+        if head.is_none() {
+            git_ok(dir, &["merge", "--quiet", "--ff-only", &tracking])?;
+        } else if is_ancestor(&tracking, "HEAD")? {
+            // 远端没有新提交
+        } else if is_ancestor("HEAD", &tracking)? {
+            git_ok(dir, &["merge", "--quiet", "--ff-only", &tracking])?;
+        } else {
+            report.resolved = Some(merge_diverged(dir, data_path, file_name, format, &tracking)?);
+        }
+        report.pulled = current_head(dir)? != head;
+    }
+
+    // 3. 推送
+    git_ok(dir, &["push", "--quiet", remote, &branch])?;
+    Ok(report)
+}
+
+/// 同步时提交的文件：数据文件，以及存在时它的校验和文件
+fn tracked_files(data_path: &Path, file_name: &str) -> Vec<String> {
+    let mut files = vec![file_name.to_string()];
+    if checksum_path(data_path).exists() {
+        files.push(checksum_file_name(data_path));
+    }
+    files
+}
+
+/// 校验和文件的文件名（与数据文件在同一目录）
+fn checksum_file_name(data_path: &Path) -> String {
+    checksum_path(data_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// 两边都有新提交时合并远端分支，数据文件按 UUID 合并后完成合并提交
+fn merge_diverged(
+    dir: &Path,
+    data_path: &Path,
+    file_name: &str,
+    format: StorageFormat,
+    tracking: &str,
+) -> TodoResult<MergeReport> {
+    // 有冲突时 git merge 返回失败，冲突的文件在下面检查
+    git(dir, &["merge", "--quiet", "--no-commit", "--no-ff", tracking])?;
+
+    // 冲突列表中的路径相对于仓库根目录，数据目录可能只是仓库的子目录；
+    // 校验和文件随数据文件重新生成，它的冲突也可以忽略
+    let prefix = git_ok(dir, &["rev-parse", "--show-prefix"])?;
+    let checksum_name = checksum_file_name(data_path);
+    let expected = [format!("{}{}", prefix, file_name), format!("{}{}", prefix, checksum_name)];
+    let conflicted = git_ok(dir, &["diff", "--name-only", "--diff-filter=U"])?;
+    if conflicted.lines().any(|path| !expected.iter().any(|expected| expected == path)) {
+        git(dir, &["merge", "--abort"])?;
+        return Err(TodoError::SyncError(format!(
+            "merge conflicts in files other than {}:\n{}",
+            file_name, conflicted
+        )));
+    }
+
+    // 某一边没有数据文件时按空列表处理
+    let version = |rev: &str| -> TodoResult<Vec<u8>> {
+        let output = git(dir, &["show", &format!("{}:./{}", rev, file_name)])?;
+        Ok(output.stdout)
+    };
+    let merged = (|| {
+        let mut ours = parse_with_format(&version("HEAD")?, format)?;
+        let theirs = parse_with_format(&version(tracking)?, format)?;
+        // 共同祖先中的版本作为三方合并的基础，可以识别两边各自删除的任务
+        let base = match git(dir, &["merge-base", "HEAD", tracking])? {
+            output if output.status.success() => {
+                let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                Some(parse_with_format(&version(&commit)?, format)?)
+            }
+            _ => None,
+        };
+        let report = ours.merge(&theirs, base.as_ref());
+        Ok::<_, TodoError>((serialize_for_path(&ours, file_name, format)?, report))
+    })();
+    let (content, report) = match merged {
+        Ok(merged) => merged,
+        Err(err) => {
+            git(dir, &["merge", "--abort"])?;
+            return Err(err);
+        }
+    };
+
+    write_data_file(&dir.join(file_name), &content)?;
+    git_ok(dir, &["add", "--", file_name, &checksum_name])?;
+    git_ok(dir, &["commit", "--quiet", "--no-edit"])?;
+    Ok(report)
+}
+
+/// 当前提交，还没有任何提交时返回 `None`
+fn current_head(dir: &Path) -> TodoResult<Option<String>> {
+    let output = git(dir, &["rev-parse", "--verify", "--quiet", "HEAD"])?;
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+/// 在 `dir` 中运行 git，不检查退出状态
+fn git(dir: &Path, args: &[&str]) -> TodoResult<Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|err| TodoError::SyncError(format!("could not run git: {}", err)))
+}
+
+/// 在 `dir` 中运行 git，失败时返回标准错误中的信息，成功时返回去掉首尾空白的标准输出
+fn git_ok(dir: &Path, args: &[&str]) -> TodoResult<String> {
+    let output = git(dir, args)?;
+    if !output.status.success() {
+        return Err(TodoError::SyncError(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{load_from_file, save_to_file, verify_checksum};
+    use crate::task::Priority;
+    use crate::todo_list::TodoList;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// 临时目录中的一个裸仓库和两个克隆，测试结束时删除
+    struct Repos {
+        root: PathBuf,
+    }
+
+    impl Repos {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("todo-git-sync-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            git_ok(&root, &["init", "--quiet", "--bare", "remote.git"]).unwrap();
+            for clone in ["laptop", "desktop"] {
+                git_ok(&root, &["clone", "--quiet", "remote.git", clone]).unwrap();
+                let dir = root.join(clone);
+                git_ok(&dir, &["config", "user.name", "todo"]).unwrap();
+                git_ok(&dir, &["config", "user.email", "todo@example.com"]).unwrap();
+            }
+            Self { root }
+        }
+
+        fn data_path(&self, clone: &str) -> PathBuf {
+            self.root.join(clone).join("todos.json")
+        }
+
+        fn sync(&self, clone: &str) -> GitSyncReport {
+            sync_git(&self.data_path(clone), StorageFormat::Json, DEFAULT_REMOTE, "Update tasks").unwrap()
+        }
+    }
+
+    impl Drop for Repos {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn test_not_a_repository() {
+        let dir = std::env::temp_dir().join(format!("todo-git-sync-plain-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let result = sync_git(&dir.join("todos.json"), StorageFormat::Json, DEFAULT_REMOTE, "Update tasks");
+        assert!(matches!(result, Err(TodoError::SyncError(msg)) if msg.contains("not a git repository")));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sync_through_remote_and_resolve_conflict() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let repos = Repos::new("conflict");
+
+        let mut list = TodoList::new();
+        list.add_task("写周报".to_string(), Priority::Low, None);
+        save_to_file(&list, repos.data_path("laptop")).unwrap();
+        let report = repos.sync("laptop");
+        assert!(report.committed && report.has_remote && !report.pulled);
+
+        let report = repos.sync("desktop");
+        assert!(report.pulled && !report.committed);
+        assert_eq!(load_from_file(repos.data_path("desktop")).unwrap().len(), 1);
+        // 校验和文件随数据文件一起提交，拉取后两者一致
+        assert_eq!(verify_checksum(repos.data_path("desktop")).unwrap(), Some(true));
+
+        // 两台机器同时修改，合并时数据文件冲突
+        let mut laptop = load_from_file(repos.data_path("laptop")).unwrap();
+        laptop.add_task("买牛奶".to_string(), Priority::Low, None);
+        save_to_file(&laptop, repos.data_path("laptop")).unwrap();
+        repos.sync("laptop");
+
+        let mut desktop = load_from_file(repos.data_path("desktop")).unwrap();
+        desktop.set_priority(1, Priority::High).unwrap();
+        desktop.add_task("开会".to_string(), Priority::Low, None);
+        save_to_file(&desktop, repos.data_path("desktop")).unwrap();
+        let report = repos.sync("desktop");
+        assert_eq!(report.resolved.map(|resolved| resolved.added.len()), Some(1));
+
+        let merged = load_from_file(repos.data_path("desktop")).unwrap();
+        let titles: Vec<&str> = merged.list_tasks().iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, ["写周报", "开会", "买牛奶"]);
+        assert_eq!(merged.find_task(1).unwrap().priority, Priority::High);
+
+        repos.sync("laptop");
+        assert_eq!(load_from_file(repos.data_path("laptop")).unwrap().len(), 3);
+        assert_eq!(verify_checksum(repos.data_path("laptop")).unwrap(), Some(true));
+    }
+
+    #[test]
+    fn test_remote_name_is_not_an_option() {
+        let result = sync_git(Path::new("todos.json"), StorageFormat::Json, "--upload-pack=touch", "Update tasks");
+        assert!(matches!(result, Err(TodoError::SyncError(msg)) if msg.contains("invalid git remote name")));
+    }
+}
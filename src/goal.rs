@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 目标：一组任务共同服务的较大结果，例如「发布 v1」
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goal {
+    /// 目标 ID（与任务 ID 分开编号）
+    pub id: u32,
+    /// 目标标题
+    pub title: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 截止日期（可选）
+    pub due_date: Option<DateTime<Utc>>,
+}
+
+impl Goal {
+    /// 创建新目标
+    pub fn new(id: u32, title: String, due_date: Option<DateTime<Utc>>) -> Self {
+        Self {
+            id,
+            title,
+            created_at: Utc::now(),
+            due_date,
+        }
+    }
+}
+
+/// 目标的完成进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// 已完成的关联任务数
+    pub completed: usize,
+    /// 关联任务总数
+    pub total: usize,
+}
+
+impl Progress {
+    /// 完成比例（0.0 ~ 1.0），没有关联任务时为 0
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.completed as f64 / self.total as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_fraction() {
+        assert_eq!(Progress { completed: 0, total: 0 }.fraction(), 0.0);
+        assert_eq!(Progress { completed: 1, total: 4 }.fraction(), 0.25);
+        assert_eq!(Progress { completed: 3, total: 3 }.fraction(), 1.0);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::task::Task;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 历史记录中的变更类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum EventKind {
+    /// 新建任务
+    Added,
+    /// 完成任务
+    Completed,
+    /// 修改截止日期
+    Rescheduled {
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    },
+    /// 跳过循环任务的一个周期，截止日期推进到下一次
+    Skipped {
+        from: Option<DateTime<Utc>>,
+        to: DateTime<Utc>,
+    },
+    /// 修改任务的其他字段（标题、优先级、标签等），记录修改前后的值，`None` 表示没有值
+    Edited {
+        field: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// 新建时使用了标签配置的默认值（`[tags.<标签>]`），记录标签和使用默认值的字段
+    DefaultsApplied {
+        tag: String,
+        fields: Vec<String>,
+    },
+    /// 重新打开已完成的任务
+    Reopened,
+    /// 删除任务
+    Deleted,
+    /// 从回收站恢复任务
+    Restored,
+}
+
+/// 一条任务变更记录
+///
+/// 记录发生时任务的标题，任务之后被修改或删除也能正确显示。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub task_id: u32,
+    pub task_uuid: String,
+    pub title: String,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl Event {
+    /// 为任务创建一条当前时间的记录
+    pub fn new(task: &Task, kind: EventKind) -> Self {
+        Self {
+            at: Utc::now(),
+            task_id: task.id,
+            task_uuid: task.uuid.clone(),
+            title: task.title.clone(),
+            kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_serialization() {
+        let task = Task::new(1, "写周报".to_string(), Priority::Low, None);
+        let event = Event::new(&task, EventKind::Rescheduled { from: None, to: None });
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["kind"], json!("rescheduled"));
+        assert_eq!(value["task_uuid"], json!(task.uuid));
+
+        let parsed: Event = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, event);
+    }
+}
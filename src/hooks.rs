@@ -0,0 +1,199 @@
+use crate::config::{data_dir_override, project_dirs};
+use crate::error::{TodoError, TodoResult};
+use crate::history::EventKind;
+use crate::task::Task;
+use crate::todo_list::TodoList;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// 钩子脚本所在的目录名（在配置目录中）
+pub const HOOKS_DIR_NAME: &str = "hooks";
+
+/// 会运行钩子脚本的任务事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Add,
+    Complete,
+    Delete,
+}
+
+impl HookEvent {
+    /// 对应的脚本文件名
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            HookEvent::Add => "on-add",
+            HookEvent::Complete => "on-complete",
+            HookEvent::Delete => "on-delete",
+        }
+    }
+}
+
+/// 钩子脚本目录（Linux 下为 `~/.config/todo/hooks`）
+///
+/// 脚本从标准输入读取一行任务 JSON，在数据保存之后运行，因此不能阻止或修改操作。
+#[derive(Debug, Clone)]
+pub struct Hooks {
+    dir: PathBuf,
+}
+
+impl Hooks {
+    /// 默认的钩子目录
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Some(dir) = data_dir_override() {
+            return Some(dir.join(HOOKS_DIR_NAME));
+        }
+        project_dirs().map(|dirs| dirs.config_dir().join(HOOKS_DIR_NAME))
+    }
+
+    /// 使用指定目录中的钩子脚本
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// 事件对应的可执行脚本，不存在时返回 `None`
+    pub fn script(&self, event: HookEvent) -> Option<PathBuf> {
+        let path = self.dir.join(event.file_name());
+        is_executable(&path).then_some(path)
+    }
+
+    /// 是否有任何钩子脚本
+    pub fn any(&self) -> bool {
+        [HookEvent::Add, HookEvent::Complete, HookEvent::Delete]
+            .into_iter()
+            .any(|event| self.script(event).is_some())
+    }
+
+    /// 运行事件对应的脚本（没有脚本时什么也不做），脚本以非零状态退出时返回它的输出
+    pub fn run(&self, event: HookEvent, task: &Task) -> TodoResult<()> {
+        let Some(script) = self.script(event) else {
+            return Ok(());
+        };
+
+        let failed = |reason: String| TodoError::Custom(format!("hook {} failed: {}", script.display(), reason));
+        let mut child = Command::new(&script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| failed(err.to_string()))?;
+
+        let mut input = serde_json::to_string(task)?;
+        input.push('\n');
+        // 脚本不读取标准输入就退出时写入会失败，这不算错误
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+
+        let output = child.wait_with_output().map_err(|err| failed(err.to_string()))?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(if output.stderr.is_empty() { &output.stdout } else { &output.stderr })
+                .trim()
+                .to_string();
+            return Err(failed(if message.is_empty() { output.status.to_string() } else { message }));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// 历史记录中第 `since` 条之后的事件对应的钩子和任务
+///
+/// 删除的任务已经不在列表中，从 `previous`（加载时的任务）中查找。
+pub fn history_hooks(todo_list: &TodoList, since: usize, previous: &[Task]) -> Vec<(HookEvent, Task)> {
+    todo_list
+        .history()
+        .iter()
+        .skip(since)
+        .filter_map(|event| {
+            let (hook, task) = match event.kind {
+                EventKind::Added => (HookEvent::Add, todo_list.find_task_by_uuid(&event.task_uuid)),
+                EventKind::Completed => (HookEvent::Complete, todo_list.find_task_by_uuid(&event.task_uuid)),
+                EventKind::Deleted => (
+                    HookEvent::Delete,
+                    previous.iter().find(|task| task.uuid == event.task_uuid),
+                ),
+                _ => return None,
+            };
+            task.map(|task| (hook, task.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use std::fs;
+
+    #[test]
+    fn test_history_hooks() {
+        let mut list = TodoList::new();
+        let old = list.add_task("旧任务".to_string(), Priority::Low, None);
+        let previous = list.list_tasks().to_vec();
+        let since = list.history().len();
+
+        let id = list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.complete_task(id).unwrap();
+        list.delete_task(old).unwrap();
+        let temporary = list.add_task("临时".to_string(), Priority::Low, None);
+        list.delete_task(temporary).unwrap();
+
+        let hooks: Vec<(HookEvent, String)> = history_hooks(&list, since, &previous)
+            .into_iter()
+            .map(|(hook, task)| (hook, task.title))
+            .collect();
+        assert_eq!(
+            hooks,
+            [
+                (HookEvent::Add, "买牛奶".to_string()),
+                (HookEvent::Complete, "买牛奶".to_string()),
+                (HookEvent::Delete, "旧任务".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_script() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("todo-hooks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let hooks = Hooks::new(&dir);
+        let task = Task::new(7, "写周报".to_string(), Priority::High, None);
+        assert!(!hooks.any());
+        assert!(hooks.run(HookEvent::Add, &task).is_ok());
+
+        let script = dir.join("on-add");
+        let received = dir.join("received.json");
+        fs::write(&script, format!("#!/bin/sh\ncat > '{}'\n", received.display())).unwrap();
+        // 没有执行权限的脚本被忽略
+        assert!(!hooks.any());
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(hooks.any());
+
+        hooks.run(HookEvent::Add, &task).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&received).unwrap()).unwrap();
+        assert_eq!(value["title"], "写周报");
+
+        fs::write(&script, "#!/bin/sh\necho 'not allowed' >&2\nexit 1\n").unwrap();
+        let err = hooks.run(HookEvent::Add, &task).unwrap_err();
+        assert!(err.to_string().contains("not allowed"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
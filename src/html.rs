@@ -0,0 +1,204 @@
+use crate::dates::{local_date, to_local};
+use crate::display::{format_date, format_minutes};
+use crate::stats;
+use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Utc};
+
+/// 页面样式：过期任务标红，已完成任务变灰
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 1100px; color: #222; padding: 0 1rem; }
+h1 { margin-bottom: 0.2rem; }
+.generated { color: #777; margin-top: 0; }
+.summary { display: flex; gap: 1rem; margin: 1.5rem 0; }
+.summary div { background: #f4f4f6; border-radius: 6px; padding: 0.6rem 1rem; }
+.summary strong { display: block; font-size: 1.4rem; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.45rem 0.6rem; border-bottom: 1px solid #e4e4e8; vertical-align: top; }
+th { cursor: pointer; user-select: none; background: #fafafa; white-space: nowrap; }
+th.sorted-asc::after { content: " \25B2"; }
+th.sorted-desc::after { content: " \25BC"; }
+tr.overdue td { background: #fdecec; }
+tr.overdue .due { color: #c0262d; font-weight: 600; }
+tr.done td { color: #999; }
+tr.done .title { text-decoration: line-through; }
+.priority-high { color: #c0262d; font-weight: 600; }
+.priority-medium { color: #b7791f; }
+.priority-low { color: #2f855a; }
+.tag { background: #eef2ff; color: #3c4fe0; border-radius: 4px; padding: 0 0.3rem; margin-right: 0.2rem; white-space: nowrap; }
+.muted { color: #888; font-size: 0.9em; }
+"#;
+
+/// 点击表头按该列排序，再次点击反向；排序值取自单元格的 `data-sort`
+const SCRIPT: &str = r#"
+document.querySelectorAll("th").forEach((th, column) => {
+  th.addEventListener("click", () => {
+    const tbody = th.closest("table").querySelector("tbody");
+    const ascending = !th.classList.contains("sorted-asc");
+    document.querySelectorAll("th").forEach((other) => other.classList.remove("sorted-asc", "sorted-desc"));
+    th.classList.add(ascending ? "sorted-asc" : "sorted-desc");
+    const key = (row) => row.children[column].dataset.sort;
+    const rows = Array.from(tbody.rows).sort((a, b) =>
+      key(a).localeCompare(key(b), undefined, { numeric: true }) * (ascending ? 1 : -1));
+    rows.forEach((row) => tbody.appendChild(row));
+  });
+});
+"#;
+
+/// 生成独立的 HTML 状态报告：汇总数字和可按列排序的任务表格，样式和脚本都内嵌在页面中
+pub fn render_html(todo_list: &TodoList, now: DateTime<Utc>) -> String {
+    let stats = stats::compute(todo_list, local_date(now));
+    let mut out = String::new();
+
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    out.push_str("<title>Todo status</title>\n");
+    out.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+
+    out.push_str("<h1>Todo status</h1>\n");
+    out.push_str(&format!(
+        "<p class=\"generated\">Generated {}</p>\n",
+        to_local(now).format("%Y-%m-%d %H:%M")
+    ));
+
+    out.push_str("<div class=\"summary\">\n");
+    for (label, value) in [
+        ("Total", stats.total.to_string()),
+        ("Pending", stats.pending.to_string()),
+        ("Completed", stats.completed.to_string()),
+        ("Overdue", stats.overdue.to_string()),
+        ("Completion", format!("{:.0}%", stats.completion_rate * 100.0)),
+    ] {
+        out.push_str(&format!("<div><strong>{}</strong>{}</div>\n", value, label));
+    }
+    out.push_str("</div>\n");
+
+    out.push_str("<table>\n<thead>\n<tr>");
+    for heading in ["ID", "Status", "Priority", "Title", "Due", "Estimate", "Tags"] {
+        out.push_str(&format!("<th>{}</th>", heading));
+    }
+    out.push_str("</tr>\n</thead>\n<tbody>\n");
+    for task in todo_list.list_tasks() {
+        out.push_str(&render_row(task, now));
+    }
+    out.push_str("</tbody>\n</table>\n");
+
+    out.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", SCRIPT));
+    out
+}
+
+/// 表格中的一行，每个单元格带有排序用的 `data-sort`
+fn render_row(task: &Task, now: DateTime<Utc>) -> String {
+    let overdue = !task.completed && task.due_date.is_some_and(|due| due < now);
+    let class = if task.completed {
+        " class=\"done\""
+    } else if overdue {
+        " class=\"overdue\""
+    } else {
+        ""
+    };
+
+    let status = if task.completed {
+        "Done"
+    } else if overdue {
+        "Overdue"
+    } else {
+        "Pending"
+    };
+    let (priority_rank, priority_class) = match task.priority {
+        Priority::High => (0, "priority-high"),
+        Priority::Medium => (1, "priority-medium"),
+        Priority::Low => (2, "priority-low"),
+    };
+
+    let mut title = escape(&task.title);
+    if let Some(parent_id) = task.parent_id {
+        title.push_str(&format!(" <span class=\"muted\">(subtask of {})</span>", parent_id));
+    }
+    if let Some(context) = &task.context {
+        title.push_str(&format!(" <span class=\"muted\">@{}</span>", escape(context)));
+    }
+
+    // 没有截止日期的任务按截止日期排序时排在最后
+    let (due_sort, due) = match task.due_date {
+        Some(due) => {
+            (local_date(due).format("%Y-%m-%d").to_string(), format_date(&due))
+        }
+        None => ("9999-12-31".to_string(), String::new()),
+    };
+    let tags: String = task
+        .tags
+        .iter()
+        .map(|tag| format!("<span class=\"tag\">#{}</span>", escape(tag)))
+        .collect();
+
+    format!(
+        "<tr{}><td data-sort=\"{}\">{}</td><td data-sort=\"{}\">{}</td><td data-sort=\"{}\" class=\"{}\">{}</td>\
+         <td data-sort=\"{}\" class=\"title\">{}</td><td data-sort=\"{}\" class=\"due\">{}</td>\
+         <td data-sort=\"{}\">{}</td><td data-sort=\"{}\">{}</td></tr>\n",
+        class,
+        task.id,
+        task.id,
+        status,
+        status,
+        priority_rank,
+        priority_class,
+        task.priority,
+        escape(&task.title.to_lowercase()),
+        title,
+        due_sort,
+        due,
+        task.estimate_minutes.unwrap_or(0),
+        task.estimate_minutes.map(format_minutes).unwrap_or_default(),
+        escape(&task.tags.join(" ")),
+        tags,
+    )
+}
+
+/// 转义 HTML 特殊字符
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::local_time;
+    use chrono::Duration;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"<b>"Tom" & 'Jerry'</b>"#), "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;");
+    }
+
+    #[test]
+    fn test_render_html() {
+        let now = local_time(2025, 1, 15, 12, 0, 0);
+        let mut list = TodoList::new();
+        let late = list.add_task("Pay <rent>".to_string(), Priority::High, Some(now - Duration::days(2)));
+        let done = list.add_task("Buy milk".to_string(), Priority::Low, None);
+        list.set_tags(late, vec!["home".to_string()]).unwrap();
+        list.complete_task(done).unwrap();
+
+        let html = render_html(&list, now);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Generated 2025-01-15 12:00"));
+        assert!(html.contains("<div><strong>1</strong>Overdue</div>"));
+        assert!(html.contains("<tr class=\"overdue\"><td data-sort=\"1\">1</td>"));
+        assert!(html.contains("Pay &lt;rent&gt;"));
+        assert!(!html.contains("Pay <rent>"));
+        assert!(html.contains("<span class=\"tag\">#home</span>"));
+        assert!(html.contains("<tr class=\"done\"><td data-sort=\"2\">2</td>"));
+    }
+}
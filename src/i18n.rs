@@ -0,0 +1,318 @@
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// 界面语言
+///
+/// 消息在代码中直接写英文原文，其他语言从下面的消息表中按原文查找译文，
+/// 表中没有的消息保持英文。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum Locale {
+    /// 英文（默认）
+    #[default]
+    #[serde(rename = "en", alias = "en-US")]
+    En,
+    /// 简体中文
+    #[serde(rename = "zh-CN", alias = "zh")]
+    ZhCn,
+}
+
+impl Locale {
+    /// 从 `LC_ALL`、`LC_MESSAGES`、`LANG` 环境变量（按此顺序取第一个非空的）判断语言
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// 解析语言名，接受 `en`、`zh-CN` 以及 `zh_CN.UTF-8` 这样的环境变量写法
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['_', '-', '.', '@']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "zh" => Ok(Locale::ZhCn),
+            "en" | "c" | "posix" => Ok(Locale::En),
+            _ => Err(format!("unsupported locale '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locale::En => write!(f, "en"),
+            Locale::ZhCn => write!(f, "zh-CN"),
+        }
+    }
+}
+
+/// 当前语言（程序启动时根据配置或环境变量设置一次）
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// 设置界面语言，只有第一次调用生效
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// 当前的界面语言，未设置时为英文
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+/// 消息表：英文原文和简体中文译文，`{}` 是按顺序填入的参数
+const MESSAGES: &[(&str, &str)] = &[
+    // 错误
+    ("Task with ID {} not found", "未找到 ID 为 {} 的任务"),
+    ("No task matches '{}'", "没有任务匹配 '{}'"),
+    ("'{}' matches {} tasks:", "'{}' 匹配到 {} 个任务："),
+    ("Use a more specific text or the task ID", "请使用更具体的文字或任务 ID"),
+    ("Goal with ID {} not found", "未找到 ID 为 {} 的目标"),
+    ("Task {} is not in the trash. See `todo trash list`", "任务 {} 不在回收站中，参见 `todo trash list`"),
+    ("Template '{}' not found. See `todo template list`", "未找到模板 '{}'，参见 `todo template list`"),
+    ("File operation failed: {}", "文件操作失败：{}"),
+    ("JSON parsing failed: {}", "JSON 解析失败：{}"),
+    ("Invalid priority '{}'.  Use:  high, medium, or low", "无效的优先级 '{}'，可用：high、medium 或 low"),
+    ("Invalid date format '{}'. Expected:  YYYY-MM-DD", "无效的日期格式 '{}'，应为 YYYY-MM-DD"),
+    ("Invalid query: {}", "无效的查询：{}"),
+    ("Invalid regular expression '{}': {}", "无效的正则表达式 '{}'：{}"),
+    ("Invalid month '{}'. Expected: YYYY-MM", "无效的月份 '{}'，应为 YYYY-MM"),
+    ("Invalid time '{}'. Expected: HH:MM", "无效的时间 '{}'，应为 HH:MM"),
+    (
+        "Invalid estimate '{}'. Expected e.g. 30m, 2h or 1h30m",
+        "无效的预估耗时 '{}'，例如 30m、2h 或 1h30m",
+    ),
+    (
+        "Invalid postpone target '{}'. Expected YYYY-MM-DD or e.g. +1d, +2w",
+        "无效的推迟目标 '{}'，应为 YYYY-MM-DD 或 +1d、+2w 等",
+    ),
+    (
+        "Invalid period '{}'. Expected e.g. today, yesterday, 7d, 2w, 24h or YYYY-MM-DD",
+        "无效的时间段 '{}'，例如 today、yesterday、7d、2w、24h 或 YYYY-MM-DD",
+    ),
+    (
+        "Invalid recurrence '{}'. Use: daily, weekly, monthly, or an RRULE such as FREQ=WEEKLY;BYDAY=MO,WE",
+        "无效的重复周期 '{}'，可用：daily、weekly、monthly，或 FREQ=WEEKLY;BYDAY=MO,WE 这样的 RRULE",
+    ),
+    ("Invalid RRULE: {}", "无效的 RRULE：{}"),
+    ("Task {} is not a recurring task", "任务 {} 不是循环任务"),
+    (
+        "Task {} has no further occurrences: its repeat rule has ended",
+        "任务 {} 的重复规则已经结束，没有下一次",
+    ),
+    ("Task {} has {} subtask(s). Use --cascade or --orphan", "任务 {} 有 {} 个子任务，请使用 --cascade 或 --orphan"),
+    ("Task {} cannot depend on task {}: that would create a cycle", "任务 {} 不能依赖任务 {}：会形成循环依赖"),
+    (
+        "Task {} is blocked by unfinished task(s) {}. Use --force to complete it anyway",
+        "任务 {} 被未完成的任务 {} 阻塞，使用 --force 强制完成",
+    ),
+    ("Invalid config: {}", "配置无效：{}"),
+    ("Sync failed: {}", "同步失败：{}"),
+    ("Failed to send notification: {}", "发送通知失败：{}"),
+    ("Invalid bundle: {}", "备份包无效：{}"),
+    (
+        "Data file version {} is newer than supported. Please upgrade todo",
+        "数据文件版本 {} 高于当前程序支持的版本，请升级 todo",
+    ),
+    ("Invalid data file: {}", "数据文件无效：{}"),
+    ("Invalid data file path '{}'", "无效的数据文件路径 '{}'"),
+    ("File '{}' does not exist", "文件 '{}' 不存在"),
+    ("No backup with timestamp '{}'. See `todo backup list`", "没有时间戳为 '{}' 的备份，参见 `todo backup list`"),
+    ("Encryption error: {}", "加密错误：{}"),
+    ("Error: {}", "错误：{}"),
+    // 相对日期
+    ("today", "今天"),
+    ("tomorrow", "明天"),
+    ("yesterday", "昨天"),
+    ("in {} days", "{} 天后"),
+    ("{} days ago", "{} 天前"),
+    ("Today", "今天"),
+    ("Tomorrow", "明天"),
+    ("overdue by {}", "已过期 {}"),
+    ("{} left", "剩余 {}"),
+    // 任务列表
+    ("No tasks found.", "没有找到任务。"),
+    ("{} task(s)", "{} 个任务"),
+    ("Pri", "优先级"),
+    ("Title", "标题"),
+    ("Due", "截止"),
+    ("Tags", "标签"),
+    ("subtasks {}", "子任务 {}"),
+    ("blocked by {}", "被 {} 阻塞"),
+    // 读屏友好的任务描述
+    ("Task {}: {}", "任务 {}：{}"),
+    ("Status: {}", "状态：{}"),
+    ("completed", "已完成"),
+    ("pending", "未完成"),
+    ("Priority: {}", "优先级：{}"),
+    ("Due: {}, overdue", "截止：{}，已过期"),
+    ("Due: {}", "截止：{}"),
+    ("Tags: {}", "标签：{}"),
+    ("Context: {}", "情境：{}"),
+    ("Subtask of task {}", "任务 {} 的子任务"),
+    ("Estimate: {}", "预估：{}"),
+    ("Repeats: {}", "重复：{}"),
+    // 任务详情
+    ("Task Details", "任务详情"),
+    ("Completed ✓", "已完成 ✓"),
+    ("Pending ○", "未完成 ○"),
+    ("Status", "状态"),
+    ("High", "高"),
+    ("Medium", "中"),
+    ("Low", "低"),
+    ("Priority", "优先级"),
+    ("Created", "创建时间"),
+    ("Completed", "完成时间"),
+    ("Due Date", "截止日期"),
+    ("None", "无"),
+    ("Estimate", "预估耗时"),
+    ("Subtask Estimate", "子任务预估"),
+    ("Pomodoros", "番茄钟"),
+    ("Repeats", "重复"),
+    ("Skipped", "已跳过"),
+    ("Parent", "父任务"),
+    ("Depends On", "依赖"),
+    ("Context", "情境"),
+    ("Goal", "目标"),
+    ("{}, overdue", "{}，已过期"),
+    ("(OVERDUE!)", "（已过期！）"),
+    ("{} (done)", "{}（已完成）"),
+    // 历史记录
+    ("none", "无"),
+    ("created", "创建"),
+    ("reopened", "重新打开"),
+    ("due date: {} → {}", "截止日期：{} → {}"),
+    ("skipped: {} → {}", "跳过：{} → {}"),
+    ("defaults from #{}: {}", "使用 #{} 的默认值：{}"),
+    ("deleted", "删除"),
+    ("restored from the trash", "从回收站恢复"),
+    ("History", "历史"),
+    ("No recorded changes.", "没有变更记录。"),
+    // 统计
+    ("Statistics", "统计"),
+    ("Total:      {}", "总数：{}"),
+    ("Pending:   {}", "未完成：{}"),
+    ("Completed: {}", "已完成：{}"),
+    ("Overdue:   {}", "已过期：{}"),
+    ("Overdue", "已过期"),
+    ("Completion rate", "完成率"),
+    ("Average time to complete", "平均完成用时"),
+    ("{} day(s) in a row (best {})", "{} 天（最长 {} 天）"),
+    ("Streak", "连续完成"),
+    ("Estimated work", "预估工作量"),
+    ("{} due today, {} pending in total", "今天到期 {}，未完成共 {}"),
+    ("Completed per day", "每天完成数"),
+    ("By priority", "按优先级"),
+    ("{} total, {} pending", "共 {} 个，未完成 {} 个"),
+    ("{:.1} hours", "{:.1} 小时"),
+    ("{:.1} days", "{:.1} 天"),
+    // 日历和日程
+    ("No tasks due this month.", "本月没有到期的任务。"),
+    ("Nothing due.", "没有到期的任务。"),
+    // 提示消息
+    ("Success", "成功"),
+    ("Error", "错误"),
+    ("Warning", "警告"),
+    ("Info", "提示"),
+    ("Alert: {} task(s) critically overdue.", "警告：{} 个任务严重过期。"),
+    ("{} TASK(S) CRITICALLY OVERDUE", "{} 个任务严重过期"),
+];
+
+/// 按指定语言翻译消息，没有译文时返回原文
+pub fn translate(locale: Locale, text: &'static str) -> &'static str {
+    match locale {
+        Locale::En => text,
+        Locale::ZhCn => MESSAGES
+            .iter()
+            .find(|(en, _)| *en == text)
+            .map_or(text, |(_, zh)| zh),
+    }
+}
+
+/// 按当前语言翻译消息
+pub fn t(text: &'static str) -> &'static str {
+    translate(locale(), text)
+}
+
+/// 按当前语言翻译消息，并按顺序把参数填入 `{}`
+///
+/// # 示例
+/// ```
+/// use rust_todo_cli::i18n::tf;
+///
+/// assert_eq!(tf("Task {} is not a recurring task", &[&3]), "Task 3 is not a recurring task");
+/// ```
+pub fn tf(template: &'static str, args: &[&dyn fmt::Display]) -> String {
+    fill(t(template), args)
+}
+
+/// 把参数按顺序填入模板中的 `{}`，`{:.1}` 表示保留一位小数的数字
+fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        match (&rest[start + 1..end], args.next()) {
+            (":.1", Some(arg)) => out.push_str(&format!("{:.1}", arg)),
+            (_, Some(arg)) => out.push_str(&arg.to_string()),
+            (_, None) => {}
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> usize {
+        text.matches('{').count()
+    }
+
+    #[test]
+    fn test_messages_are_consistent() {
+        for (i, (en, zh)) in MESSAGES.iter().enumerate() {
+            assert_eq!(placeholders(en), placeholders(zh), "placeholders differ for '{}'", en);
+            assert!(
+                MESSAGES[..i].iter().all(|(other, _)| other != en),
+                "duplicate message '{}'",
+                en
+            );
+        }
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate(Locale::En, "Task Details"), "Task Details");
+        assert_eq!(translate(Locale::ZhCn, "Task Details"), "任务详情");
+        // 表中没有的消息保持英文
+        assert_eq!(translate(Locale::ZhCn, "not in the table"), "not in the table");
+
+        let template = translate(Locale::ZhCn, "Task {} has {} subtask(s). Use --cascade or --orphan");
+        assert_eq!(fill(template, &[&3, &2]), "任务 3 有 2 个子任务，请使用 --cascade 或 --orphan");
+        assert_eq!(fill("{:.1} hours", &[&5.26]), "5.3 hours");
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!("zh_CN.UTF-8".parse(), Ok(Locale::ZhCn));
+        assert_eq!("zh-CN".parse(), Ok(Locale::ZhCn));
+        assert_eq!("en_US.UTF-8".parse(), Ok(Locale::En));
+        assert_eq!("C".parse(), Ok(Locale::En));
+        assert!("fr_FR".parse::<Locale>().is_err());
+        assert_eq!(Locale::ZhCn.to_string(), "zh-CN");
+    }
+}
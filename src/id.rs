@@ -0,0 +1,111 @@
+use std::fmt;
+
+/// 新任务的 ID 策略
+///
+/// 每个任务都有一个文件内递增的数字 ID（命令行中使用）和一个稳定的 `Task::uuid`。
+/// 策略决定是否再生成一个应用自定义格式的全局 ID（保存在 `Task::uid`），
+/// 供需要特定 ID 格式的嵌入方使用。
+pub trait IdStrategy: fmt::Debug + Send + Sync {
+    /// 为新任务生成全局唯一 ID，返回 `None` 表示只使用顺序编号
+    fn global_id(&self) -> Option<String>;
+}
+
+/// 只使用文件内的顺序编号（默认）
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sequential;
+
+impl IdStrategy for Sequential {
+    fn global_id(&self) -> Option<String> {
+        None
+    }
+}
+
+/// 随机生成的 UUID v4，例如 `67e55044-10b1-426f-9247-bb680e5fe0c8`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UuidV4;
+
+impl IdStrategy for UuidV4 {
+    fn global_id(&self) -> Option<String> {
+        Some(uuid::Uuid::new_v4().to_string())
+    }
+}
+
+/// 21 个字符的 nanoid，例如 `V1StGXR8_Z5jdHi6B-myT`
+#[cfg(feature = "nanoid")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NanoId;
+
+#[cfg(feature = "nanoid")]
+impl IdStrategy for NanoId {
+    fn global_id(&self) -> Option<String> {
+        Some(nanoid::nanoid!())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use crate::todo_list::TodoList;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// 测试用策略：按顺序生成带前缀的 ID
+    #[derive(Debug, Default)]
+    struct Counter(AtomicU32);
+
+    impl IdStrategy for Counter {
+        fn global_id(&self) -> Option<String> {
+            Some(format!("uid-{}", self.0.fetch_add(1, Ordering::SeqCst)))
+        }
+    }
+
+    #[test]
+    fn test_sequential_has_no_uid() {
+        let mut list = TodoList::new();
+        let id = list.add_task("任务".to_string(), Priority::Low, None);
+
+        assert_eq!(id, 1);
+        assert!(list.find_task(id).unwrap().uid.is_none());
+    }
+
+    #[test]
+    fn test_custom_strategy() {
+        let mut list = TodoList::new();
+        list.set_id_strategy(Counter::default());
+
+        let parent = list.add_task("父任务".to_string(), Priority::Low, None);
+        let child = list
+            .add_subtask(parent, "子任务".to_string(), Priority::Low, None)
+            .unwrap();
+
+        assert_eq!(list.find_task(parent).unwrap().uid.as_deref(), Some("uid-0"));
+        assert_eq!(list.find_task_by_uid("uid-1").unwrap().id, child);
+    }
+
+    #[test]
+    fn test_recurring_instance_gets_new_uid() {
+        let mut list = TodoList::new();
+        list.set_id_strategy(Counter::default());
+
+        let id = list.add_task("每日站会".to_string(), Priority::Low, Some(chrono::Utc::now()));
+        list.set_recurrence(id, Some(crate::task::Recurrence::Daily)).unwrap();
+        let next = list.complete_task(id).unwrap().unwrap();
+
+        assert_eq!(list.find_task(next).unwrap().uid.as_deref(), Some("uid-1"));
+    }
+
+    #[test]
+    fn test_uuid_strategy() {
+        let uid = UuidV4.global_id().unwrap();
+        assert_eq!(uid.len(), 36);
+        assert_ne!(uid, UuidV4.global_id().unwrap());
+    }
+
+    #[cfg(feature = "nanoid")]
+    #[test]
+    fn test_nanoid_strategy() {
+        let uid = NanoId.global_id().unwrap();
+        assert_eq!(uid.chars().count(), 21);
+        assert_ne!(uid, NanoId.global_id().unwrap());
+    }
+}
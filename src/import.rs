@@ -0,0 +1,480 @@
+use crate::error::{TodoError, TodoResult};
+use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+
+/// Taskwarrior 导出的日期格式（例如 `20250101T120000Z`）
+const TASKWARRIOR_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Taskwarrior `task export` 输出中的单条任务（只保留需要映射的字段）
+#[derive(Debug, Deserialize)]
+struct TaskwarriorTask {
+    uuid: Option<String>,
+    description: String,
+    #[serde(default)]
+    status: String,
+    priority: Option<String>,
+    due: Option<String>,
+    entry: Option<String>,
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// 跳过某条导入记录的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// 来源中已删除的任务
+    Deleted,
+    /// 循环任务的模板
+    RecurringTemplate,
+    /// 与已有任务完全相同
+    Duplicate(u32),
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Deleted => write!(f, "deleted in source"),
+            SkipReason::RecurringTemplate => write!(f, "recurring template"),
+            SkipReason::Duplicate(id) => write!(f, "duplicate of task {}", id),
+        }
+    }
+}
+
+/// 一次导入的结果汇总
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    /// 新建的任务 ID
+    pub created: Vec<u32>,
+    /// 被更新的已有任务 ID
+    pub updated: Vec<u32>,
+    /// 被跳过的任务标题及原因
+    pub skipped: Vec<(String, SkipReason)>,
+}
+
+/// 从 Taskwarrior 的 JSON 导出内容中导入任务
+///
+/// 同时支持新版的 JSON 数组格式和旧版的每行一个 JSON 对象格式。
+/// 已删除（`deleted`）和循环模板（`recurring`）任务会被跳过。
+///
+/// UUID 相同，或标题相同（忽略大小写和首尾空白）的已有任务视为同一任务：
+/// 内容完全相同时跳过，否则用导入的标题、状态、优先级、截止日期和标签更新。
+/// 新建的任务沿用 Taskwarrior 的 UUID。
+///
+/// # 示例
+/// ```
+/// use rust_todo_cli::import::import_taskwarrior;
+/// use rust_todo_cli::todo_list::TodoList;
+///
+/// let mut list = TodoList::new();
+/// let json = r#"[{"description":"Buy milk","status":"pending","priority":"H","tags":["home"]}]"#;
+/// let report = import_taskwarrior(&mut list, json).unwrap();
+/// assert_eq!(report.created.len(), 1);
+/// assert_eq!(list.list_tasks()[0].tags, vec!["home".to_string()]);
+///
+/// // 再次导入同样的内容不会产生重复任务
+/// let report = import_taskwarrior(&mut list, json).unwrap();
+/// assert_eq!(report.skipped.len(), 1);
+/// assert_eq!(list.len(), 1);
+/// ```
+pub fn import_taskwarrior(todo_list: &mut TodoList, content: &str) -> TodoResult<ImportReport> {
+    let content = content.trim();
+
+    let entries: Vec<TaskwarriorTask> = if content.starts_with('[') {
+        serde_json::from_str(content)?
+    } else {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut report = ImportReport::default();
+    for entry in entries {
+        let skip_reason = match entry.status.as_str() {
+            "deleted" => Some(SkipReason::Deleted),
+            "recurring" => Some(SkipReason::RecurringTemplate),
+            _ => None,
+        };
+        if let Some(reason) = skip_reason {
+            report.skipped.push((entry.description, reason));
+            continue;
+        }
+
+        merge_task(todo_list, convert_taskwarrior_task(entry)?, &mut report);
+    }
+
+    Ok(report)
+}
+
+/// 从 Markdown 清单中导入任务
+///
+/// 只处理 `- [ ] 标题` 和 `- [x] 标题` 形式的列表项（`*`、`+` 开头也可以），其他行忽略。
+/// 缩进更深的项作为上一个缩进更浅的项的子任务。
+///
+/// 标题相同（忽略大小写和首尾空白）的已有任务视为同一任务：完成状态相同时跳过，
+/// 否则按清单完成或重新打开，其他字段保持不变。
+///
+/// # 示例
+/// ```
+/// use rust_todo_cli::import::import_markdown;
+/// use rust_todo_cli::todo_list::TodoList;
+///
+/// let mut list = TodoList::new();
+/// let notes = "## TODO\n- [ ] Release v2\n  - [x] Changelog\n  - [ ] Tag\n";
+/// let report = import_markdown(&mut list, notes).unwrap();
+/// assert_eq!(report.created, vec![1, 2, 3]);
+/// assert_eq!(list.children(1).len(), 2);
+/// assert!(list.find_task(2).unwrap().completed);
+/// ```
+pub fn import_markdown(todo_list: &mut TodoList, content: &str) -> TodoResult<ImportReport> {
+    let mut report = ImportReport::default();
+    // 当前项的祖先（缩进，任务 ID），缩进从浅到深
+    let mut ancestors: Vec<(usize, u32)> = Vec::new();
+
+    for item in content.lines().filter_map(parse_checklist_item) {
+        while ancestors.last().is_some_and(|&(indent, _)| indent >= item.indent) {
+            ancestors.pop();
+        }
+
+        let key = item.title.to_lowercase();
+        let existing = todo_list
+            .list_tasks()
+            .iter()
+            .find(|task| task.title.trim().to_lowercase() == key)
+            .map(|task| (task.id, task.completed));
+
+        let id = match existing {
+            Some((id, completed)) if completed == item.checked => {
+                report.skipped.push((item.title, SkipReason::Duplicate(id)));
+                id
+            }
+            Some((id, _)) => {
+                if item.checked {
+                    todo_list.complete_task(id)?;
+                } else {
+                    todo_list.reopen_task(id)?;
+                }
+                report.updated.push(id);
+                id
+            }
+            None => {
+                let id = match ancestors.last() {
+                    Some(&(_, parent_id)) => todo_list.add_subtask(parent_id, item.title, Priority::Medium, None)?,
+                    None => todo_list.add_task(item.title, Priority::Medium, None),
+                };
+                if item.checked {
+                    todo_list.complete_task(id)?;
+                }
+                report.created.push(id);
+                id
+            }
+        };
+
+        ancestors.push((item.indent, id));
+    }
+
+    Ok(report)
+}
+
+/// Markdown 清单中的一项
+struct ChecklistItem {
+    /// 缩进的空格数（制表符按 4 个空格计算）
+    indent: usize,
+    checked: bool,
+    title: String,
+}
+
+/// 解析 `- [ ] 标题` 形式的一行，不是清单项时返回 `None`
+fn parse_checklist_item(line: &str) -> Option<ChecklistItem> {
+    let rest = line.trim_start();
+    let indent = line[..line.len() - rest.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+
+    let rest = rest.strip_prefix(['-', '*', '+'])?.strip_prefix(' ')?.trim_start();
+    let (checked, title) = if let Some(title) = rest.strip_prefix("[ ]") {
+        (false, title)
+    } else {
+        (true, rest.strip_prefix("[x]").or_else(|| rest.strip_prefix("[X]"))?)
+    };
+
+    let title = title.trim();
+    (!title.is_empty()).then(|| ChecklistItem {
+        indent,
+        checked,
+        title: title.to_string(),
+    })
+}
+
+/// 将导入的任务合并到列表中，并记录到报告
+fn merge_task(todo_list: &mut TodoList, task: Task, report: &mut ImportReport) {
+    let key = task.title.trim().to_lowercase();
+    let existing = todo_list
+        .find_task_by_uuid(&task.uuid)
+        .or_else(|| {
+            todo_list
+                .list_tasks()
+                .iter()
+                .find(|other| other.title.trim().to_lowercase() == key)
+        })
+        .map(|other| other.id);
+
+    let Some(id) = existing else {
+        report.created.push(todo_list.insert_task(task));
+        return;
+    };
+
+    let Some(current) = todo_list.find_task(id) else {
+        return;
+    };
+
+    if current.title == task.title
+        && current.completed == task.completed
+        && current.priority == task.priority
+        && current.due_date == task.due_date
+        && current.tags == task.tags
+    {
+        report.skipped.push((task.title, SkipReason::Duplicate(id)));
+        return;
+    }
+
+    let mut updated = current.clone();
+    updated.title = task.title;
+    updated.completed = task.completed;
+    updated.completed_at = task.completed_at;
+    updated.priority = task.priority;
+    updated.due_date = task.due_date;
+    updated.tags = task.tags;
+    updated.touch();
+    if todo_list.replace_task(id, updated).is_ok() {
+        report.updated.push(id);
+    }
+}
+
+/// 将 Taskwarrior 任务转换为本工具的 Task
+fn convert_taskwarrior_task(entry: TaskwarriorTask) -> TodoResult<Task> {
+
+    let priority = match entry.priority.as_deref() {
+        Some("H") => Priority::High,
+        Some("M") | None => Priority::Medium,
+        Some("L") => Priority::Low,
+        Some(other) => return Err(TodoError::InvalidPriority(other.to_string())),
+    };
+
+    let due_date = entry
+        .due
+        .as_deref()
+        .map(parse_taskwarrior_date)
+        .transpose()?;
+
+    // ID 由 TodoList::insert_task 重新分配
+    let mut task = Task::new(0, entry.description, priority, due_date);
+    task.completed = entry.status == "completed";
+    task.tags = entry.tags;
+    if let Some(uuid) = entry.uuid {
+        task.uuid = uuid;
+    }
+
+    if let Some(entry_date) = entry.entry.as_deref() {
+        task.created_at = parse_taskwarrior_date(entry_date)?;
+    }
+
+    // 已完成任务的 end 是完成时间
+    if let Some(end) = entry.end.as_deref().filter(|_| task.completed) {
+        task.completed_at = Some(parse_taskwarrior_date(end)?);
+    }
+
+    Ok(task)
+}
+
+/// 解析 Taskwarrior 的日期字符串
+fn parse_taskwarrior_date(date_str: &str) -> TodoResult<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(date_str, TASKWARRIOR_DATE_FORMAT)
+        .map_err(|_| TodoError::Custom(format!("Invalid Taskwarrior date '{}'", date_str)))?;
+
+    Ok(Utc.from_utc_datetime(&naive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EventKind;
+
+    #[test]
+    fn test_import_json_array() {
+        let json = r#"[
+            {"description":"写周报","status":"pending","priority":"H","due":"20251231T235959Z","tags":["work","report"]},
+            {"description":"买牛奶","status":"completed","priority":"L","entry":"20250101T080000Z","end":"20250102T090000Z"}
+        ]"#;
+
+        let mut list = TodoList::new();
+        assert_eq!(import_taskwarrior(&mut list, json).unwrap().created, vec![1, 2]);
+
+        let first = &list.list_tasks()[0];
+        assert_eq!(first.id, 1);
+        assert_eq!(first.title, "写周报");
+        assert_eq!(first.priority, Priority::High);
+        assert_eq!(first.tags, vec!["work", "report"]);
+        assert_eq!(
+            first.due_date.unwrap().format("%Y-%m-%d").to_string(),
+            "2025-12-31"
+        );
+
+        let second = &list.list_tasks()[1];
+        assert!(second.completed);
+        assert_eq!(second.priority, Priority::Low);
+        assert_eq!(second.created_at.format("%Y-%m-%d").to_string(), "2025-01-01");
+        assert_eq!(second.completed_at.unwrap().format("%Y-%m-%d").to_string(), "2025-01-02");
+    }
+
+    #[test]
+    fn test_import_markdown_nesting() {
+        let notes = "# Notes\n\
+                     Some text, not a task\n\
+                     - [ ] 发布 v2\n\
+                     \t- [x] 更新日志\n\
+                     \t- [ ] 打标签\n\
+                     \t\t* [X] 签名\n\
+                     - [ ] 写周报\n\
+                     - [] 不是清单项\n\
+                     - [ ]   \n";
+
+        let mut list = TodoList::new();
+        let report = import_markdown(&mut list, notes).unwrap();
+        assert_eq!(report.created, vec![1, 2, 3, 4, 5]);
+
+        let parents: Vec<Option<u32>> = list.list_tasks().iter().map(|task| task.parent_id).collect();
+        assert_eq!(parents, [None, Some(1), Some(1), Some(3), None]);
+        let completed: Vec<bool> = list.list_tasks().iter().map(|task| task.completed).collect();
+        assert_eq!(completed, [false, true, false, true, false]);
+        assert!(list.find_task(2).unwrap().completed_at.is_some());
+    }
+
+    #[test]
+    fn test_import_markdown_updates_completion() {
+        let mut list = TodoList::new();
+        import_markdown(&mut list, "- [ ] 写周报\n- [x] 买牛奶\n").unwrap();
+        list.set_priority(1, Priority::High).unwrap();
+
+        let report = import_markdown(&mut list, "- [x] 写周报\n- [x] 买牛奶\n").unwrap();
+        assert_eq!(report.updated, vec![1]);
+        assert_eq!(report.skipped, vec![("买牛奶".to_string(), SkipReason::Duplicate(2))]);
+        assert!(list.find_task(1).unwrap().completed);
+        // 清单中没有的字段保持不变
+        assert_eq!(list.find_task(1).unwrap().priority, Priority::High);
+    }
+
+    #[test]
+    fn test_import_line_format_skips_deleted() {
+        let lines = r#"{"description":"保留","status":"pending"}
+{"description":"已删除","status":"deleted"}
+"#;
+
+        let mut list = TodoList::new();
+        let report = import_taskwarrior(&mut list, lines).unwrap();
+        assert_eq!(report.created.len(), 1);
+        assert_eq!(
+            report.skipped,
+            vec![("已删除".to_string(), SkipReason::Deleted)]
+        );
+        assert_eq!(list.list_tasks()[0].title, "保留");
+        assert_eq!(list.list_tasks()[0].priority, Priority::Medium);
+    }
+
+    #[test]
+    fn test_import_matches_by_uuid() {
+        let json = r#"[{"uuid":"5f4c3b2a-0000-4000-8000-000000000001","description":"写周报","status":"pending"}]"#;
+
+        let mut list = TodoList::new();
+        let report = import_taskwarrior(&mut list, json).unwrap();
+        let id = report.created[0];
+        assert_eq!(
+            list.find_task(id).unwrap().uuid,
+            "5f4c3b2a-0000-4000-8000-000000000001"
+        );
+
+        // 在 Taskwarrior 中改名并完成后再次导入，更新的是同一个任务
+        let json = r#"[{"uuid":"5f4c3b2a-0000-4000-8000-000000000001","description":"写月报","status":"completed"}]"#;
+        let report = import_taskwarrior(&mut list, json).unwrap();
+        assert_eq!(report.updated, vec![id]);
+        assert!(list.find_task(id).unwrap().completed);
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_import_update_records_history() {
+        let json = r#"[{"uuid":"5f4c3b2a-0000-4000-8000-000000000001","description":"写周报","status":"pending","priority":"L"}]"#;
+        let mut list = TodoList::new();
+        let id = import_taskwarrior(&mut list, json).unwrap().created[0];
+
+        let json = r#"[{"uuid":"5f4c3b2a-0000-4000-8000-000000000001","description":"写月报","status":"completed","priority":"H","tags":["work"]}]"#;
+        import_taskwarrior(&mut list, json).unwrap();
+
+        let kinds: Vec<EventKind> = list.task_history(id).into_iter().map(|event| event.kind.clone()).collect();
+        let edited = |field: &str, from: Option<&str>, to: &str| EventKind::Edited {
+            field: field.to_string(),
+            from: from.map(str::to_string),
+            to: Some(to.to_string()),
+        };
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Added,
+                edited("title", Some("写周报"), "写月报"),
+                edited("priority", Some("low"), "high"),
+                edited("tags", None, "work"),
+                EventKind::Completed,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_deduplicates_by_title() {
+        let mut list = TodoList::new();
+        let same = list.add_task("写周报".to_string(), Priority::High, None);
+        let changed = list.add_task("买牛奶".to_string(), Priority::Low, None);
+
+        let json = r#"[
+            {"description":"写周报","status":"pending","priority":"H"},
+            {"description":" 买牛奶 ","status":"completed","priority":"L"},
+            {"description":"新任务","status":"pending"},
+            {"description":"新任务","status":"pending"}
+        ]"#;
+        let report = import_taskwarrior(&mut list, json).unwrap();
+
+        assert_eq!(report.created, vec![3]);
+        assert_eq!(report.updated, vec![changed]);
+        assert_eq!(
+            report.skipped,
+            vec![
+                ("写周报".to_string(), SkipReason::Duplicate(same)),
+                ("新任务".to_string(), SkipReason::Duplicate(3)),
+            ]
+        );
+        assert!(list.find_task(changed).unwrap().completed);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_import_invalid_priority() {
+        let json = r#"[{"description":"任务","status":"pending","priority":"X"}]"#;
+
+        let mut list = TodoList::new();
+        let result = import_taskwarrior(&mut list, json);
+        assert!(matches!(result, Err(TodoError::InvalidPriority(p)) if p == "X"));
+    }
+
+    #[test]
+    fn test_import_invalid_json() {
+        let mut list = TodoList::new();
+        assert!(matches!(
+            import_taskwarrior(&mut list, "[not json"),
+            Err(TodoError::SerdeError(_))
+        ));
+    }
+}
@@ -0,0 +1,405 @@
+use crate::error::TodoResult;
+use crate::goal::Goal;
+use crate::history::Event;
+use crate::storage::{save_to_file_with_format, FileStorage, Storage, StorageFormat};
+use crate::task::Task;
+use crate::todo_list::TodoList;
+use crate::trash::TrashedTask;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+/// 日志中的变更超过这个数量时合并回数据文件
+pub const COMPACT_AFTER: usize = 500;
+
+/// 变更日志中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    /// 新增或修改了任务（整个任务）
+    Put { task: Box<Task> },
+    /// 移除了任务
+    Remove { id: u32 },
+    /// 新的历史记录
+    Event { event: Event },
+    /// 目标有变化（全部目标）
+    Goals { goals: Vec<Goal> },
+    /// 回收站有变化（整个回收站）
+    Trash { trash: Vec<TrashedTask> },
+    /// 下一个任务 ID 或目标 ID 有变化
+    NextIds { next_id: u32, next_goal_id: u32 },
+}
+
+impl Change {
+    /// 把变更应用到列表
+    fn apply(self, todo_list: &mut TodoList) {
+        match self {
+            Change::Put { task } => todo_list.put_task(*task),
+            Change::Remove { id } => todo_list.remove_task_entry(id),
+            Change::Event { event } => todo_list.push_event(event),
+            Change::Goals { goals } => todo_list.set_goals(goals),
+            Change::Trash { trash } => todo_list.set_trash(trash),
+            Change::NextIds { next_id, next_goal_id } => todo_list.set_next_ids(next_id, next_goal_id),
+        }
+    }
+}
+
+/// 从 `before` 变成 `after` 需要的变更；历史记录被改写（不只是追加）时无法用变更表示，返回 `None`
+pub fn diff(before: &TodoList, after: &TodoList) -> Option<Vec<Change>> {
+    let (old_history, new_history) = (before.history(), after.history());
+    if new_history.len() < old_history.len() || new_history[..old_history.len()] != *old_history {
+        return None;
+    }
+
+    let old_tasks: HashMap<u32, &Task> = before.list_tasks().iter().map(|task| (task.id, task)).collect();
+    let new_ids: HashMap<u32, ()> = after.list_tasks().iter().map(|task| (task.id, ())).collect();
+
+    let mut changes: Vec<Change> = before
+        .list_tasks()
+        .iter()
+        .filter(|task| !new_ids.contains_key(&task.id))
+        .map(|task| Change::Remove { id: task.id })
+        .collect();
+    changes.extend(
+        after
+            .list_tasks()
+            .iter()
+            .filter(|task| old_tasks.get(&task.id) != Some(task))
+            .map(|task| Change::Put { task: Box::new(task.clone()) }),
+    );
+    changes.extend(
+        new_history[old_history.len()..]
+            .iter()
+            .map(|event| Change::Event { event: event.clone() }),
+    );
+    if after.goals() != before.goals() {
+        changes.push(Change::Goals {
+            goals: after.goals().to_vec(),
+        });
+    }
+    if after.trash() != before.trash() {
+        changes.push(Change::Trash {
+            trash: after.trash().to_vec(),
+        });
+    }
+    if after.next_ids() != before.next_ids() {
+        let (next_id, next_goal_id) = after.next_ids();
+        changes.push(Change::NextIds { next_id, next_goal_id });
+    }
+    Some(changes)
+}
+
+/// 日志文件的第一行：写日志时数据文件的大小和修改时间
+///
+/// 数据文件被其他程序改写后（例如手动编辑或 git 拉取）与它不再一致，此时日志作废。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Header {
+    base_len: u64,
+    base_modified: u64,
+}
+
+impl Header {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            base_len: metadata.len(),
+            base_modified: modified.as_nanos() as u64,
+        })
+    }
+}
+
+/// 上次加载或保存后的状态
+#[derive(Debug, Default)]
+struct JournalState {
+    /// 与磁盘上的数据一致的列表内容，保存时与它比较
+    saved: Option<TodoList>,
+    /// 日志中的变更数量
+    entries: usize,
+}
+
+/// 以追加变更的方式保存的待办列表（只支持 JSON 格式，不加密）
+///
+/// 完整的列表保存在数据文件中，之后的修改逐条追加到旁边的日志文件
+/// （例如 `.todos.json.journal`），保存的开销只与修改的大小有关。
+/// 日志超过 [`COMPACT_AFTER`] 条时把完整列表写回数据文件并清空日志，自动备份也在这时进行。
+///
+/// # 示例
+/// ```
+/// use rust_todo_cli::journal::JournalStorage;
+/// use rust_todo_cli::prelude::*;
+///
+/// let storage = JournalStorage::new("journal_example.json");
+/// let mut list = storage.load().unwrap();
+/// list.add_task("写周报".to_string(), Priority::High, None);
+/// storage.save(&list).unwrap();
+/// assert_eq!(JournalStorage::new("journal_example.json").load().unwrap().len(), 1);
+/// # rust_todo_cli::storage::delete_file("journal_example.json").unwrap();
+/// # std::fs::remove_file(".journal_example.json.journal").ok();
+/// ```
+#[derive(Debug)]
+pub struct JournalStorage {
+    base: FileStorage,
+    journal_path: PathBuf,
+    compact_after: usize,
+    state: Mutex<JournalState>,
+}
+
+impl JournalStorage {
+    /// 使用指定的数据文件，合并时不自动备份
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        Self {
+            base: FileStorage::new(path, StorageFormat::Json),
+            journal_path: Self::path_for(path),
+            compact_after: COMPACT_AFTER,
+            state: Mutex::new(JournalState::default()),
+        }
+    }
+
+    /// 合并日志时把旧的数据文件备份到 `backups/` 目录，只保留最近的 `keep` 个
+    pub fn with_backups(mut self, keep: usize) -> Self {
+        self.base = self.base.with_backups(keep);
+        self
+    }
+
+    /// 日志超过多少条变更时合并
+    pub fn compact_after(mut self, entries: usize) -> Self {
+        self.compact_after = entries;
+        self
+    }
+
+    /// 数据文件对应的日志文件（同一目录下的隐藏文件，例如 `.todos.json.journal`）
+    pub fn path_for(data_path: &Path) -> PathBuf {
+        let file_name = data_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        data_path.with_file_name(format!(".{}.journal", file_name))
+    }
+
+    /// 日志文件路径
+    pub fn journal_path(&self) -> &Path {
+        &self.journal_path
+    }
+
+    /// 把完整列表写回数据文件并清空日志（不备份）
+    ///
+    /// 直接读写数据文件的操作（备份恢复、git 同步等）之前先调用它，数据文件才是最新的。
+    pub fn compact(&self, todo_list: &TodoList) -> TodoResult<()> {
+        let mut state = self.lock();
+        save_to_file_with_format(todo_list, self.base.path(), StorageFormat::Json)?;
+        if self.journal_path.exists() {
+            fs::remove_file(&self.journal_path)?;
+        }
+        state.saved = Some(todo_list.contents());
+        state.entries = 0;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, JournalState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// 读取与数据文件一致的日志；日志不存在或已经作废时返回空列表
+    fn read_journal(&self) -> TodoResult<Vec<Change>> {
+        let Ok(file) = fs::File::open(&self.journal_path) else {
+            return Ok(Vec::new());
+        };
+        let mut lines = BufReader::new(file).lines();
+        let header: Option<Header> = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).ok(),
+            None => None,
+        };
+        if header.is_none() || header != Header::of(self.base.path()) {
+            return Ok(Vec::new());
+        }
+
+        let mut changes = Vec::new();
+        for line in lines {
+            let line = line?;
+            // 最后一行可能在写入时中断，丢弃它和之后的内容
+            match serde_json::from_str(&line) {
+                Ok(change) => changes.push(change),
+                Err(_) => break,
+            }
+        }
+        Ok(changes)
+    }
+
+    /// 把变更追加到日志，日志不存在或已经作废时先写入新的第一行
+    fn append(&self, changes: &[Change], entries: usize) -> TodoResult<()> {
+        let mut content = String::new();
+        if entries == 0 {
+            // 数据文件还不存在时先写入，日志才有可以对应的数据文件
+            if !self.base.path().exists() {
+                self.base.save(&TodoList::new())?;
+            }
+            content.push_str(&serde_json::to_string(&Header::of(self.base.path()))?);
+            content.push('\n');
+        }
+        for change in changes {
+            content.push_str(&serde_json::to_string(change)?);
+            content.push('\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(entries > 0)
+            .truncate(entries == 0)
+            .open(&self.journal_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+impl Storage for JournalStorage {
+    fn load(&self) -> TodoResult<TodoList> {
+        let mut todo_list = self.base.load()?;
+        let changes = self.read_journal()?;
+        let entries = changes.len();
+        for change in changes {
+            change.apply(&mut todo_list);
+        }
+
+        let mut state = self.lock();
+        state.saved = Some(todo_list.contents());
+        state.entries = entries;
+        Ok(todo_list)
+    }
+
+    fn save(&self, todo_list: &TodoList) -> TodoResult<()> {
+        let state = self.lock();
+        let changes = state.saved.as_ref().and_then(|saved| diff(saved, todo_list));
+        let entries = state.entries;
+        drop(state);
+
+        let Some(changes) = changes else {
+            // 没有加载过或历史记录被改写（例如撤销），写入完整列表
+            self.backup()?;
+            return self.compact(todo_list);
+        };
+        if changes.is_empty() {
+            return Ok(());
+        }
+        if entries + changes.len() > self.compact_after {
+            self.backup()?;
+            return self.compact(todo_list);
+        }
+
+        self.append(&changes, entries)?;
+        let mut state = self.lock();
+        state.saved = Some(todo_list.contents());
+        state.entries = entries + changes.len();
+        Ok(())
+    }
+
+    fn backup(&self) -> TodoResult<()> {
+        self.base.backup()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("todo-journal-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("todos.json")
+    }
+
+    #[test]
+    fn test_changes_are_appended_and_replayed() {
+        let path = temp_path("replay");
+        let storage = JournalStorage::new(&path);
+        let mut list = storage.load().unwrap();
+        let id = list.add_task("写周报".to_string(), Priority::High, None);
+        storage.save(&list).unwrap();
+        let base = fs::read_to_string(&path).unwrap();
+
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.complete_task(id).unwrap();
+        storage.save(&list).unwrap();
+
+        // 数据文件没有重写，修改都在日志中
+        assert_eq!(fs::read_to_string(&path).unwrap(), base);
+        let journal = fs::read_to_string(storage.journal_path()).unwrap();
+        assert_eq!(journal.lines().count(), 1 + 3 + 5);
+
+        let reloaded = JournalStorage::new(&path).load().unwrap();
+        assert_eq!(reloaded.len(), 2);
+        assert!(reloaded[id].completed);
+        assert_eq!(reloaded.history(), list.history());
+        assert_eq!(reloaded.list_tasks(), list.list_tasks());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_compaction() {
+        let path = temp_path("compact");
+        let storage = JournalStorage::new(&path).compact_after(3);
+        let mut list = storage.load().unwrap();
+        list.add_task("写周报".to_string(), Priority::High, None);
+        storage.save(&list).unwrap();
+        assert!(storage.journal_path().exists());
+
+        // 超过上限时写回数据文件并清空日志
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        storage.save(&list).unwrap();
+        assert!(!storage.journal_path().exists());
+        assert_eq!(FileStorage::new(&path, StorageFormat::Json).load().unwrap().len(), 2);
+
+        // 删除任务同样可以追加
+        list.delete_task(1).unwrap();
+        storage.save(&list).unwrap();
+        assert!(storage.journal_path().exists());
+        assert_eq!(JournalStorage::new(&path).load().unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_stale_journal_is_ignored() {
+        let path = temp_path("stale");
+        let storage = JournalStorage::new(&path);
+        let mut list = storage.load().unwrap();
+        list.add_task("写周报".to_string(), Priority::High, None);
+        storage.save(&list).unwrap();
+
+        // 数据文件被其他程序改写后，旧的日志不再适用
+        let mut other = TodoList::new();
+        other.add_task("其他".to_string(), Priority::Low, None);
+        other.add_task("其他 2".to_string(), Priority::Low, None);
+        FileStorage::new(&path, StorageFormat::Json).save(&other).unwrap();
+        let reloaded = JournalStorage::new(&path).load().unwrap();
+        assert_eq!(reloaded.list_tasks(), other.list_tasks());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut before = TodoList::new();
+        let id = before.add_task("写周报".to_string(), Priority::High, None);
+        let mut after = before.clone();
+        assert_eq!(diff(&before, &after), Some(Vec::new()));
+
+        after.complete_task(id).unwrap();
+        let changes = diff(&before, &after).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(&changes[0], Change::Put { task } if task.completed));
+        assert!(matches!(changes[1], Change::Event { .. }));
+
+        // 历史记录变短时无法追加
+        assert!(diff(&after, &before).is_none());
+    }
+}
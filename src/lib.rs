@@ -1,7 +1,53 @@
 pub mod task;
+pub mod rrule;
+pub mod dates;
+pub mod i18n;
 pub mod todo_list;
+pub mod filter;
+pub mod query;
+pub mod id;
+pub mod history;
+pub mod hooks;
+pub mod undo;
+pub mod goal;
+pub mod trash;
+pub mod archive;
 pub mod error;
 pub mod storage;
+#[cfg(feature = "tokio")]
+pub mod async_storage;
+pub mod backups;
+pub mod journal;
+#[cfg(feature = "encryption")]
+pub mod crypto;
+pub mod todotxt;
 pub mod cli;
+pub mod command;
 pub mod display;
-
+pub mod import;
+pub mod bundle;
+pub mod anonymize;
+pub mod html;
+pub mod merge;
+pub mod git_sync;
+pub mod doctor;
+pub mod demo;
+pub mod config;
+pub mod session;
+pub mod template;
+pub mod triage;
+pub mod report;
+pub mod stats;
+pub mod prelude;
+#[cfg(feature = "notify")]
+pub mod notify;
+#[cfg(feature = "todoist")]
+pub mod todoist;
+#[cfg(feature = "relay")]
+pub mod relay;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "webhooks")]
+pub mod webhook;
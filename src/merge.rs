@@ -0,0 +1,304 @@
+use crate::task::Task;
+use crate::todo_list::{DeletePolicy, TodoList};
+use std::collections::HashMap;
+
+/// 合并结果统计
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MergeReport {
+    /// 从另一份列表新增的任务（本地 ID）
+    pub added: Vec<u32>,
+    /// 合并了另一份列表中修改的任务（本地 ID）
+    pub updated: Vec<u32>,
+    /// 另一份列表中已删除、本地也没有再修改的任务（原来的本地 ID）
+    pub removed: Vec<u32>,
+}
+
+/// 用 UUID 表示的父任务和依赖，两份列表的 ID 互不相关，只能按 UUID 比较
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Links {
+    parent: Option<String>,
+    depends_on: Vec<String>,
+}
+
+impl Links {
+    fn of(todo_list: &TodoList, task: &Task) -> Self {
+        let uuid = |id: u32| todo_list.find_task(id).map(|task| task.uuid.clone());
+        Self {
+            parent: task.parent_id.and_then(uuid),
+            depends_on: task.depends_on.iter().filter_map(|&id| uuid(id)).collect(),
+        }
+    }
+}
+
+impl TodoList {
+    /// 把另一份列表按 UUID 合并进来
+    ///
+    /// `base` 是两份列表共同的上一个版本（例如上次同步后的内容），有它时逐个字段三方合并：
+    /// 只有一边修改的字段采用修改后的值，两边都修改的字段采用 `updated_at` 较新的一边；
+    /// 标签、依赖、跳过的日期和番茄钟记录按集合合并，两边各自增删的项都会保留。
+    /// 另一边删除且本地没有再修改的任务会被删除，本地删除且另一边没有再修改的任务不会恢复。
+    ///
+    /// 没有 `base` 时无法判断哪一边修改了字段，逐个字段采用较新的一边，集合取并集，不删除任务。
+    pub fn merge(&mut self, other: &TodoList, base: Option<&TodoList>) -> MergeReport {
+        let mut report = MergeReport::default();
+        // 合并后的父任务和依赖，等所有任务都有本地 ID 后再换算
+        let mut links = HashMap::new();
+
+        for theirs in other.list_tasks() {
+            let (base_task, base_links) = base
+                .and_then(|base| base.find_task_by_uuid(&theirs.uuid).map(|task| (task, Links::of(base, task))))
+                .unzip();
+            let their_links = Links::of(other, theirs);
+
+            let existing = self.find_task_by_uuid(&theirs.uuid).map(|ours| (ours.id, ours.clone()));
+            match existing {
+                Some((id, ours)) => {
+                    let our_links = Links::of(self, &ours);
+                    let (merged, merged_links) =
+                        merge_task(&ours, theirs, base_task, &our_links, &their_links, base_links.as_ref());
+                    if let Some(merged) = merged {
+                        if let Some(task) = self.find_task_mut(id) {
+                            *task = merged;
+                        }
+                        report.updated.push(id);
+                    }
+                    if merged_links != our_links {
+                        links.insert(id, merged_links);
+                        report.updated.push(id);
+                    }
+                }
+                // 本地已删除，另一边没有再修改
+                None if base_task.is_some_and(|base_task| theirs.updated_at <= base_task.updated_at) => {}
+                None => {
+                    let id = self.insert_task(theirs.clone());
+                    links.insert(id, their_links);
+                    report.added.push(id);
+                }
+            }
+        }
+
+        // 另一边删除的任务
+        if let Some(base) = base {
+            let deleted: Vec<u32> = self
+                .list_tasks()
+                .iter()
+                .filter(|ours| other.find_task_by_uuid(&ours.uuid).is_none())
+                .filter(|ours| {
+                    base.find_task_by_uuid(&ours.uuid)
+                        .is_some_and(|base_task| ours.updated_at <= base_task.updated_at)
+                })
+                .map(|ours| ours.id)
+                .collect();
+            for id in deleted {
+                if self.delete_task_with_policy(id, DeletePolicy::Orphan).is_ok() {
+                    report.removed.push(id);
+                }
+            }
+        }
+
+        for (id, task_links) in links {
+            let local_id = |uuid: &String| self.find_task_by_uuid(uuid).map(|task| task.id);
+            let parent_id = task_links.parent.as_ref().and_then(local_id);
+            let depends_on: Vec<u32> = task_links.depends_on.iter().filter_map(local_id).collect();
+            if let Some(task) = self.find_task_mut(id) {
+                task.parent_id = parent_id;
+                task.depends_on = depends_on;
+            }
+        }
+
+        report.updated.sort_unstable();
+        report.updated.dedup();
+        report
+    }
+}
+
+/// 逐个字段合并同一个任务的两个版本，内容有变化时返回合并后的任务（保留本地 ID），同时返回合并后的关联
+fn merge_task(
+    ours: &Task,
+    theirs: &Task,
+    base: Option<&Task>,
+    our_links: &Links,
+    their_links: &Links,
+    base_links: Option<&Links>,
+) -> (Option<Task>, Links) {
+    let theirs_newer = theirs.updated_at > ours.updated_at;
+    let mut merged = ours.clone();
+    let mut changed = false;
+
+    macro_rules! merge_fields {
+        ($($field:ident),+) => {
+            $(
+                if take_theirs(&ours.$field, &theirs.$field, base.map(|base| &base.$field), theirs_newer) {
+                    merged.$field = theirs.$field.clone();
+                    changed = true;
+                }
+            )+
+        };
+    }
+    merge_fields!(title, priority, due_date, context, recurrence, estimate_minutes, todoist_id, uid, goal_id);
+
+    // 完成状态和完成时间一起合并
+    let state = |task: &Task| (task.completed, task.completed_at);
+    if take_theirs(&state(ours), &state(theirs), base.map(state).as_ref(), theirs_newer) {
+        merged.completed = theirs.completed;
+        merged.completed_at = theirs.completed_at;
+        changed = true;
+    }
+
+    merged.tags = merge_set(&ours.tags, &theirs.tags, base.map(|base| base.tags.as_slice()));
+    merged.skipped = merge_set(&ours.skipped, &theirs.skipped, base.map(|base| base.skipped.as_slice()));
+    merged.pomodoros = merge_set(&ours.pomodoros, &theirs.pomodoros, base.map(|base| base.pomodoros.as_slice()));
+    changed |= merged.tags != ours.tags || merged.skipped != ours.skipped || merged.pomodoros != ours.pomodoros;
+
+    let parent = if take_theirs(
+        &our_links.parent,
+        &their_links.parent,
+        base_links.map(|links| &links.parent),
+        theirs_newer,
+    ) {
+        their_links.parent.clone()
+    } else {
+        our_links.parent.clone()
+    };
+    let merged_links = Links {
+        parent,
+        depends_on: merge_set(
+            &our_links.depends_on,
+            &their_links.depends_on,
+            base_links.map(|links| links.depends_on.as_slice()),
+        ),
+    };
+
+    if !changed {
+        return (None, merged_links);
+    }
+    merged.updated_at = ours.updated_at.max(theirs.updated_at);
+    (Some(merged), merged_links)
+}
+
+/// 三方合并单个字段，返回是否采用另一边的值：只有另一边修改时采用，两边都修改时采用较新的一边
+fn take_theirs<T: PartialEq>(ours: &T, theirs: &T, base: Option<&T>, theirs_newer: bool) -> bool {
+    if ours == theirs {
+        return false;
+    }
+    match base {
+        Some(base) if base == ours => true,
+        Some(base) if base == theirs => false,
+        _ => theirs_newer,
+    }
+}
+
+/// 三方合并集合：保留本地的项（另一边删除的除外），再加上另一边新增的项
+fn merge_set<T: PartialEq + Clone>(ours: &[T], theirs: &[T], base: Option<&[T]>) -> Vec<T> {
+    let in_base = |item: &T| base.is_some_and(|base| base.contains(item));
+    let mut merged: Vec<T> = ours
+        .iter()
+        .filter(|item| theirs.contains(item) || !in_base(item))
+        .cloned()
+        .collect();
+    // 基础版本中已有、本地却没有的项是本地删除的
+    for item in theirs {
+        if !merged.contains(item) && !in_base(item) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use chrono::Duration;
+
+    #[test]
+    fn test_newest_version_wins_without_base() {
+        let mut ours = TodoList::new();
+        let shared = ours.add_task("写周报".to_string(), Priority::Low, None);
+        let mut theirs = ours.clone();
+
+        // 对方后修改，采用对方的值
+        theirs.set_priority(shared, Priority::High).unwrap();
+        ours.find_task_mut(shared).unwrap().updated_at = theirs.find_task(shared).unwrap().updated_at - Duration::seconds(1);
+        let report = ours.merge(&theirs, None);
+        assert_eq!(report.updated, vec![shared]);
+        assert_eq!(ours.find_task(shared).unwrap().priority, Priority::High);
+
+        // 本地后修改，保留本地的值
+        ours.set_priority(shared, Priority::Medium).unwrap();
+        let report = ours.merge(&theirs, None);
+        assert!(report.updated.is_empty());
+        assert_eq!(ours.find_task(shared).unwrap().priority, Priority::Medium);
+    }
+
+    #[test]
+    fn test_three_way_merge_keeps_both_sides_edits() {
+        let mut base = TodoList::new();
+        let id = base.add_task("写周报".to_string(), Priority::Low, None);
+        base.set_tags(id, vec!["work".to_string(), "weekly".to_string()]).unwrap();
+
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.set_priority(id, Priority::High).unwrap();
+        ours.set_tags(id, vec!["work".to_string(), "weekly".to_string(), "urgent".to_string()]).unwrap();
+        theirs.find_task_mut(id).unwrap().title = "写月报".to_string();
+        theirs.set_tags(id, vec!["work".to_string(), "report".to_string()]).unwrap();
+        theirs.find_task_mut(id).unwrap().updated_at = ours.find_task(id).unwrap().updated_at + Duration::seconds(1);
+
+        let report = ours.merge(&theirs, Some(&base));
+        assert_eq!(report.updated, vec![id]);
+        let task = ours.find_task(id).unwrap();
+        assert_eq!(task.title, "写月报");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.tags, ["work", "urgent", "report"]);
+    }
+
+    #[test]
+    fn test_three_way_merge_handles_deletions() {
+        let mut base = TodoList::new();
+        let kept = base.add_task("保留".to_string(), Priority::Low, None);
+        let deleted = base.add_task("对方删除".to_string(), Priority::Low, None);
+        let edited = base.add_task("对方删除但本地修改".to_string(), Priority::Low, None);
+
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        theirs.delete_task(deleted).unwrap();
+        theirs.delete_task(edited).unwrap();
+        ours.find_task_mut(edited).unwrap().updated_at += Duration::seconds(1);
+        ours.delete_task(kept).unwrap();
+
+        let report = ours.merge(&theirs, Some(&base));
+        assert_eq!(report.removed, vec![deleted]);
+        assert!(report.added.is_empty());
+        assert!(ours.find_task(kept).is_none());
+        assert!(ours.find_task(edited).is_some());
+    }
+
+    #[test]
+    fn test_new_tasks_are_added_with_local_ids() {
+        let mut ours = TodoList::new();
+        ours.add_task("本地任务".to_string(), Priority::Low, None);
+
+        // 对方用同样的 ID 1、2 新建了父子任务
+        let mut theirs = TodoList::new();
+        let parent = theirs.add_task("发布".to_string(), Priority::High, None);
+        let child = theirs.add_subtask(parent, "写更新日志".to_string(), Priority::Low, None).unwrap();
+        theirs.add_dependency(child, parent).unwrap();
+
+        let report = ours.merge(&theirs, None);
+        assert_eq!(report.added, vec![2, 3]);
+        assert_eq!(ours.len(), 3);
+        assert_eq!(ours.find_task(3).unwrap().parent_id, Some(2));
+        assert_eq!(ours.find_task(3).unwrap().depends_on, [2]);
+
+        // 再次合并没有变化
+        assert_eq!(ours.merge(&theirs, None), MergeReport::default());
+    }
+
+    #[test]
+    fn test_merge_set() {
+        let base = ["a", "b", "c"];
+        assert_eq!(merge_set(&["a", "b", "d"], &["b", "c", "e"], Some(&base)), ["b", "d", "e"]);
+        assert_eq!(merge_set(&["a"], &["b"], None), ["a", "b"]);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::display::format_minutes;
+use crate::error::{TodoError, TodoResult};
+use crate::task::Task;
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Duration, Utc};
+use notify_rust::{Notification, Urgency};
+
+/// 通知的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReminderKind {
+    /// 已经过期
+    Overdue,
+    /// 即将到期
+    DueSoon,
+}
+
+/// 一条待发送的提醒
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    pub task_id: u32,
+    pub task_uuid: String,
+    pub title: String,
+    pub due: DateTime<Utc>,
+    pub kind: ReminderKind,
+}
+
+impl Reminder {
+    fn new(task: &Task, due: DateTime<Utc>, kind: ReminderKind) -> Self {
+        Self {
+            task_id: task.id,
+            task_uuid: task.uuid.clone(),
+            title: task.title.clone(),
+            due,
+            kind,
+        }
+    }
+
+    /// 通知标题
+    pub fn summary(&self) -> String {
+        match self.kind {
+            ReminderKind::Overdue => format!("Overdue: {}", self.title),
+            ReminderKind::DueSoon => format!("Due soon: {}", self.title),
+        }
+    }
+
+    /// 通知正文
+    pub fn body(&self, now: DateTime<Utc>) -> String {
+        let minutes = (self.due - now).num_minutes().unsigned_abs();
+        let amount = format_minutes(u32::try_from(minutes).unwrap_or(u32::MAX));
+
+        match self.kind {
+            ReminderKind::Overdue => format!("Task {} is overdue by {}", self.task_id, amount),
+            ReminderKind::DueSoon => format!("Task {} is due in {}", self.task_id, amount),
+        }
+    }
+}
+
+/// 找出 `now` 时已过期或将在 `window` 内到期的未完成任务
+pub fn reminders(todo_list: &TodoList, window: Duration, now: DateTime<Utc>) -> Vec<Reminder> {
+    todo_list
+        .list_pending_tasks()
+        .into_iter()
+        .filter_map(|task| {
+            let due = task.due_date?;
+            if due < now {
+                Some(Reminder::new(task, due, ReminderKind::Overdue))
+            } else if due - now <= window {
+                Some(Reminder::new(task, due, ReminderKind::DueSoon))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 发送桌面通知
+pub fn send(reminder: &Reminder) -> TodoResult<()> {
+    let urgency = match reminder.kind {
+        ReminderKind::Overdue => Urgency::Critical,
+        ReminderKind::DueSoon => Urgency::Normal,
+    };
+
+    show(&reminder.summary(), &reminder.body(Utc::now()), urgency)
+}
+
+/// 发送番茄钟结束的通知
+pub fn send_pomodoro_done(task: &Task, minutes: u32) -> TodoResult<()> {
+    let body = format!("{} on task {}: {}", format_minutes(minutes), task.id, task.title);
+    show("Pomodoro done", &body, Urgency::Normal)
+}
+
+fn show(summary: &str, body: &str, urgency: Urgency) -> TodoResult<()> {
+    Notification::new()
+        .appname("todo")
+        .summary(summary)
+        .body(body)
+        .urgency(urgency)
+        .show()
+        .map(|_| ())
+        .map_err(|err| TodoError::NotificationError(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_reminders() {
+        let now = Utc::now();
+        let mut list = TodoList::new();
+        let overdue = list.add_task("交房租".to_string(), Priority::High, Some(now - Duration::hours(2)));
+        let soon = list.add_task("开会".to_string(), Priority::Low, Some(now + Duration::minutes(30)));
+        list.add_task("下周的事".to_string(), Priority::Low, Some(now + Duration::days(7)));
+        list.add_task("没有截止日期".to_string(), Priority::Low, None);
+        let done = list.add_task("已完成".to_string(), Priority::Low, Some(now - Duration::hours(1)));
+        list.complete_task(done).unwrap();
+
+        let reminders = reminders(&list, Duration::hours(1), now);
+        assert_eq!(reminders.len(), 2);
+        assert_eq!((reminders[0].task_id, reminders[0].kind), (overdue, ReminderKind::Overdue));
+        assert_eq!((reminders[1].task_id, reminders[1].kind), (soon, ReminderKind::DueSoon));
+
+        assert_eq!(reminders[0].summary(), "Overdue: 交房租");
+        assert_eq!(reminders[0].body(now), format!("Task {} is overdue by 2h", overdue));
+        assert_eq!(reminders[1].body(now), format!("Task {} is due in 30m", soon));
+    }
+}
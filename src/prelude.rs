@@ -0,0 +1,36 @@
+//! 作为库使用时的常用类型
+//!
+//! ```
+//! use rust_todo_cli::prelude::*;
+//!
+//! let mut list = TodoList::new();
+//! let id = list.add_task("写周报".to_string(), Priority::High, None);
+//! list.complete_task(id).unwrap();
+//! assert_eq!(list.list_completed_tasks().len(), 1);
+//! ```
+//!
+//! # 稳定性
+//!
+//! 这里导出的类型和函数遵循语义化版本：在同一个主版本内不会删除、改名或改变签名。
+//! `TodoError` 和 `EventKind` 标记了 `#[non_exhaustive]`，次版本中可能新增成员，
+//! 匹配时需要保留 `_` 分支。
+//! 其他模块（`cli`、`display`、`config` 等）服务于命令行程序，可能在次版本中变化。
+
+pub use crate::error::{TodoError, TodoResult};
+pub use crate::filter::{ListFilter, SortKey, StatusFilter};
+pub use crate::goal::{Goal, Progress};
+pub use crate::history::{Event, EventKind};
+pub use crate::id::{IdStrategy, Sequential, UuidV4};
+pub use crate::query::Query;
+pub use crate::stats::Stats;
+pub use crate::storage::{
+    load_from_file, load_from_file_with_format, save_to_file, save_to_file_with_format, FileStorage, MemoryStorage,
+    Storage, StorageFormat,
+};
+pub use crate::task::{Priority, Recurrence, Task, TaskBuilder};
+pub use crate::todo_list::{DeletePolicy, TodoList};
+pub use crate::trash::TrashedTask;
+pub use crate::undo::UndoStack;
+
+#[cfg(feature = "nanoid")]
+pub use crate::id::NanoId;
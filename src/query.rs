@@ -0,0 +1,393 @@
+use crate::dates::local_date;
+use crate::error::{TodoError, TodoResult};
+use crate::filter::StatusFilter;
+use crate::task::{Priority, Task};
+use chrono::NaiveDate;
+use std::str::FromStr;
+
+/// 解析后的查询表达式
+///
+/// 语法示例：`priority:high AND (tag:work OR overdue)`
+/// - 条件：`priority:<high|medium|low>`、`tag:<名称>`、`context:<名称>`、`title:<文字>`、
+///   `status:<pending|completed|overdue|today|week>`、`due-before:<YYYY-MM-DD>`、`due-after:<YYYY-MM-DD>`，以及单独的 `pending`、`completed`、`overdue`、`today`、`week`
+/// - 简写：`#work` 等同于 `tag:work`，`@home` 等同于 `context:home`
+/// - 运算符：`NOT`、`AND`、`OR`（不区分大小写，优先级依次降低），相邻的条件默认为 `AND`
+/// - 括号用于分组，包含空格的值用双引号括起来，例如 `title:"buy milk"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Term(Term),
+}
+
+/// 查询中的单个条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Status(StatusFilter),
+    Priority(Priority),
+    /// 标签名（不含 `#`，不区分大小写）
+    Tag(String),
+    /// 情境名（不含 `@`，不区分大小写）
+    Context(String),
+    /// 标题包含的文字（不区分大小写）
+    Title(String),
+    /// 截止日期早于这一天（不含当天）
+    DueBefore(NaiveDate),
+    /// 截止日期晚于这一天（不含当天）
+    DueAfter(NaiveDate),
+}
+
+impl Query {
+    /// 任务是否满足查询
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Query::And(left, right) => left.matches(task) && right.matches(task),
+            Query::Or(left, right) => left.matches(task) || right.matches(task),
+            Query::Not(inner) => !inner.matches(task),
+            Query::Term(term) => term.matches(task),
+        }
+    }
+}
+
+impl Term {
+    /// 任务是否满足条件
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Term::Status(status) => status.matches(task),
+            Term::Priority(priority) => task.priority == *priority,
+            Term::Tag(tag) => task.tags.iter().any(|task_tag| task_tag.eq_ignore_ascii_case(tag)),
+            Term::Context(context) => task
+                .context
+                .as_deref()
+                .is_some_and(|task_context| task_context.eq_ignore_ascii_case(context)),
+            Term::Title(text) => task.title.to_lowercase().contains(&text.to_lowercase()),
+            Term::DueBefore(date) => task.due_date.is_some_and(|due| local_date(due) < *date),
+            Term::DueAfter(date) => task.due_date.is_some_and(|due| local_date(due) > *date),
+        }
+    }
+
+    /// 解析 `字段:值` 形式的条件或单独的关键字
+    fn parse(word: &str) -> TodoResult<Self> {
+        if let Some(tag) = word.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+            return Ok(Term::Tag(tag.to_string()));
+        }
+        if let Some(context) = word.strip_prefix('@').filter(|context| !context.is_empty()) {
+            return Ok(Term::Context(context.to_string()));
+        }
+
+        let Some((field, value)) = word.split_once(':') else {
+            return parse_status(word)
+                .map(Term::Status)
+                .ok_or_else(|| invalid(format!("unknown keyword '{}'", word)));
+        };
+
+        if value.is_empty() {
+            return Err(invalid(format!("missing value for '{}'", field)));
+        }
+
+        match field.to_lowercase().as_str() {
+            "status" | "is" => parse_status(value)
+                .map(Term::Status)
+                .ok_or_else(|| invalid(format!("unknown status '{}'", value))),
+            "priority" | "p" => Priority::from_str(value)
+                .map(Term::Priority)
+                .map_err(|_| invalid(format!("unknown priority '{}'", value))),
+            "tag" | "t" => Ok(Term::Tag(value.trim_start_matches('#').to_string())),
+            "context" | "c" => Ok(Term::Context(value.trim_start_matches('@').to_string())),
+            "title" => Ok(Term::Title(value.to_string())),
+            "due-before" => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Term::DueBefore)
+                .map_err(|_| invalid(format!("invalid date '{}', expected YYYY-MM-DD", value))),
+            "due-after" => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Term::DueAfter)
+                .map_err(|_| invalid(format!("invalid date '{}', expected YYYY-MM-DD", value))),
+            _ => Err(invalid(format!("unknown field '{}'", field))),
+        }
+    }
+}
+
+fn parse_status(word: &str) -> Option<StatusFilter> {
+    match word.to_lowercase().as_str() {
+        "pending" => Some(StatusFilter::Pending),
+        "completed" | "done" => Some(StatusFilter::Completed),
+        "overdue" => Some(StatusFilter::Overdue),
+        "today" => Some(StatusFilter::Today),
+        "week" => Some(StatusFilter::Week),
+        _ => None,
+    }
+}
+
+fn invalid(message: String) -> TodoError {
+    TodoError::InvalidQuery(message)
+}
+
+impl FromStr for Query {
+    type Err = TodoError;
+
+    fn from_str(input: &str) -> TodoResult<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        if parser.tokens.is_empty() {
+            return Err(invalid("empty query".to_string()));
+        }
+
+        let query = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(Token::RParen) => Err(invalid("unexpected ')'".to_string())),
+            Some(token) => Err(invalid(format!("unexpected {}", token))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Word(word) => write!(f, "'{}'", word),
+        }
+    }
+}
+
+/// 拆分为括号、运算符和单词，双引号内的空格和括号属于单词的一部分
+fn tokenize(input: &str) -> TodoResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            _ => {
+                let mut word = String::new();
+                let mut quoted = false;
+                let mut has_quotes = false;
+                while let Some(&c) = chars.peek() {
+                    if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        quoted = !quoted;
+                        has_quotes = true;
+                    } else {
+                        word.push(c);
+                    }
+                }
+                if quoted {
+                    return Err(invalid("unterminated quote".to_string()));
+                }
+
+                let token = match word.to_uppercase().as_str() {
+                    _ if has_quotes => Token::Word(word),
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                };
+                tokens.push(token);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// 递归下降解析器，优先级从低到高：OR、AND、NOT
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> TodoResult<Query> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            query = Query::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> TodoResult<Query> {
+        let mut query = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                // 相邻的条件之间省略了 AND
+                Some(Token::Word(_) | Token::LParen | Token::Not) => {}
+                _ => break,
+            }
+            query = Query::And(Box::new(query), Box::new(self.parse_not()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_not(&mut self) -> TodoResult<Query> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> TodoResult<Query> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let query = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(query),
+                    _ => Err(invalid("missing ')'".to_string())),
+                }
+            }
+            Some(Token::Word(word)) => Term::parse(&word).map(Query::Term),
+            Some(token) => Err(invalid(format!("unexpected {}", token))),
+            None => Err(invalid("unexpected end of query".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn parse(input: &str) -> Query {
+        input.parse().unwrap()
+    }
+
+    fn error(input: &str) -> String {
+        match input.parse::<Query>() {
+            Err(TodoError::InvalidQuery(message)) => message,
+            other => panic!("expected an invalid query, got {:?}", other),
+        }
+    }
+
+    fn term(term: Term) -> Box<Query> {
+        Box::new(Query::Term(term))
+    }
+
+    #[test]
+    fn test_precedence_and_grouping() {
+        assert_eq!(
+            parse("priority:high AND (tag:work OR overdue)"),
+            Query::And(
+                term(Term::Priority(Priority::High)),
+                Box::new(Query::Or(
+                    term(Term::Tag("work".to_string())),
+                    term(Term::Status(StatusFilter::Overdue))
+                )),
+            )
+        );
+
+        // AND 比 OR 优先，NOT 只作用于紧随其后的条件
+        assert_eq!(
+            parse("tag:a or not tag:b and pending"),
+            Query::Or(
+                term(Term::Tag("a".to_string())),
+                Box::new(Query::And(
+                    Box::new(Query::Not(term(Term::Tag("b".to_string())))),
+                    term(Term::Status(StatusFilter::Pending)),
+                )),
+            )
+        );
+
+        // 相邻的条件之间默认为 AND
+        assert_eq!(parse("p:h #work"), parse("p:h AND #work"));
+    }
+
+    #[test]
+    fn test_terms() {
+        assert_eq!(parse("tag:#Work"), Query::Term(Term::Tag("Work".to_string())));
+        assert_eq!(parse("context:@home"), Query::Term(Term::Context("home".to_string())));
+        assert_eq!(parse("@home"), parse("context:home"));
+        assert_eq!(parse("title:\"buy milk\""), Query::Term(Term::Title("buy milk".to_string())));
+        assert_eq!(parse("\"title:and\""), Query::Term(Term::Title("and".to_string())));
+        assert_eq!(parse("is:done"), Query::Term(Term::Status(StatusFilter::Completed)));
+        assert_eq!(
+            parse("due-before:2025-07-01"),
+            Query::Term(Term::DueBefore(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()))
+        );
+        assert_eq!(
+            parse("due-after:2025-07-01"),
+            Query::Term(Term::DueAfter(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error(""), "empty query");
+        assert_eq!(error("(tag:work"), "missing ')'");
+        assert_eq!(error("tag:work)"), "unexpected ')'");
+        assert_eq!(error("tag:work AND"), "unexpected end of query");
+        assert_eq!(error("OR tag:work"), "unexpected OR");
+        assert_eq!(error("colour:red"), "unknown field 'colour'");
+        assert_eq!(error("urgent"), "unknown keyword 'urgent'");
+        assert_eq!(error("priority:urgent"), "unknown priority 'urgent'");
+        assert_eq!(error("tag:"), "missing value for 'tag'");
+        assert_eq!(error("title:\"buy"), "unterminated quote");
+    }
+
+    #[test]
+    fn test_matches() {
+        let query = parse("priority:high AND (tag:work OR overdue) AND NOT title:draft");
+
+        let mut work = Task::new(1, "Quarterly report".to_string(), Priority::High, None);
+        work.tags = vec!["work".to_string()];
+        assert!(query.matches(&work));
+
+        let mut overdue = Task::new(2, "Pay rent".to_string(), Priority::High, None);
+        overdue.due_date = Some(Utc::now() - Duration::days(1));
+        assert!(query.matches(&overdue));
+
+        let mut draft = work.clone();
+        draft.title = "Draft slides".to_string();
+        assert!(!query.matches(&draft));
+
+        let mut low = work.clone();
+        low.priority = Priority::Low;
+        assert!(!query.matches(&low));
+
+        let mut later = Task::new(3, "Plan offsite".to_string(), Priority::High, None);
+        later.due_date = Some(Utc.with_ymd_and_hms(2099, 1, 1, 0, 0, 0).unwrap());
+        assert!(!query.matches(&later));
+    }
+}
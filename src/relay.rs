@@ -0,0 +1,543 @@
+use crate::config::{data_dir_override, project_dirs};
+use crate::crypto::{decrypt, encrypt};
+use crate::error::{TodoError, TodoResult};
+use crate::storage::write_atomic;
+use crate::task::Task;
+use crate::todo_list::{DeletePolicy, TodoList};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 读取中继服务器地址的环境变量
+pub const RELAY_URL_ENV: &str = "TODO_RELAY_URL";
+
+/// 中继服务器上的一条消息，内容是加密后的差异，服务器无法解读
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayMessage {
+    /// 服务器分配的递增序号
+    pub seq: u64,
+    /// 十六进制编码的密文
+    pub data: String,
+}
+
+/// 中继服务器的最小接口，便于在测试中替换
+pub trait RelayApi {
+    /// 获取房间中序号大于 `after` 的消息（按序号升序）
+    fn fetch(&self, room: &str, after: u64) -> TodoResult<Vec<RelayMessage>>;
+    /// 向房间追加一条消息，返回服务器分配的序号
+    fn post(&self, room: &str, data: &str) -> TodoResult<u64>;
+}
+
+/// 基于 HTTP 的中继客户端
+///
+/// 服务器只需要按房间保存和返回不透明的消息：
+/// - `GET {server}/rooms/{room}/messages?after={seq}` 返回 `[{"seq": 1, "data": "..."}]`
+/// - `POST {server}/rooms/{room}/messages`，请求体为 `{"data": "..."}`，返回 `{"seq": 2}`
+pub struct RelayClient {
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct PostBody<'a> {
+    data: &'a str,
+}
+
+#[derive(Deserialize)]
+struct PostResponse {
+    seq: u64,
+}
+
+impl RelayClient {
+    /// 使用服务器地址创建客户端
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// 从环境变量读取服务器地址创建客户端
+    pub fn from_env() -> TodoResult<Self> {
+        std::env::var(RELAY_URL_ENV)
+            .map(Self::new)
+            .map_err(|_| TodoError::SyncError(format!("no relay server given and {} is not set", RELAY_URL_ENV)))
+    }
+
+    fn messages_url(&self, room: &str) -> String {
+        format!("{}/rooms/{}/messages", self.base_url, encode_segment(room))
+    }
+}
+
+/// 编码 URL 路径中的一段，房间 ID 中的 `/`、`?`、`#` 等不会改变请求的路径
+fn encode_segment(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+impl RelayApi for RelayClient {
+    fn fetch(&self, room: &str, after: u64) -> TodoResult<Vec<RelayMessage>> {
+        ureq::get(&self.messages_url(room))
+            .query("after", after.to_string())
+            .call()
+            .map_err(sync_error)?
+            .body_mut()
+            .read_json()
+            .map_err(sync_error)
+    }
+
+    fn post(&self, room: &str, data: &str) -> TodoResult<u64> {
+        let response: PostResponse = ureq::post(&self.messages_url(room))
+            .send_json(PostBody { data })
+            .map_err(sync_error)?
+            .body_mut()
+            .read_json()
+            .map_err(sync_error)?;
+        Ok(response.seq)
+    }
+}
+
+fn sync_error(err: ureq::Error) -> TodoError {
+    TodoError::SyncError(err.to_string())
+}
+
+/// 设备之间交换的任务内容：去掉只在本机有意义的字段，父任务用 UUID 表示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedTask {
+    pub task: Task,
+    pub parent_uuid: Option<String>,
+}
+
+impl SharedTask {
+    fn from_local(todo_list: &TodoList, task: &Task) -> Self {
+        let parent_uuid = task
+            .parent_id
+            .and_then(|id| todo_list.find_task(id))
+            .map(|parent| parent.uuid.clone());
+
+        let mut task = task.clone();
+        task.id = 0;
+        task.parent_id = None;
+        task.depends_on.clear();
+        task.goal_id = None;
+        task.todoist_id = None;
+        Self { task, parent_uuid }
+    }
+
+    /// 用于比较内容是否变化的序列化结果
+    fn fingerprint(&self) -> TodoResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// 一次推送的差异（加密前的内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diff {
+    /// 发送方设备 ID，用于忽略自己推送的消息
+    pub device: String,
+    /// 新增或修改的任务
+    pub upserts: Vec<SharedTask>,
+    /// 删除的任务 UUID
+    pub deletes: Vec<String>,
+}
+
+/// 某个房间的本地同步状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayState {
+    /// 本机的设备 ID
+    pub device: String,
+    /// 已经处理过的最大消息序号
+    pub last_seq: u64,
+    /// 上次同步后各任务的内容（UUID → 序列化的 `SharedTask`），用于计算本地差异
+    pub snapshot: BTreeMap<String, String>,
+}
+
+impl Default for RelayState {
+    fn default() -> Self {
+        Self {
+            device: uuid::Uuid::new_v4().to_string(),
+            last_seq: 0,
+            snapshot: BTreeMap::new(),
+        }
+    }
+}
+
+impl RelayState {
+    /// 房间同步状态的默认路径（Linux 下为 `~/.local/state/todo/relay/<room>.json`）
+    pub fn default_path(room: &str) -> Option<PathBuf> {
+        let file_name = format!("{}.json", sanitize_room(room));
+        if let Some(dir) = data_dir_override() {
+            return Some(dir.join("relay").join(file_name));
+        }
+        project_dirs().map(|dirs| {
+            dirs.state_dir()
+                .unwrap_or_else(|| dirs.data_local_dir())
+                .join("relay")
+                .join(file_name)
+        })
+    }
+
+    /// 从指定文件加载同步状态，文件不存在时返回新状态
+    pub fn load_from<P: AsRef<Path>>(path: P) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !path_ref.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path_ref)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存到指定文件
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> TodoResult<()> {
+        let path_ref = path.as_ref();
+        if let Some(parent) = path_ref.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path_ref, content.as_bytes())
+    }
+}
+
+/// 房间 ID 中只保留可以安全用作文件名的字符
+fn sanitize_room(room: &str) -> String {
+    room.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// 同步结果统计
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RelaySyncReport {
+    /// 收到的其他设备的消息数
+    pub received: usize,
+    /// 应用到本地的任务变更数
+    pub applied: usize,
+    /// 推送出去的任务变更数
+    pub sent: usize,
+}
+
+/// 通过中继服务器同步
+///
+/// 1. 拉取上次同步后的消息，用 `key` 解密并应用其他设备的变更；
+///    上次同步后在本地修改过的任务保留本地内容
+/// 2. 把上次同步后本地新增、修改和删除的任务加密后推送到房间
+pub fn sync_relay<A: RelayApi>(
+    todo_list: &mut TodoList,
+    state: &mut RelayState,
+    api: &A,
+    room: &str,
+    key: &str,
+) -> TodoResult<RelaySyncReport> {
+    let mut report = RelaySyncReport::default();
+
+    let before = shared_tasks(todo_list)?;
+    let changed_locally: BTreeSet<String> = before
+        .keys()
+        .chain(state.snapshot.keys())
+        .filter(|uuid| before.get(*uuid).map(|(_, fp)| fp) != state.snapshot.get(*uuid))
+        .cloned()
+        .collect();
+
+    // 1. 应用其他设备的变更
+    for message in api.fetch(room, state.last_seq)? {
+        state.last_seq = state.last_seq.max(message.seq);
+
+        let plaintext = decrypt(&from_hex(&message.data)?, key)?;
+        let diff: Diff = serde_json::from_slice(&plaintext)?;
+        if diff.device == state.device {
+            continue;
+        }
+        report.received += 1;
+
+        let mut parents = Vec::new();
+        for shared in diff.upserts {
+            if changed_locally.contains(&shared.task.uuid) {
+                continue;
+            }
+            parents.push((shared.task.uuid.clone(), shared.parent_uuid.clone()));
+            apply_upsert(todo_list, shared.task)?;
+            report.applied += 1;
+        }
+
+        // 父任务可能在同一条消息中稍后才出现，所有任务插入后再关联
+        for (uuid, parent_uuid) in parents {
+            let parent_id = parent_uuid
+                .and_then(|parent| todo_list.find_task_by_uuid(&parent))
+                .map(|parent| parent.id);
+            if let Some(id) = todo_list.find_task_by_uuid(&uuid).map(|task| task.id) {
+                todo_list.set_parent(id, parent_id)?;
+            }
+        }
+
+        for uuid in diff.deletes {
+            if changed_locally.contains(&uuid) {
+                continue;
+            }
+            if let Some(id) = todo_list.find_task_by_uuid(&uuid).map(|task| task.id) {
+                todo_list.delete_task_with_policy(id, DeletePolicy::Orphan)?;
+                report.applied += 1;
+            }
+        }
+    }
+
+    // 2. 推送本地变更：没有在本地修改过的任务此时都已与房间一致
+    let current = shared_tasks(todo_list)?;
+    let mut synced: BTreeMap<String, String> = state
+        .snapshot
+        .iter()
+        .filter(|(uuid, _)| changed_locally.contains(*uuid))
+        .map(|(uuid, fp)| (uuid.clone(), fp.clone()))
+        .collect();
+    synced.extend(
+        current
+            .iter()
+            .filter(|(uuid, _)| !changed_locally.contains(*uuid))
+            .map(|(uuid, (_, fp))| (uuid.clone(), fp.clone())),
+    );
+
+    let upserts: Vec<SharedTask> = current
+        .iter()
+        .filter(|(uuid, (_, fp))| synced.get(*uuid) != Some(fp))
+        .map(|(_, (shared, _))| shared.clone())
+        .collect();
+    let deletes: Vec<String> = synced
+        .keys()
+        .filter(|uuid| !current.contains_key(*uuid))
+        .cloned()
+        .collect();
+
+    if !upserts.is_empty() || !deletes.is_empty() {
+        report.sent = upserts.len() + deletes.len();
+        let diff = Diff {
+            device: state.device.clone(),
+            upserts,
+            deletes,
+        };
+        let ciphertext = encrypt(&serde_json::to_vec(&diff)?, key)?;
+        api.post(room, &to_hex(&ciphertext))?;
+    }
+
+    state.snapshot = current.into_iter().map(|(uuid, (_, fp))| (uuid, fp)).collect();
+    Ok(report)
+}
+
+/// 本地所有任务的共享内容和指纹（按 UUID）
+fn shared_tasks(todo_list: &TodoList) -> TodoResult<BTreeMap<String, (SharedTask, String)>> {
+    todo_list
+        .list_tasks()
+        .iter()
+        .map(|task| {
+            let shared = SharedTask::from_local(todo_list, task);
+            let fingerprint = shared.fingerprint()?;
+            Ok((task.uuid.clone(), (shared, fingerprint)))
+        })
+        .collect()
+}
+
+/// 新增或覆盖任务，已有任务保留本地 ID 和只在本机有意义的关联（父任务、依赖、目标）
+fn apply_upsert(todo_list: &mut TodoList, mut incoming: Task) -> TodoResult<()> {
+    match todo_list.find_task_by_uuid(&incoming.uuid) {
+        Some(task) => {
+            let id = task.id;
+            incoming.parent_id = task.parent_id;
+            incoming.depends_on = task.depends_on.clone();
+            incoming.goal_id = task.goal_id;
+            incoming.todoist_id = task.todoist_id.clone();
+            todo_list.replace_task(id, incoming)
+        }
+        None => {
+            todo_list.insert_task(incoming);
+            Ok(())
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> TodoResult<Vec<u8>> {
+    let invalid = || TodoError::SyncError("relay returned a malformed message".to_string());
+
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()).ok_or_else(invalid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use std::cell::RefCell;
+
+    const ROOM: &str = "room-1";
+    const KEY: &str = "correct horse battery staple";
+
+    /// 内存中的中继服务器
+    #[derive(Default)]
+    struct MemoryRelay {
+        messages: RefCell<Vec<RelayMessage>>,
+    }
+
+    impl RelayApi for MemoryRelay {
+        fn fetch(&self, _room: &str, after: u64) -> TodoResult<Vec<RelayMessage>> {
+            Ok(self.messages.borrow().iter().filter(|m| m.seq > after).cloned().collect())
+        }
+
+        fn post(&self, _room: &str, data: &str) -> TodoResult<u64> {
+            let mut messages = self.messages.borrow_mut();
+            let seq = messages.len() as u64 + 1;
+            messages.push(RelayMessage { seq, data: data.to_string() });
+            Ok(seq)
+        }
+    }
+
+    struct Device {
+        list: TodoList,
+        state: RelayState,
+    }
+
+    impl Device {
+        fn new() -> Self {
+            Self {
+                list: TodoList::new(),
+                state: RelayState::default(),
+            }
+        }
+
+        fn sync(&mut self, relay: &MemoryRelay) -> RelaySyncReport {
+            sync_relay(&mut self.list, &mut self.state, relay, ROOM, KEY).unwrap()
+        }
+
+        fn task(&self, uuid: &str) -> Option<&Task> {
+            self.list.find_task_by_uuid(uuid)
+        }
+    }
+
+    #[test]
+    fn test_two_devices_sync_through_relay() {
+        let relay = MemoryRelay::default();
+        let mut laptop = Device::new();
+        let mut desktop = Device::new();
+
+        // 桌面机已有一个任务，占用 ID 1，同步过来的任务会分配新的本地 ID
+        desktop.list.add_task("本地任务".to_string(), Priority::Low, None);
+
+        let parent = laptop.list.add_task("发布 v1".to_string(), Priority::High, None);
+        let child = laptop.list.add_subtask(parent, "写更新日志".to_string(), Priority::Medium, None).unwrap();
+        let parent_uuid = laptop.list.find_task(parent).unwrap().uuid.clone();
+        let child_uuid = laptop.list.find_task(child).unwrap().uuid.clone();
+
+        let report = laptop.sync(&relay);
+        assert_eq!(report, RelaySyncReport { received: 0, applied: 0, sent: 2 });
+        // 中继服务器只能看到密文
+        assert!(!relay.messages.borrow()[0].data.contains(&to_hex("发布".as_bytes())));
+
+        let report = desktop.sync(&relay);
+        assert_eq!((report.received, report.applied, report.sent), (1, 2, 1));
+        let synced_parent = desktop.task(&parent_uuid).unwrap().id;
+        assert_ne!(synced_parent, parent);
+        assert_eq!(desktop.task(&child_uuid).unwrap().parent_id, Some(synced_parent));
+
+        // 桌面机完成子任务，笔记本删除父任务
+        let synced_child = desktop.task(&child_uuid).unwrap().id;
+        desktop.list.complete_task(synced_child).unwrap();
+        desktop.sync(&relay);
+
+        let report = laptop.sync(&relay);
+        assert_eq!(report.received, 2);
+        assert!(laptop.task(&child_uuid).unwrap().completed);
+        assert_eq!(laptop.list.len(), 3);
+
+        laptop.list.delete_task_with_policy(parent, DeletePolicy::Orphan).unwrap();
+        laptop.sync(&relay);
+        desktop.sync(&relay);
+        assert!(desktop.task(&parent_uuid).is_none());
+        assert_eq!(desktop.task(&child_uuid).unwrap().parent_id, None);
+
+        // 再次同步时没有新的变更
+        assert_eq!(laptop.sync(&relay), RelaySyncReport::default());
+        assert_eq!(desktop.sync(&relay), RelaySyncReport::default());
+    }
+
+    #[test]
+    fn test_local_changes_win_over_remote() {
+        let relay = MemoryRelay::default();
+        let mut laptop = Device::new();
+        let mut desktop = Device::new();
+
+        let id = laptop.list.add_task("买牛奶".to_string(), Priority::Low, None);
+        let uuid = laptop.list.find_task(id).unwrap().uuid.clone();
+        laptop.sync(&relay);
+        desktop.sync(&relay);
+
+        // 两端在同步之间修改了同一个任务，后同步的一端保留自己的内容并推送出去
+        laptop.list.set_priority(id, Priority::High).unwrap();
+        let desktop_id = desktop.task(&uuid).unwrap().id;
+        desktop.list.complete_task(desktop_id).unwrap();
+
+        laptop.sync(&relay);
+        desktop.sync(&relay);
+        assert!(desktop.task(&uuid).unwrap().completed);
+        assert_eq!(desktop.task(&uuid).unwrap().priority, Priority::Low);
+
+        laptop.sync(&relay);
+        assert!(laptop.task(&uuid).unwrap().completed);
+    }
+
+    #[test]
+    fn test_wrong_key_is_rejected() {
+        let relay = MemoryRelay::default();
+        let mut laptop = Device::new();
+        laptop.list.add_task("买牛奶".to_string(), Priority::Low, None);
+        laptop.sync(&relay);
+
+        let mut list = TodoList::new();
+        let mut state = RelayState::default();
+        let result = sync_relay(&mut list, &mut state, &relay, ROOM, "wrong key");
+        assert!(matches!(result, Err(TodoError::EncryptionError(_))));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = [0u8, 15, 16, 255];
+        assert_eq!(to_hex(&bytes), "000f10ff");
+        assert_eq!(from_hex("000f10ff").unwrap(), bytes);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+
+    #[test]
+    fn test_messages_url_encodes_room() {
+        let client = RelayClient::new("https://relay.example.com/".to_string());
+        assert_eq!(
+            client.messages_url("team/room 1?x#y"),
+            "https://relay.example.com/rooms/team%2Froom%201%3Fx%23y/messages"
+        );
+        assert_eq!(client.messages_url("room-1"), "https://relay.example.com/rooms/room-1/messages");
+    }
+
+    #[test]
+    fn test_state_path_uses_safe_file_name() {
+        assert_eq!(sanitize_room("team/room 1"), "team_room_1");
+
+        let path = "test_relay_state.json";
+        let mut state = RelayState::load_from("test_missing_relay_state.json").unwrap();
+        state.last_seq = 7;
+        state.save_to(path).unwrap();
+        assert_eq!(RelayState::load_from(path).unwrap(), state);
+        let _ = fs::remove_file(path);
+    }
+}
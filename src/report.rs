@@ -0,0 +1,392 @@
+use crate::dates::{end_of_day, local_date};
+use crate::display::format_date;
+use crate::history::{Event, EventKind};
+use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+/// 周报覆盖的天数
+pub const REVIEW_DAYS: i64 = 7;
+
+/// 一段时间内的列表变更汇总
+#[derive(Debug)]
+pub struct Changelog<'a> {
+    /// 起始时间
+    pub since: DateTime<Utc>,
+    pub added: Vec<&'a Event>,
+    pub completed: Vec<&'a Event>,
+    pub rescheduled: Vec<&'a Event>,
+    pub skipped: Vec<&'a Event>,
+    pub deleted: Vec<&'a Event>,
+}
+
+/// 汇总 `since` 之后的历史记录
+pub fn changelog(todo_list: &TodoList, since: DateTime<Utc>) -> Changelog<'_> {
+    let mut changelog = Changelog {
+        since,
+        added: Vec::new(),
+        completed: Vec::new(),
+        rescheduled: Vec::new(),
+        skipped: Vec::new(),
+        deleted: Vec::new(),
+    };
+
+    for event in todo_list.history().iter().filter(|event| event.at >= since) {
+        let section = match event.kind {
+            // 恢复的任务重新出现在列表中，与新建的一起列出
+            EventKind::Added | EventKind::Restored => &mut changelog.added,
+            EventKind::Completed => &mut changelog.completed,
+            EventKind::Rescheduled { .. } => &mut changelog.rescheduled,
+            EventKind::Skipped { .. } => &mut changelog.skipped,
+            EventKind::Deleted => &mut changelog.deleted,
+            // 修改和重新打开不单独汇总，见 `todo show <id> --history`
+            EventKind::Edited { .. } | EventKind::DefaultsApplied { .. } | EventKind::Reopened => continue,
+        };
+        section.push(event);
+    }
+
+    changelog
+}
+
+impl Changelog<'_> {
+    /// 期间是否没有任何变更
+    pub fn is_empty(&self) -> bool {
+        self.sections().iter().all(|(_, events)| events.is_empty())
+    }
+
+    /// 纯文本格式（不带颜色，便于粘贴到邮件中）
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Changelog since {}\n", format_date(&self.since));
+
+        if self.is_empty() {
+            out.push_str("\nNo activity.\n");
+            return out;
+        }
+
+        for (name, events) in self.sections().iter().filter(|(_, events)| !events.is_empty()) {
+            out.push_str(&format!("\n{} ({})\n", name, events.len()));
+            for event in events.iter() {
+                out.push_str(&format!("  - [{}] {}{}\n", event.task_id, event.title, describe(event)));
+            }
+        }
+
+        out
+    }
+
+    /// Markdown 格式
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Changelog since {}\n", format_date(&self.since));
+
+        if self.is_empty() {
+            out.push_str("\n_No activity._\n");
+            return out;
+        }
+
+        for (name, events) in self.sections().iter().filter(|(_, events)| !events.is_empty()) {
+            out.push_str(&format!("\n## {} ({})\n\n", name, events.len()));
+            for event in events.iter() {
+                out.push_str(&format!("- {}{}\n", event.title, describe(event)));
+            }
+        }
+
+        out
+    }
+
+    fn sections(&self) -> [(&'static str, &[&Event]); 5] {
+        [
+            ("Added", &self.added),
+            ("Completed", &self.completed),
+            ("Rescheduled", &self.rescheduled),
+            ("Skipped", &self.skipped),
+            ("Deleted", &self.deleted),
+        ]
+    }
+}
+
+/// 改期和跳过记录显示新旧截止日期，其他记录不附加内容
+fn describe(event: &Event) -> String {
+    let show = |date: &Option<DateTime<Utc>>| date.as_ref().map_or("none".to_string(), format_date);
+    match &event.kind {
+        EventKind::Rescheduled { from, to } => format!(" ({} → {})", show(from), show(to)),
+        EventKind::Skipped { from, to } => format!(" ({} → {})", show(from), format_date(to)),
+        _ => String::new(),
+    }
+}
+
+/// 周报和任务列表中任务的分组方式
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupBy {
+    /// 按优先级分组
+    #[default]
+    Priority,
+    /// 按标签分组（带多个标签的任务出现在每个标签下）
+    Tag,
+    /// 按截止日期所在的周（从周一开始）分组
+    DueWeek,
+    /// 按状态分组：过期、待办、已完成
+    Status,
+}
+
+/// 最近一周的回顾：完成了什么、新增了什么、还有什么过期
+#[derive(Debug)]
+pub struct WeeklyReview<'a> {
+    /// 起始时间
+    pub since: DateTime<Utc>,
+    /// 结束时间
+    pub until: DateTime<Utc>,
+    pub group_by: GroupBy,
+    pub completed: Vec<&'a Task>,
+    pub added: Vec<&'a Task>,
+    pub overdue: Vec<&'a Task>,
+}
+
+/// 汇总截至 `now` 的最近一周
+pub fn weekly_review(todo_list: &TodoList, now: DateTime<Utc>, group_by: GroupBy) -> WeeklyReview<'_> {
+    let since = now - Duration::days(REVIEW_DAYS);
+    let tasks = todo_list.list_tasks();
+
+    WeeklyReview {
+        since,
+        until: now,
+        group_by,
+        completed: tasks
+            .iter()
+            .filter(|task| task.completed_at.is_some_and(|at| at >= since))
+            .collect(),
+        added: tasks.iter().filter(|task| task.created_at >= since).collect(),
+        overdue: tasks
+            .iter()
+            .filter(|task| !task.completed && task.due_date.is_some_and(|due| due < now))
+            .collect(),
+    }
+}
+
+impl WeeklyReview<'_> {
+    /// 纯文本格式（不带颜色，便于粘贴到状态更新中）
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Weekly review: {} to {}\n", format_date(&self.since), format_date(&self.until));
+
+        for (name, tasks) in self.sections() {
+            out.push_str(&format!("\n{} ({})\n", name, tasks.len()));
+            if tasks.is_empty() {
+                out.push_str("  None\n");
+            }
+            for (group, tasks) in self.groups(tasks) {
+                out.push_str(&format!("  {}\n", group));
+                for task in tasks {
+                    out.push_str(&format!("    - [{}] {}\n", task.id, task.title));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Markdown 格式
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Weekly review: {} to {}\n",
+            format_date(&self.since),
+            format_date(&self.until)
+        );
+
+        for (name, tasks) in self.sections() {
+            out.push_str(&format!("\n## {} ({})\n", name, tasks.len()));
+            if tasks.is_empty() {
+                out.push_str("\n_None._\n");
+            }
+            for (group, tasks) in self.groups(tasks) {
+                out.push_str(&format!("\n### {}\n\n", group));
+                for task in tasks {
+                    out.push_str(&format!("- {}\n", task.title));
+                }
+            }
+        }
+
+        out
+    }
+
+    fn sections(&self) -> [(&'static str, &[&Task]); 3] {
+        [
+            ("Completed", &self.completed),
+            ("Added", &self.added),
+            ("Still overdue", &self.overdue),
+        ]
+    }
+
+    /// 按分组方式整理任务，省略空分组
+    fn groups<'t>(&self, tasks: &[&'t Task]) -> Vec<(String, Vec<&'t Task>)> {
+        group_tasks(tasks, self.group_by)
+    }
+}
+
+/// 按分组方式整理任务，返回按顺序排列的组名和组内任务，省略空分组
+pub fn group_tasks<'t>(tasks: &[&'t Task], group_by: GroupBy) -> Vec<(String, Vec<&'t Task>)> {
+    let group = |label: &str, filter: &dyn Fn(&Task) -> bool| {
+        (label.to_string(), tasks.iter().copied().filter(|task| filter(task)).collect::<Vec<_>>())
+    };
+
+    let groups = match group_by {
+        GroupBy::Priority => [Priority::High, Priority::Medium, Priority::Low]
+            .into_iter()
+            .map(|priority| group(&priority.to_string(), &|task| task.priority == priority))
+            .collect(),
+        GroupBy::Tag => {
+            let mut tags: Vec<&String> = tasks.iter().flat_map(|task| &task.tags).collect();
+            tags.sort();
+            tags.dedup();
+
+            let mut groups: Vec<(String, Vec<&Task>)> = tags
+                .into_iter()
+                .map(|tag| group(&format!("#{}", tag), &|task| task.tags.contains(tag)))
+                .collect();
+            groups.push(group("Untagged", &|task| task.tags.is_empty()));
+            groups
+        }
+        GroupBy::DueWeek => {
+            // 按本地日期所在的周分组，周一开始
+            let week_start = |due: DateTime<Utc>| {
+                let date = local_date(due);
+                date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            };
+            let mut weeks: Vec<NaiveDate> = tasks.iter().filter_map(|task| task.due_date).map(week_start).collect();
+            weeks.sort();
+            weeks.dedup();
+
+            let mut groups: Vec<(String, Vec<&Task>)> = weeks
+                .into_iter()
+                .map(|week| {
+                    group(&format!("Week of {}", format_date(&end_of_day(week))), &|task| {
+                        task.due_date.is_some_and(|due| week_start(due) == week)
+                    })
+                })
+                .collect();
+            groups.push(group("No due date", &|task| task.due_date.is_none()));
+            groups
+        }
+        GroupBy::Status => vec![
+            group("Overdue", &|task| task.is_overdue()),
+            group("Pending", &|task| !task.completed && !task.is_overdue()),
+            group("Completed", &|task| task.completed),
+        ],
+    };
+
+    groups.into_iter().filter(|(_, tasks)| !tasks.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::local_time;
+    use crate::task::Priority;
+    use chrono::Duration;
+
+    #[test]
+    fn test_changelog_sections() {
+        let mut list = TodoList::new();
+        let report = list.add_task("写周报".to_string(), Priority::High, None);
+        let call = list.add_task("给妈妈打电话".to_string(), Priority::Low, None);
+        let due = local_time(2025, 1, 3, 23, 59, 59);
+        list.set_due_date(report, Some(due)).unwrap();
+        list.complete_task(report).unwrap();
+        list.delete_task(call).unwrap();
+
+        let changelog = changelog(&list, Utc::now() - Duration::days(7));
+        assert_eq!(changelog.added.len(), 2);
+        assert_eq!(changelog.completed.len(), 1);
+        assert_eq!(changelog.rescheduled.len(), 1);
+        assert_eq!(changelog.deleted.len(), 1);
+
+        let text = changelog.to_text();
+        assert!(text.contains("Added (2)"));
+        assert!(text.contains("[1] 写周报 (none → 2025-01-03)"));
+
+        let markdown = changelog.to_markdown();
+        assert!(markdown.starts_with("# Changelog since"));
+        assert!(markdown.contains("## Deleted (1)\n\n- 给妈妈打电话\n"));
+    }
+
+    #[test]
+    fn test_changelog_ignores_older_events() {
+        let mut list = TodoList::new();
+        list.add_task("旧任务".to_string(), Priority::Low, None);
+
+        let changelog = changelog(&list, Utc::now() + Duration::seconds(1));
+        assert!(changelog.is_empty());
+        assert!(changelog.to_text().contains("No activity."));
+    }
+
+    #[test]
+    fn test_weekly_review_by_priority() {
+        let now = Utc::now();
+        let mut list = TodoList::new();
+        let report = list.add_task("写周报".to_string(), Priority::High, None);
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        let rent = list.add_task("交房租".to_string(), Priority::High, Some(now - Duration::days(1)));
+        let old = list.add_task("旧任务".to_string(), Priority::Low, None);
+        list.complete_task(report).unwrap();
+        list.find_task_mut(old).unwrap().created_at = now - Duration::days(30);
+
+        let review = weekly_review(&list, now, GroupBy::Priority);
+        assert_eq!(review.completed.len(), 1);
+        assert_eq!(review.added.len(), 3);
+        assert_eq!(review.overdue.len(), 1);
+        assert_eq!(review.overdue[0].id, rent);
+
+        let text = review.to_text();
+        assert!(text.contains("Completed (1)\n  High\n    - [1] 写周报\n"));
+        assert!(text.contains("Added (3)\n  High\n"));
+
+        let markdown = review.to_markdown();
+        assert!(markdown.starts_with("# Weekly review:"));
+        assert!(markdown.contains("## Still overdue (1)\n\n### High\n\n- 交房租\n"));
+    }
+
+    #[test]
+    fn test_weekly_review_by_tag() {
+        let mut list = TodoList::new();
+        let deploy = list.add_task("上线".to_string(), Priority::High, None);
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.find_task_mut(deploy).unwrap().tags = vec!["work".to_string(), "ops".to_string()];
+
+        let review = weekly_review(&list, Utc::now(), GroupBy::Tag);
+        let text = review.to_text();
+        assert!(text.contains("Added (2)\n  #ops\n    - [1] 上线\n  #work\n    - [1] 上线\n  Untagged\n    - [2] 买牛奶\n"));
+        assert!(text.contains("Completed (0)\n  None\n"));
+    }
+
+    #[test]
+    fn test_group_tasks_by_due_week_and_status() {
+        let at = |day| Some(local_time(2099, 1, day, 12, 0, 0));
+        let mut list = TodoList::new();
+        // 2099-01-05 是周一
+        let monday = list.add_task("周一".to_string(), Priority::Low, at(5));
+        let sunday = list.add_task("周日".to_string(), Priority::Low, at(11));
+        let next = list.add_task("下周".to_string(), Priority::Low, at(12));
+        let undated = list.add_task("没有日期".to_string(), Priority::Low, None);
+        list.complete_task(undated).unwrap();
+        let tasks: Vec<&Task> = list.list_tasks().iter().collect();
+
+        let ids = |groups: Vec<(String, Vec<&Task>)>| -> Vec<(String, Vec<u32>)> {
+            groups
+                .into_iter()
+                .map(|(label, tasks)| (label, tasks.iter().map(|task| task.id).collect()))
+                .collect()
+        };
+        assert_eq!(
+            ids(group_tasks(&tasks, GroupBy::DueWeek)),
+            [
+                ("Week of 2099-01-05".to_string(), vec![monday, sunday]),
+                ("Week of 2099-01-12".to_string(), vec![next]),
+                ("No due date".to_string(), vec![undated]),
+            ]
+        );
+        assert_eq!(
+            ids(group_tasks(&tasks, GroupBy::Status)),
+            [
+                ("Pending".to_string(), vec![monday, sunday, next]),
+                ("Completed".to_string(), vec![undated]),
+            ]
+        );
+    }
+}
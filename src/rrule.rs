@@ -0,0 +1,474 @@
+use crate::dates::{from_local, to_local};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 查找下一次日期时最多检查的周期数，超过后认为规则不会再产生日期
+const MAX_PERIODS: u32 = 5000;
+
+/// 星期几在 RRULE 中的写法，按周一到周日排列
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// 重复的基本单位（`FREQ`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// iCalendar 的重复规则（RFC 5545 中 RRULE 的一个子集），例如 `FREQ=MONTHLY;BYDAY=1MO`（每月第一个周一）
+///
+/// 支持 `FREQ`、`INTERVAL`、`BYDAY`（月和年规则中可以带序号，例如 `-1FR`）、`BYMONTHDAY`、`BYMONTH`
+/// 和 `UNTIL`；`COUNT`、`BYSETPOS` 等其他部分会被拒绝。一周从周一开始。
+///
+/// 规则以当前的截止日期为起点计算，保留截止日期中的时间。
+///
+/// # 示例
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use rust_todo_cli::rrule::RRule;
+///
+/// let rule: RRule = "FREQ=MONTHLY;BYDAY=1MO".parse().unwrap();
+/// let due = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+/// assert_eq!(rule.next_after(due), Some(Utc.with_ymd_and_hms(2025, 2, 3, 9, 0, 0).unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RRule {
+    freq: Frequency,
+    interval: u32,
+    /// BYDAY，下标为星期几（周一为 0）：第 0 位表示每个，第 1-5 位表示第 1-5 个，第 6-10 位表示倒数第 1-5 个
+    by_day: [u16; 7],
+    /// BYMONTHDAY：第 0-30 位表示 1-31 日，第 31-61 位表示倒数第 1-31 日
+    by_month_day: u64,
+    /// BYMONTH：第 0-11 位表示 1-12 月
+    by_month: u16,
+    until: Option<DateTime<Utc>>,
+}
+
+impl RRule {
+    /// 给定日期之后的下一次日期，规则已经结束（超过 `UNTIL`）或不会再产生日期时返回 `None`
+    pub fn next_after(&self, date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // 按本地日期计算星期几和月份中的日子
+        let local = to_local(date).naive_local();
+        let time = local.time();
+        let anchor = local.date();
+
+        for period in 0..MAX_PERIODS {
+            let step = period.checked_mul(self.interval)?;
+            let mut days = self.period_dates(anchor, step)?;
+            days.sort();
+
+            let next = days
+                .into_iter()
+                .map(|day| from_local(day.and_time(time)))
+                .find(|candidate| *candidate > date);
+            if let Some(next) = next {
+                return self.until.is_none_or(|until| next <= until).then_some(next);
+            }
+        }
+        None
+    }
+
+    /// 从 `anchor` 所在的周期往后数第 `step` 个周期中的所有日期，日期超出范围时返回 `None`
+    fn period_dates(&self, anchor: NaiveDate, step: u32) -> Option<Vec<NaiveDate>> {
+        let days = match self.freq {
+            Frequency::Daily => {
+                let day = anchor.checked_add_signed(Duration::days(step.into()))?;
+                let matches = self.by_day_is_empty() || self.by_day[weekday_index(day.weekday())] & 1 != 0;
+                if matches && self.matches_month_day(day) {
+                    vec![day]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let monday = anchor - Duration::days(anchor.weekday().num_days_from_monday().into());
+                let monday = monday.checked_add_signed(Duration::weeks(step.into()))?;
+                if self.by_day_is_empty() {
+                    vec![monday + Duration::days(anchor.weekday().num_days_from_monday().into())]
+                } else {
+                    (0..7)
+                        .filter(|&index| self.by_day[index] != 0)
+                        .map(|index| monday + Duration::days(index as i64))
+                        .collect()
+                }
+            }
+            Frequency::Monthly => {
+                let first = anchor.with_day(1)?.checked_add_months(Months::new(step))?;
+                self.month_dates(first, anchor.day())
+            }
+            Frequency::Yearly => {
+                let year = anchor.year().checked_add(i32::try_from(step).ok()?)?;
+                // 没有任何 BY* 部分时只在起点所在的月份，否则展开到每个月再按 BYMONTH 筛选
+                let months: Vec<u32> = if self.by_month == 0 && self.by_day_is_empty() && self.by_month_day == 0 {
+                    vec![anchor.month()]
+                } else {
+                    (1..=12).collect()
+                };
+                months
+                    .into_iter()
+                    .filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1))
+                    .flat_map(|first| self.month_dates(first, anchor.day()))
+                    .collect()
+            }
+        };
+
+        Some(days.into_iter().filter(|day| self.matches_month(*day)).collect())
+    }
+
+    /// 某个月中符合 BYDAY 和 BYMONTHDAY 的日期；两者都没有时为与起点相同的日子（这个月没有这一天时跳过）
+    fn month_dates(&self, first: NaiveDate, anchor_day: u32) -> Vec<NaiveDate> {
+        let last = days_in_month(first);
+        let by_month_day: Vec<u32> = (1..=last).filter(|&day| self.month_day_allowed(day, last)).collect();
+        let by_day: Vec<u32> = (1..=last)
+            .filter(|&day| {
+                let date = first.with_day(day).unwrap_or(first);
+                let bits = self.by_day[weekday_index(date.weekday())];
+                let nth = day.div_ceil(7);
+                let nth_from_end = (last - day) / 7 + 1;
+                bits & 1 != 0 || bits & (1 << nth) != 0 || bits & (1 << (5 + nth_from_end)) != 0
+            })
+            .collect();
+
+        let days = match (self.by_month_day != 0, !self.by_day_is_empty()) {
+            (true, true) => by_month_day.into_iter().filter(|day| by_day.contains(day)).collect(),
+            (true, false) => by_month_day,
+            (false, true) => by_day,
+            (false, false) if anchor_day <= last => vec![anchor_day],
+            (false, false) => Vec::new(),
+        };
+        days.into_iter().filter_map(|day| first.with_day(day)).collect()
+    }
+
+    fn by_day_is_empty(&self) -> bool {
+        self.by_day.iter().all(|bits| *bits == 0)
+    }
+
+    fn matches_month(&self, date: NaiveDate) -> bool {
+        self.by_month == 0 || self.by_month & (1 << (date.month() - 1)) != 0
+    }
+
+    fn matches_month_day(&self, date: NaiveDate) -> bool {
+        self.by_month_day == 0 || self.month_day_allowed(date.day(), days_in_month(date))
+    }
+
+    /// BYMONTHDAY 是否包含有 `last` 天的月份中的第 `day` 天
+    fn month_day_allowed(&self, day: u32, last: u32) -> bool {
+        self.by_month_day & (1 << (day - 1)) != 0 || self.by_month_day & (1 << (30 + last + 1 - day)) != 0
+    }
+}
+
+impl FromStr for RRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let body = match s.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &s[6..],
+            _ => s,
+        };
+
+        let mut rule = RRule {
+            freq: Frequency::Daily,
+            interval: 1,
+            by_day: [0; 7],
+            by_month_day: 0,
+            by_month: 0,
+            until: None,
+        };
+        let mut freq = None;
+
+        for part in body.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", part))?;
+            let value = value.to_ascii_uppercase();
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("FREQ must be DAILY, WEEKLY, MONTHLY or YEARLY, got '{}'", value)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| format!("INTERVAL must be a positive number, got '{}'", value))?;
+                }
+                "BYDAY" => {
+                    for item in value.split(',') {
+                        let (index, bit) = parse_weekday_num(item)?;
+                        rule.by_day[index] |= 1 << bit;
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for item in value.split(',') {
+                        let day: i32 = item.parse().map_err(|_| format!("invalid BYMONTHDAY '{}'", item))?;
+                        rule.by_month_day |= match day {
+                            1..=31 => 1 << (day - 1),
+                            -31..=-1 => 1 << (30 - day),
+                            _ => return Err(format!("BYMONTHDAY must be between 1 and 31 or -31 and -1, got {}", day)),
+                        };
+                    }
+                }
+                "BYMONTH" => {
+                    for item in value.split(',') {
+                        let month: u32 = item
+                            .parse()
+                            .ok()
+                            .filter(|month| (1..=12).contains(month))
+                            .ok_or_else(|| format!("BYMONTH must be between 1 and 12, got '{}'", item))?;
+                        rule.by_month |= 1 << (month - 1);
+                    }
+                }
+                "UNTIL" => rule.until = Some(parse_until(&value)?),
+                "WKST" if value == "MO" => {}
+                "WKST" => return Err("only WKST=MO is supported".to_string()),
+                other => return Err(format!("{} is not supported", other)),
+            }
+        }
+
+        rule.freq = freq.ok_or("FREQ is required")?;
+        let has_ordinals = rule.by_day.iter().any(|bits| bits & !1 != 0);
+        match rule.freq {
+            Frequency::Daily | Frequency::Weekly if has_ordinals => {
+                return Err("numbered BYDAY (e.g. 1MO) needs FREQ=MONTHLY or FREQ=YEARLY".to_string())
+            }
+            Frequency::Yearly if has_ordinals && rule.by_month == 0 => {
+                return Err("numbered BYDAY with FREQ=YEARLY needs BYMONTH".to_string())
+            }
+            Frequency::Weekly if rule.by_month_day != 0 => {
+                return Err("BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string())
+            }
+            _ => {}
+        }
+        if rule.by_month_day != 0 && !rule.month_days_possible() {
+            return Err("BYMONTHDAY never falls in the given months".to_string());
+        }
+        Ok(rule)
+    }
+}
+
+impl RRule {
+    /// BYMONTHDAY 中是否有某一天落在 BYMONTH 的某个月中（闰年的二月按 29 天算）
+    fn month_days_possible(&self) -> bool {
+        (1..=12u32)
+            .filter(|month| self.by_month == 0 || self.by_month & (1 << (month - 1)) != 0)
+            .filter_map(|month| NaiveDate::from_ymd_opt(2024, month, 1))
+            .any(|first| {
+                let last = days_in_month(first);
+                (1..=last).any(|day| self.month_day_allowed(day, last))
+            })
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", freq)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+
+        if self.by_month != 0 {
+            let months: Vec<String> = (1..=12)
+                .filter(|month| self.by_month & (1 << (month - 1)) != 0)
+                .map(|month: u32| month.to_string())
+                .collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if self.by_month_day != 0 {
+            let days: Vec<String> = (1..=31)
+                .filter(|day| self.by_month_day & (1 << (day - 1)) != 0)
+                .chain((1..=31).filter(|day| self.by_month_day & (1 << (30 + day)) != 0).map(|day: i64| -day))
+                .map(|day| day.to_string())
+                .collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if !self.by_day_is_empty() {
+            let mut days = Vec::new();
+            for (index, code) in WEEKDAY_CODES.iter().enumerate() {
+                let bits = self.by_day[index];
+                if bits & 1 != 0 {
+                    days.push(code.to_string());
+                }
+                days.extend((1..=5).filter(|n| bits & (1 << n) != 0).map(|n| format!("{}{}", n, code)));
+                days.extend((1..=5).filter(|n| bits & (1 << (5 + n)) != 0).map(|n| format!("-{}{}", n, code)));
+            }
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for RRule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RRule> for String {
+    fn from(rule: RRule) -> Self {
+        rule.to_string()
+    }
+}
+
+/// 解析 BYDAY 中的一项，例如 `MO`、`1MO`、`-1FR`，返回星期几的下标和对应的位
+fn parse_weekday_num(item: &str) -> Result<(usize, u32), String> {
+    let invalid = || format!("invalid BYDAY '{}'", item);
+    let split = item.len().saturating_sub(2);
+    let (ordinal, code) = (item.get(..split).ok_or_else(invalid)?, &item[split..]);
+    let index = WEEKDAY_CODES
+        .iter()
+        .position(|weekday| *weekday == code)
+        .ok_or_else(invalid)?;
+
+    let bit = match ordinal {
+        "" => 0,
+        _ => match ordinal.trim_start_matches('+').parse::<i32>() {
+            Ok(n @ 1..=5) => n as u32,
+            Ok(n @ -5..=-1) => (5 - n) as u32,
+            _ => return Err(format!("BYDAY position must be between 1 and 5 or -5 and -1, got '{}'", item)),
+        },
+    };
+    Ok((index, bit))
+}
+
+/// 解析 UNTIL：`YYYYMMDD`（当天结束前都有效）或 `YYYYMMDDTHHMMSSZ`
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return date
+            .and_hms_opt(23, 59, 59)
+            .map(|datetime| datetime.and_utc())
+            .ok_or_else(|| format!("invalid UNTIL '{}'", value));
+    }
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+        .map(|datetime| datetime.and_utc())
+        .map_err(|_| format!("UNTIL must look like 20251231 or 20251231T170000Z, got '{}'", value))
+}
+
+fn weekday_index(weekday: Weekday) -> usize {
+    weekday.num_days_from_monday() as usize
+}
+
+/// `first` 所在月份的天数
+fn days_in_month(first: NaiveDate) -> u32 {
+    let first = first.with_day(1).unwrap_or(first);
+    first
+        .checked_add_months(Months::new(1))
+        .map(|next| (next - first).num_days() as u32)
+        .unwrap_or(31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 9, 30, 0).unwrap()
+    }
+
+    /// 从 `start` 开始依次计算的 `count` 个日期
+    fn dates(rule: &str, start: DateTime<Utc>, count: usize) -> Vec<String> {
+        let rule: RRule = rule.parse().unwrap();
+        std::iter::successors(rule.next_after(start), |date| rule.next_after(*date))
+            .take(count)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_weekly_by_day() {
+        // 2025-01-01 是周三
+        assert_eq!(
+            dates("FREQ=WEEKLY;BYDAY=MO,WE", at(2025, 1, 1), 4),
+            ["2025-01-06", "2025-01-08", "2025-01-13", "2025-01-15"]
+        );
+        assert_eq!(dates("FREQ=WEEKLY;INTERVAL=2", at(2025, 1, 1), 2), ["2025-01-15", "2025-01-29"]);
+    }
+
+    #[test]
+    fn test_monthly() {
+        assert_eq!(
+            dates("FREQ=MONTHLY;BYDAY=1MO", at(2025, 1, 6), 3),
+            ["2025-02-03", "2025-03-03", "2025-04-07"]
+        );
+        assert_eq!(
+            dates("FREQ=MONTHLY;BYDAY=-1FR", at(2025, 1, 1), 2),
+            ["2025-01-31", "2025-02-28"]
+        );
+        assert_eq!(
+            dates("FREQ=MONTHLY;BYMONTHDAY=-1", at(2024, 1, 31), 2),
+            ["2024-02-29", "2024-03-31"]
+        );
+        // 没有 31 日的月份被跳过
+        assert_eq!(dates("FREQ=MONTHLY", at(2025, 1, 31), 2), ["2025-03-31", "2025-05-31"]);
+        // 每月 13 日且是周五
+        assert_eq!(dates("FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13", at(2025, 1, 1), 2), ["2025-06-13", "2026-02-13"]);
+    }
+
+    #[test]
+    fn test_daily_and_yearly() {
+        assert_eq!(
+            dates("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR", at(2025, 1, 3), 2),
+            ["2025-01-06", "2025-01-07"]
+        );
+        assert_eq!(dates("FREQ=YEARLY", at(2025, 3, 15), 2), ["2026-03-15", "2027-03-15"]);
+        assert_eq!(
+            dates("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH", at(2025, 1, 1), 2),
+            ["2025-11-27", "2026-11-26"]
+        );
+        assert_eq!(dates("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29", at(2025, 1, 1), 2), ["2028-02-29", "2032-02-29"]);
+    }
+
+    #[test]
+    fn test_keeps_time_and_respects_until() {
+        let rule: RRule = "FREQ=DAILY;UNTIL=20250102".parse().unwrap();
+        assert_eq!(rule.next_after(at(2025, 1, 1)), Some(at(2025, 1, 2)));
+        assert_eq!(rule.next_after(at(2025, 1, 2)), None);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let rule: RRule = "rrule:freq=monthly;byday=mo,-1fr,1mo;interval=2".parse().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;INTERVAL=2;BYDAY=MO,1MO,-1FR");
+        assert_eq!(rule.to_string().parse::<RRule>().unwrap(), rule);
+
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(json, "\"FREQ=MONTHLY;INTERVAL=2;BYDAY=MO,1MO,-1FR\"");
+        assert_eq!(serde_json::from_str::<RRule>(&json).unwrap(), rule);
+
+        for invalid in [
+            "BYDAY=MO",
+            "FREQ=HOURLY",
+            "FREQ=WEEKLY;COUNT=3",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=MONTHLY;BYDAY=6MO",
+            "FREQ=MONTHLY;INTERVAL=0",
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+            "FREQ=DAILY;UNTIL=tomorrow",
+        ] {
+            assert!(invalid.parse::<RRule>().is_err(), "{} should be rejected", invalid);
+        }
+    }
+}
@@ -0,0 +1,501 @@
+use crate::cli::{AddArgs, CompleteArgs, DeleteArgs, EditArgs, ReopenArgs};
+use crate::command::{add_tasks, CommandHandler, Context};
+use crate::error::{TodoError, TodoResult};
+use crate::task::Task;
+use crate::todo_list::TodoList;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::io::Read;
+
+/// 读取 API Token 的环境变量
+pub const API_TOKEN_ENV: &str = "TODO_API_TOKEN";
+
+/// 请求体的最大字节数，超过时返回 413
+pub const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// 一次请求的处理结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiResponse {
+    /// HTTP 状态码
+    pub status: u16,
+    /// JSON 响应体，`None` 表示没有内容（204）
+    pub body: Option<Value>,
+}
+
+impl ApiResponse {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body: Some(body) }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: Some(json!({ "error": message.into() })),
+        }
+    }
+}
+
+impl From<TodoError> for ApiResponse {
+    fn from(err: TodoError) -> Self {
+        let status = match err {
+            TodoError::TaskNotFound(_) => 404,
+            TodoError::TaskBlocked(..) | TodoError::TaskHasChildren(..) => 409,
+            // 读写数据文件失败不是请求的问题
+            TodoError::IoError(_) | TodoError::EncryptionError(_) => 500,
+            _ => 400,
+        };
+        // 终端输出用的 ❌ 前缀对 API 调用方没有意义
+        ApiResponse::error(status, err.to_string().trim_start_matches("❌ "))
+    }
+}
+
+/// `POST /tasks` 的请求体
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewTask {
+    title: String,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    context: Option<String>,
+}
+
+/// 处理一个 REST 请求
+///
+/// 修改任务的请求交给对应子命令的处理器执行（与命令行相同的校验、默认值、历史记录、保存和钩子），
+/// 执行后从列表中读出任务作为响应。
+/// - `GET /tasks`：所有任务，可以用 `?status=pending|completed` 和 `?tag=work` 筛选
+/// - `GET /tasks/{id}`：单个任务
+/// - `POST /tasks`：新建任务，请求体为 `{"title": "...", "priority": "high", "due": "2025-01-31", "tags": [], "context": "..."}`
+/// - `PATCH /tasks/{id}`：修改任务，可以包含 `title`、`priority`、`due`（`null` 清除）、`tags`、`context`（`null` 清除）和 `completed`
+/// - `DELETE /tasks/{id}`：删除任务，子任务提升为顶层任务
+pub fn handle_request(ctx: &mut Context, method: &str, url: &str, body: &str) -> ApiResponse {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let result = match (method, segments.as_slice()) {
+        ("GET", ["tasks"]) => list_tasks(&ctx.todo_list, query),
+        ("POST", ["tasks"]) => create_task(ctx, body),
+        (_, ["tasks"]) => Ok(ApiResponse::error(405, "method not allowed")),
+        (method, ["tasks", id]) => match id.parse::<u32>() {
+            Ok(id) => match method {
+                "GET" => get_task(&ctx.todo_list, id),
+                "PATCH" => update_task(ctx, id, body),
+                "DELETE" => delete_task(ctx, id),
+                _ => Ok(ApiResponse::error(405, "method not allowed")),
+            },
+            Err(_) => Ok(ApiResponse::error(404, format!("invalid task ID '{}'", id))),
+        },
+        _ => Ok(ApiResponse::error(404, "not found")),
+    };
+
+    result.unwrap_or_else(ApiResponse::from)
+}
+
+fn list_tasks(todo_list: &TodoList, query: &str) -> TodoResult<ApiResponse> {
+    let mut tasks: Vec<&Task> = todo_list.list_tasks().iter().collect();
+
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        let Some(value) = decode_query_value(value) else {
+            return Ok(ApiResponse::error(400, format!("invalid encoding in query parameter '{}'", key)));
+        };
+        match (key, value.as_str()) {
+            ("status", "pending") => tasks.retain(|task| !task.completed),
+            ("status", "completed") => tasks.retain(|task| task.completed),
+            ("status", "all") => {}
+            ("tag", tag) => {
+                let tag = tag.trim_start_matches('#');
+                tasks.retain(|task| task.tags.iter().any(|task_tag| task_tag == tag));
+            }
+            _ => {
+                return Ok(ApiResponse::error(400, format!("unsupported query parameter '{}={}'", key, value)));
+            }
+        }
+    }
+
+    Ok(ApiResponse::ok(serde_json::to_value(tasks)?))
+}
+
+/// 解码查询参数的值：`+` 表示空格，`%XX` 表示一个字节；编码无效或结果不是 UTF-8 时返回 `None`
+fn decode_query_value(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [input.next()?, input.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn get_task(todo_list: &TodoList, id: u32) -> TodoResult<ApiResponse> {
+    let task = todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+    Ok(ApiResponse::ok(serde_json::to_value(task)?))
+}
+
+fn create_task(ctx: &mut Context, body: &str) -> TodoResult<ApiResponse> {
+    let new_task: NewTask = match serde_json::from_str(body) {
+        Ok(new_task) => new_task,
+        Err(err) => return Ok(ApiResponse::error(400, format!("invalid request body: {}", err))),
+    };
+
+    let args = AddArgs {
+        title: Some(new_task.title),
+        priority: new_task.priority,
+        due: new_task.due,
+        tags: new_task.tags,
+        context: new_task.context,
+        ..AddArgs::default()
+    };
+    let ids = add_tasks(&ctx.data_file, &ctx.config, &mut ctx.todo_list, args)?;
+    let id = ids[0];
+
+    let task = ctx.todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+    Ok(ApiResponse { status: 201, body: Some(serde_json::to_value(task)?) })
+}
+
+fn update_task(ctx: &mut Context, id: u32, body: &str) -> TodoResult<ApiResponse> {
+    ctx.todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+    let changes: Map<String, Value> = match serde_json::from_str(body) {
+        Ok(changes) => changes,
+        Err(err) => return Ok(ApiResponse::error(400, format!("invalid request body: {}", err))),
+    };
+
+    // 字段对应 `todo edit` 的参数，值由它校验；`completed` 对应 `todo complete` 和 `todo reopen`
+    let mut edit = EditArgs { id, ..EditArgs::default() };
+    let mut completed = None;
+    for (key, value) in &changes {
+        let invalid = || ApiResponse::error(400, format!("invalid value for '{}'", key));
+        match (key.as_str(), value) {
+            ("title", Value::String(text)) => edit.title = Some(text.clone()),
+            ("priority", Value::String(text)) => edit.priority = Some(text.clone()),
+            ("due", Value::String(text)) => edit.due = Some(text.clone()),
+            ("due", Value::Null) => edit.no_due = true,
+            ("tags", Value::Array(items)) => {
+                let items: Option<Vec<String>> = items.iter().map(|item| item.as_str().map(str::to_string)).collect();
+                match items {
+                    Some(tags) if tags.is_empty() => edit.no_tags = true,
+                    Some(tags) => edit.tags = Some(tags),
+                    None => return Ok(invalid()),
+                }
+            }
+            ("context", Value::String(text)) => edit.context = Some(text.clone()),
+            ("context", Value::Null) => edit.no_context = true,
+            ("completed", Value::Bool(done)) => completed = Some(*done),
+            ("title" | "priority" | "due" | "tags" | "context" | "completed", _) => return Ok(invalid()),
+            _ => return Ok(ApiResponse::error(400, format!("unknown field '{}'", key))),
+        }
+    }
+
+    // 修改字段之前先确认可以完成，避免只应用一部分
+    if completed == Some(true) {
+        let blockers: Vec<u32> = ctx.todo_list.blockers(id).iter().map(|task| task.id).collect();
+        if !blockers.is_empty() {
+            return Err(TodoError::TaskBlocked(id, blockers));
+        }
+    }
+
+    let has_edits = edit.title.is_some()
+        || edit.priority.is_some()
+        || edit.due.is_some()
+        || edit.no_due
+        || edit.tags.is_some()
+        || edit.no_tags
+        || edit.context.is_some()
+        || edit.no_context;
+    if has_edits {
+        edit.handle(ctx)?;
+    }
+    match completed {
+        Some(true) => CompleteArgs { id: Some(id), pattern: None, force: false }.handle(ctx)?,
+        Some(false) => ReopenArgs { id }.handle(ctx)?,
+        None => {}
+    }
+
+    let task = ctx.todo_list.find_task(id).ok_or(TodoError::TaskNotFound(id))?;
+    Ok(ApiResponse::ok(serde_json::to_value(task)?))
+}
+
+fn delete_task(ctx: &mut Context, id: u32) -> TodoResult<ApiResponse> {
+    // 子任务提升为顶层任务，API 没有办法询问
+    DeleteArgs { id: Some(id), pattern: None, cascade: false, orphan: true }.handle(ctx)?;
+    Ok(ApiResponse { status: 204, body: None })
+}
+
+/// 请求是否带有正确的 `Authorization: Bearer <token>`，没有设置 `token` 时总是允许
+fn is_authorized(headers: &[tiny_http::Header], token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let expected = format!("Bearer {}", token);
+    headers
+        .iter()
+        .filter(|header| header.field.equiv("Authorization"))
+        .any(|header| constant_time_eq(header.value.as_str().as_bytes(), expected.as_bytes()))
+}
+
+/// 请求是否声明了 `Content-Type: application/json`（可以带 `charset` 等参数）
+///
+/// 浏览器允许网页不经预检直接跨站发送 `text/plain` 的 POST 请求，带请求体的请求要求 JSON 可以挡住它们。
+fn has_json_body(headers: &[tiny_http::Header]) -> bool {
+    headers
+        .iter()
+        .filter(|header| header.field.equiv("Content-Type"))
+        .any(|header| {
+            let media_type = header.value.as_str().split(';').next().unwrap_or_default();
+            media_type.trim().eq_ignore_ascii_case("application/json")
+        })
+}
+
+/// 比较两个字节串，耗时只和长度有关，避免通过响应时间逐字节猜出 Token
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// 读取请求体，超过 [`MAX_BODY_BYTES`] 时返回 413，不是 UTF-8 时返回 400
+fn read_body(reader: impl Read) -> Result<String, ApiResponse> {
+    let mut bytes = Vec::new();
+    reader
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|err| ApiResponse::error(400, format!("could not read request body: {}", err)))?;
+    if bytes.len() as u64 > MAX_BODY_BYTES {
+        return Err(ApiResponse::error(413, format!("request body exceeds {} bytes", MAX_BODY_BYTES)));
+    }
+    String::from_utf8(bytes).map_err(|_| ApiResponse::error(400, "request body is not valid UTF-8"))
+}
+
+/// 在 `address` 上运行 HTTP 服务，直到进程退出
+///
+/// 每个请求都重新读取数据文件，因此同时使用命令行做的修改会立即反映在 API 中；
+/// 每个请求各有一条撤销记录，可以用 `todo undo` 撤销。设置了 `token` 时，请求必须带上
+/// `Authorization: Bearer <token>`。POST 和 PATCH 请求必须是 `Content-Type: application/json`，
+/// 请求体最多 [`MAX_BODY_BYTES`] 字节。
+pub fn serve(ctx: &mut Context, address: &str, token: Option<&str>) -> TodoResult<()> {
+    let server = tiny_http::Server::http(address)
+        .map_err(|err| TodoError::Custom(format!("could not listen on {}: {}", address, err)))?;
+
+    for mut request in server.incoming_requests() {
+        let sends_body = matches!(request.method(), tiny_http::Method::Post | tiny_http::Method::Patch);
+        let body = if !is_authorized(request.headers(), token) {
+            Err(ApiResponse::error(401, "missing or invalid API token"))
+        } else if sends_body && !has_json_body(request.headers()) {
+            Err(ApiResponse::error(415, "request body must be sent as Content-Type: application/json"))
+        } else if request.body_length().is_some_and(|length| length as u64 > MAX_BODY_BYTES) {
+            Err(ApiResponse::error(413, format!("request body exceeds {} bytes", MAX_BODY_BYTES)))
+        } else {
+            read_body(request.as_reader())
+        };
+
+        let response = match body {
+            Err(response) => response,
+            Ok(body) => {
+                let method = request.method().as_str().to_string();
+                match ctx.reload() {
+                    Ok(()) => {
+                        ctx.todo_list.checkpoint(format!("{} {}", method, request.url()));
+                        handle_request(ctx, &method, request.url(), &body)
+                    }
+                    Err(err) => ApiResponse::error(500, err.to_string().trim_start_matches("❌ ")),
+                }
+            }
+        };
+
+        let content = response.body.map(|body| body.to_string()).unwrap_or_default();
+        let mut reply = tiny_http::Response::from_string(content).with_status_code(response.status);
+        if response.status != 204 {
+            reply.add_header(
+                tiny_http::Header::from_bytes("Content-Type", "application/json").expect("valid header"),
+            );
+        }
+        // 客户端提前断开时忽略
+        let _ = request.respond(reply);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    /// 数据保存在临时目录中的上下文
+    fn context(name: &str) -> Context {
+        let dir = std::env::temp_dir().join(format!("todo-server-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Context::for_test(dir.join("todos.json"))
+    }
+
+    fn request(ctx: &mut Context, method: &str, url: &str, body: &str) -> ApiResponse {
+        handle_request(ctx, method, url, body)
+    }
+
+    #[test]
+    fn test_create_and_list_tasks() {
+        let mut ctx = context("create");
+        let response = request(
+            &mut ctx,
+            "POST",
+            "/tasks",
+            r##"{"title": "写周报", "priority": "high", "due": "2025-01-31", "tags": ["#work"]}"##,
+        );
+        assert_eq!(response.status, 201);
+        let task = ctx.todo_list.find_task(1).unwrap();
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.tags, ["work"]);
+        assert_eq!(crate::dates::local_date(task.due_date.unwrap()).to_string(), "2025-01-31");
+
+        // 修改已经保存到数据文件
+        ctx.reload().unwrap();
+        assert_eq!(ctx.todo_list.find_task(1).unwrap().title, "写周报");
+
+        request(&mut ctx, "POST", "/tasks", r#"{"title": "买牛奶"}"#);
+        ctx.todo_list.complete_task(2).unwrap();
+
+        let response = request(&mut ctx, "GET", "/tasks?status=pending", "");
+        let tasks = response.body.unwrap();
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+        assert_eq!(tasks[0]["title"], "写周报");
+
+        let response = request(&mut ctx, "GET", "/tasks?tag=work", "");
+        assert_eq!(response.body.unwrap().as_array().unwrap().len(), 1);
+
+        assert_eq!(request(&mut ctx, "POST", "/tasks", r#"{"title": ""}"#).status, 400);
+        assert_eq!(request(&mut ctx, "POST", "/tasks", r#"{"title": "x", "color": "red"}"#).status, 400);
+    }
+
+    #[test]
+    fn test_update_and_delete_task() {
+        let mut ctx = context("update");
+        let id = ctx.todo_list.add_task("写周报".to_string(), Priority::Low, None);
+
+        let response = request(
+            &mut ctx,
+            "PATCH",
+            "/tasks/1",
+            r#"{"title": "写月报", "context": "@office", "completed": true}"#,
+        );
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.unwrap()["title"], "写月报");
+        let task = ctx.todo_list.find_task(id).unwrap();
+        assert!(task.completed);
+        assert_eq!(task.context.as_deref(), Some("office"));
+
+        // 无效的字段不会应用任何修改
+        let response = request(&mut ctx, "PATCH", "/tasks/1", r#"{"title": "新标题", "priority": "urgent"}"#);
+        assert_eq!(response.status, 400);
+        assert_eq!(ctx.todo_list.find_task(id).unwrap().title, "写月报");
+        assert_eq!(request(&mut ctx, "PATCH", "/tasks/1", r#"{"title": " "}"#).status, 400);
+        assert_eq!(request(&mut ctx, "PATCH", "/tasks/1", r#"{"tags": [1]}"#).status, 400);
+
+        assert_eq!(request(&mut ctx, "PATCH", "/tasks/1", r#"{"due": null, "completed": false}"#).status, 200);
+        assert!(!ctx.todo_list.find_task(id).unwrap().completed);
+        assert_eq!(request(&mut ctx, "DELETE", "/tasks/1", "").status, 204);
+        let response = request(&mut ctx, "GET", "/tasks/1", "");
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body.unwrap()["error"], "Task with ID 1 not found");
+    }
+
+    #[test]
+    fn test_blocked_task_cannot_be_completed() {
+        let mut ctx = context("blocked");
+        let design = ctx.todo_list.add_task("设计".to_string(), Priority::Low, None);
+        let build = ctx.todo_list.add_task("开发".to_string(), Priority::Low, None);
+        ctx.todo_list.add_dependency(build, design).unwrap();
+
+        let response = request(&mut ctx, "PATCH", "/tasks/2", r#"{"title": "开发 v2", "completed": true}"#);
+        assert_eq!(response.status, 409);
+        let task = ctx.todo_list.find_task(build).unwrap();
+        assert!(!task.completed);
+        assert_eq!(task.title, "开发");
+    }
+
+    #[test]
+    fn test_query_values_are_decoded() {
+        let mut ctx = context("query");
+        request(&mut ctx, "POST", "/tasks", r#"{"title": "写周报", "tags": ["q&a work"]}"#);
+        request(&mut ctx, "POST", "/tasks", r#"{"title": "买牛奶", "tags": ["家务"]}"#);
+
+        let count = |ctx: &mut Context, url: &str| request(ctx, "GET", url, "").body.unwrap().as_array().unwrap().len();
+        assert_eq!(count(&mut ctx, "/tasks?tag=q%26a+work"), 1);
+        assert_eq!(count(&mut ctx, "/tasks?tag=%23q%26a%20work"), 1);
+        assert_eq!(count(&mut ctx, "/tasks?tag=%E5%AE%B6%E5%8A%A1"), 1);
+        assert_eq!(count(&mut ctx, "/tasks?status=pending&tag=%E5%AE%B6%E5%8A%A1"), 1);
+        assert_eq!(request(&mut ctx, "GET", "/tasks?tag=%E5%A", "").status, 400);
+        assert_eq!(request(&mut ctx, "GET", "/tasks?tag=%zz", "").status, 400);
+    }
+
+    #[test]
+    fn test_read_body_limit() {
+        assert_eq!(read_body(&b"{}"[..]).unwrap(), "{}");
+
+        let too_large = vec![b' '; MAX_BODY_BYTES as usize + 1];
+        assert_eq!(read_body(too_large.as_slice()).unwrap_err().status, 413);
+        assert_eq!(read_body(&[0xff, 0xfe][..]).unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn test_token_check() {
+        let header = |value: &str| tiny_http::Header::from_bytes("Authorization", value).unwrap();
+        assert!(is_authorized(&[], None));
+        assert!(is_authorized(&[header("Bearer secret")], Some("secret")));
+        assert!(!is_authorized(&[header("Bearer secreT")], Some("secret")));
+        assert!(!is_authorized(&[header("Bearer secret2")], Some("secret")));
+        assert!(!is_authorized(&[], Some("secret")));
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+    }
+
+    #[test]
+    fn test_create_task_applies_tag_defaults() {
+        use crate::config::TagDefaults;
+        use crate::history::EventKind;
+
+        let mut ctx = context("tag-defaults");
+        let defaults = TagDefaults {
+            priority: Some("high".to_string()),
+            context: Some("office".to_string()),
+            ..TagDefaults::default()
+        };
+        ctx.config.tags.insert("work".to_string(), defaults);
+
+        let response = request(&mut ctx, "POST", "/tasks", r#"{"title": "写周报", "tags": ["work"], "context": "home"}"#);
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body.as_ref().unwrap()["priority"], "High");
+        assert_eq!(response.body.unwrap()["context"], "home");
+
+        // 一次建好所有字段：只有新建和应用默认值两条历史，没有随后的修改
+        let kinds: Vec<&EventKind> = ctx.todo_list.task_history(1).into_iter().map(|event| &event.kind).collect();
+        assert!(matches!(kinds[..], [EventKind::Added, EventKind::DefaultsApplied { .. }]), "{:?}", kinds);
+    }
+
+    #[test]
+    fn test_json_content_type_required() {
+        let header = |value: &str| tiny_http::Header::from_bytes("Content-Type", value).unwrap();
+        assert!(has_json_body(&[header("application/json")]));
+        assert!(has_json_body(&[header("Application/JSON; charset=utf-8")]));
+        assert!(!has_json_body(&[header("text/plain")]));
+        assert!(!has_json_body(&[header("application/x-www-form-urlencoded")]));
+        assert!(!has_json_body(&[]));
+    }
+
+    #[test]
+    fn test_unknown_routes() {
+        let mut ctx = context("routes");
+        assert_eq!(request(&mut ctx, "GET", "/", "").status, 404);
+        assert_eq!(request(&mut ctx, "PUT", "/tasks", "").status, 405);
+        assert_eq!(request(&mut ctx, "GET", "/tasks/abc", "").status, 404);
+        assert_eq!(request(&mut ctx, "GET", "/tasks?sort=due", "").status, 400);
+    }
+}
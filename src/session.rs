@@ -0,0 +1,145 @@
+use crate::config::{data_dir_override, project_dirs};
+use crate::error::TodoResult;
+use crate::storage::write_atomic;
+use crate::task::Task;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 会话状态文件名
+pub const SESSION_FILE_NAME: &str = "session.json";
+
+/// 跨命令保留的临时状态（不属于任务数据，不会被备份或导出）
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// 当前的专注模式
+    pub focus: Option<Focus>,
+}
+
+/// 专注模式：只显示带有指定标签的任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Focus {
+    /// 标签名（不含 `#`）
+    pub tag: String,
+    /// 结束时间，为空时一直有效直到手动关闭
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl Focus {
+    /// 在 `now` 时是否仍然有效
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+
+    /// 任务是否在专注范围内
+    pub fn matches(&self, task: &Task) -> bool {
+        task.tags.iter().any(|tag| tag.eq_ignore_ascii_case(&self.tag))
+    }
+}
+
+impl Session {
+    /// 默认会话文件路径（Linux 下为 `~/.local/state/todo/session.json`）
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(dir) = data_dir_override() {
+            return Some(dir.join(SESSION_FILE_NAME));
+        }
+        project_dirs().map(|dirs| {
+            dirs.state_dir()
+                .unwrap_or_else(|| dirs.data_local_dir())
+                .join(SESSION_FILE_NAME)
+        })
+    }
+
+    /// 从默认位置加载会话状态，文件不存在时返回空状态
+    pub fn load() -> TodoResult<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 从指定文件加载会话状态，文件不存在时返回空状态
+    pub fn load_from<P: AsRef<Path>>(path: P) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !path_ref.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path_ref)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存到默认位置
+    pub fn save(&self) -> TodoResult<()> {
+        match Self::default_path() {
+            Some(path) => self.save_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// 保存到指定文件
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> TodoResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path.as_ref(), content.as_bytes())
+    }
+
+    /// 当前仍然有效的专注模式
+    pub fn active_focus(&self, now: DateTime<Utc>) -> Option<&Focus> {
+        self.focus.as_ref().filter(|focus| focus.is_active(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use chrono::Duration;
+
+    #[test]
+    fn test_focus_expires() {
+        let now = Utc::now();
+        let session = Session {
+            focus: Some(Focus {
+                tag: "deepwork".to_string(),
+                until: Some(now + Duration::hours(1)),
+            }),
+        };
+
+        assert!(session.active_focus(now).is_some());
+        assert!(session.active_focus(now + Duration::hours(2)).is_none());
+    }
+
+    #[test]
+    fn test_focus_matches_tag() {
+        let focus = Focus {
+            tag: "deepwork".to_string(),
+            until: None,
+        };
+
+        let mut task = Task::new(1, "写论文".to_string(), Priority::High, None);
+        assert!(!focus.matches(&task));
+
+        task.tags = vec!["DeepWork".to_string()];
+        assert!(focus.matches(&task));
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let path = "test_session.json";
+        assert_eq!(Session::load_from("test_missing_session.json").unwrap(), Session::default());
+
+        let session = Session {
+            focus: Some(Focus {
+                tag: "deepwork".to_string(),
+                until: None,
+            }),
+        };
+        session.save_to(path).unwrap();
+        assert_eq!(Session::load_from(path).unwrap(), session);
+
+        let _ = fs::remove_file(path);
+    }
+}
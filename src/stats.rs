@@ -0,0 +1,237 @@
+use crate::dates::{local_date, today};
+use crate::history::EventKind;
+use crate::task::Priority;
+use crate::todo_list::TodoList;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// 「每天完成数」统计的天数（含今天）
+pub const RECENT_DAYS: i64 = 7;
+
+/// 列表的统计指标
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub pending: usize,
+    pub completed: usize,
+    pub overdue: usize,
+    /// 完成率（0.0 ~ 1.0），没有任务时为 0
+    pub completion_rate: f64,
+    /// 最近几天每天完成的任务数，从最早的一天开始
+    pub completed_per_day: Vec<DailyCount>,
+    /// 从创建到完成的平均小时数（只统计记录了完成时间的任务）
+    pub average_hours_to_complete: Option<f64>,
+    /// 各优先级的任务数
+    pub by_priority: Vec<PriorityCount>,
+    /// 今天到期的未完成任务的预估耗时之和（分钟）
+    pub estimated_minutes_today: u32,
+    /// 所有未完成任务的预估耗时之和（分钟）
+    pub estimated_minutes_pending: u32,
+    /// 连续有任务完成的天数
+    pub streak: Streak,
+}
+
+/// 连续有任务完成的天数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Streak {
+    /// 截至今天的连续天数；今天还没有完成任务时从昨天算起，当天结束前不算中断
+    pub current: u32,
+    /// 最长的连续天数
+    pub best: u32,
+}
+
+/// 某一天完成的任务数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub completed: usize,
+}
+
+/// 某个优先级的任务数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PriorityCount {
+    pub priority: Priority,
+    pub total: usize,
+    pub pending: usize,
+}
+
+/// 计算统计指标，`today` 为「每天完成数」的最后一天
+pub fn compute(todo_list: &TodoList, today: NaiveDate) -> Stats {
+    let tasks = todo_list.list_tasks();
+    let total = tasks.len();
+    let completed = todo_list.list_completed_tasks().len();
+
+    let completed_per_day = (0..RECENT_DAYS)
+        .rev()
+        .map(|days_ago| {
+            let date = today - Duration::days(days_ago);
+            let completed = tasks
+                .iter()
+                .filter(|task| task.completed_at.is_some_and(|at| local_date(at) == date))
+                .count();
+            DailyCount { date, completed }
+        })
+        .collect();
+
+    let durations: Vec<Duration> = tasks
+        .iter()
+        .filter_map(|task| Some(task.completed_at? - task.created_at))
+        .collect();
+    let average_hours_to_complete = (!durations.is_empty()).then(|| {
+        let total_minutes: i64 = durations.iter().map(Duration::num_minutes).sum();
+        total_minutes as f64 / durations.len() as f64 / 60.0
+    });
+
+    let by_priority = [Priority::High, Priority::Medium, Priority::Low]
+        .into_iter()
+        .map(|priority| PriorityCount {
+            priority,
+            total: tasks.iter().filter(|task| task.priority == priority).count(),
+            pending: tasks
+                .iter()
+                .filter(|task| task.priority == priority && !task.completed)
+                .count(),
+        })
+        .collect();
+
+    Stats {
+        total,
+        pending: total - completed,
+        completed,
+        overdue: todo_list.overdue_tasks().len(),
+        completion_rate: if total == 0 { 0.0 } else { completed as f64 / total as f64 },
+        completed_per_day,
+        average_hours_to_complete,
+        by_priority,
+        estimated_minutes_today: todo_list.estimated_minutes_on(today),
+        estimated_minutes_pending: tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .filter_map(|task| task.estimate_minutes)
+            .sum(),
+        streak: streak(todo_list, today),
+    }
+}
+
+/// 计算截至 `today` 的连续完成天数
+///
+/// 完成日期取自历史记录中的完成事件和任务的完成时间：已删除的任务仍有完成事件，
+/// 记录历史之前完成的任务仍有完成时间。`today` 之后的日期不计入。
+pub fn streak(todo_list: &TodoList, today: NaiveDate) -> Streak {
+    let days: BTreeSet<NaiveDate> = todo_list
+        .history()
+        .iter()
+        .filter(|event| event.kind == EventKind::Completed)
+        .map(|event| event.at)
+        .chain(todo_list.list_tasks().iter().filter_map(|task| task.completed_at))
+        .map(local_date)
+        .filter(|date| *date <= today)
+        .collect();
+
+    let mut best = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in &days {
+        run = if previous.and_then(|day| day.succ_opt()) == Some(date) { run + 1 } else { 1 };
+        best = best.max(run);
+        previous = Some(date);
+    }
+
+    let start = if days.contains(&today) { today } else { today - Duration::days(1) };
+    let current = std::iter::successors(Some(start), |day| day.pred_opt())
+        .take_while(|day| days.contains(day))
+        .count() as u32;
+
+    Streak { current, best }
+}
+
+/// 以今天（本地时区）为最后一天计算统计指标
+pub fn compute_now(todo_list: &TodoList) -> Stats {
+    compute(todo_list, today())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_compute_stats() {
+        let mut list = TodoList::new();
+        let first = list.add_task("写周报".to_string(), Priority::High, None);
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.add_task("开会".to_string(), Priority::Low, None);
+        list.complete_task(first).unwrap();
+
+        // 模拟两天前创建、昨天完成的任务
+        let today = today();
+        let task = list.find_task_mut(first).unwrap();
+        let completed_at = Utc::now() - Duration::days(1);
+        task.completed_at = Some(completed_at);
+        task.created_at = completed_at - Duration::hours(30);
+
+        let stats = compute(&list, today);
+        assert_eq!((stats.total, stats.pending, stats.completed), (3, 2, 1));
+        assert!((stats.completion_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.average_hours_to_complete, Some(30.0));
+
+        assert_eq!(stats.completed_per_day.len(), RECENT_DAYS as usize);
+        assert_eq!(stats.completed_per_day.last().unwrap().date, today);
+        let yesterday = &stats.completed_per_day[stats.completed_per_day.len() - 2];
+        assert_eq!(yesterday.completed, 1);
+
+        assert_eq!(
+            stats.by_priority[2],
+            PriorityCount { priority: Priority::Low, total: 2, pending: 2 }
+        );
+    }
+
+    #[test]
+    fn test_estimated_work_counts_pending_tasks() {
+        let mut list = TodoList::new();
+        let today = today();
+        let due_today = crate::dates::end_of_day(today);
+
+        let report = list.add_task("写周报".to_string(), Priority::High, Some(due_today));
+        let groceries = list.add_task("买菜".to_string(), Priority::Low, None);
+        let done = list.add_task("开会".to_string(), Priority::Low, Some(due_today));
+        list.set_estimate(report, Some(90)).unwrap();
+        list.set_estimate(groceries, Some(30)).unwrap();
+        list.set_estimate(done, Some(60)).unwrap();
+        list.complete_task(done).unwrap();
+
+        let stats = compute(&list, today);
+        assert_eq!(stats.estimated_minutes_today, 90);
+        assert_eq!(stats.estimated_minutes_pending, 120);
+    }
+
+    #[test]
+    fn test_streak() {
+        let day = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let today = day(15);
+        let mut list = TodoList::new();
+        // 最长 3 天（1 月 5 日到 7 日），当前从 1 月 13 日到昨天共 2 天
+        for (month_day, hour) in [(5, 9), (6, 9), (6, 18), (7, 9), (10, 9), (13, 9), (14, 9), (16, 9)] {
+            let id = list.add_task("写日报".to_string(), Priority::Low, None);
+            list.complete_task(id).unwrap();
+            let at = day(month_day).and_hms_opt(hour, 0, 0).unwrap().and_utc();
+            list.find_task_mut(id).unwrap().completed_at = Some(at);
+            list.history_mut().last_mut().unwrap().at = at;
+        }
+        assert_eq!(streak(&list, today), Streak { current: 2, best: 3 });
+
+        // 今天完成后延续，前天之后没有完成则中断
+        assert_eq!(streak(&list, day(14)).current, 2);
+        assert_eq!(streak(&list, day(9)).current, 0);
+        assert_eq!(streak(&TodoList::new(), today), Streak::default());
+    }
+
+    #[test]
+    fn test_empty_list() {
+        let stats = compute(&TodoList::new(), today());
+        assert_eq!(stats.completion_rate, 0.0);
+        assert!(stats.average_hours_to_complete.is_none());
+        assert!(stats.completed_per_day.iter().all(|day| day.completed == 0));
+    }
+}
@@ -1,11 +1,293 @@
+use crate::dates::end_of_day;
 use crate::error::{TodoError, TodoResult};
-use crate::todo_list::TodoList;
-use std::fs;
-use std::path::Path;
+use crate::config::{data_dir_override, project_dirs};
+use crate::todo_list::{TodoList, SCHEMA_VERSION};
+use crate::todotxt;
+use chrono::{DateTime, NaiveTime, Utc};
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 
-/// 默认数据文件路径
+/// 默认数据文件名
 pub const DEFAULT_FILE_PATH: &str = "todos.json";
 
+/// todo.txt 格式的默认数据文件名
+pub const DEFAULT_TODOTXT_PATH: &str = "todo.txt";
+
+/// 二进制格式的默认数据文件名
+pub const DEFAULT_BINARY_PATH: &str = "todos.bin";
+
+/// 指定数据文件路径的环境变量
+pub const TODO_FILE_ENV: &str = "TODO_FILE";
+
+/// 数据文件格式
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageFormat {
+    /// JSON（默认，保存全部字段）
+    #[default]
+    Json,
+    /// todo.txt 纯文本格式
+    #[value(name = "todotxt")]
+    TodoTxt,
+    /// MessagePack 二进制格式（保存全部字段，比 JSON 更小、读写更快，适合很大的列表）
+    Binary,
+}
+
+impl StorageFormat {
+    /// 该格式的默认数据文件名
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            StorageFormat::Json => DEFAULT_FILE_PATH,
+            StorageFormat::TodoTxt => DEFAULT_TODOTXT_PATH,
+            StorageFormat::Binary => DEFAULT_BINARY_PATH,
+        }
+    }
+
+    /// 命令行和配置文件中使用的名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageFormat::Json => "json",
+            StorageFormat::TodoTxt => "todotxt",
+            StorageFormat::Binary => "binary",
+        }
+    }
+
+    /// 是否是文本格式（可以按 UTF-8 字符串处理）
+    pub fn is_text(&self) -> bool {
+        !matches!(self, StorageFormat::Binary)
+    }
+}
+
+/// 平台数据目录中的默认数据文件路径
+///
+/// Linux 下为 `~/.local/share/todo/todos.json`（遵循 `XDG_DATA_HOME`），
+/// 无法确定用户目录时退回到当前目录下的文件；设置了 `--data-dir` 时为该目录下的文件。
+pub fn default_data_path(format: StorageFormat) -> PathBuf {
+    if let Some(dir) = data_dir_override() {
+        return dir.join(format.file_name());
+    }
+    match project_dirs() {
+        Some(dirs) => dirs.data_dir().join(format.file_name()),
+        None => PathBuf::from(format.file_name()),
+    }
+}
+
+/// 环境变量 `TODO_FILE` 指定的数据文件路径，未设置或为空时返回 `None`
+pub fn env_data_path() -> Option<PathBuf> {
+    std::env::var_os(TODO_FILE_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 未通过命令行指定时使用的数据文件路径
+///
+/// 优先使用 `TODO_FILE` 环境变量，其次是配置文件中的路径，最后是平台数据目录。
+pub fn resolve_data_path(format: StorageFormat, configured: Option<PathBuf>) -> PathBuf {
+    env_data_path()
+        .or(configured)
+        .unwrap_or_else(|| default_data_path(format))
+}
+
+/// 确保文件所在的目录存在
+fn ensure_parent_dir(path: &Path) -> TodoResult<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// 原子地写入文件：先写入同目录下的临时文件，再重命名覆盖原文件
+///
+/// 写入过程中崩溃或出错时，原文件保持不变。
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> TodoResult<()> {
+    write_atomic_with(path, |file| file.write_all(contents))
+}
+
+/// `write_atomic` 的实现，由 `write` 负责写入临时文件的内容
+fn write_atomic_with<F>(path: &Path, write: F) -> TodoResult<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    ensure_parent_dir(path)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| TodoError::InvalidPath(path.to_path_buf()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = File::create(&temp_path).and_then(|mut file| {
+        write(&mut file)?;
+        file.sync_all()
+    });
+
+    if let Err(err) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err.into());
+    }
+
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// 加密数据文件的文件头
+pub const ENCRYPTED_MAGIC: &[u8] = b"TODOENC1";
+
+/// 检查数据文件是否已加密，文件不存在时返回 `false`
+pub fn is_encrypted_file<P: AsRef<Path>>(path: P) -> TodoResult<bool> {
+    let path_ref = path.as_ref();
+    if !path_ref.exists() {
+        return Ok(false);
+    }
+
+    let mut header = [0u8; 8];
+    let read = File::open(path_ref)?.read(&mut header)?;
+    Ok(header[..read] == *ENCRYPTED_MAGIC)
+}
+
+/// 数据文件对应的校验和文件（同一目录下的隐藏文件，例如 `.todos.json.checksum`）
+pub fn checksum_path(data_path: &Path) -> PathBuf {
+    let file_name = data_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    data_path.with_file_name(format!(".{}.checksum", file_name))
+}
+
+/// 内容的校验和（CRC-32 和长度）
+fn checksum(content: &[u8]) -> String {
+    format!("crc32:{:08x} {}\n", crc32fast::hash(content), content.len())
+}
+
+/// 写入数据文件，同时更新校验和文件
+pub(crate) fn write_data_file(path: &Path, content: &[u8]) -> TodoResult<()> {
+    write_atomic(path, content)?;
+    write_atomic(&checksum_path(path), checksum(content).as_bytes())
+}
+
+/// 按数据文件当前的内容更新校验和，用于数据文件被直接复制或替换之后
+pub fn update_checksum<P: AsRef<Path>>(path: P) -> TodoResult<()> {
+    let path = path.as_ref();
+    write_atomic(&checksum_path(path), checksum(&fs::read(path)?).as_bytes())
+}
+
+/// 检查数据文件与保存时记下的校验和是否一致；没有校验和文件时返回 `None`
+///
+/// 数据文件和校验和文件不是同时写入的，保存过程中断或被其他程序修改时也会不一致，
+/// 因此不一致只说明文件可能有问题，能否使用以能否正常解析为准。
+pub fn verify_checksum<P: AsRef<Path>>(path: P) -> TodoResult<Option<bool>> {
+    let path = path.as_ref();
+    let Ok(expected) = fs::read_to_string(checksum_path(path)) else {
+        return Ok(None);
+    };
+    Ok(Some(checksum(&fs::read(path)?) == expected))
+}
+
+/// gzip 文件头
+pub const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// 路径是否以 `.gz` 结尾（这样的数据文件压缩保存）
+pub fn is_gzip_path<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref().extension().is_some_and(|extension| extension == "gz")
+}
+
+/// 压缩保存时使用的路径：在原路径后加上 `.gz`，已经以 `.gz` 结尾时不变
+pub fn gzip_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    if is_gzip_path(path) {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+/// gzip 压缩
+fn compress(content: &[u8]) -> TodoResult<Vec<u8>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(content)?;
+    Ok(encoder.finish()?)
+}
+
+/// 内容以 gzip 文件头开始时解压，否则原样返回
+fn decompress(content: &[u8]) -> TodoResult<std::borrow::Cow<'_, [u8]>> {
+    if !content.starts_with(GZIP_MAGIC) {
+        return Ok(content.into());
+    }
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(content)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| TodoError::InvalidDataFile(format!("corrupt gzip data: {}", err)))?;
+    Ok(decompressed.into())
+}
+
+/// 按格式序列化，路径以 `.gz` 结尾时再压缩，得到写入该文件的内容
+pub fn serialize_for_path<P: AsRef<Path>>(todo_list: &TodoList, path: P, format: StorageFormat) -> TodoResult<Vec<u8>> {
+    let content = serialize_with_format(todo_list, format)?;
+    if is_gzip_path(path) {
+        return compress(&content);
+    }
+    Ok(content)
+}
+
+/// 把文件压缩后写入 `to`，原文件保持不变
+pub fn compress_file<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> TodoResult<()> {
+    write_data_file(to.as_ref(), &compress(&fs::read(from)?)?)
+}
+
+/// 读取未加密的数据文件内容，加密的文件返回 `EncryptionError`
+fn read_plaintext(path: &Path) -> TodoResult<Vec<u8>> {
+    let bytes = fs::read(path)?;
+
+    if bytes.starts_with(ENCRYPTED_MAGIC) {
+        return Err(TodoError::EncryptionError(format!(
+            "{} is encrypted",
+            path.display()
+        )));
+    }
+
+    Ok(bytes)
+}
+
+/// 按格式序列化待办列表
+pub fn serialize_with_format(todo_list: &TodoList, format: StorageFormat) -> TodoResult<Vec<u8>> {
+    match format {
+        // 格式化输出，便于人类阅读
+        StorageFormat::Json => Ok(serde_json::to_vec_pretty(todo_list)?),
+        StorageFormat::TodoTxt => Ok(todotxt::serialize(todo_list).into_bytes()),
+        // 按字段名保存，与 JSON 一样可以经过迁移读取旧版本的数据
+        StorageFormat::Binary => {
+            rmp_serde::to_vec_named(todo_list).map_err(|err| TodoError::InvalidDataFile(err.to_string()))
+        }
+    }
+}
+
+/// 按格式解析待办列表，空内容返回空列表；gzip 压缩的内容会先解压
+pub fn parse_with_format(content: &[u8], format: StorageFormat) -> TodoResult<TodoList> {
+    let content = &*decompress(content)?;
+    if content.is_empty() || format.is_text() && content.trim_ascii().is_empty() {
+        return Ok(TodoList::new());
+    }
+
+    match format {
+        StorageFormat::Json => parse_json(text(content)?),
+        StorageFormat::TodoTxt => todotxt::parse(text(content)?),
+        StorageFormat::Binary => {
+            let value = rmp_serde::from_slice(content).map_err(|err| TodoError::InvalidDataFile(err.to_string()))?;
+            migrate(value)
+        }
+    }
+}
+
+/// 文本格式的内容，不是有效的 UTF-8 时返回错误
+fn text(content: &[u8]) -> TodoResult<&str> {
+    std::str::from_utf8(content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into())
+}
+
 /// 将 TodoList 保存到文件
 ///
 /// # 参数
@@ -22,15 +304,10 @@ pub const DEFAULT_FILE_PATH: &str = "todos.json";
 ///
 /// let todo_list = TodoList::new();
 /// save_to_file(&todo_list, "test_todos.json").unwrap();
+/// # std::fs::remove_file(".test_todos.json.checksum").unwrap();
 /// ```
 pub fn save_to_file<P: AsRef<Path>>(todo_list: &TodoList, path: P) -> TodoResult<()> {
-    // 序列化为 JSON（格式化输出，便于人类阅读）
-    let json = serde_json::to_string_pretty(todo_list)?;
-    
-    // 写入文件
-    fs::write(path, json)?;
-    
-    Ok(())
+    save_to_file_with_format(todo_list, path, StorageFormat::Json)
 }
 
 /// 从文件加载 TodoList
@@ -50,25 +327,380 @@ pub fn save_to_file<P: AsRef<Path>>(todo_list: &TodoList, path: P) -> TodoResult
 /// println!("加载了 {} 个任务", todo_list.len());
 /// ```
 pub fn load_from_file<P: AsRef<Path>>(path: P) -> TodoResult<TodoList> {
+    load_from_file_with_format(path, StorageFormat::Json)
+}
+
+/// 解析 JSON 格式的待办列表，旧版本的数据会依次经过迁移升级到当前版本
+pub fn parse_json(content: &str) -> TodoResult<TodoList> {
+    migrate(serde_json::from_str(content)?)
+}
+
+/// 把任意版本的数据依次经过迁移升级到当前版本
+fn migrate(mut value: Value) -> TodoResult<TodoList> {
+    // 没有版本号的是加入版本号之前的文件（版本 1）
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |version| version as u32);
+
+    if version > SCHEMA_VERSION {
+        return Err(TodoError::UnsupportedVersion(version));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate() {
+        if version <= from as u32 + 1 {
+            migration(&mut value)?;
+        }
+    }
+    value["version"] = json!(SCHEMA_VERSION);
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// 数据迁移，第 i 项把版本 i + 1 升级到版本 i + 2
+type Migration = fn(&mut Value) -> TodoResult<()>;
+
+const MIGRATIONS: [Migration; (SCHEMA_VERSION - 1) as usize] = [
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+];
+
+/// 版本 1 → 2：补全后来加入的任务字段，缺少 `next_id` 时按最大 ID 计算
+fn migrate_v1_to_v2(value: &mut Value) -> TodoResult<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| TodoError::InvalidDataFile("not a JSON object".to_string()))?;
+
+    let tasks = root
+        .entry("tasks")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .ok_or_else(|| TodoError::InvalidDataFile("'tasks' is not a list".to_string()))?;
+
+    let mut max_id = 0;
+    for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+        max_id = max_id.max(task.get("id").and_then(Value::as_u64).unwrap_or(0));
+
+        for (field, default) in [
+            ("tags", json!([])),
+            ("parent_id", Value::Null),
+            ("recurrence", Value::Null),
+            ("skipped", json!([])),
+            ("estimate_minutes", Value::Null),
+            ("todoist_id", Value::Null),
+        ] {
+            task.entry(field).or_insert(default);
+        }
+    }
+
+    root.entry("next_id").or_insert_with(|| json!(max_id + 1));
+    Ok(())
+}
+
+/// 版本 2 → 3：为每个任务生成 UUID
+fn migrate_v2_to_v3(value: &mut Value) -> TodoResult<()> {
+    let tasks = value
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| TodoError::InvalidDataFile("'tasks' is not a list".to_string()))?;
+
+    for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+        task.entry("uuid")
+            .or_insert_with(|| json!(uuid::Uuid::new_v4().to_string()));
+    }
+
+    Ok(())
+}
+
+/// 版本 3 → 4：加入空的历史记录
+fn migrate_v3_to_v4(value: &mut Value) -> TodoResult<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| TodoError::InvalidDataFile("not a JSON object".to_string()))?;
+
+    root.entry("history").or_insert_with(|| json!([]));
+    Ok(())
+}
+
+/// 版本 4 → 5：加入目标列表
+fn migrate_v4_to_v5(value: &mut Value) -> TodoResult<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| TodoError::InvalidDataFile("not a JSON object".to_string()))?;
+
+    root.entry("goals").or_insert_with(|| json!([]));
+    root.entry("next_goal_id").or_insert_with(|| json!(1));
+    Ok(())
+}
+
+/// 版本 5 → 6：加入最后修改时间，取已知的最近时间（完成时间或创建时间）
+fn migrate_v5_to_v6(value: &mut Value) -> TodoResult<()> {
+    let tasks = value
+        .get_mut("tasks")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| TodoError::InvalidDataFile("'tasks' is not a list".to_string()))?;
+
+    for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+        let updated_at = task
+            .get("completed_at")
+            .filter(|at| !at.is_null())
+            .or_else(|| task.get("created_at"))
+            .cloned()
+            .unwrap_or(Value::Null);
+        if !updated_at.is_null() {
+            task.entry("updated_at").or_insert(updated_at);
+        }
+    }
+
+    Ok(())
+}
+
+/// 版本 6 → 7：加入空的回收站
+fn migrate_v6_to_v7(value: &mut Value) -> TodoResult<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| TodoError::InvalidDataFile("not a JSON object".to_string()))?;
+
+    root.entry("trash").or_insert_with(|| json!([]));
+    Ok(())
+}
+
+/// 版本 7 → 8：只有日期的截止日期原来保存为 UTC 当天 23:59:59，改为本地时区当天结束
+fn migrate_v7_to_v8(value: &mut Value) -> TodoResult<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| TodoError::InvalidDataFile("not a JSON object".to_string()))?;
+
+    if let Some(tasks) = root.get_mut("tasks").and_then(Value::as_array_mut) {
+        tasks.iter_mut().for_each(localize_date_only_due);
+    }
+    if let Some(trash) = root.get_mut("trash").and_then(Value::as_array_mut) {
+        trash
+            .iter_mut()
+            .filter_map(|entry| entry.get_mut("task"))
+            .for_each(localize_date_only_due);
+    }
+
+    Ok(())
+}
+
+fn localize_date_only_due(task: &mut Value) {
+    let end_of_utc_day = NaiveTime::from_hms_opt(23, 59, 59);
+    let due = task
+        .get("due_date")
+        .and_then(Value::as_str)
+        .and_then(|due| due.parse::<DateTime<Utc>>().ok())
+        .filter(|due| Some(due.time()) == end_of_utc_day);
+    if let Some(due) = due {
+        task["due_date"] = json!(end_of_day(due.date_naive()));
+    }
+}
+
+/// 按指定格式将 TodoList 保存到文件
+///
+/// todo.txt 格式只保存该格式支持的字段（完成状态、优先级、创建日期、标题、标签、截止日期）。
+/// 路径以 `.gz` 结尾时压缩保存，加载时自动识别压缩的文件。
+pub fn save_to_file_with_format<P: AsRef<Path>>(
+    todo_list: &TodoList,
+    path: P,
+    format: StorageFormat,
+) -> TodoResult<()> {
+    let content = serialize_for_path(todo_list, path.as_ref(), format)?;
+
+    // 原子写入，避免写到一半时崩溃损坏数据文件
+    write_data_file(path.as_ref(), &content)
+}
+
+/// 按指定格式从文件加载 TodoList
+///
+/// 与 `load_from_file` 一样，文件不存在或为空时返回空列表
+pub fn load_from_file_with_format<P: AsRef<Path>>(
+    path: P,
+    format: StorageFormat,
+) -> TodoResult<TodoList> {
     let path_ref = path.as_ref();
-    
-    // 如果文件不存在，返回空列表
+
     if !path_ref.exists() {
         return Ok(TodoList::new());
     }
-    
-    // 读取文件内容
-    let content = fs:: read_to_string(path_ref)?;
-    
-    // 如果文件为空，返回空列表
-    if content. trim().is_empty() {
-        return Ok(TodoList::new());
+
+    parse_with_format(&read_plaintext(path_ref)?, format)
+}
+
+/// 加密后保存到文件
+#[cfg(feature = "encryption")]
+pub fn save_encrypted<P: AsRef<Path>>(
+    todo_list: &TodoList,
+    path: P,
+    format: StorageFormat,
+    passphrase: &str,
+) -> TodoResult<()> {
+    let content = serialize_for_path(todo_list, path.as_ref(), format)?;
+    let encrypted = crate::crypto::encrypt(&content, passphrase)?;
+
+    write_data_file(path.as_ref(), &encrypted)
+}
+
+/// 从加密的文件加载，文件未加密时按普通文件加载
+#[cfg(feature = "encryption")]
+pub fn load_encrypted<P: AsRef<Path>>(
+    path: P,
+    format: StorageFormat,
+    passphrase: &str,
+) -> TodoResult<TodoList> {
+    let path_ref = path.as_ref();
+
+    if !is_encrypted_file(path_ref)? {
+        return load_from_file_with_format(path_ref, format);
+    }
+
+    let decrypted = crate::crypto::decrypt(&fs::read(path_ref)?, passphrase)?;
+    parse_with_format(&decrypted, format)
+}
+
+/// 待办列表的存储后端
+///
+/// 命令处理只通过这个 trait 读写数据，换用其他后端（数据库、远程存储、测试用的内存存储）
+/// 不需要修改处理逻辑。
+pub trait Storage {
+    /// 加载待办列表，还没有数据时返回空列表
+    fn load(&self) -> TodoResult<TodoList>;
+
+    /// 保存待办列表，保存前先调用 `backup`
+    fn save(&self, todo_list: &TodoList) -> TodoResult<()>;
+
+    /// 备份当前保存的数据，默认不做任何事
+    fn backup(&self) -> TodoResult<()> {
+        Ok(())
+    }
+}
+
+impl<S: Storage + ?Sized> Storage for &S {
+    fn load(&self) -> TodoResult<TodoList> {
+        (**self).load()
+    }
+
+    fn save(&self, todo_list: &TodoList) -> TodoResult<()> {
+        (**self).save(todo_list)
+    }
+
+    fn backup(&self) -> TodoResult<()> {
+        (**self).backup()
+    }
+}
+
+/// 保存在本地文件中的待办列表（JSON 或 todo.txt 格式）
+///
+/// # 示例
+/// ```
+/// use rust_todo_cli::prelude::*;
+///
+/// let storage = FileStorage::new("storage_example.json", StorageFormat::Json);
+/// let mut list = storage.load().unwrap();
+/// list.add_task("写周报".to_string(), Priority::High, None);
+/// storage.save(&list).unwrap();
+/// assert_eq!(storage.load().unwrap().len(), 1);
+/// # rust_todo_cli::storage::delete_file("storage_example.json").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    path: PathBuf,
+    format: StorageFormat,
+    backup_count: usize,
+    #[cfg(feature = "encryption")]
+    passphrase: Option<String>,
+}
+
+impl FileStorage {
+    /// 使用指定的文件和格式，不自动备份
+    pub fn new<P: AsRef<Path>>(path: P, format: StorageFormat) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            format,
+            backup_count: 0,
+            #[cfg(feature = "encryption")]
+            passphrase: None,
+        }
+    }
+
+    /// 保存前把当前文件备份到 `backups/` 目录，只保留最近的 `keep` 个
+    pub fn with_backups(mut self, keep: usize) -> Self {
+        self.backup_count = keep;
+        self
+    }
+
+    /// 用口令加密保存；加载时文件未加密也可以正常读取
+    #[cfg(feature = "encryption")]
+    pub fn with_passphrase(mut self, passphrase: Option<String>) -> Self {
+        self.passphrase = passphrase;
+        self
+    }
+
+    /// 数据文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self) -> TodoResult<TodoList> {
+        #[cfg(feature = "encryption")]
+        if let Some(passphrase) = &self.passphrase {
+            return load_encrypted(&self.path, self.format, passphrase);
+        }
+
+        load_from_file_with_format(&self.path, self.format)
+    }
+
+    fn save(&self, todo_list: &TodoList) -> TodoResult<()> {
+        self.backup()?;
+
+        #[cfg(feature = "encryption")]
+        if let Some(passphrase) = &self.passphrase {
+            return save_encrypted(todo_list, &self.path, self.format, passphrase);
+        }
+
+        save_to_file_with_format(todo_list, &self.path, self.format)
+    }
+
+    fn backup(&self) -> TodoResult<()> {
+        crate::backups::rotate(&self.path, self.backup_count)?;
+        Ok(())
+    }
+}
+
+/// 保存在内存中的待办列表，数据按 JSON 序列化保存，与文件存储的行为一致（适合测试）
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    content: std::sync::Mutex<Option<String>>,
+}
+
+impl MemoryStorage {
+    /// 空的存储
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self) -> TodoResult<TodoList> {
+        match self.content.lock().unwrap_or_else(|err| err.into_inner()).as_deref() {
+            Some(content) => parse_json(content),
+            None => Ok(TodoList::new()),
+        }
+    }
+
+    fn save(&self, todo_list: &TodoList) -> TodoResult<()> {
+        self.backup()?;
+        let content = serde_json::to_string(todo_list)?;
+        *self.content.lock().unwrap_or_else(|err| err.into_inner()) = Some(content);
+        Ok(())
     }
-    
-    // 反序列化 JSON
-    let todo_list:  TodoList = serde_json:: from_str(&content)?;
-    
-    Ok(todo_list)
 }
 
 /// 检查文件是否存在
@@ -89,6 +721,11 @@ pub fn delete_file<P: AsRef<Path>>(path: P) -> TodoResult<()> {
     if path_ref.exists() {
         fs::remove_file(path_ref)?;
     }
+    // 校验和文件随数据文件一起删除
+    let checksum = checksum_path(path_ref);
+    if checksum.exists() {
+        fs::remove_file(checksum)?;
+    }
     
     Ok(())
 }
@@ -105,9 +742,7 @@ pub fn backup_file<P: AsRef<Path>, Q: AsRef<Path>>(source: P, backup: Q) -> Todo
     let source_ref = source. as_ref();
     
     if ! source_ref.exists() {
-        return Err(TodoError:: Custom(
-            format!("Source file '{}' does not exist", source_ref.display())
-        ));
+        return Err(TodoError::FileNotFound(source_ref.to_path_buf()));
     }
     
     fs::copy(source_ref, backup)?;
@@ -120,6 +755,43 @@ mod tests {
     use crate::task::Priority;
     use std::fs;
 
+    #[test]
+    fn test_memory_storage() {
+        let storage: Box<dyn Storage> = Box::new(MemoryStorage::new());
+        assert!(storage.load().unwrap().is_empty());
+
+        let mut list = storage.load().unwrap();
+        let id = list.add_task("写周报".to_string(), Priority::High, None);
+        storage.save(&list).unwrap();
+
+        // 每次加载得到独立的副本
+        list.complete_task(id).unwrap();
+        let loaded = storage.load().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(!loaded[id].completed);
+    }
+
+    #[test]
+    fn test_file_storage_backups() {
+        let dir = std::env::temp_dir().join(format!("todo-file-storage-{}", std::process::id()));
+        let path = dir.join("todos.txt");
+        let storage = FileStorage::new(&path, StorageFormat::TodoTxt).with_backups(2);
+
+        let mut list = TodoList::new();
+        for title in ["一", "二", "三"] {
+            list.add_task(title.to_string(), Priority::Low, None);
+            storage.save(&list).unwrap();
+            // 备份文件名精确到毫秒
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(storage.load().unwrap().len(), 3);
+        assert!(fs::read_to_string(storage.path()).unwrap().contains("三"));
+        // 第一次保存时还没有文件可以备份
+        assert_eq!(crate::backups::list(&path).unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     // 测试辅助函数：创建临时测试文件路径
     fn test_file_path(name: &str) -> String {
         format!("test_{}.json", name)
@@ -127,7 +799,7 @@ mod tests {
 
     // 测试后清理函数
     fn cleanup(path: &str) {
-        let _ = fs::remove_file(path);
+        let _ = delete_file(path);
     }
 
     #[test]
@@ -182,6 +854,125 @@ mod tests {
         cleanup(&path);
     }
 
+    #[test]
+    fn test_failed_write_keeps_original() {
+        let path = test_file_path("atomic");
+
+        let mut list = TodoList::new();
+        list.add_task("原有任务".to_string(), Priority::High, None);
+        save_to_file(&list, &path).unwrap();
+
+        // 模拟写到一半时出错
+        let result = write_atomic_with(Path::new(&path), |file| {
+            file.write_all(b"{\"tasks\": [")?;
+            Err(io::Error::other("disk full"))
+        });
+        assert!(matches!(result, Err(TodoError::IoError(_))));
+
+        // 原文件不受影响，临时文件已被清理
+        let loaded = load_from_file(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.list_tasks()[0].title, "原有任务");
+        assert!(!file_exists(".test_atomic.json.tmp"));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_migrate_unversioned_file() {
+        // 加入版本号和新字段之前的文件格式，且缺少 next_id
+        let content = r#"{
+            "tasks": [
+                {"id": 3, "title": "旧任务", "completed": false, "priority": "High",
+                 "created_at": "2025-01-01T00:00:00Z", "due_date": null}
+            ]
+        }"#;
+
+        let mut list = parse_json(content).unwrap();
+        assert_eq!(list.len(), 1);
+        assert!(list.list_tasks()[0].tags.is_empty());
+        assert_eq!(list.list_tasks()[0].uuid.len(), 36);
+        assert_eq!(list.list_tasks()[0].updated_at, list.list_tasks()[0].created_at);
+        assert_eq!(list.add_task("新任务".to_string(), Priority::Low, None), 4);
+
+        let saved = serde_json::to_value(&list).unwrap();
+        assert_eq!(saved["version"], json!(SCHEMA_VERSION));
+        assert_eq!(saved["history"].as_array().unwrap().len(), 1);
+        assert_eq!(saved["trash"], json!([]));
+    }
+
+    #[test]
+    fn test_migrate_date_only_due_to_local_end_of_day() {
+        let content = r#"{
+            "tasks": [
+                {"id": 1, "title": "交报告", "completed": false, "priority": "High",
+                 "created_at": "2025-01-01T00:00:00Z", "due_date": "2025-01-31T23:59:59Z"},
+                {"id": 2, "title": "开会", "completed": false, "priority": "Low",
+                 "created_at": "2025-01-01T00:00:00Z", "due_date": "2025-01-31T09:30:00Z"}
+            ]
+        }"#;
+
+        let list = parse_json(content).unwrap();
+        let end = crate::dates::end_of_day(chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap());
+        assert_eq!(list[1].due_date, Some(end));
+        // 带具体时间的截止日期保持不变
+        assert_eq!(list[2].due_date.unwrap().to_rfc3339(), "2025-01-31T09:30:00+00:00");
+    }
+
+    #[test]
+    fn test_uuid_survives_save_and_load() {
+        let path = test_file_path("uuid");
+
+        let mut list = TodoList::new();
+        list.add_task("任务".to_string(), Priority::Low, None);
+        save_to_file(&list, &path).unwrap();
+
+        let loaded = load_from_file(&path).unwrap();
+        assert_eq!(loaded.list_tasks()[0].uuid, list.list_tasks()[0].uuid);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let content = format!(r#"{{"version": {}, "tasks": [], "next_id": 1}}"#, SCHEMA_VERSION + 1);
+
+        let result = parse_json(&content);
+        assert!(matches!(result, Err(TodoError::UnsupportedVersion(v)) if v == SCHEMA_VERSION + 1));
+    }
+
+    #[test]
+    fn test_encrypted_file_is_not_loaded_as_plaintext() {
+        let path = test_file_path("locked");
+        let mut content = ENCRYPTED_MAGIC.to_vec();
+        content.extend_from_slice(&[0xff, 0x00, 0x13]);
+        fs::write(&path, content).unwrap();
+
+        assert!(is_encrypted_file(&path).unwrap());
+        assert!(matches!(load_from_file(&path), Err(TodoError::EncryptionError(_))));
+
+        cleanup(&path);
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_round_trip() {
+        let path = test_file_path("encrypted");
+
+        let mut list = TodoList::new();
+        list.add_task("秘密任务".to_string(), Priority::High, None);
+        save_encrypted(&list, &path, StorageFormat::Json, "hunter2").unwrap();
+
+        assert!(is_encrypted_file(&path).unwrap());
+        assert!(!fs::read(&path).unwrap().windows(4).any(|w| w == "秘密".as_bytes()));
+
+        let loaded = load_encrypted(&path, StorageFormat::Json, "hunter2").unwrap();
+        assert_eq!(loaded.list_tasks()[0].title, "秘密任务");
+        assert!(load_encrypted(&path, StorageFormat::Json, "wrong").is_err());
+
+        cleanup(&path);
+    }
+
     #[test]
     fn test_file_exists() {
         let path = test_file_path("exists");
@@ -243,10 +1034,10 @@ mod tests {
         let result = backup_file(&source, &backup);
         assert!(result.is_err());
         
-        if let Err(TodoError::Custom(msg)) = result {
-            assert!(msg.contains("does not exist"));
+        if let Err(TodoError::FileNotFound(path)) = result {
+            assert_eq!(path, source);
         } else {
-            panic!("Expected Custom error");
+            panic!("Expected FileNotFound error");
         }
     }
 
@@ -268,6 +1059,152 @@ mod tests {
         cleanup(&path);
     }
 
+    #[test]
+    fn test_save_and_load_todotxt() {
+        let path = test_file_path("todotxt").replace(".json", ".txt");
+
+        let mut list = TodoList::new();
+        list.add_task("写报告 @office".to_string(), Priority::High, None);
+        let id = list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.complete_task(id).unwrap();
+
+        save_to_file_with_format(&list, &path, StorageFormat::TodoTxt).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("(A) "));
+        assert!(content.contains("写报告 @office"));
+        assert!(content.contains("\nx "));
+
+        let loaded = load_from_file_with_format(&path, StorageFormat::TodoTxt).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.list_tasks()[0].priority, Priority::High);
+        assert!(loaded.list_tasks()[1].completed);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_save_and_load_binary() {
+        let path = test_file_path("binary").replace(".json", ".bin");
+
+        let mut list = TodoList::new();
+        let id = list.add_task("写报告".to_string(), Priority::High, None);
+        list.set_tags(id, vec!["work".to_string()]).unwrap();
+        list.complete_task(id).unwrap();
+        save_to_file_with_format(&list, &path, StorageFormat::Binary).unwrap();
+
+        // 比格式化的 JSON 小
+        let size = fs::metadata(&path).unwrap().len() as usize;
+        assert!(size < serialize_with_format(&list, StorageFormat::Json).unwrap().len());
+
+        let loaded = load_from_file_with_format(&path, StorageFormat::Binary).unwrap();
+        assert_eq!(loaded.list_tasks(), list.list_tasks());
+        assert_eq!(loaded.history(), list.history());
+        assert!(load_from_file_with_format(&path, StorageFormat::Json).is_err());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_binary_data_is_migrated() {
+        // 旧版本的数据同样经过迁移
+        let old = json!({
+            "tasks": [{"id": 3, "title": "旧任务", "completed": false, "priority": "High",
+                       "created_at": "2025-01-01T00:00:00Z", "due_date": null}]
+        });
+        let content = rmp_serde::to_vec_named(&old).unwrap();
+        let list = parse_with_format(&content, StorageFormat::Binary).unwrap();
+        assert_eq!(list.list_tasks()[0].title, "旧任务");
+        assert_eq!(list.list_tasks()[0].uuid.len(), 36);
+
+        assert!(parse_with_format(&[], StorageFormat::Binary).unwrap().is_empty());
+        assert!(matches!(
+            parse_with_format(b"not msgpack", StorageFormat::Binary),
+            Err(TodoError::InvalidDataFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_checksum() {
+        let path = test_file_path("checksum");
+        assert_eq!(verify_checksum(&path).unwrap(), None);
+
+        save_to_file(&TodoList::new(), &path).unwrap();
+        assert_eq!(verify_checksum(&path).unwrap(), Some(true));
+
+        // 在 todo 之外修改后不再一致，更新后恢复一致
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(verify_checksum(&path).unwrap(), Some(false));
+        update_checksum(&path).unwrap();
+        assert_eq!(verify_checksum(&path).unwrap(), Some(true));
+
+        cleanup(&path);
+        assert!(!checksum_path(Path::new(&path)).exists());
+    }
+
+    #[test]
+    fn test_save_and_load_gzip() {
+        let path = test_file_path("gzip").replace(".json", ".json.gz");
+        assert!(is_gzip_path(&path));
+        assert_eq!(gzip_path(&path), Path::new(&path));
+        assert_eq!(gzip_path("todos.json"), Path::new("todos.json.gz"));
+
+        let mut list = TodoList::new();
+        for i in 0..50 {
+            list.add_task(format!("任务 {}", i), Priority::Low, None);
+        }
+        save_to_file_with_format(&list, &path, StorageFormat::Json).unwrap();
+
+        let content = fs::read(&path).unwrap();
+        assert!(content.starts_with(GZIP_MAGIC));
+        assert!(content.len() < serialize_with_format(&list, StorageFormat::Json).unwrap().len() / 4);
+        assert_eq!(load_from_file(&path).unwrap().list_tasks(), list.list_tasks());
+
+        // 不以 .gz 结尾的压缩文件同样可以读取
+        let plain_name = path.replace(".gz", "");
+        fs::rename(&path, &plain_name).unwrap();
+        assert_eq!(load_from_file(&plain_name).unwrap().len(), 50);
+
+        cleanup(&plain_name);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_save_creates_parent_dir() {
+        let dir = test_file_path("nested_dir").replace(".json", "");
+        let path = Path::new(&dir).join("sub").join("todos.json");
+
+        assert!(save_to_file(&TodoList::new(), &path).is_ok());
+        assert!(file_exists(&path));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_default_data_path() {
+        let path = default_data_path(StorageFormat::Json);
+        assert!(path.ends_with("todos.json"));
+
+        let path = default_data_path(StorageFormat::TodoTxt);
+        assert!(path.ends_with("todo.txt"));
+    }
+
+    #[test]
+    fn test_resolve_data_path_from_env() {
+        // 本模块中只有这个测试修改 TODO_FILE
+        std::env::set_var(TODO_FILE_ENV, "env_todos.json");
+        let path = resolve_data_path(StorageFormat::Json, Some(PathBuf::from("config.json")));
+        assert_eq!(path, PathBuf::from("env_todos.json"));
+
+        std::env::set_var(TODO_FILE_ENV, "");
+        let path = resolve_data_path(StorageFormat::Json, Some(PathBuf::from("config.json")));
+        assert_eq!(path, PathBuf::from("config.json"));
+
+        std::env::remove_var(TODO_FILE_ENV);
+        let path = resolve_data_path(StorageFormat::Json, None);
+        assert_eq!(path, default_data_path(StorageFormat::Json));
+    }
+
     #[test]
     fn test_json_format() {
         let path = test_file_path("format");
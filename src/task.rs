@@ -1,4 +1,6 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
+use crate::dates::{from_local, local_date, to_local};
+use crate::rrule::RRule;
 use serde::{Deserialize, Serialize};
 
 /// 任务优先级枚举
@@ -34,11 +36,79 @@ impl std::fmt::Display for Priority {
     }
 }
 
+/// 循环任务的重复周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
+    /// iCalendar RRULE，例如 `FREQ=MONTHLY;BYDAY=1MO`
+    Rule(RRule),
+}
+
+impl Recurrence {
+    /// 计算给定日期之后的下一次发生时间，RRULE 已经结束时返回 `None`
+    pub fn next_after(&self, date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Recurrence::Daily => Some(date + Duration::days(1)),
+            Recurrence::Weekly => Some(date + Duration::weeks(1)),
+            Recurrence::Monthly => next_month_on(date, local_date(date).day()),
+            Recurrence::Rule(rule) => rule.next_after(date),
+        }
+    }
+}
+
+/// `date` 下个月的第 `day` 日（本地日期，时间不变），该月没有这一天时取月末
+fn next_month_on(date: DateTime<Utc>, day: u32) -> Option<DateTime<Utc>> {
+    let local = to_local(date).naive_local();
+    let month = local.date().with_day(1)?.checked_add_months(Months::new(1))?;
+    let last_day = month.checked_add_months(Months::new(1))?.pred_opt()?.day();
+    Some(from_local(month.with_day(day.clamp(1, last_day))?.and_time(local.time())))
+}
+
+/// 实现 Recurrence 的字符串转换，方便 CLI 使用
+impl std::str::FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" | "d" => Ok(Recurrence::Daily),
+            "weekly" | "w" => Ok(Recurrence::Weekly),
+            "monthly" | "m" => Ok(Recurrence::Monthly),
+            _ if s.contains('=') => s.parse().map(Recurrence::Rule),
+            _ => Err(format!("Invalid recurrence: {}", s)),
+        }
+    }
+}
+
+/// 实现 Display trait 用于打印
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly => write!(f, "weekly"),
+            Recurrence::Monthly => write!(f, "monthly"),
+            Recurrence::Rule(rule) => write!(f, "{}", rule),
+        }
+    }
+}
+
+/// 一个完成的番茄钟
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pomodoro {
+    /// 开始时间
+    pub started_at: DateTime<Utc>,
+    /// 时长（分钟）
+    pub minutes: u32,
+}
+
 /// 待办任务结构体
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     /// 任务唯一标识符
     pub id: u32,
+    /// 稳定的全局标识，创建时生成，在导出、同步和合并中保持不变
+    pub uuid: String,
     /// 任务标题
     pub title: String,
     /// 是否已完成
@@ -49,6 +119,48 @@ pub struct Task {
     pub created_at: DateTime<Utc>,
     /// 截止日期（可选）
     pub due_date: Option<DateTime<Utc>>,
+    /// 完成时间（在记录完成时间之前完成的任务没有）
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// 最后修改时间，每次修改任务时更新，用于同步时判断哪一份更新
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+    /// 标签（旧数据文件中没有该字段时为空）
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 情境（GTD 中的 `@home`、`@work` 等，不含 `@`）
+    #[serde(default)]
+    pub context: Option<String>,
+    /// 父任务 ID（仅子任务有）
+    #[serde(default)]
+    pub parent_id: Option<u32>,
+    /// 依赖的任务 ID，这些任务全部完成前该任务处于阻塞状态
+    #[serde(default)]
+    pub depends_on: Vec<u32>,
+    /// 重复周期（仅循环任务有）
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// 被跳过的循环周期（记录原定的截止日期）
+    #[serde(default)]
+    pub skipped: Vec<DateTime<Utc>>,
+    /// 按月重复的锚定日（1-31），月份没有这一天时截止日期取月末，之后的月份仍回到这一天
+    #[serde(default)]
+    pub anchor_day: Option<u32>,
+    /// 预估耗时（分钟）
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    /// 关联的 Todoist 任务 ID（同步过的任务才有）
+    #[serde(default)]
+    pub todoist_id: Option<String>,
+    /// 全局唯一 ID（由 `IdStrategy` 生成，顺序编号策略下为空）
+    #[serde(default)]
+    pub uid: Option<String>,
+    /// 关联的目标 ID
+    #[serde(default)]
+    pub goal_id: Option<u32>,
+    /// 在该任务上完成的番茄钟
+    #[serde(default)]
+    pub pomodoros: Vec<Pomodoro>,
 }
 
 impl Task {
@@ -69,19 +181,144 @@ impl Task {
     /// assert!(!task.completed);
     /// ```
     pub fn new(id:  u32, title: String, priority: Priority, due_date: Option<DateTime<Utc>>) -> Self {
+        let now = Utc::now();
         Self {
             id,
+            uuid: uuid::Uuid::new_v4().to_string(),
             title,
             completed: false,
             priority,
-            created_at:  Utc::now(),
+            created_at: now,
             due_date,
+            completed_at: None,
+            updated_at: now,
+            tags: Vec::new(),
+            context: None,
+            parent_id: None,
+            depends_on: Vec::new(),
+            recurrence: None,
+            skipped: Vec::new(),
+            anchor_day: None,
+            estimate_minutes: None,
+            todoist_id: None,
+            uid: None,
+            goal_id: None,
+            pomodoros: Vec::new(),
         }
     }
 
-    /// 标记任务为已完成
+    /// 记录任务被修改，所有修改任务的操作都应调用
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+
+    /// 标记任务为已完成，并记录完成时间（已完成的任务保持原来的完成时间）
     pub fn complete(&mut self) {
-        self.completed = true;
+        if !self.completed {
+            self.completed = true;
+            self.completed_at = Some(Utc::now());
+            self.touch();
+        }
+    }
+
+    /// 重新打开已完成的任务，清除完成时间
+    pub fn reopen(&mut self) {
+        if self.completed {
+            self.completed = false;
+            self.completed_at = None;
+            self.touch();
+        }
+    }
+
+    /// 创建循环任务的下一次实例，非循环任务或 RRULE 已经结束时返回 `None`
+    ///
+    /// 新实例的 ID 由调用方重新分配。
+    pub fn next_occurrence(&self) -> Option<Task> {
+        let mut next = self.clone();
+        next.advance_due_date()?;
+        next.completed = false;
+        next.completed_at = None;
+        next.created_at = Utc::now();
+        next.updated_at = next.created_at;
+        next.skipped.clear();
+        next.pomodoros.clear();
+        next.todoist_id = None;
+        next.uuid = uuid::Uuid::new_v4().to_string();
+        next.uid = None;
+        Some(next)
+    }
+
+    /// 把截止日期推进到重复规则的下一次，返回新的截止日期
+    ///
+    /// 没有截止日期时从现在开始计算。按月重复时第一次推进会记下当前的日子作为锚定日，
+    /// 例如 1 月 31 日之后依次是 2 月 28 日、3 月 31 日。
+    /// 非循环任务或 RRULE 已经结束时返回 `None`，任务不变。
+    pub fn advance_due_date(&mut self) -> Option<DateTime<Utc>> {
+        let recurrence = self.recurrence?;
+        let base = self.due_date.unwrap_or_else(Utc::now);
+
+        let next = if recurrence == Recurrence::Monthly {
+            let day = self.anchor_day.unwrap_or_else(|| local_date(base).day());
+            let next = next_month_on(base, day)?;
+            self.anchor_day = Some(day);
+            next
+        } else {
+            recurrence.next_after(base)?
+        };
+        self.due_date = Some(next);
+        Some(next)
+    }
+
+    /// 复制任务，作为新的未完成任务
+    ///
+    /// 保留标题、优先级、标签、情境、预估耗时、父任务和目标；截止日期、重复周期、依赖和番茄钟记录不复制。
+    /// 新任务的 ID 由调用方重新分配。
+    pub fn duplicate(&self) -> Task {
+        let mut copy = Task::new(self.id, self.title.clone(), self.priority, None);
+        copy.tags = self.tags.clone();
+        copy.context = self.context.clone();
+        copy.estimate_minutes = self.estimate_minutes;
+        copy.parent_id = self.parent_id;
+        copy.goal_id = self.goal_id;
+        copy
+    }
+
+    /// 紧急程度，越大越紧急，已完成的任务为 0
+    ///
+    /// 参照 Taskwarrior 的 urgency，由三部分相加：
+    /// - 优先级：高 6.0、中 3.9、低 1.8
+    /// - 截止日期：过期 7 天以上为 12.0，14 天以后到期为 2.4，之间线性变化；没有截止日期为 0
+    /// - 创建至今的时间：每天增加一点，满一年为 2.0
+    pub fn urgency(&self, now: DateTime<Utc>) -> f64 {
+        const DUE_WEIGHT: f64 = 12.0;
+        const AGE_WEIGHT: f64 = 2.0;
+
+        if self.completed {
+            return 0.0;
+        }
+
+        let priority = match self.priority {
+            Priority::High => 6.0,
+            Priority::Medium => 3.9,
+            Priority::Low => 1.8,
+        };
+
+        let due = self.due_date.map_or(0.0, |due| {
+            let days_overdue = (now - due).num_seconds() as f64 / 86_400.0;
+            let factor = if days_overdue >= 7.0 {
+                1.0
+            } else if days_overdue <= -14.0 {
+                0.2
+            } else {
+                (days_overdue + 14.0) * 0.8 / 21.0 + 0.2
+            };
+            factor * DUE_WEIGHT
+        });
+
+        let age_days = (now - self.created_at).num_seconds().max(0) as f64 / 86_400.0;
+        let age = (age_days / 365.0).min(1.0) * AGE_WEIGHT;
+
+        priority + due + age
     }
 
     /// 检查任务是否过期
@@ -92,11 +329,110 @@ impl Task {
             false
         }
     }
+
+    /// 用构建器创建任务，默认为中优先级、没有截止日期
+    ///
+    /// 构建出的任务 ID 为 0，用 [`TodoList::insert_task`](crate::todo_list::TodoList::insert_task)
+    /// 加入列表时分配 ID。
+    ///
+    /// # 示例
+    /// ```
+    /// use rust_todo_cli::prelude::*;
+    ///
+    /// let mut list = TodoList::new();
+    /// let task = Task::builder("写周报").priority(Priority::High).tag("work").context("office").build();
+    /// let id = list.insert_task(task);
+    /// assert_eq!(list.find_task(id).unwrap().tags, ["work"]);
+    /// ```
+    pub fn builder(title: impl Into<String>) -> TaskBuilder {
+        TaskBuilder {
+            task: Task::new(0, title.into(), Priority::Medium, None),
+        }
+    }
+}
+
+/// 任务构建器，由 [`Task::builder`] 创建
+///
+/// 父任务、依赖和目标引用列表中的其他 ID，需要在任务加入列表后通过 `TodoList` 设置。
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct TaskBuilder {
+    task: Task,
+}
+
+impl TaskBuilder {
+    /// 优先级
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.task.priority = priority;
+        self
+    }
+
+    /// 截止日期
+    pub fn due(mut self, due: DateTime<Utc>) -> Self {
+        self.task.due_date = Some(due);
+        self
+    }
+
+    /// 添加一个标签（可以带 `#`，重复的标签被忽略）
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        let tag = tag.trim().trim_start_matches('#');
+        if !tag.is_empty() && !self.task.tags.iter().any(|existing| existing == tag) {
+            self.task.tags.push(tag.to_string());
+        }
+        self
+    }
+
+    /// 情境（可以带 `@`）
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        let context = context.into();
+        self.task.context = Some(context.trim().trim_start_matches('@').to_string());
+        self
+    }
+
+    /// 重复周期，循环任务需要同时设置截止日期
+    pub fn recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.task.recurrence = Some(recurrence);
+        self
+    }
+
+    /// 预估耗时（分钟）
+    pub fn estimate_minutes(mut self, minutes: u32) -> Self {
+        self.task.estimate_minutes = Some(minutes);
+        self
+    }
+
+    /// 创建任务
+    pub fn build(self) -> Task {
+        self.task
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dates::local_time;
+
+    #[test]
+    fn test_urgency() {
+        let now = Utc::now();
+        let task = |priority, due: Option<i64>| Task::new(1, "任务".to_string(), priority, due.map(|days| now + chrono::Duration::days(days)));
+
+        let undated = task(Priority::High, None).urgency(now);
+        assert!((undated - 6.0).abs() < 0.01);
+        // 截止日期越近越紧急，过期 7 天以上和 14 天以后不再变化
+        assert!((task(Priority::Low, Some(30)).urgency(now) - (1.8 + 2.4)).abs() < 0.01);
+        assert!((task(Priority::Low, Some(-30)).urgency(now) - (1.8 + 12.0)).abs() < 0.01);
+        assert!(task(Priority::Low, Some(1)).urgency(now) > task(Priority::Low, Some(5)).urgency(now));
+        // 即将到期的低优先级任务比没有截止日期的高优先级任务更紧急
+        assert!(task(Priority::Low, Some(0)).urgency(now) > undated);
+
+        let mut old = task(Priority::High, None);
+        old.created_at = now - chrono::Duration::days(730);
+        assert!((old.urgency(now) - 8.0).abs() < 0.01);
+        old.complete();
+        assert_eq!(old.urgency(now), 0.0);
+    }
 
     #[test]
     fn test_create_task() {
@@ -107,12 +443,47 @@ mod tests {
         assert_eq!(task.priority, Priority::Medium);
     }
 
+    #[test]
+    fn test_builder() {
+        let due = Utc::now();
+        let task = Task::builder("发布")
+            .priority(Priority::High)
+            .due(due)
+            .tag("#work")
+            .tag("work")
+            .tag("release")
+            .context("@office")
+            .recurrence(Recurrence::Weekly)
+            .estimate_minutes(90)
+            .build();
+        assert_eq!(task.title, "发布");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.due_date, Some(due));
+        assert_eq!(task.tags, ["work", "release"]);
+        assert_eq!(task.context.as_deref(), Some("office"));
+        assert_eq!(task.recurrence, Some(Recurrence::Weekly));
+        assert_eq!(task.estimate_minutes, Some(90));
+
+        let task = Task::builder("默认值").build();
+        assert_eq!(task.priority, Priority::Medium);
+        assert!(task.due_date.is_none() && task.tags.is_empty());
+    }
+
     #[test]
     fn test_complete_task() {
         let mut task = Task::new(1, "完成测试".to_string(), Priority::Low, None);
         assert! (!task.completed);
         task.complete();
         assert!(task.completed);
+
+        // 重复完成不改变完成时间
+        let completed_at = task.completed_at.unwrap();
+        task.complete();
+        assert_eq!(task.completed_at, Some(completed_at));
+
+        task.reopen();
+        assert!(!task.completed);
+        assert!(task.completed_at.is_none());
     }
 
     #[test]
@@ -125,6 +496,55 @@ mod tests {
         assert!(Priority::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_recurrence_next_after() {
+
+        let date = local_time(2025, 1, 31, 23, 59, 59);
+        let next = |recurrence: Recurrence| local_date(recurrence.next_after(date).unwrap()).to_string();
+        assert_eq!(next(Recurrence::Daily), "2025-02-01");
+        assert_eq!(next(Recurrence::Weekly), "2025-02-07");
+        assert_eq!(next(Recurrence::Monthly), "2025-02-28");
+        assert_eq!(next("FREQ=MONTHLY;BYDAY=-1MO".parse().unwrap()), "2025-02-24");
+
+        let rule: Recurrence = "FREQ=DAILY;UNTIL=20250131".parse().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=DAILY;UNTIL=20250131T235959Z");
+        assert!(rule.next_after(date).is_none());
+        assert_eq!(serde_json::to_string(&rule).unwrap(), r#"{"Rule":"FREQ=DAILY;UNTIL=20250131T235959Z"}"#);
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        let task = Task::new(1, "普通任务".to_string(), Priority::Low, None);
+        assert!(task.next_occurrence().is_none());
+
+        let due = Utc::now();
+        let mut task = Task::new(2, "每周例会".to_string(), Priority::High, Some(due));
+        task.recurrence = Some(Recurrence::Weekly);
+        task.pomodoros.push(Pomodoro { started_at: due, minutes: 25 });
+        task.complete();
+
+        let next = task.next_occurrence().unwrap();
+        assert!(!next.completed);
+        assert!(next.pomodoros.is_empty());
+        assert!(next.completed_at.is_none());
+        assert_eq!(next.due_date, Some(due + Duration::weeks(1)));
+        assert_eq!(next.recurrence, Some(Recurrence::Weekly));
+    }
+
+    #[test]
+    fn test_monthly_keeps_anchor_day() {
+
+        let due = local_time(2025, 1, 31, 23, 59, 59);
+        let mut task = Task::new(1, "交房租".to_string(), Priority::High, Some(due));
+        task.recurrence = Some(Recurrence::Monthly);
+
+        let dates: Vec<String> = std::iter::successors(task.next_occurrence(), |task| task.next_occurrence())
+            .take(3)
+            .map(|task| local_date(task.due_date.unwrap()).to_string())
+            .collect();
+        assert_eq!(dates, ["2025-02-28", "2025-03-31", "2025-04-30"]);
+    }
+
     #[test]
     fn test_is_overdue() {
         use chrono::Duration;
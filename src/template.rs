@@ -0,0 +1,190 @@
+use crate::config::{data_dir_override, project_dirs};
+use crate::dates::{end_of_day, local_date};
+use crate::error::{TodoError, TodoResult};
+use crate::storage::write_atomic;
+use crate::task::{Priority, Recurrence, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 模板文件名
+pub const TEMPLATES_FILE_NAME: &str = "templates.json";
+
+/// 任务模板：常用任务的优先级、标签、相对截止日期和清单，用于快速创建同样形状的任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// 预估耗时（分钟）
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// 截止日期距离创建当天的天数
+    #[serde(default)]
+    pub due_in_days: Option<i64>,
+    /// 清单，创建任务时逐项添加为子任务
+    #[serde(default)]
+    pub checklist: Vec<String>,
+}
+
+impl Template {
+    /// 从已有任务生成模板：截止日期换算为相对创建当天的天数，直接子任务的标题作为清单
+    pub fn from_task(todo_list: &TodoList, task: &Task) -> Self {
+        Self {
+            priority: task.priority,
+            tags: task.tags.clone(),
+            context: task.context.clone(),
+            estimate_minutes: task.estimate_minutes,
+            recurrence: task.recurrence,
+            due_in_days: task
+                .due_date
+                .map(|due| (local_date(due) - local_date(task.created_at)).num_days()),
+            checklist: todo_list
+                .children(task.id)
+                .into_iter()
+                .map(|child| child.title.clone())
+                .collect(),
+        }
+    }
+
+    /// 相对 `now` 的截止日期（当天结束，与命令行中 YYYY-MM-DD 格式的截止日期一致）
+    pub fn due_date(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let days = self.due_in_days?;
+        Some(end_of_day(local_date(now) + Duration::days(days)))
+    }
+}
+
+/// 按名称保存的所有模板
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Templates {
+    templates: BTreeMap<String, Template>,
+}
+
+impl Templates {
+    /// 默认模板文件路径（与数据文件在同一目录，Linux 下为 `~/.local/share/todo/templates.json`）
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(dir) = data_dir_override() {
+            return Some(dir.join(TEMPLATES_FILE_NAME));
+        }
+        project_dirs().map(|dirs| dirs.data_dir().join(TEMPLATES_FILE_NAME))
+    }
+
+    /// 从默认位置加载模板，文件不存在时返回空集合
+    pub fn load() -> TodoResult<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 从指定文件加载模板，文件不存在时返回空集合
+    pub fn load_from<P: AsRef<Path>>(path: P) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !path_ref.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path_ref)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存到默认位置
+    pub fn save(&self) -> TodoResult<()> {
+        match Self::default_path() {
+            Some(path) => self.save_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// 保存到指定文件
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> TodoResult<()> {
+        let path_ref = path.as_ref();
+        if let Some(parent) = path_ref.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path_ref, content.as_bytes())
+    }
+
+    /// 按名称查找模板
+    pub fn get(&self, name: &str) -> TodoResult<&Template> {
+        self.templates
+            .get(name)
+            .ok_or_else(|| TodoError::TemplateNotFound(name.to_string()))
+    }
+
+    /// 添加或覆盖模板，返回被覆盖的旧模板
+    pub fn insert(&mut self, name: String, template: Template) -> Option<Template> {
+        self.templates.insert(name, template)
+    }
+
+    /// 删除模板
+    pub fn remove(&mut self, name: &str) -> TodoResult<Template> {
+        self.templates
+            .remove(name)
+            .ok_or_else(|| TodoError::TemplateNotFound(name.to_string()))
+    }
+
+    /// 按名称排序的所有模板
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Template)> {
+        self.templates.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_from_task() {
+        let mut list = TodoList::new();
+        let date = |month, day| chrono::NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let created = crate::dates::from_local(date(1, 6).and_hms_opt(9, 0, 0).unwrap());
+        let due = end_of_day(date(1, 9));
+        let id = list.add_task("发布版本".to_string(), Priority::High, Some(due));
+        list.add_subtask(id, "更新日志".to_string(), Priority::Medium, None).unwrap();
+        list.add_subtask(id, "打标签".to_string(), Priority::Medium, None).unwrap();
+        list.set_tags(id, vec!["release".to_string()]).unwrap();
+        list.find_task_mut(id).unwrap().created_at = created;
+
+        let template = Template::from_task(&list, list.find_task(id).unwrap());
+        assert_eq!(template.priority, Priority::High);
+        assert_eq!(template.tags, ["release"]);
+        assert_eq!(template.due_in_days, Some(3));
+        assert_eq!(template.checklist, ["更新日志", "打标签"]);
+
+        let now = crate::dates::from_local(date(3, 1).and_hms_opt(15, 0, 0).unwrap());
+        assert_eq!(template.due_date(now), Some(end_of_day(date(3, 4))));
+    }
+
+    #[test]
+    fn test_templates_round_trip() {
+        let path = "test_templates.json";
+        let mut templates = Templates::load_from("test_missing_templates.json").unwrap();
+        let template = Template {
+            priority: Priority::Low,
+            tags: vec!["home".to_string()],
+            context: None,
+            estimate_minutes: Some(30),
+            recurrence: None,
+            due_in_days: None,
+            checklist: Vec::new(),
+        };
+
+        assert!(templates.insert("chores".to_string(), template.clone()).is_none());
+        templates.save_to(path).unwrap();
+        let loaded = Templates::load_from(path).unwrap();
+        assert_eq!(loaded.get("chores").unwrap(), &template);
+        assert!(matches!(loaded.get("missing"), Err(TodoError::TemplateNotFound(_))));
+        let _ = fs::remove_file(path);
+    }
+}
@@ -1,32 +1,436 @@
-use crate::task::{Priority, Task};
+use crate::task::{Pomodoro, Priority, Recurrence, Task};
+use crate::dates::local_date;
 use crate::error::{TodoError, TodoResult};
-use chrono::{DateTime, Utc};
+use crate::filter::ListFilter;
+use crate::goal::{Goal, Progress};
+use crate::history::{Event, EventKind};
+use crate::id::{IdStrategy, Sequential};
+use crate::trash::TrashedTask;
+use crate::undo::UndoStack;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+/// 删除带有子任务的任务时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// 存在子任务时拒绝删除
+    Abort,
+    /// 连同所有子孙任务一起删除
+    Cascade,
+    /// 只删除该任务，其直接子任务提升为顶层任务
+    Orphan,
+}
+
+/// 当前的数据格式版本，修改序列化结构时递增，并在 storage.rs 中添加对应的迁移
+pub const SCHEMA_VERSION: u32 = 8;
 
 /// 待办事项列表管理器
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TodoList {
-    tasks: Vec<Task>,
+    /// 数据格式版本
+    version: u32,
+    tasks: Tasks,
     next_id: u32,
+    /// 新建、完成、改期和删除任务的历史记录（按时间顺序）
+    ///
+    /// 整个列表共用一份记录，按任务 UUID 查询（见 [`TodoList::task_history`]）：
+    /// 任务删除后记录仍然保留，变更日志和监听器也只需要读取这一处。
+    history: Vec<Event>,
+    goals: Vec<Goal>,
+    next_goal_id: u32,
+    /// 已删除的任务（按删除顺序），可以恢复
+    trash: Vec<TrashedTask>,
+    /// 新任务的 ID 策略（不保存到文件，加载后默认为顺序编号）
+    #[serde(skip, default = "default_id_strategy")]
+    id_strategy: Arc<dyn IdStrategy>,
+    /// 通过 `subscribe` 注册的监听器（不保存到文件）
+    #[serde(skip)]
+    listeners: Listeners,
+    /// 撤销和重做栈（不保存在数据文件中）
+    #[serde(skip)]
+    undo_stack: UndoStack,
+    /// 正在进行的事务层数，事务中产生的事件在提交后才通知监听器
+    #[serde(skip)]
+    transaction_depth: usize,
+}
+
+/// 历史事件的监听器
+type Listener = Arc<dyn Fn(&Event) + Send + Sync>;
+
+/// 监听器列表，单独包装以便 `TodoList` 继续派生 `Debug`
+#[derive(Clone, Default)]
+struct Listeners(Vec<Listener>);
+
+impl std::fmt::Debug for Listeners {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Listeners({})", self.0.len())
+    }
+}
+
+/// 按添加顺序保存的任务，附带 ID 到位置的索引，按 ID 查找不需要遍历
+///
+/// 序列化为普通的任务数组，加载时重建索引。任务加入后不应再修改 ID。
+#[derive(Debug, Clone, Default)]
+struct Tasks {
+    items: Vec<Task>,
+    index: HashMap<u32, usize>,
+}
+
+impl Tasks {
+    fn get(&self, id: u32) -> Option<&Task> {
+        self.index.get(&id).map(|&position| &self.items[position])
+    }
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Task> {
+        self.index.get(&id).map(|&position| &mut self.items[position])
+    }
+
+    fn push(&mut self, task: Task) {
+        self.index.entry(task.id).or_insert(self.items.len());
+        self.items.push(task);
+    }
+
+    fn iter_mut(&mut self) -> std::slice::IterMut<'_, Task> {
+        self.items.iter_mut()
+    }
+
+    fn retain<F: FnMut(&Task) -> bool>(&mut self, keep: F) {
+        self.items.retain(keep);
+        self.rebuild_index();
+    }
+
+    /// 重建索引，ID 重复时（损坏的数据文件）与线性查找一样指向第一个
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for (position, task) in self.items.iter().enumerate() {
+            self.index.entry(task.id).or_insert(position);
+        }
+    }
+}
+
+impl std::ops::Deref for Tasks {
+    type Target = [Task];
+
+    fn deref(&self) -> &[Task] {
+        &self.items
+    }
+}
+
+impl From<Vec<Task>> for Tasks {
+    fn from(items: Vec<Task>) -> Self {
+        let mut tasks = Tasks { items, index: HashMap::new() };
+        tasks.rebuild_index();
+        tasks
+    }
+}
+
+impl serde::Serialize for Tasks {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.items.serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Tasks {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Task>::deserialize(deserializer).map(Tasks::from)
+    }
+}
+
+/// 修改记录中的标签列表，没有标签时为 `None`
+fn join_tags(tags: &[String]) -> Option<String> {
+    (!tags.is_empty()).then(|| tags.join(", "))
+}
+
+/// 修改记录中的任务 ID 列表，没有时为 `None`
+fn join_ids(ids: &[u32]) -> Option<String> {
+    (!ids.is_empty()).then(|| ids.iter().map(u32::to_string).collect::<Vec<_>>().join(", "))
+}
+
+/// 正则表达式错误的原因，例如 `unclosed group`（regex 的错误信息包含多行的位置提示，只取最后一行）
+fn regex_error_reason(err: &regex::Error) -> String {
+    let message = err.to_string();
+    let last = message.lines().last().unwrap_or_default().trim();
+    last.strip_prefix("error: ").unwrap_or(last).to_string()
+}
+
+/// 默认的 ID 策略：只使用顺序编号
+fn default_id_strategy() -> Arc<dyn IdStrategy> {
+    Arc::new(Sequential)
 }
 
 impl TodoList {
     /// 创建一个新的空待办列表
     pub fn new() -> Self {
         Self {
-            tasks: Vec::new(),
+            version: SCHEMA_VERSION,
+            tasks: Tasks::default(),
             next_id: 1,
+            history: Vec::new(),
+            goals: Vec::new(),
+            next_goal_id: 1,
+            trash: Vec::new(),
+            id_strategy: default_id_strategy(),
+            listeners: Listeners::default(),
+            undo_stack: UndoStack::default(),
+            transaction_depth: 0,
         }
     }
 
+    /// 设置新任务的 ID 策略
+    ///
+    /// 任务始终有一个文件内递增的数字 ID；策略额外生成的全局唯一 ID 保存在 `Task::uid`。
+    /// 已有任务不受影响。
+    ///
+    /// # 示例
+    /// ```
+    /// use rust_todo_cli::id::IdStrategy;
+    /// use rust_todo_cli::task::Priority;
+    /// use rust_todo_cli::todo_list::TodoList;
+    ///
+    /// #[derive(Debug)]
+    /// struct Prefixed;
+    ///
+    /// impl IdStrategy for Prefixed {
+    ///     fn global_id(&self) -> Option<String> {
+    ///         Some("app-42".to_string())
+    ///     }
+    /// }
+    ///
+    /// let mut list = TodoList::new();
+    /// list.set_id_strategy(Prefixed);
+    /// let id = list.add_task("写周报".to_string(), Priority::High, None);
+    /// assert_eq!(list.find_task(id).unwrap().uid.as_deref(), Some("app-42"));
+    /// ```
+    pub fn set_id_strategy<S: IdStrategy + 'static>(&mut self, strategy: S) {
+        self.id_strategy = Arc::new(strategy);
+    }
+
+    /// 注册监听器，每当列表记录一条历史事件（新建、完成、改期、删除任务）时调用
+    ///
+    /// 监听器在修改完成后同步调用，不保存到文件；克隆的列表共享同样的监听器。
+    ///
+    /// # 示例
+    /// ```
+    /// use rust_todo_cli::prelude::*;
+    /// use std::sync::mpsc;
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// let mut list = TodoList::new();
+    /// list.subscribe(move |event: &Event| sender.send(event.kind.clone()).unwrap());
+    ///
+    /// let id = list.add_task("写周报".to_string(), Priority::High, None);
+    /// list.complete_task(id).unwrap();
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [EventKind::Added, EventKind::Completed]);
+    /// ```
+    pub fn subscribe<F>(&mut self, listener: F)
+    where
+        F: Fn(&Event) + Send + Sync + 'static,
+    {
+        self.listeners.0.push(Arc::new(listener));
+    }
+
+    /// 在事务中执行一组修改：`apply` 返回错误时撤销其中的所有修改，列表恢复原样
+    ///
+    /// 事务中记录的历史事件在成功后才通知监听器，失败时不通知。事务可以嵌套，
+    /// 内层事务失败只撤销内层的修改。
+    ///
+    /// # 示例
+    /// ```
+    /// use rust_todo_cli::prelude::*;
+    ///
+    /// let mut list = TodoList::new();
+    /// let id = list.add_task("写周报".to_string(), Priority::High, None);
+    ///
+    /// // 第二个任务不存在，第一个任务的完成也被撤销
+    /// let result = list.transaction(|txn| {
+    ///     txn.complete_task(id)?;
+    ///     txn.complete_task(42)
+    /// });
+    /// assert!(result.is_err());
+    /// assert!(!list.find_task(id).unwrap().completed);
+    /// ```
+    pub fn transaction<T, F>(&mut self, apply: F) -> TodoResult<T>
+    where
+        F: FnOnce(&mut TodoList) -> TodoResult<T>,
+    {
+        let before = self.contents();
+        let history_len = self.history.len();
+
+        self.transaction_depth += 1;
+        let result = apply(self);
+        self.transaction_depth -= 1;
+
+        if result.is_err() {
+            self.restore_contents(before);
+        } else if self.transaction_depth == 0 {
+            for event in &self.history[history_len..] {
+                for listener in &self.listeners.0 {
+                    listener(event);
+                }
+            }
+        }
+        result
+    }
+
     /// 添加新任务
     pub fn add_task(&mut self, title: String, priority:  Priority, due_date: Option<DateTime<Utc>>) -> u32 {
+        self.insert_task(Task::new(0, title, priority, due_date))
+    }
+
+    /// 添加子任务，父任务不存在时返回 TaskNotFound
+    pub fn add_subtask(
+        &mut self,
+        parent_id: u32,
+        title: String,
+        priority: Priority,
+        due_date: Option<DateTime<Utc>>,
+    ) -> TodoResult<u32> {
+        if self.find_task(parent_id).is_none() {
+            return Err(TodoError::TaskNotFound(parent_id));
+        }
+
+        let mut task = Task::new(0, title, priority, due_date);
+        task.parent_id = Some(parent_id);
+        Ok(self.insert_task(task))
+    }
+
+    /// 插入一个已构建好的任务（例如导入的任务），并为其分配新的 ID
+    ///
+    /// 任务没有全局唯一 ID 时按当前的 ID 策略生成，并在历史中记录新建。
+    pub fn insert_task(&mut self, task: Task) -> u32 {
+        let id = self.restore_task(task);
+        self.record(id, EventKind::Added);
+        id
+    }
+
+    /// 插入任务但不记录历史，用于从不保存历史的格式中重建列表
+    pub(crate) fn restore_task(&mut self, mut task: Task) -> u32 {
         let id = self.next_id;
-        let task = Task:: new(id, title, priority, due_date);
+        task.id = id;
+        if task.uid.is_none() {
+            task.uid = self.id_strategy.global_id();
+        }
         self.tasks.push(task);
         self.next_id += 1;
         id
     }
 
+    /// 获取历史记录（按时间顺序）
+    pub fn history(&self) -> &[Event] {
+        &self.history
+    }
+
+    /// 获取历史记录的可变引用
+    pub(crate) fn history_mut(&mut self) -> &mut [Event] {
+        &mut self.history
+    }
+
+    /// 为任务追加一条历史记录，任务不存在时忽略
+    fn record(&mut self, id: u32, kind: EventKind) {
+        if let Some(task) = self.find_task(id) {
+            let event = Event::new(task, kind);
+            if self.transaction_depth == 0 {
+                for listener in &self.listeners.0 {
+                    listener(&event);
+                }
+            }
+            self.history.push(event);
+        }
+    }
+
+    /// 值确实改变时为任务记录一条修改
+    fn record_edit(&mut self, id: u32, field: &str, from: Option<String>, to: Option<String>) {
+        if from != to {
+            self.record(id, EventKind::Edited { field: field.to_string(), from, to });
+        }
+    }
+
+    /// 记录新任务使用了标签 `tag` 配置的默认值，`fields` 为使用默认值的字段
+    pub fn record_defaults_applied(&mut self, id: u32, tag: &str, fields: &[&str]) {
+        let fields = fields.iter().map(|field| field.to_string()).collect();
+        self.record(id, EventKind::DefaultsApplied { tag: tag.to_string(), fields });
+    }
+
+    /// 任务的历史记录（按时间顺序），按 UUID 匹配，与任务 ID 无关
+    pub fn task_history(&self, id: u32) -> Vec<&Event> {
+        let Some(task) = self.find_task(id) else {
+            return Vec::new();
+        };
+        self.history.iter().filter(|event| event.task_uuid == task.uuid).collect()
+    }
+
+    /// 不含撤销记录、监听器和 ID 策略的副本，用作撤销快照
+    pub(crate) fn contents(&self) -> TodoList {
+        TodoList {
+            version: self.version,
+            tasks: self.tasks.clone(),
+            next_id: self.next_id,
+            history: self.history.clone(),
+            goals: self.goals.clone(),
+            next_goal_id: self.next_goal_id,
+            trash: self.trash.clone(),
+            ..TodoList::new()
+        }
+    }
+
+    /// 用快照替换任务、历史、目标和回收站，保留撤销记录、监听器和 ID 策略
+    pub(crate) fn restore_contents(&mut self, snapshot: TodoList) {
+        self.tasks = snapshot.tasks;
+        self.next_id = snapshot.next_id;
+        self.history = snapshot.history;
+        self.goals = snapshot.goals;
+        self.next_goal_id = snapshot.next_goal_id;
+        self.trash = snapshot.trash;
+    }
+
+    /// 按 ID 替换任务，没有该 ID 时追加到末尾；不记录历史，用于重放变更日志
+    pub(crate) fn put_task(&mut self, task: Task) {
+        match self.tasks.get_mut(task.id) {
+            Some(existing) => *existing = task,
+            None => self.tasks.push(task),
+        }
+    }
+
+    /// 移除任务但不记录历史，也不处理子任务和依赖，用于重放变更日志
+    pub(crate) fn remove_task_entry(&mut self, id: u32) {
+        self.tasks.retain(|task| task.id != id);
+    }
+
+    /// 追加一条历史记录，用于重放变更日志
+    pub(crate) fn push_event(&mut self, event: Event) {
+        self.history.push(event);
+    }
+
+    /// 下一个任务 ID 和下一个目标 ID
+    pub(crate) fn next_ids(&self) -> (u32, u32) {
+        (self.next_id, self.next_goal_id)
+    }
+
+    /// 设置下一个任务 ID 和下一个目标 ID，用于重放变更日志
+    pub(crate) fn set_next_ids(&mut self, next_id: u32, next_goal_id: u32) {
+        self.next_id = next_id;
+        self.next_goal_id = next_goal_id;
+    }
+
+    /// 替换全部目标，用于重放变更日志
+    pub(crate) fn set_goals(&mut self, goals: Vec<Goal>) {
+        self.goals = goals;
+    }
+
+    /// 替换整个回收站，用于重放变更日志
+    pub(crate) fn set_trash(&mut self, trash: Vec<TrashedTask>) {
+        self.trash = trash;
+    }
+
+    pub(crate) fn undo_stack_ref(&self) -> &UndoStack {
+        &self.undo_stack
+    }
+
+    pub(crate) fn undo_stack_mut(&mut self) -> &mut UndoStack {
+        &mut self.undo_stack
+    }
+
     /// 获取所有任务的不可变引用
     pub fn list_tasks(&self) -> &[Task] {
         &self.tasks
@@ -42,36 +446,600 @@ impl TodoList {
         self.tasks.iter().filter(|task| task.completed).collect()
     }
 
+    /// 按标题片段（不区分大小写）查找唯一的任务，`include_completed` 为 false 时只查找未完成的任务
+    ///
+    /// 有多个任务包含该片段时，标题与之完全相同（不区分大小写）的任务优先。
+    pub fn match_task(&self, pattern: &str, include_completed: bool) -> TodoResult<&Task> {
+        let needle = pattern.trim().to_lowercase();
+        let matches: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|task| include_completed || !task.completed)
+            .filter(|task| task.title.to_lowercase().contains(&needle))
+            .collect();
+
+        let exact: Vec<&Task> = matches
+            .iter()
+            .copied()
+            .filter(|task| task.title.to_lowercase() == needle)
+            .collect();
+
+        match (matches.as_slice(), exact.as_slice()) {
+            ([], _) => Err(TodoError::NoMatchingTask(pattern.to_string())),
+            ([task], _) | (_, [task]) => Ok(task),
+            _ => Err(TodoError::AmbiguousMatch(
+                pattern.to_string(),
+                matches.iter().map(|task| (task.id, task.title.clone())).collect(),
+            )),
+        }
+    }
+
+    /// 按标题搜索任务（包括已完成的任务），按添加顺序返回
+    ///
+    /// `regex` 为 false 时按片段匹配（不区分大小写），否则把 `pattern` 作为正则表达式。
+    ///
+    /// # 示例
+    /// ```
+    /// use rust_todo_cli::prelude::*;
+    ///
+    /// let mut list = TodoList::new();
+    /// list.add_task("Fix login on prod".to_string(), Priority::High, None);
+    /// list.add_task("Write prod runbook".to_string(), Priority::Low, None);
+    ///
+    /// assert_eq!(list.search("PROD", false).unwrap().len(), 2);
+    /// assert_eq!(list.search("^Fix .*prod", true).unwrap()[0].id, 1);
+    /// assert!(list.search("(", true).is_err());
+    /// ```
+    pub fn search(&self, pattern: &str, regex: bool) -> TodoResult<Vec<&Task>> {
+        let matches: Box<dyn Fn(&str) -> bool> = if regex {
+            let regex = regex::Regex::new(pattern)
+                .map_err(|err| TodoError::InvalidRegex(pattern.to_string(), regex_error_reason(&err)))?;
+            Box::new(move |title| regex.is_match(title))
+        } else {
+            let needle = pattern.to_lowercase();
+            Box::new(move |title| title.to_lowercase().contains(&needle))
+        };
+
+        Ok(self.tasks.iter().filter(|task| matches(&task.title)).collect())
+    }
+
     /// 根据 ID 查找任务的可变引用
-    fn find_task_mut(&mut self, id: u32) -> Option<&mut Task> {
-        self.tasks.iter_mut().find(|task| task.id == id)
+    pub(crate) fn find_task_mut(&mut self, id: u32) -> Option<&mut Task> {
+        self.tasks.get_mut(id)
+    }
+
+    /// 根据 UUID 查找任务
+    pub fn find_task_by_uuid(&self, uuid: &str) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.uuid == uuid)
+    }
+
+    /// 根据 ID 策略生成的全局 ID 查找任务
+    pub fn find_task_by_uid(&self, uid: &str) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.uid.as_deref() == Some(uid))
     }
 
     /// 根据 ID 查找任务的不可变引用
     pub fn find_task(&self, id: u32) -> Option<&Task> {
-        self.tasks.iter().find(|task| task.id == id)
+        self.tasks.get(id)
     }
 
     /// 标记任务为已完成 - 使用新的错误类型
-    pub fn complete_task(&mut self, id: u32) -> TodoResult<()> {
-        match self.find_task_mut(id) {
-            Some(task) => {
-                task.complete();
-                Ok(())
+    ///
+    /// 完成循环任务时会自动创建下一次实例，并返回新实例的 ID
+    pub fn complete_task(&mut self, id: u32) -> TodoResult<Option<u32>> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let was_completed = task.completed;
+        task.complete();
+
+        // 循环规则转移到下一次实例上，已完成的实例不再保留
+        let next = task.next_occurrence();
+        task.recurrence = None;
+
+        if !was_completed {
+            self.record(id, EventKind::Completed);
+        }
+        Ok(next.map(|next| self.insert_task(next)))
+    }
+
+    /// 重新打开已完成的任务
+    pub fn reopen_task(&mut self, id: u32) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let was_completed = task.completed;
+        task.reopen();
+
+        if was_completed {
+            self.record(id, EventKind::Reopened);
+        }
+        Ok(())
+    }
+
+    /// 在任务上记录一个完成的番茄钟
+    pub fn log_pomodoro(&mut self, id: u32, pomodoro: Pomodoro) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        task.pomodoros.push(pomodoro);
+        task.touch();
+        Ok(())
+    }
+
+    /// 让任务 `id` 依赖任务 `on`，`on` 完成前 `id` 处于阻塞状态
+    ///
+    /// 依赖自身或形成环（`on` 直接或间接依赖 `id`）时返回错误，列表保持不变。
+    pub fn add_dependency(&mut self, id: u32, on: u32) -> TodoResult<()> {
+        if self.find_task(on).is_none() {
+            return Err(TodoError::TaskNotFound(on));
+        }
+        if self.find_task(id).is_none() {
+            return Err(TodoError::TaskNotFound(id));
+        }
+        if id == on || self.dependency_ids(on).contains(&id) {
+            return Err(TodoError::DependencyCycle(id, on));
+        }
+
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        if !task.depends_on.contains(&on) {
+            let from = join_ids(&task.depends_on);
+            task.depends_on.push(on);
+            task.touch();
+            let to = join_ids(&task.depends_on);
+            self.record_edit(id, "depends on", from, to);
+        }
+        Ok(())
+    }
+
+    /// 移除任务 `id` 对任务 `on` 的依赖，返回依赖是否存在
+    pub fn remove_dependency(&mut self, id: u32, on: u32) -> TodoResult<bool> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = join_ids(&task.depends_on);
+        let before = task.depends_on.len();
+        task.depends_on.retain(|&dependency| dependency != on);
+
+        let removed = task.depends_on.len() != before;
+        if removed {
+            task.touch();
+            let to = join_ids(&task.depends_on);
+            self.record_edit(id, "depends on", from, to);
+        }
+        Ok(removed)
+    }
+
+    /// 获取任务所有直接和间接依赖的任务 ID（广度优先）
+    pub fn dependency_ids(&self, id: u32) -> Vec<u32> {
+        let mut result = Vec::new();
+        let mut queue = VecDeque::from([id]);
+
+        while let Some(current) = queue.pop_front() {
+            let Some(task) = self.find_task(current) else {
+                continue;
+            };
+            for &dependency in &task.depends_on {
+                // 防御损坏数据中的环
+                if dependency != id && !result.contains(&dependency) {
+                    result.push(dependency);
+                    queue.push_back(dependency);
+                }
             }
-            None => Err(TodoError::TaskNotFound(id)),
         }
+
+        result
+    }
+
+    /// 获取阻塞任务的未完成依赖
+    pub fn blockers(&self, id: u32) -> Vec<&Task> {
+        let Some(task) = self.find_task(id) else {
+            return Vec::new();
+        };
+        task.depends_on
+            .iter()
+            .filter_map(|&dependency| self.find_task(dependency))
+            .filter(|dependency| !dependency.completed)
+            .collect()
+    }
+
+    /// 任务是否有未完成的依赖
+    pub fn is_blocked(&self, id: u32) -> bool {
+        !self.blockers(id).is_empty()
+    }
+
+    /// 设置任务的重复周期，传入 `None` 取消循环
+    pub fn set_recurrence(&mut self, id: u32, recurrence: Option<Recurrence>) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.recurrence, recurrence);
+        if from != recurrence {
+            task.touch();
+        }
+
+        self.record_edit(id, "repeats", from.map(|r| r.to_string()), recurrence.map(|r| r.to_string()));
+        Ok(())
+    }
+
+    /// 修改任务的标题
+    pub fn set_title(&mut self, id: u32, title: String) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.title, title.clone());
+        if from != title {
+            task.touch();
+        }
+
+        self.record_edit(id, "title", Some(from), Some(title));
+        Ok(())
+    }
+
+    /// 设置任务的优先级
+    pub fn set_priority(&mut self, id: u32, priority: Priority) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.priority, priority);
+        if from != priority {
+            task.touch();
+        }
+
+        let show = |priority: Priority| Some(priority.to_string().to_lowercase());
+        self.record_edit(id, "priority", show(from), show(priority));
+        Ok(())
+    }
+
+    /// 设置任务的截止日期，传入 `None` 清除
+    pub fn set_due_date(&mut self, id: u32, due_date: Option<DateTime<Utc>>) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.due_date, due_date);
+        if from != due_date {
+            task.touch();
+            // 手动改期后，按月重复从新的日子重新计算
+            task.anchor_day = None;
+            self.record(id, EventKind::Rescheduled { from, to: due_date });
+        }
+        Ok(())
+    }
+
+    /// 设置任务的标签
+    pub fn set_tags(&mut self, id: u32, tags: Vec<String>) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let to = join_tags(&tags);
+        if task.tags != tags {
+            task.touch();
+        }
+        let from = join_tags(&std::mem::replace(&mut task.tags, tags));
+
+        self.record_edit(id, "tags", from, to);
+        Ok(())
+    }
+
+    /// 设置任务的情境，传入 `None` 清除
+    pub fn set_context(&mut self, id: u32, context: Option<String>) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.context, context.clone());
+        if from != context {
+            task.touch();
+        }
+
+        self.record_edit(id, "context", from, context);
+        Ok(())
+    }
+
+    /// 设置任务的预估耗时（分钟），传入 `None` 清除
+    pub fn set_estimate(&mut self, id: u32, minutes: Option<u32>) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.estimate_minutes, minutes);
+        if from != minutes {
+            task.touch();
+        }
+
+        let show = |minutes: Option<u32>| minutes.map(|minutes| format!("{}m", minutes));
+        self.record_edit(id, "estimate", show(from), show(minutes));
+        Ok(())
+    }
+
+    /// 设置任务的父任务，传入 `None` 变为顶层任务
+    pub fn set_parent(&mut self, id: u32, parent_id: Option<u32>) -> TodoResult<()> {
+        if let Some(parent_id) = parent_id {
+            self.find_task(parent_id).ok_or(TodoError::TaskNotFound(parent_id))?;
+        }
+
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.parent_id, parent_id);
+        if from != parent_id {
+            task.touch();
+        }
+
+        self.record_edit(id, "parent", from.map(|parent| parent.to_string()), parent_id.map(|parent| parent.to_string()));
+        Ok(())
+    }
+
+    /// 用导入或同步得到的内容整体覆盖任务，每个改变的字段都记录到历史
+    ///
+    /// 任务保留原来的 ID；修改时间使用 `incoming` 中的值，由调用方决定是否更新。
+    pub fn replace_task(&mut self, id: u32, mut incoming: Task) -> TodoResult<()> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        incoming.id = id;
+        let old = std::mem::replace(task, incoming.clone());
+        let new = incoming;
+
+        let show_priority = |priority: Priority| Some(priority.to_string().to_lowercase());
+        let show_estimate = |minutes: Option<u32>| minutes.map(|minutes| format!("{}m", minutes));
+        let show_recurrence = |recurrence: Option<Recurrence>| recurrence.map(|r| r.to_string());
+        self.record_edit(id, "title", Some(old.title), Some(new.title));
+        self.record_edit(id, "priority", show_priority(old.priority), show_priority(new.priority));
+        self.record_edit(id, "tags", join_tags(&old.tags), join_tags(&new.tags));
+        self.record_edit(id, "context", old.context, new.context);
+        self.record_edit(id, "estimate", show_estimate(old.estimate_minutes), show_estimate(new.estimate_minutes));
+        self.record_edit(id, "repeats", show_recurrence(old.recurrence), show_recurrence(new.recurrence));
+        if old.due_date != new.due_date {
+            self.record(id, EventKind::Rescheduled { from: old.due_date, to: new.due_date });
+        }
+        match (old.completed, new.completed) {
+            (false, true) => self.record(id, EventKind::Completed),
+            (true, false) => self.record(id, EventKind::Reopened),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 跳过循环任务的当前周期：截止日期推进到下一次，但不标记完成
+    ///
+    /// # 返回
+    /// 成功返回新的截止日期
+    pub fn skip_occurrence(&mut self, id: u32) -> TodoResult<DateTime<Utc>> {
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        if task.recurrence.is_none() {
+            return Err(TodoError::NotRecurring(id));
+        }
+
+        let from = task.due_date;
+        let next_due = task.advance_due_date().ok_or(TodoError::RecurrenceEnded(id))?;
+        task.skipped.push(from.unwrap_or_else(Utc::now));
+        task.touch();
+
+        self.record(id, EventKind::Skipped { from, to: next_due });
+        Ok(next_due)
+    }
+
+    /// 复制任务（见 [`Task::duplicate`]），新任务使用 `due_date` 作为截止日期，返回新任务的 ID
+    pub fn duplicate_task(&mut self, id: u32, due_date: Option<DateTime<Utc>>) -> TodoResult<u32> {
+        let mut copy = self.find_task(id).ok_or(TodoError::TaskNotFound(id))?.duplicate();
+        copy.due_date = due_date;
+        Ok(self.insert_task(copy))
     }
 
     /// 删除任务 - 使用新的错误类型
+    ///
+    /// 如果任务有子任务，子任务会被提升为顶层任务（等同于 `DeletePolicy::Orphan`）
     pub fn delete_task(&mut self, id: u32) -> TodoResult<()> {
-        let original_len = self.tasks.len();
-        self.tasks.retain(|task| task.id != id);
-        
-        if self.tasks.len() < original_len {
-            Ok(())
+        self.delete_task_with_policy(id, DeletePolicy::Orphan)
+            .map(|_| ())
+    }
+
+    /// 按指定策略删除任务，被删除的任务移入回收站
+    ///
+    /// 所有检查都在修改列表之前完成，失败时列表保持不变。
+    ///
+    /// # 返回
+    /// 成功返回被删除的所有任务 ID（第一个是 `id` 本身）
+    pub fn delete_task_with_policy(&mut self, id: u32, policy: DeletePolicy) -> TodoResult<Vec<u32>> {
+        if self.find_task(id).is_none() {
+            return Err(TodoError::TaskNotFound(id));
+        }
+
+        let child_count = self.children(id).len();
+        let mut deleted = vec![id];
+
+        match policy {
+            DeletePolicy::Abort if child_count > 0 => {
+                return Err(TodoError::TaskHasChildren(id, child_count));
+            }
+            DeletePolicy::Abort => {}
+            DeletePolicy::Cascade => deleted.extend(self.descendant_ids(id)),
+            DeletePolicy::Orphan => {
+                for child in self.children(id).iter().map(|task| task.id).collect::<Vec<_>>() {
+                    self.set_parent(child, None)?;
+                }
+            }
+        }
+
+        for &deleted_id in &deleted {
+            self.record(deleted_id, EventKind::Deleted);
+            if let Some(task) = self.find_task(deleted_id) {
+                self.trash.push(TrashedTask::new(task.clone()));
+            }
+        }
+        self.tasks.retain(|task| !deleted.contains(&task.id));
+
+        // 被删除的任务不再阻塞其他任务
+        for task in self.tasks.iter_mut() {
+            if task.depends_on.iter().any(|dependency| deleted.contains(dependency)) {
+                task.depends_on.retain(|dependency| !deleted.contains(dependency));
+                task.touch();
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// 回收站中的任务（按删除顺序）
+    pub fn trash(&self) -> &[TrashedTask] {
+        &self.trash
+    }
+
+    /// 获取回收站的可变引用
+    pub(crate) fn trash_mut(&mut self) -> &mut [TrashedTask] {
+        &mut self.trash
+    }
+
+    /// 从回收站恢复任务，返回恢复后的 ID
+    ///
+    /// 任务保留原来的 ID；父任务或目标已不存在时取消关联，已不存在的依赖被移除。
+    /// 级联删除的子任务仍留在回收站中，需要分别恢复。
+    pub fn restore_from_trash(&mut self, id: u32) -> TodoResult<u32> {
+        let position = self
+            .trash
+            .iter()
+            .rposition(|trashed| trashed.task.id == id)
+            .ok_or(TodoError::NotInTrash(id))?;
+        let mut task = self.trash.remove(position).task;
+
+        if task.parent_id.is_some_and(|parent| self.find_task(parent).is_none()) {
+            task.parent_id = None;
+        }
+        if task.goal_id.is_some_and(|goal| self.find_goal(goal).is_none()) {
+            task.goal_id = None;
+        }
+        task.depends_on.retain(|dependency| self.tasks.get(*dependency).is_some());
+        task.touch();
+
+        // ID 不会被重新分配，只有损坏或手动编辑的数据才会冲突，此时分配新的 ID
+        let id = if self.tasks.get(task.id).is_some() {
+            self.restore_task(task)
         } else {
-            Err(TodoError::TaskNotFound(id))
+            self.next_id = self.next_id.max(task.id + 1);
+            let id = task.id;
+            self.tasks.push(task);
+            id
+        };
+        self.record(id, EventKind::Restored);
+        Ok(id)
+    }
+
+    /// 永久删除回收站中在 `before` 之前删除的任务，`None` 时清空回收站，返回删除的数量
+    pub fn empty_trash(&mut self, before: Option<DateTime<Utc>>) -> usize {
+        let count = self.trash.len();
+        self.trash
+            .retain(|trashed| before.is_some_and(|before| trashed.deleted_at >= before));
+        count - self.trash.len()
+    }
+
+    /// 从列表中移出在 `before` 之前完成的任务并返回，用于归档
+    ///
+    /// 还有子任务留在列表中的任务不会移出；没有完成时间的任务（旧版数据）也不会。
+    /// 其他任务对移出任务的依赖随之去掉（已完成的依赖本来就不再阻塞）。不记录历史。
+    pub fn archive_completed(&mut self, before: DateTime<Utc>) -> Vec<Task> {
+        let mut ids: HashSet<u32> = self
+            .tasks
+            .iter()
+            .filter(|task| task.completed && task.completed_at.is_some_and(|at| at < before))
+            .map(|task| task.id)
+            .collect();
+
+        // 父任务要等所有子任务都可以归档时才一起归档
+        loop {
+            let parents: Vec<u32> = self
+                .tasks
+                .iter()
+                .filter(|task| !ids.contains(&task.id))
+                .filter_map(|task| task.parent_id)
+                .filter(|parent| ids.contains(parent))
+                .collect();
+            if parents.is_empty() {
+                break;
+            }
+            for parent in parents {
+                ids.remove(&parent);
+            }
+        }
+
+        if ids.is_empty() {
+            return Vec::new();
+        }
+
+        let archived: Vec<Task> = self.tasks.iter().filter(|task| ids.contains(&task.id)).cloned().collect();
+        self.tasks.retain(|task| !ids.contains(&task.id));
+        for task in self.tasks.iter_mut() {
+            if task.depends_on.iter().any(|dependency| ids.contains(dependency)) {
+                task.depends_on.retain(|dependency| !ids.contains(dependency));
+                task.touch();
+            }
+        }
+        archived
+    }
+
+    /// 删除所有任务、目标、历史记录和回收站，ID 重新从 1 开始；撤销记录保留，可以撤销
+    pub fn reset(&mut self) {
+        self.restore_contents(TodoList::new());
+    }
+
+    /// 获取任务的直接子任务
+    pub fn children(&self, id: u32) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task.parent_id == Some(id))
+            .collect()
+    }
+
+    /// 获取任务所有子孙任务的 ID（广度优先）
+    pub fn descendant_ids(&self, id: u32) -> Vec<u32> {
+        let mut result = Vec::new();
+        let mut queue = VecDeque::from([id]);
+
+        while let Some(current) = queue.pop_front() {
+            for child in self.children(current) {
+                // 防御损坏数据中的环
+                if child.id != id && !result.contains(&child.id) {
+                    result.push(child.id);
+                    queue.push_back(child.id);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// 汇总任务所有子孙任务的预估耗时（分钟），没有任何子任务设置预估时返回 `None`
+    pub fn subtask_estimate_minutes(&self, id: u32) -> Option<u32> {
+        self.descendant_ids(id)
+            .into_iter()
+            .filter_map(|child_id| self.find_task(child_id)?.estimate_minutes)
+            .reduce(|a, b| a + b)
+    }
+
+    /// 添加新目标
+    pub fn add_goal(&mut self, title: String, due_date: Option<DateTime<Utc>>) -> u32 {
+        let id = self.next_goal_id;
+        self.goals.push(Goal::new(id, title, due_date));
+        self.next_goal_id += 1;
+        id
+    }
+
+    /// 获取所有目标
+    pub fn goals(&self) -> &[Goal] {
+        &self.goals
+    }
+
+    /// 获取所有目标的可变引用
+    pub(crate) fn goals_mut(&mut self) -> &mut [Goal] {
+        &mut self.goals
+    }
+
+    /// 根据 ID 查找目标
+    pub fn find_goal(&self, id: u32) -> Option<&Goal> {
+        self.goals.iter().find(|goal| goal.id == id)
+    }
+
+    /// 将任务关联到目标，传入 `None` 取消关联
+    pub fn set_goal(&mut self, id: u32, goal_id: Option<u32>) -> TodoResult<()> {
+        if let Some(goal_id) = goal_id {
+            self.find_goal(goal_id).ok_or(TodoError::GoalNotFound(goal_id))?;
+        }
+
+        let task = self.find_task_mut(id).ok_or(TodoError::TaskNotFound(id))?;
+        let from = std::mem::replace(&mut task.goal_id, goal_id);
+        if from != goal_id {
+            task.touch();
+        }
+
+        self.record_edit(id, "goal", from.map(|goal| goal.to_string()), goal_id.map(|goal| goal.to_string()));
+        Ok(())
+    }
+
+    /// 获取关联到目标的任务
+    pub fn goal_tasks(&self, goal_id: u32) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task.goal_id == Some(goal_id))
+            .collect()
+    }
+
+    /// 目标的完成进度（按关联任务计算）
+    pub fn goal_progress(&self, goal_id: u32) -> Progress {
+        let tasks = self.goal_tasks(goal_id);
+        Progress {
+            completed: tasks.iter().filter(|task| task.completed).count(),
+            total: tasks.len(),
         }
     }
 
@@ -85,6 +1053,31 @@ impl TodoList {
         self.tasks. is_empty()
     }
 
+    /// 按 ID 获取任务，与 `find_task` 相同
+    pub fn get(&self, id: u32) -> Option<&Task> {
+        self.find_task(id)
+    }
+
+    /// 按添加顺序遍历所有任务
+    pub fn iter(&self) -> std::slice::Iter<'_, Task> {
+        self.tasks.iter()
+    }
+
+    /// 只保留 `keep` 返回 `true` 的任务，返回被删除的任务 ID
+    ///
+    /// 与逐个调用 `delete_task` 相同：记录删除历史，子任务提升为顶层任务，依赖随之移除。
+    pub fn retain<F>(&mut self, mut keep: F) -> Vec<u32>
+    where
+        F: FnMut(&Task) -> bool,
+    {
+        let removed: Vec<u32> = self.tasks.iter().filter(|task| !keep(task)).map(|task| task.id).collect();
+        for &id in &removed {
+            // ID 来自列表本身，使用 Orphan 策略时不会失败
+            let _ = self.delete_task_with_policy(id, DeletePolicy::Orphan);
+        }
+        removed
+    }
+
     /// 按优先级排序任务
     pub fn tasks_by_priority(&self) -> Vec<&Task> {
         let mut tasks:  Vec<&Task> = self. tasks.iter().collect();
@@ -100,10 +1093,68 @@ impl TodoList {
         tasks
     }
 
+    /// 获取某一天（本地时区）到期的未完成任务
+    pub fn tasks_due_on(&self, date: NaiveDate) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .filter(|task| task.due_date.is_some_and(|due| local_date(due) == date))
+            .collect()
+    }
+
+    /// 某一天到期的未完成任务的预估耗时之和（分钟）
+    pub fn estimated_minutes_on(&self, date: NaiveDate) -> u32 {
+        self.tasks_due_on(date)
+            .into_iter()
+            .filter_map(|task| task.estimate_minutes)
+            .sum()
+    }
+
+    /// 获取截止时间在 `[from, until)` 内的未完成任务，按截止时间排序
+    pub fn tasks_due_between(&self, from: DateTime<Utc>, until: DateTime<Utc>) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .filter(|task| task.due_date.is_some_and(|due| from <= due && due < until))
+            .collect();
+        tasks.sort_by_key(|task| task.due_date);
+        tasks
+    }
+
+    /// 获取在 `since` 之后完成的任务，按完成时间排序
+    pub fn completed_since(&self, since: DateTime<Utc>) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|task| task.completed && task.completed_at.is_some_and(|at| at >= since))
+            .collect();
+        tasks.sort_by_key(|task| task.completed_at);
+        tasks
+    }
+
+    /// 获取满足筛选条件的任务
+    pub fn filter_tasks(&self, filter: &ListFilter) -> Vec<&Task> {
+        self.tasks.iter().filter(|task| filter.matches(task)).collect()
+    }
+
     /// 获取过期任务
     pub fn overdue_tasks(&self) -> Vec<&Task> {
         self.tasks.iter().filter(|task| task.is_overdue()).collect()
     }
+
+    /// 获取过期时间超过各自阈值的未完成任务，`threshold` 返回每个任务的阈值
+    pub fn critical_tasks<F>(&self, threshold: F) -> Vec<&Task>
+    where
+        F: Fn(&Task) -> chrono::Duration,
+    {
+        let now = Utc::now();
+        self.tasks
+            .iter()
+            .filter(|task| !task.completed)
+            .filter(|task| task.due_date.is_some_and(|due| now - due > threshold(task)))
+            .collect()
+    }
 }
 
 impl Default for TodoList {
@@ -112,10 +1163,264 @@ impl Default for TodoList {
     }
 }
 
+/// 按 ID 取任务，任务不存在时 panic；不确定时使用 [`TodoList::get`]
+impl std::ops::Index<u32> for TodoList {
+    type Output = Task;
+
+    fn index(&self, id: u32) -> &Task {
+        self.find_task(id)
+            .unwrap_or_else(|| panic!("no task with ID {}", id))
+    }
+}
+
+impl<'a> IntoIterator for &'a TodoList {
+    type Item = &'a Task;
+    type IntoIter = std::slice::Iter<'a, Task>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for TodoList {
+    type Item = Task;
+    type IntoIter = std::vec::IntoIter<Task>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.items.into_iter()
+    }
+}
+
+/// 逐个通过 `insert_task` 加入任务，任务获得新的 ID
+impl Extend<Task> for TodoList {
+    fn extend<I: IntoIterator<Item = Task>>(&mut self, tasks: I) {
+        for task in tasks {
+            self.insert_task(task);
+        }
+    }
+}
+
+impl FromIterator<Task> for TodoList {
+    fn from_iter<I: IntoIterator<Item = Task>>(tasks: I) -> Self {
+        let mut list = TodoList::new();
+        list.extend(tasks);
+        list
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_collection_traits() {
+        let mut list: TodoList = ["写周报", "买牛奶"].into_iter().map(|title| Task::builder(title).build()).collect();
+        list.extend([Task::builder("交房租").priority(Priority::High).build()]);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[3].title, "交房租");
+        assert!(list.get(4).is_none());
+
+        let titles: Vec<&str> = (&list).into_iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, ["写周报", "买牛奶", "交房租"]);
+
+        let parent = list.add_task("发布".to_string(), Priority::Low, None);
+        let child = list.add_subtask(parent, "写更新日志".to_string(), Priority::Low, None).unwrap();
+        list.add_dependency(1, parent).unwrap();
+        assert_eq!(list.retain(|task| task.id != parent && task.id != 2), vec![2, parent]);
+        assert_eq!(list[child].parent_id, None);
+        assert!(list[1].depends_on.is_empty());
+        assert_eq!(list.history().last().unwrap().kind, EventKind::Deleted);
+
+        let ids: Vec<u32> = list.into_iter().map(|task| task.id).collect();
+        assert_eq!(ids, [1, 3, child]);
+    }
+
+    #[test]
+    fn test_archive_completed() {
+        let now = Utc::now();
+        let mut list = TodoList::new();
+        let old = list.add_task("旧任务".to_string(), Priority::Low, None);
+        let recent = list.add_task("刚完成".to_string(), Priority::Low, None);
+        let parent = list.add_task("发布".to_string(), Priority::High, None);
+        let child = list.add_subtask(parent, "写更新日志".to_string(), Priority::Low, None).unwrap();
+        let pending = list.add_task("待办".to_string(), Priority::Low, None);
+        list.add_dependency(pending, old).unwrap();
+        for id in [old, recent, parent] {
+            list.complete_task(id).unwrap();
+        }
+        for id in [old, parent] {
+            list.find_task_mut(id).unwrap().completed_at = Some(now - chrono::Duration::days(40));
+        }
+        let history_len = list.history().len();
+
+        // 子任务还没完成，父任务留在列表中
+        let archived = list.archive_completed(now - chrono::Duration::days(30));
+        assert_eq!(archived.iter().map(|task| task.id).collect::<Vec<_>>(), [old]);
+        assert!(list.find_task(old).is_none());
+        assert!(list[pending].depends_on.is_empty());
+        assert_eq!(list.history().len(), history_len);
+
+        list.complete_task(child).unwrap();
+        list.find_task_mut(child).unwrap().completed_at = Some(now - chrono::Duration::days(35));
+        let archived = list.archive_completed(now - chrono::Duration::days(30));
+        assert_eq!(archived.len(), 2);
+        assert_eq!(list.list_tasks().iter().map(|task| task.id).collect::<Vec<_>>(), [recent, pending]);
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let mut list = TodoList::new();
+        let parent = list.add_task("发布".to_string(), Priority::High, None);
+        let child = list.add_subtask(parent, "写更新日志".to_string(), Priority::Low, None).unwrap();
+        let other = list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.add_dependency(other, parent).unwrap();
+
+        list.delete_task_with_policy(parent, DeletePolicy::Cascade).unwrap();
+        let trashed: Vec<u32> = list.trash().iter().map(|trashed| trashed.task.id).collect();
+        assert_eq!(trashed, [parent, child]);
+        assert!(list[other].depends_on.is_empty());
+
+        // 子任务先恢复时父任务不存在，成为顶层任务；之后恢复的父任务保留原 ID
+        assert_eq!(list.restore_from_trash(child).unwrap(), child);
+        assert_eq!(list[child].parent_id, None);
+        assert_eq!(list.restore_from_trash(parent).unwrap(), parent);
+        assert_eq!(list.history().last().unwrap().kind, EventKind::Restored);
+        assert!(list.trash().is_empty());
+        assert!(matches!(list.restore_from_trash(parent), Err(TodoError::NotInTrash(_))));
+        assert_eq!(list.add_task("新任务".to_string(), Priority::Low, None), 4);
+
+        list.delete_task(other).unwrap();
+        list.delete_task(child).unwrap();
+        list.trash[0].deleted_at -= chrono::Duration::days(40);
+        assert_eq!(list.empty_trash(Some(Utc::now() - chrono::Duration::days(30))), 1);
+        assert_eq!(list.trash()[0].task.id, child);
+        assert_eq!(list.empty_trash(None), 1);
+        assert!(list.trash().is_empty());
+    }
+
+    #[test]
+    fn test_task_history_records_edits() {
+        let mut list = TodoList::new();
+        let id = list.add_task("写周报".to_string(), Priority::Low, None);
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.set_priority(id, Priority::High).unwrap();
+        list.set_priority(id, Priority::High).unwrap();
+        list.set_tags(id, vec!["work".to_string(), "weekly".to_string()]).unwrap();
+        list.set_estimate(id, Some(30)).unwrap();
+        list.complete_task(id).unwrap();
+        list.reopen_task(id).unwrap();
+        list.reopen_task(id).unwrap();
+
+        let kinds: Vec<EventKind> = list.task_history(id).into_iter().map(|event| event.kind.clone()).collect();
+        let edited = |field: &str, from: Option<&str>, to: &str| EventKind::Edited {
+            field: field.to_string(),
+            from: from.map(str::to_string),
+            to: Some(to.to_string()),
+        };
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Added,
+                edited("priority", Some("low"), "high"),
+                edited("tags", None, "work, weekly"),
+                edited("estimate", None, "30m"),
+                EventKind::Completed,
+                EventKind::Reopened,
+            ]
+        );
+        assert!(list.task_history(9).is_empty());
+    }
+
+    #[test]
+    fn test_unchanged_edit_keeps_updated_at() {
+        let mut list = TodoList::new();
+        let id = list.add_task("写周报".to_string(), Priority::Low, None);
+        let stale = Utc::now() - chrono::Duration::days(1);
+        list.find_task_mut(id).unwrap().updated_at = stale;
+
+        list.set_title(id, "写周报".to_string()).unwrap();
+        list.set_priority(id, Priority::Low).unwrap();
+        list.set_due_date(id, None).unwrap();
+        list.set_tags(id, Vec::new()).unwrap();
+        list.set_context(id, None).unwrap();
+        list.set_estimate(id, None).unwrap();
+        list.set_recurrence(id, None).unwrap();
+        list.set_goal(id, None).unwrap();
+        assert_eq!(list.find_task(id).unwrap().updated_at, stale);
+
+        list.set_priority(id, Priority::High).unwrap();
+        assert!(list.find_task(id).unwrap().updated_at > stale);
+    }
+
+    #[test]
+    fn test_completed_since() {
+        let mut list = TodoList::new();
+        let old = list.add_task("上周".to_string(), Priority::Low, None);
+        let first = list.add_task("早上".to_string(), Priority::Low, None);
+        let second = list.add_task("下午".to_string(), Priority::Low, None);
+        list.add_task("还没做".to_string(), Priority::Low, None);
+        for id in [second, first, old] {
+            list.complete_task(id).unwrap();
+        }
+        let now = Utc::now();
+        list.find_task_mut(old).unwrap().completed_at = Some(now - chrono::Duration::days(7));
+        list.find_task_mut(first).unwrap().completed_at = Some(now - chrono::Duration::hours(2));
+
+        let ids: Vec<u32> = list
+            .completed_since(now - chrono::Duration::days(1))
+            .iter()
+            .map(|task| task.id)
+            .collect();
+        assert_eq!(ids, [first, second]);
+    }
+
+    #[test]
+    fn test_duplicate_task() {
+        let mut list = TodoList::new();
+        let parent = list.add_task("周会".to_string(), Priority::Low, None);
+        let id = list.add_subtask(parent, "写周报".to_string(), Priority::High, Some(Utc::now())).unwrap();
+        list.set_tags(id, vec!["work".to_string()]).unwrap();
+        list.set_estimate(id, Some(30)).unwrap();
+        list.complete_task(id).unwrap();
+
+        let due = Utc::now() + chrono::Duration::days(7);
+        let copy = list.duplicate_task(id, Some(due)).unwrap();
+        let (original, duplicate) = (&list[id], &list[copy]);
+        assert_eq!(copy, 3);
+        assert_eq!(duplicate.title, original.title);
+        assert_eq!(duplicate.tags, original.tags);
+        assert_eq!(duplicate.parent_id, Some(parent));
+        assert_eq!(duplicate.estimate_minutes, Some(30));
+        assert_eq!(duplicate.due_date, Some(due));
+        assert!(!duplicate.completed);
+        assert_ne!(duplicate.uuid, original.uuid);
+        assert_eq!(list.history().last().unwrap().kind, EventKind::Added);
+
+        assert!(matches!(list.duplicate_task(9, None), Err(TodoError::TaskNotFound(9))));
+    }
+
+    #[test]
+    fn test_reset_can_be_undone() {
+        let mut list = TodoList::new();
+        list.add_task("写周报".to_string(), Priority::High, None);
+        list.add_goal("发布 v1".to_string(), None);
+
+        list.checkpoint("reset");
+        list.reset();
+        assert!(list.is_empty() && list.goals().is_empty() && list.history().is_empty());
+        assert_eq!(list.add_task("新任务".to_string(), Priority::Low, None), 1);
+
+        list.undo();
+        assert_eq!(list[1].title, "写周报");
+        assert_eq!(list.goals().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "no task with ID 9")]
+    fn test_index_missing_task_panics() {
+        let _ = &TodoList::new()[9];
+    }
+
     #[test]
     fn test_complete_task_with_error() {
         let mut list = TodoList::new();
@@ -135,6 +1440,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reopen_task() {
+        let mut list = TodoList::new();
+        let id = list.add_task("测试".to_string(), Priority::Medium, None);
+        list.complete_task(id).unwrap();
+        assert!(list.find_task(id).unwrap().completed_at.is_some());
+
+        list.reopen_task(id).unwrap();
+        let task = list.find_task(id).unwrap();
+        assert!(!task.completed);
+        assert!(task.completed_at.is_none());
+        assert!(matches!(list.reopen_task(999), Err(TodoError::TaskNotFound(999))));
+    }
+
+    #[test]
+    fn test_mutations_update_modified_time() {
+        let mut list = TodoList::new();
+        let id = list.add_task("测试".to_string(), Priority::Medium, None);
+        let stale = DateTime::<Utc>::default();
+        let touched = |list: &TodoList| list.find_task(id).unwrap().updated_at > stale;
+
+        let edits: [fn(&mut TodoList, u32); 5] = [
+            |list, id| list.set_priority(id, Priority::High).unwrap(),
+            |list, id| list.set_due_date(id, Some(Utc::now())).unwrap(),
+            |list, id| list.set_tags(id, vec!["work".to_string()]).unwrap(),
+            |list, id| assert!(list.complete_task(id).unwrap().is_none()),
+            |list, id| list.reopen_task(id).unwrap(),
+        ];
+        for edit in edits {
+            list.find_task_mut(id).unwrap().updated_at = stale;
+            edit(&mut list, id);
+            assert!(touched(&list));
+        }
+    }
+
+    #[test]
+    fn test_log_pomodoro() {
+        let mut list = TodoList::new();
+        let id = list.add_task("写周报".to_string(), Priority::Medium, None);
+        let pomodoro = Pomodoro { started_at: Utc::now(), minutes: 25 };
+
+        list.log_pomodoro(id, pomodoro).unwrap();
+        list.log_pomodoro(id, pomodoro).unwrap();
+        assert_eq!(list.find_task(id).unwrap().pomodoros, [pomodoro, pomodoro]);
+        assert!(matches!(list.log_pomodoro(999, pomodoro), Err(TodoError::TaskNotFound(999))));
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let mut list = TodoList::new();
+        let design = list.add_task("设计".to_string(), Priority::High, None);
+        let build = list.add_task("开发".to_string(), Priority::High, None);
+        let release = list.add_task("发布".to_string(), Priority::High, None);
+
+        list.add_dependency(build, design).unwrap();
+        list.add_dependency(release, build).unwrap();
+        // 重复添加不会产生重复的依赖
+        list.add_dependency(release, build).unwrap();
+        assert_eq!(list.find_task(release).unwrap().depends_on, [build]);
+        assert_eq!(list.dependency_ids(release), [build, design]);
+
+        assert!(list.is_blocked(release));
+        list.complete_task(build).unwrap();
+        assert!(!list.is_blocked(release));
+        assert!(list.is_blocked(build));
+
+        assert!(matches!(list.add_dependency(design, release), Err(TodoError::DependencyCycle(..))));
+        assert!(matches!(list.add_dependency(design, design), Err(TodoError::DependencyCycle(..))));
+        assert!(matches!(list.add_dependency(design, 999), Err(TodoError::TaskNotFound(999))));
+        assert!(list.find_task(design).unwrap().depends_on.is_empty());
+
+        assert!(list.remove_dependency(build, design).unwrap());
+        assert!(!list.remove_dependency(build, design).unwrap());
+    }
+
+    #[test]
+    fn test_delete_removes_dependencies_on_task() {
+        let mut list = TodoList::new();
+        let design = list.add_task("设计".to_string(), Priority::High, None);
+        let build = list.add_task("开发".to_string(), Priority::High, None);
+        list.add_dependency(build, design).unwrap();
+
+        list.delete_task(design).unwrap();
+        assert!(list.find_task(build).unwrap().depends_on.is_empty());
+    }
+
     #[test]
     fn test_delete_task_with_error() {
         let mut list = TodoList::new();
@@ -146,4 +1537,363 @@ mod tests {
         assert!(result. is_err());
         assert!(matches!(result, Err(TodoError::TaskNotFound(999))));
     }
+
+    #[test]
+    fn test_complete_recurring_task() {
+        let mut list = TodoList::new();
+        let due = Utc::now();
+        let id = list.add_task("浇花".to_string(), Priority::Low, Some(due));
+        list.set_recurrence(id, Some(Recurrence::Daily)).unwrap();
+
+        let next_id = list.complete_task(id).unwrap().expect("next occurrence");
+        assert_eq!(list.task_history(next_id).len(), 1);
+        assert_eq!(list.task_history(next_id)[0].kind, EventKind::Added);
+        assert!(list.find_task(id).unwrap().completed);
+        assert!(list.find_task(id).unwrap().recurrence.is_none());
+
+        let next = list.find_task(next_id).unwrap();
+        assert!(!next.completed);
+        assert_eq!(next.due_date, Some(due + chrono::Duration::days(1)));
+        assert_eq!(next.recurrence, Some(Recurrence::Daily));
+        assert_ne!(next.uuid, list.find_task(id).unwrap().uuid);
+        assert_eq!(list.find_task_by_uuid(&next.uuid).unwrap().id, next_id);
+    }
+
+    #[test]
+    fn test_history_records_changes() {
+        let mut list = TodoList::new();
+        let id = list.add_task("周报".to_string(), Priority::Low, None);
+        list.set_recurrence(id, Some(Recurrence::Weekly)).unwrap();
+        list.set_due_date(id, Some(Utc::now())).unwrap();
+        list.set_due_date(id, list.find_task(id).unwrap().due_date).unwrap();
+        list.complete_task(id).unwrap();
+        list.complete_task(id).unwrap();
+        list.delete_task(id).unwrap();
+
+        // 未改变的截止日期和重复完成不记录，自动生成的下一次实例记录为新建
+        let kinds: Vec<&EventKind> = list.history().iter().map(|event| &event.kind).collect();
+        assert_eq!(kinds.len(), 6);
+        assert!(matches!(kinds[0], EventKind::Added));
+        assert!(matches!(kinds[1], EventKind::Edited { field, .. } if field == "repeats"));
+        assert!(matches!(kinds[2], EventKind::Rescheduled { from: None, to: Some(_) }));
+        assert!(matches!(kinds[3], EventKind::Completed));
+        assert!(matches!(kinds[4], EventKind::Added));
+        assert!(matches!(kinds[5], EventKind::Deleted));
+        assert_ne!(list.history()[4].task_uuid, list.history()[0].task_uuid);
+        assert!(list.history().iter().all(|event| event.title == "周报"));
+    }
+
+    #[test]
+    fn test_listeners_receive_history_events() {
+        use std::sync::Mutex;
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let mut list = TodoList::new();
+        let sink = Arc::clone(&received);
+        list.subscribe(move |event| sink.lock().unwrap().push((event.task_id, event.kind.clone())));
+
+        let parent = list.add_task("发布".to_string(), Priority::High, None);
+        let child = list.add_subtask(parent, "写更新日志".to_string(), Priority::Low, None).unwrap();
+        list.set_priority(child, Priority::Low).unwrap();
+        list.delete_task_with_policy(parent, DeletePolicy::Cascade).unwrap();
+
+        // 只通知记录到历史中的变更（没有改变的优先级不记录）
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                (parent, EventKind::Added),
+                (child, EventKind::Added),
+                (parent, EventKind::Deleted),
+                (child, EventKind::Deleted),
+            ]
+        );
+        assert_eq!(received.lock().unwrap().len(), list.history().len());
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        use std::sync::Mutex;
+
+        let notified = Arc::new(Mutex::new(0));
+        let mut list = TodoList::new();
+        let counter = Arc::clone(&notified);
+        list.subscribe(move |_| *counter.lock().unwrap() += 1);
+        let first = list.add_task("写周报".to_string(), Priority::Low, None);
+        let second = list.add_task("买牛奶".to_string(), Priority::Low, None);
+
+        let result = list.transaction(|txn| {
+            txn.complete_task(first)?;
+            txn.delete_task(second)?;
+            txn.add_dependency(first, 42)
+        });
+        assert!(matches!(result, Err(TodoError::TaskNotFound(42))));
+        assert!(!list[first].completed);
+        assert!(list.get(second).is_some());
+        assert_eq!(list.history().len(), 2);
+        assert_eq!(*notified.lock().unwrap(), 2);
+
+        // 成功时提交后才通知，内层事务失败只撤销内层的修改
+        let deleted = list
+            .transaction(|txn| {
+                txn.complete_task(first)?;
+                let inner = txn.transaction(|inner| inner.delete_task(99));
+                assert!(inner.is_err());
+                assert_eq!(*notified.lock().unwrap(), 2);
+                txn.delete_task_with_policy(second, DeletePolicy::Abort)
+            })
+            .unwrap();
+        assert_eq!(deleted, [second]);
+        assert!(list[first].completed);
+        assert_eq!(*notified.lock().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_lookup_index_follows_changes() {
+        let mut list = TodoList::new();
+        for i in 1..=5 {
+            list.add_task(format!("任务 {}", i), Priority::Low, None);
+        }
+        list.delete_task(2).unwrap();
+        list.retain(|task| task.id != 4);
+        assert!(list.find_task(2).is_none() && list.find_task(4).is_none());
+        assert_eq!(list.find_task(5).unwrap().title, "任务 5");
+        list.find_task_mut(3).unwrap().title = "改过".to_string();
+        assert_eq!(list[3].title, "改过");
+
+        // 加载和撤销后重建索引
+        let json = serde_json::to_string(&list).unwrap();
+        let mut loaded: TodoList = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.find_task(5).unwrap().title, "任务 5");
+        loaded.checkpoint("delete 1");
+        loaded.delete_task(1).unwrap();
+        loaded.undo();
+        assert_eq!(loaded.find_task(1).unwrap().title, "任务 1");
+        assert_eq!(loaded.find_task(5).unwrap().title, "任务 5");
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        let mut list = TodoList::new();
+        let goal = list.add_goal("发布 v1".to_string(), None);
+        let first = list.add_task("写文档".to_string(), Priority::Low, None);
+        let second = list.add_task("修复 bug".to_string(), Priority::High, None);
+        list.add_task("无关任务".to_string(), Priority::Low, None);
+
+        list.set_goal(first, Some(goal)).unwrap();
+        list.set_goal(second, Some(goal)).unwrap();
+        list.complete_task(first).unwrap();
+        assert_eq!(list.goal_progress(goal), Progress { completed: 1, total: 2 });
+
+        list.set_goal(second, None).unwrap();
+        assert_eq!(list.goal_progress(goal), Progress { completed: 1, total: 1 });
+
+        assert!(matches!(list.set_goal(first, Some(99)), Err(TodoError::GoalNotFound(99))));
+    }
+
+    #[test]
+    fn test_estimated_minutes_on() {
+        let mut list = TodoList::new();
+        let monday = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let due = crate::dates::end_of_day(monday);
+
+        let first = list.add_task("写报告".to_string(), Priority::High, Some(due));
+        let second = list.add_task("开会".to_string(), Priority::Low, Some(due));
+        let done = list.add_task("已完成".to_string(), Priority::Low, Some(due));
+        list.add_task("没有预估".to_string(), Priority::Low, Some(due));
+        list.set_estimate(first, Some(120)).unwrap();
+        list.set_estimate(second, Some(45)).unwrap();
+        list.set_estimate(done, Some(60)).unwrap();
+        list.complete_task(done).unwrap();
+
+        assert_eq!(list.estimated_minutes_on(monday), 165);
+        assert_eq!(list.tasks_due_on(monday).len(), 3);
+        assert_eq!(list.estimated_minutes_on(monday.succ_opt().unwrap()), 0);
+    }
+
+    #[test]
+    fn test_tasks_due_between() {
+        let mut list = TodoList::new();
+        let monday = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let at = |hours| Some(monday + chrono::Duration::hours(hours));
+
+        let evening = list.add_task("晚上".to_string(), Priority::Low, at(20));
+        let morning = list.add_task("早上".to_string(), Priority::Low, at(9));
+        list.add_task("第二天".to_string(), Priority::Low, at(24));
+        let done = list.add_task("已完成".to_string(), Priority::Low, at(10));
+        list.complete_task(done).unwrap();
+
+        let ids: Vec<u32> = list
+            .tasks_due_between(monday, monday + chrono::Duration::days(1))
+            .iter()
+            .map(|task| task.id)
+            .collect();
+        assert_eq!(ids, vec![morning, evening]);
+    }
+
+    #[test]
+    fn test_skip_occurrence() {
+        let mut list = TodoList::new();
+        let due = Utc::now();
+        let id = list.add_task("周报".to_string(), Priority::Medium, Some(due));
+
+        // 非循环任务不能跳过
+        assert!(matches!(list.skip_occurrence(id), Err(TodoError::NotRecurring(_))));
+
+        list.set_recurrence(id, Some(Recurrence::Weekly)).unwrap();
+        let next_due = list.skip_occurrence(id).unwrap();
+
+        let task = list.find_task(id).unwrap();
+        assert_eq!(next_due, due + chrono::Duration::weeks(1));
+        assert_eq!(task.due_date, Some(next_due));
+        assert_eq!(task.skipped, vec![due]);
+        assert!(!task.completed);
+        assert_eq!(list.len(), 1);
+
+        // 跳过单独记录，不算作改期
+        let last = list.history().last().unwrap();
+        assert_eq!(last.kind, EventKind::Skipped { from: Some(due), to: next_due });
+    }
+
+    #[test]
+    fn test_critical_tasks() {
+        let mut list = TodoList::new();
+        let now = Utc::now();
+        let critical = list.add_task("很久以前".to_string(), Priority::High, Some(now - chrono::Duration::days(3)));
+        list.add_task("刚刚过期".to_string(), Priority::High, Some(now - chrono::Duration::hours(1)));
+        let done = list.add_task("已完成".to_string(), Priority::Low, Some(now - chrono::Duration::days(5)));
+        list.complete_task(done).unwrap();
+
+        let ids: Vec<u32> = list
+            .critical_tasks(|_| chrono::Duration::days(1))
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![critical]);
+        assert_eq!(list.critical_tasks(|_| chrono::Duration::zero()).len(), 2);
+    }
+
+    #[test]
+    fn test_add_subtask() {
+        let mut list = TodoList::new();
+        let parent = list.add_task("父任务".to_string(), Priority::High, None);
+        let child = list.add_subtask(parent, "子任务".to_string(), Priority::Low, None).unwrap();
+
+        assert_eq!(list.find_task(child).unwrap().parent_id, Some(parent));
+        assert_eq!(list.children(parent).len(), 1);
+
+        let result = list.add_subtask(999, "孤儿".to_string(), Priority::Low, None);
+        assert!(matches!(result, Err(TodoError::TaskNotFound(999))));
+    }
+
+    #[test]
+    fn test_match_task_by_title() {
+        let mut list = TodoList::new();
+        let groceries = list.add_task("Buy groceries".to_string(), Priority::Low, None);
+        let milk = list.add_task("Buy milk".to_string(), Priority::Low, None);
+        let done = list.add_task("Return library books".to_string(), Priority::Low, None);
+        list.complete_task(done).unwrap();
+
+        assert_eq!(list.match_task("GROCER", false).unwrap().id, groceries);
+        assert!(matches!(list.match_task("pizza", false), Err(TodoError::NoMatchingTask(_))));
+
+        match list.match_task("buy", false) {
+            Err(TodoError::AmbiguousMatch(pattern, candidates)) => {
+                assert_eq!(pattern, "buy");
+                assert_eq!(candidates, vec![(groceries, "Buy groceries".to_string()), (milk, "Buy milk".to_string())]);
+            }
+            other => panic!("expected an ambiguous match, got {:?}", other),
+        }
+
+        // 完全相同的标题优先
+        list.add_task("Buy milk and eggs".to_string(), Priority::Low, None);
+        assert_eq!(list.match_task("buy milk", false).unwrap().id, milk);
+
+        // 已完成的任务只在 include_completed 时参与匹配
+        assert!(list.match_task("library", false).is_err());
+        assert_eq!(list.match_task("library", true).unwrap().id, done);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut list = TodoList::new();
+        let fix = list.add_task("Fix crash on prod".to_string(), Priority::High, None);
+        let runbook = list.add_task("Update prod runbook".to_string(), Priority::Low, None);
+        let old = list.add_task("Fix typo".to_string(), Priority::Low, None);
+        list.complete_task(old).unwrap();
+
+        let ids = |tasks: Vec<&Task>| tasks.iter().map(|task| task.id).collect::<Vec<_>>();
+        assert_eq!(ids(list.search("PROD", false).unwrap()), [fix, runbook]);
+        assert_eq!(ids(list.search("^Fix", true).unwrap()), [fix, old]);
+        assert_eq!(ids(list.search("^Fix .*prod", true).unwrap()), [fix]);
+        // 正则表达式默认区分大小写
+        assert!(list.search("^fix", true).unwrap().is_empty());
+        assert_eq!(ids(list.search("(?i)^fix .*PROD", true).unwrap()), [fix]);
+
+        match list.search("(prod", true) {
+            Err(TodoError::InvalidRegex(pattern, reason)) => {
+                assert_eq!(pattern, "(prod");
+                assert_eq!(reason, "unclosed group");
+            }
+            other => panic!("expected an invalid regex error, got {:?}", other),
+        }
+    }
+
+    fn list_with_hierarchy() -> (TodoList, u32, u32, u32) {
+        let mut list = TodoList::new();
+        let parent = list.add_task("父任务".to_string(), Priority::High, None);
+        let child = list.add_subtask(parent, "子任务".to_string(), Priority::Medium, None).unwrap();
+        let grandchild = list.add_subtask(child, "孙任务".to_string(), Priority::Low, None).unwrap();
+        list.add_task("无关任务".to_string(), Priority::Low, None);
+        (list, parent, child, grandchild)
+    }
+
+    #[test]
+    fn test_subtask_estimate_rollup() {
+        let (mut list, parent, child, grandchild) = list_with_hierarchy();
+        assert_eq!(list.subtask_estimate_minutes(parent), None);
+
+        list.find_task_mut(parent).unwrap().estimate_minutes = Some(5);
+        list.find_task_mut(child).unwrap().estimate_minutes = Some(30);
+        list.find_task_mut(grandchild).unwrap().estimate_minutes = Some(90);
+
+        assert_eq!(list.subtask_estimate_minutes(parent), Some(120));
+        assert_eq!(list.subtask_estimate_minutes(child), Some(90));
+        assert_eq!(list.subtask_estimate_minutes(grandchild), None);
+    }
+
+    #[test]
+    fn test_delete_with_abort_policy() {
+        let (mut list, parent, _, _) = list_with_hierarchy();
+
+        let result = list.delete_task_with_policy(parent, DeletePolicy::Abort);
+        assert!(matches!(result, Err(TodoError::TaskHasChildren(id, 1)) if id == parent));
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_delete_with_cascade_policy() {
+        let (mut list, parent, child, grandchild) = list_with_hierarchy();
+
+        let deleted = list.delete_task_with_policy(parent, DeletePolicy::Cascade).unwrap();
+        assert_eq!(deleted, vec![parent, child, grandchild]);
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_with_orphan_policy() {
+        let (mut list, parent, child, grandchild) = list_with_hierarchy();
+
+        let deleted = list.delete_task_with_policy(parent, DeletePolicy::Orphan).unwrap();
+        assert_eq!(deleted, vec![parent]);
+        assert_eq!(list.find_task(child).unwrap().parent_id, None);
+        assert_eq!(list.find_task(grandchild).unwrap().parent_id, Some(child));
+        assert_eq!(
+            list.task_history(child).last().unwrap().kind,
+            EventKind::Edited {
+                field: "parent".to_string(),
+                from: Some(parent.to_string()),
+                to: None,
+            }
+        );
+    }
 }
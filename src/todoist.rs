@@ -0,0 +1,490 @@
+use crate::config::TodoistConfig;
+use crate::dates::{end_of_day, local_date};
+use crate::error::{TodoError, TodoResult};
+use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Todoist API 地址
+pub const TODOIST_API_URL: &str = "https://api.todoist.com/api/v1";
+
+/// 配置中没有 Token 时读取 API Token 的环境变量
+pub const TODOIST_TOKEN_ENV: &str = "TODOIST_API_TOKEN";
+
+/// Todoist 中的任务（只保留需要同步的字段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoistTask {
+    pub id: String,
+    pub content: String,
+    /// 1（普通）到 4（紧急）
+    #[serde(default = "default_todoist_priority")]
+    pub priority: u8,
+    pub due: Option<TodoistDue>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// 在 Todoist 中最后修改的时间，与本地任务的修改时间比较决定以哪一端为准
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 一页任务列表
+#[derive(Debug, Deserialize)]
+struct TodoistPage {
+    results: Vec<TodoistTask>,
+    next_cursor: Option<String>,
+}
+
+/// Todoist 任务的截止日期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoistDue {
+    /// 格式：YYYY-MM-DD
+    pub date: String,
+}
+
+/// 创建或修改 Todoist 任务时提交的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct NewTodoistTask {
+    pub content: String,
+    pub priority: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    /// 修改时用 `no date` 清除截止日期
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_string: Option<String>,
+    pub labels: Vec<String>,
+}
+
+fn default_todoist_priority() -> u8 {
+    1
+}
+
+/// 同步结果统计
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// 推送到 Todoist 的新任务数
+    pub pushed: usize,
+    /// 从 Todoist 拉取的新任务数
+    pub pulled: usize,
+    /// 被 Todoist 中的内容更新的本地任务数
+    pub updated: usize,
+    /// 本地修改更新到 Todoist 的任务数
+    pub updated_remote: usize,
+    /// 在 Todoist 中关闭的任务数
+    pub closed_remote: usize,
+    /// 因 Todoist 中已完成或删除而在本地完成的任务数
+    pub completed_local: usize,
+}
+
+/// Todoist API 的最小接口，便于在测试中替换
+pub trait TodoistApi {
+    /// 获取所有未完成的任务
+    fn fetch_tasks(&self) -> TodoResult<Vec<TodoistTask>>;
+    /// 创建任务，返回 Todoist 中的新任务
+    fn create_task(&self, task: &NewTodoistTask) -> TodoResult<TodoistTask>;
+    /// 修改任务的内容
+    fn update_task(&self, id: &str, task: &NewTodoistTask) -> TodoResult<()>;
+    /// 关闭（完成）任务
+    fn close_task(&self, id: &str) -> TodoResult<()>;
+}
+
+/// 基于 HTTP 的 Todoist 客户端
+pub struct TodoistClient {
+    token: String,
+    base_url: String,
+}
+
+impl TodoistClient {
+    /// 使用 API Token 创建客户端
+    pub fn new(token: String) -> Self {
+        Self {
+            token,
+            base_url: TODOIST_API_URL.to_string(),
+        }
+    }
+
+    /// 使用配置中的 Token 创建客户端，配置中没有时从环境变量读取
+    pub fn from_config(config: &TodoistConfig) -> TodoResult<Self> {
+        config
+            .token
+            .clone()
+            .filter(|token| !token.is_empty())
+            .or_else(|| std::env::var(TODOIST_TOKEN_ENV).ok())
+            .map(Self::new)
+            .ok_or_else(|| {
+                TodoError::SyncError(format!("set todoist.token in the config or {}", TODOIST_TOKEN_ENV))
+            })
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+}
+
+impl TodoistApi for TodoistClient {
+    fn fetch_tasks(&self) -> TodoResult<Vec<TodoistTask>> {
+        let mut tasks = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut request = ureq::get(&format!("{}/tasks", self.base_url))
+                .header("Authorization", &self.authorization())
+                .query("limit", "200");
+            if let Some(cursor) = &cursor {
+                request = request.query("cursor", cursor);
+            }
+
+            let page: TodoistPage = request
+                .call()
+                .map_err(sync_error)?
+                .body_mut()
+                .read_json()
+                .map_err(sync_error)?;
+            tasks.extend(page.results);
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(tasks),
+            }
+        }
+    }
+
+    fn create_task(&self, task: &NewTodoistTask) -> TodoResult<TodoistTask> {
+        ureq::post(&format!("{}/tasks", self.base_url))
+            .header("Authorization", &self.authorization())
+            .send_json(task)
+            .map_err(sync_error)?
+            .body_mut()
+            .read_json()
+            .map_err(sync_error)
+    }
+
+    fn update_task(&self, id: &str, task: &NewTodoistTask) -> TodoResult<()> {
+        ureq::post(&format!("{}/tasks/{}", self.base_url, id))
+            .header("Authorization", &self.authorization())
+            .send_json(task)
+            .map_err(sync_error)?;
+        Ok(())
+    }
+
+    fn close_task(&self, id: &str) -> TodoResult<()> {
+        ureq::post(&format!("{}/tasks/{}/close", self.base_url, id))
+            .header("Authorization", &self.authorization())
+            .send_empty()
+            .map_err(sync_error)?;
+        Ok(())
+    }
+}
+
+fn sync_error(err: ureq::Error) -> TodoError {
+    TodoError::SyncError(err.to_string())
+}
+
+/// 与 Todoist 双向同步
+///
+/// 1. 已关联的任务：本地已完成的在 Todoist 中关闭；Todoist 中已不存在的在本地标记完成；
+///    其余内容不同的任务以后修改的一端为准：Todoist 中较新时更新本地任务，本地较新时更新 Todoist
+/// 2. 本地未关联的未完成任务推送到 Todoist
+/// 3. Todoist 中未关联的任务拉取到本地
+pub fn sync_todoist<A: TodoistApi>(todo_list: &mut TodoList, api: &A) -> TodoResult<SyncReport> {
+    let remote_tasks = api.fetch_tasks()?;
+    let mut report = SyncReport::default();
+
+    // 1. 已关联的任务
+    let linked: Vec<(u32, String, bool)> = todo_list
+        .list_tasks()
+        .iter()
+        .filter_map(|t| t.todoist_id.clone().map(|rid| (t.id, rid, t.completed)))
+        .collect();
+
+    for (id, remote_id, completed) in &linked {
+        match remote_tasks.iter().find(|r| &r.id == remote_id) {
+            Some(_) if *completed => {
+                api.close_task(remote_id)?;
+                report.closed_remote += 1;
+            }
+            Some(remote) => {
+                let task = todo_list
+                    .find_task(*id)
+                    .ok_or(TodoError::TaskNotFound(*id))?;
+                if matches_remote(task, remote)? {
+                    continue;
+                }
+
+                // Todoist 没有返回修改时间时视为更早，保留本地的修改
+                if remote.updated_at.is_some_and(|remote_time| remote_time >= task.updated_at) {
+                    let mut updated = task.clone();
+                    apply_remote(&mut updated, remote)?;
+                    todo_list.replace_task(*id, updated)?;
+                    report.updated += 1;
+                } else {
+                    let mut update = to_todoist(task);
+                    if update.due_date.is_none() {
+                        update.due_string = Some("no date".to_string());
+                    }
+                    api.update_task(remote_id, &update)?;
+                    report.updated_remote += 1;
+                }
+            }
+            None if !*completed => {
+                todo_list.complete_task(*id)?;
+                report.completed_local += 1;
+            }
+            None => {}
+        }
+    }
+
+    // 2. 推送本地新任务
+    let unlinked: Vec<u32> = todo_list
+        .list_tasks()
+        .iter()
+        .filter(|t| !t.completed && t.todoist_id.is_none())
+        .map(|t| t.id)
+        .collect();
+
+    for id in unlinked {
+        let task = todo_list
+            .find_task_mut(id)
+            .ok_or(TodoError::TaskNotFound(id))?;
+        let created = api.create_task(&to_todoist(task))?;
+        task.todoist_id = Some(created.id);
+        report.pushed += 1;
+    }
+
+    // 3. 拉取远端新任务
+    for remote in &remote_tasks {
+        if linked.iter().any(|(_, rid, _)| rid == &remote.id) {
+            continue;
+        }
+
+        let mut task = Task::new(0, String::new(), Priority::Medium, None);
+        apply_remote(&mut task, remote)?;
+        task.todoist_id = Some(remote.id.clone());
+        todo_list.insert_task(task);
+        report.pulled += 1;
+    }
+
+    Ok(report)
+}
+
+/// 本地任务的标题、优先级、截止日期和标签是否与 Todoist 任务相同
+fn matches_remote(task: &Task, remote: &TodoistTask) -> TodoResult<bool> {
+    Ok(task.title == remote.content
+        && task.priority == priority_from_todoist(remote.priority)
+        && task.due_date == remote_due_date(remote)?
+        && task.tags == remote.labels)
+}
+
+/// 用 Todoist 任务的内容覆盖本地任务
+fn apply_remote(task: &mut Task, remote: &TodoistTask) -> TodoResult<()> {
+    task.title = remote.content.clone();
+    task.priority = priority_from_todoist(remote.priority);
+    task.due_date = remote_due_date(remote)?;
+    task.tags = remote.labels.clone();
+    task.touch();
+    Ok(())
+}
+
+/// Todoist 任务的截止日期
+fn remote_due_date(remote: &TodoistTask) -> TodoResult<Option<DateTime<Utc>>> {
+    remote.due.as_ref().map(|due| parse_todoist_date(&due.date)).transpose()
+}
+
+/// 将本地任务转换为 Todoist 的创建请求
+fn to_todoist(task: &Task) -> NewTodoistTask {
+    NewTodoistTask {
+        content: task.title.clone(),
+        priority: priority_to_todoist(task.priority),
+        due_date: task.due_date.map(|due| local_date(due).format("%Y-%m-%d").to_string()),
+        due_string: None,
+        labels: task.tags.clone(),
+    }
+}
+
+/// Todoist 优先级（4 为最高）转换为本地优先级
+fn priority_from_todoist(priority: u8) -> Priority {
+    match priority {
+        4 => Priority::High,
+        2 => Priority::Low,
+        _ => Priority::Medium,
+    }
+}
+
+/// 本地优先级转换为 Todoist 优先级
+fn priority_to_todoist(priority: Priority) -> u8 {
+    match priority {
+        Priority::High => 4,
+        Priority::Medium => 3,
+        Priority::Low => 2,
+    }
+}
+
+/// 解析 Todoist 的日期（与 `--due` 一样取当天结束）
+fn parse_todoist_date(date_str: &str) -> TodoResult<DateTime<Utc>> {
+    // 带时间的截止日期形如 2025-01-01T12:00:00，只取日期部分
+    let date_part = date_str.get(..10).unwrap_or(date_str);
+
+    NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
+        .map(end_of_day)
+        .map_err(|_| TodoError::InvalidDateFormat(date_str.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EventKind;
+    use std::cell::RefCell;
+
+    /// 内存中的假 Todoist
+    #[derive(Default)]
+    struct FakeTodoist {
+        tasks: Vec<TodoistTask>,
+        created: RefCell<Vec<NewTodoistTask>>,
+        updated: RefCell<Vec<(String, NewTodoistTask)>>,
+        closed: RefCell<Vec<String>>,
+    }
+
+    impl TodoistApi for FakeTodoist {
+        fn fetch_tasks(&self) -> TodoResult<Vec<TodoistTask>> {
+            Ok(self.tasks.clone())
+        }
+
+        fn create_task(&self, task: &NewTodoistTask) -> TodoResult<TodoistTask> {
+            self.created.borrow_mut().push(task.clone());
+            Ok(TodoistTask {
+                id: format!("new-{}", self.created.borrow().len()),
+                content: task.content.clone(),
+                priority: task.priority,
+                due: None,
+                labels: task.labels.clone(),
+                updated_at: Some(Utc::now()),
+            })
+        }
+
+        fn update_task(&self, id: &str, task: &NewTodoistTask) -> TodoResult<()> {
+            self.updated.borrow_mut().push((id.to_string(), task.clone()));
+            Ok(())
+        }
+
+        fn close_task(&self, id: &str) -> TodoResult<()> {
+            self.closed.borrow_mut().push(id.to_string());
+            Ok(())
+        }
+    }
+
+    fn remote(id: &str, content: &str, priority: u8) -> TodoistTask {
+        TodoistTask {
+            id: id.to_string(),
+            content: content.to_string(),
+            priority,
+            due: Some(TodoistDue { date: "2025-06-01".to_string() }),
+            labels: vec!["work".to_string()],
+            updated_at: Some(Utc::now()),
+        }
+    }
+
+    #[test]
+    fn test_sync_pushes_and_pulls() {
+        let mut list = TodoList::new();
+        list.add_task("本地任务".to_string(), Priority::High, None);
+
+        let api = FakeTodoist {
+            tasks: vec![remote("r1", "远端任务", 4)],
+            ..Default::default()
+        };
+
+        let report = sync_todoist(&mut list, &api).unwrap();
+        assert_eq!(report.pushed, 1);
+        assert_eq!(report.pulled, 1);
+        assert_eq!(api.created.borrow()[0].priority, 4);
+
+        let local = list.find_task(1).unwrap();
+        assert_eq!(local.todoist_id.as_deref(), Some("new-1"));
+
+        let pulled = list.find_task(2).unwrap();
+        assert_eq!(pulled.title, "远端任务");
+        assert_eq!(pulled.priority, Priority::High);
+        assert_eq!(pulled.tags, vec!["work"]);
+        assert_eq!(pulled.todoist_id.as_deref(), Some("r1"));
+    }
+
+    #[test]
+    fn test_sync_linked_tasks() {
+        let mut list = TodoList::new();
+        let done = list.add_task("本地已完成".to_string(), Priority::Low, None);
+        let gone = list.add_task("远端已完成".to_string(), Priority::Low, None);
+        let edited = list.add_task("旧标题".to_string(), Priority::Low, None);
+        list.complete_task(done).unwrap();
+        list.find_task_mut(done).unwrap().todoist_id = Some("r1".to_string());
+        list.find_task_mut(gone).unwrap().todoist_id = Some("r2".to_string());
+        list.find_task_mut(edited).unwrap().todoist_id = Some("r3".to_string());
+
+        let api = FakeTodoist {
+            tasks: vec![remote("r1", "本地已完成", 2), remote("r3", "新标题", 2)],
+            ..Default::default()
+        };
+
+        let report = sync_todoist(&mut list, &api).unwrap();
+        assert_eq!(
+            report,
+            SyncReport {
+                updated: 1,
+                closed_remote: 1,
+                completed_local: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(api.closed.borrow().as_slice(), ["r1"]);
+        assert!(list.find_task(gone).unwrap().completed);
+        assert_eq!(list.find_task(edited).unwrap().title, "新标题");
+        let retitled = EventKind::Edited {
+            field: "title".to_string(),
+            from: Some("旧标题".to_string()),
+            to: Some("新标题".to_string()),
+        };
+        assert!(list.task_history(edited).iter().any(|event| event.kind == retitled));
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_sync_keeps_newer_local_edit() {
+        let mut list = TodoList::new();
+        let id = list.add_task("远端任务".to_string(), Priority::High, None);
+        list.set_tags(id, vec!["work".to_string()]).unwrap();
+        list.set_due_date(id, Some(parse_todoist_date("2025-06-01").unwrap())).unwrap();
+        list.find_task_mut(id).unwrap().todoist_id = Some("r1".to_string());
+
+        // Todoist 中的版本比本地修改更早
+        let mut stale = remote("r1", "远端任务", 4);
+        stale.updated_at = Some(Utc::now() - chrono::Duration::hours(1));
+        let api = FakeTodoist {
+            tasks: vec![stale],
+            ..Default::default()
+        };
+
+        list.set_title(id, "本地改过的标题".to_string()).unwrap();
+        list.set_due_date(id, None).unwrap();
+        let report = sync_todoist(&mut list, &api).unwrap();
+
+        assert_eq!(report, SyncReport { updated_remote: 1, ..Default::default() });
+        assert_eq!(list.find_task(id).unwrap().title, "本地改过的标题");
+
+        let updated = api.updated.borrow();
+        assert_eq!(updated[0].0, "r1");
+        assert_eq!(updated[0].1.content, "本地改过的标题");
+        assert_eq!(updated[0].1.due_string.as_deref(), Some("no date"));
+    }
+
+    #[test]
+    fn test_token_from_config() {
+        let config = TodoistConfig { token: Some("secret".to_string()) };
+        assert_eq!(TodoistClient::from_config(&config).unwrap().token, "secret");
+    }
+
+    #[test]
+    fn test_parse_todoist_date() {
+        let date = parse_todoist_date("2025-06-01T09:30:00").unwrap();
+        assert_eq!(local_date(date).to_string(), "2025-06-01");
+        assert!(parse_todoist_date("tomorrow").is_err());
+    }
+}
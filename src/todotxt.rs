@@ -0,0 +1,238 @@
+use crate::dates::{end_of_day, local_date};
+use crate::error::{TodoError, TodoResult};
+use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// todo.txt 中的日期格式
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 解析 todo.txt 格式的内容
+///
+/// 支持的约定：
+/// - `x` 开头表示已完成
+/// - `(A)` 优先级：A 为高，B 为中，其余为低（已完成任务使用 `pri:A`）
+/// - 行首的创建日期（已完成任务为「完成日期 创建日期」，只取创建日期）
+/// - `+project` 映射为标签
+/// - `due:YYYY-MM-DD` 映射为截止日期
+/// - `uuid:...` 映射为任务的 UUID（没有时生成新的）
+/// - 第一个 `@context` 映射为情境，其余的和其他内容保留在标题中
+///
+/// 任务 ID 按行号依次分配，空行会被跳过。
+pub fn parse(content: &str) -> TodoResult<TodoList> {
+    let mut todo_list = TodoList::new();
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        todo_list.restore_task(parse_line(line)?);
+    }
+
+    Ok(todo_list)
+}
+
+/// 将待办列表序列化为 todo.txt 格式（每个任务一行）
+pub fn serialize(todo_list: &TodoList) -> String {
+    todo_list
+        .list_tasks()
+        .iter()
+        .map(|task| format_line(task) + "\n")
+        .collect()
+}
+
+/// 解析单行 todo.txt 任务
+pub fn parse_line(line: &str) -> TodoResult<Task> {
+    let mut tokens = line.split_whitespace().peekable();
+    let mut task = Task::new(0, String::new(), Priority::Medium, None);
+
+    if tokens.peek() == Some(&"x") {
+        task.completed = true;
+        tokens.next();
+    }
+
+    if let Some(priority) = tokens.peek().and_then(|token| parse_priority_token(token)) {
+        task.priority = priority;
+        tokens.next();
+    }
+
+    // 行首最多两个日期：已完成任务为「完成日期 创建日期」，否则为创建日期。
+    // 已完成任务只有一个日期时（没有完成时间的任务）也当作创建日期
+    let mut dates = Vec::new();
+    while dates.len() < 2 {
+        match tokens.peek().and_then(|token| parse_date(token).ok()) {
+            Some(date) => {
+                dates.push(date);
+                tokens.next();
+            }
+            None => break,
+        }
+    }
+    if let Some(created) = dates.last() {
+        task.created_at = *created;
+    }
+    if task.completed && dates.len() == 2 {
+        task.completed_at = Some(dates[0]);
+    }
+
+    let mut title_words = Vec::new();
+    for token in tokens {
+        if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            task.tags.push(project.to_string());
+        } else if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty() && task.context.is_none()) {
+            task.context = Some(context.to_string());
+        } else if let Some(due) = token.strip_prefix("due:") {
+            task.due_date = Some(parse_date(due)?);
+        } else if let Some(uuid) = token.strip_prefix("uuid:").filter(|u| !u.is_empty()) {
+            task.uuid = uuid.to_string();
+        } else if let Some(priority) = token.strip_prefix("pri:").and_then(priority_from_letter) {
+            task.priority = priority;
+        } else {
+            title_words.push(token);
+        }
+    }
+    task.title = title_words.join(" ");
+
+    Ok(task)
+}
+
+/// 将单个任务格式化为一行 todo.txt
+pub fn format_line(task: &Task) -> String {
+    let mut parts = Vec::new();
+    let letter = priority_to_letter(task.priority);
+
+    if task.completed {
+        parts.push("x".to_string());
+        if let Some(completed_at) = task.completed_at {
+            parts.push(local_date(completed_at).format(DATE_FORMAT).to_string());
+        }
+    } else {
+        parts.push(format!("({})", letter));
+    }
+
+    parts.push(local_date(task.created_at).format(DATE_FORMAT).to_string());
+    parts.push(task.title.clone());
+    if let Some(context) = &task.context {
+        parts.push(format!("@{}", context));
+    }
+    parts.extend(task.tags.iter().map(|tag| format!("+{}", tag)));
+
+    if let Some(due) = task.due_date {
+        parts.push(format!("due:{}", local_date(due).format(DATE_FORMAT)));
+    }
+
+    if task.completed {
+        parts.push(format!("pri:{}", letter));
+    }
+
+    parts.push(format!("uuid:{}", task.uuid));
+
+    parts.join(" ")
+}
+
+/// 解析 `(A)` 形式的优先级标记
+fn parse_priority_token(token: &str) -> Option<Priority> {
+    token
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .and_then(priority_from_letter)
+}
+
+/// 优先级字母转换为优先级
+fn priority_from_letter(letter: &str) -> Option<Priority> {
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some('A'), None) => Some(Priority::High),
+        (Some('B'), None) => Some(Priority::Medium),
+        (Some(c), None) if c.is_ascii_uppercase() => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+/// 优先级转换为字母
+fn priority_to_letter(priority: Priority) -> char {
+    match priority {
+        Priority::High => 'A',
+        Priority::Medium => 'B',
+        Priority::Low => 'C',
+    }
+}
+
+/// 解析 YYYY-MM-DD 日期（截止日期取当天结束，与 `--due` 一致）
+fn parse_date(date_str: &str) -> TodoResult<DateTime<Utc>> {
+    NaiveDate::parse_from_str(date_str, DATE_FORMAT)
+        .map(end_of_day)
+        .map_err(|_| TodoError::InvalidDateFormat(date_str.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pending_line() {
+        let task = parse_line("(A) 2025-01-01 Call mom @phone +family due:2025-01-05").unwrap();
+
+        assert!(!task.completed);
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.title, "Call mom");
+        assert_eq!(task.context.as_deref(), Some("phone"));
+        assert_eq!(task.tags, vec!["family"]);
+        assert_eq!(local_date(task.created_at).to_string(), "2025-01-01");
+        assert_eq!(local_date(task.due_date.unwrap()).to_string(), "2025-01-05");
+    }
+
+    #[test]
+    fn test_parse_completed_line() {
+        let task = parse_line("x 2025-01-03 2025-01-01 Pay rent pri:C").unwrap();
+
+        assert!(task.completed);
+        assert_eq!(task.priority, Priority::Low);
+        assert_eq!(task.title, "Pay rent");
+        assert_eq!(local_date(task.created_at).to_string(), "2025-01-01");
+        assert_eq!(local_date(task.completed_at.unwrap()).to_string(), "2025-01-03");
+    }
+
+    #[test]
+    fn test_parse_uuid() {
+        let task = parse_line("Pay rent uuid:0f8fad5b-d9cb-469f-a165-70867728950e").unwrap();
+
+        assert_eq!(task.title, "Pay rent");
+        assert_eq!(task.uuid, "0f8fad5b-d9cb-469f-a165-70867728950e");
+    }
+
+    #[test]
+    fn test_parse_plain_line() {
+        let task = parse_line("Just a title").unwrap();
+
+        assert!(!task.completed);
+        assert_eq!(task.priority, Priority::Medium);
+        assert_eq!(task.title, "Just a title");
+    }
+
+    #[test]
+    fn test_parse_invalid_due() {
+        let result = parse_line("Task due:tomorrow");
+        assert!(matches!(result, Err(TodoError::InvalidDateFormat(d)) if d == "tomorrow"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let content = "(A) 2025-01-01 Write report @office +work due:2025-02-01\n\
+                       x 2025-01-02 Buy milk +home pri:B\n\
+                       x 2025-01-04 2025-01-03 Pay rent pri:C\n";
+
+        let list = parse(content).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.list_tasks()[1].id, 2);
+
+        let reparsed = parse(&serialize(&list)).unwrap();
+        for (a, b) in list.list_tasks().iter().zip(reparsed.list_tasks()) {
+            assert_eq!(a.title, b.title);
+            assert_eq!(a.priority, b.priority);
+            assert_eq!(a.completed, b.completed);
+            assert_eq!(a.tags, b.tags);
+            assert_eq!(a.context, b.context);
+            assert_eq!(a.due_date, b.due_date);
+            assert_eq!(a.uuid, b.uuid);
+            assert_eq!(a.completed_at, b.completed_at);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::task::Task;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 回收站中的任务：删除时的任务内容和删除时间
+///
+/// 任务保留原来的 ID（ID 不会被重新分配），恢复后仍可以用同一个 ID 引用。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashedTask {
+    pub task: Task,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashedTask {
+    /// 以当前时间放入回收站
+    pub fn new(task: Task) -> Self {
+        Self {
+            task,
+            deleted_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::dates::{end_of_day, today};
+use crate::error::{TodoError, TodoResult};
+use crate::task::{Priority, Task};
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+
+/// 参与建议的最多相似任务数
+const MAX_MATCHES: usize = 5;
+
+/// 标题相似度的最低阈值（Jaccard 系数）
+const MIN_SIMILARITY: f64 = 0.25;
+
+/// 根据相似的历史任务给出的整理建议
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// 建议的标签
+    pub tags: Vec<String>,
+    /// 建议的优先级
+    pub priority: Option<Priority>,
+    /// 建议的截止日期（距今天数）
+    pub due_in_days: Option<i64>,
+    /// 作为依据的相似任务 ID
+    pub based_on: Vec<u32>,
+}
+
+impl Suggestion {
+    /// 是否没有任何可建议的内容
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.priority.is_none() && self.due_in_days.is_none()
+    }
+
+    /// 建议的截止日期（从今天起算，取当天结束）
+    pub fn due_date(&self) -> Option<DateTime<Utc>> {
+        let days = self.due_in_days?;
+        Some(end_of_day(today() + Duration::days(days)))
+    }
+}
+
+/// 将建议应用到任务上（标签合并，优先级和截止日期覆盖）
+pub fn apply(todo_list: &mut TodoList, id: u32, suggestion: &Suggestion) -> TodoResult<()> {
+    let mut tags = todo_list
+        .find_task(id)
+        .ok_or(TodoError::TaskNotFound(id))?
+        .tags
+        .clone();
+    for tag in &suggestion.tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    todo_list.set_tags(id, tags)?;
+
+    if let Some(priority) = suggestion.priority {
+        todo_list.set_priority(id, priority)?;
+    }
+
+    if let Some(due) = suggestion.due_date() {
+        todo_list.set_due_date(id, Some(due))?;
+    }
+
+    Ok(())
+}
+
+/// 收件箱中待整理的任务：未完成、没有标签也没有截止日期
+pub fn inbox(todo_list: &TodoList) -> Vec<&Task> {
+    todo_list
+        .list_tasks()
+        .iter()
+        .filter(|task| !task.completed && task.tags.is_empty() && task.due_date.is_none())
+        .collect()
+}
+
+/// 根据标题相似的其他任务为 `task` 生成建议，没有相似任务时返回 `None`
+///
+/// - 标签：至少一半相似任务都有的标签
+/// - 优先级：相似任务中最常见的优先级
+/// - 截止日期：相似任务从创建到截止的天数中位数
+pub fn suggest(todo_list: &TodoList, task: &Task) -> Option<Suggestion> {
+    let tokens = tokenize(&task.title);
+    if tokens.is_empty() {
+        return None;
+    }
+
+    let mut matches: Vec<(f64, &Task)> = todo_list
+        .list_tasks()
+        .iter()
+        .filter(|other| other.id != task.id)
+        .map(|other| (similarity(&tokens, &tokenize(&other.title)), other))
+        .filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .collect();
+
+    if matches.is_empty() {
+        return None;
+    }
+
+    matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+    matches.truncate(MAX_MATCHES);
+    let similar: Vec<&Task> = matches.into_iter().map(|(_, other)| other).collect();
+
+    let suggestion = Suggestion {
+        tags: common_tags(&similar),
+        priority: most_common_priority(&similar),
+        due_in_days: median_lead_days(&similar),
+        based_on: similar.iter().map(|t| t.id).collect(),
+    };
+
+    Some(suggestion).filter(|s| !s.is_empty())
+}
+
+/// 将标题拆分为小写词
+fn tokenize(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// 两组词的 Jaccard 相似度
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// 至少一半任务都有的标签（按出现次数排序）
+fn common_tags(tasks: &[&Task]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in tasks.iter().flat_map(|t| t.tags.iter()) {
+        *counts.entry(tag.as_str()).or_default() += 1;
+    }
+
+    let mut tags: Vec<(&str, usize)> = counts
+        .into_iter()
+        .filter(|(_, count)| count * 2 >= tasks.len())
+        .collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    tags.into_iter().map(|(tag, _)| tag.to_string()).collect()
+}
+
+/// 出现最多的优先级，数量相同时取较高的优先级
+fn most_common_priority(tasks: &[&Task]) -> Option<Priority> {
+    [Priority::High, Priority::Medium, Priority::Low]
+        .into_iter()
+        .map(|p| (p, tasks.iter().filter(|t| t.priority == p).count()))
+        .filter(|(_, count)| *count > 0)
+        .fold(None, |best: Option<(Priority, usize)>, (p, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((p, count)),
+        })
+        .map(|(p, _)| p)
+}
+
+/// 有截止日期的任务从创建到截止的天数中位数
+fn median_lead_days(tasks: &[&Task]) -> Option<i64> {
+    let mut days: Vec<i64> = tasks
+        .iter()
+        .filter_map(|t| t.due_date.map(|due| (due - t.created_at).num_days().max(0)))
+        .collect();
+
+    if days.is_empty() {
+        return None;
+    }
+
+    days.sort_unstable();
+    Some(days[days.len() / 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn add(list: &mut TodoList, title: &str, priority: Priority, tags: &[&str], due_days: Option<i64>) -> u32 {
+        let due = due_days.map(|days| Utc::now() + Duration::days(days) + Duration::hours(1));
+        let id = list.add_task(title.to_string(), priority, due);
+        list.set_tags(id, tags.iter().map(|t| t.to_string()).collect()).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_inbox() {
+        let mut list = TodoList::new();
+        let plain = add(&mut list, "Plain task", Priority::Medium, &[], None);
+        add(&mut list, "Tagged task", Priority::Medium, &["work"], None);
+        add(&mut list, "Due task", Priority::Medium, &[], Some(3));
+
+        let ids: Vec<u32> = inbox(&list).iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![plain]);
+    }
+
+    #[test]
+    fn test_suggest_from_similar_tasks() {
+        let mut list = TodoList::new();
+        add(&mut list, "Write weekly report", Priority::High, &["work", "report"], Some(2));
+        add(&mut list, "Write monthly report", Priority::High, &["work"], Some(4));
+        add(&mut list, "Buy groceries", Priority::Low, &["home"], None);
+        let new = add(&mut list, "Write quarterly report", Priority::Medium, &[], None);
+
+        let task = list.find_task(new).unwrap();
+        let suggestion = suggest(&list, task).unwrap();
+
+        assert_eq!(suggestion.tags, vec!["work", "report"]);
+        assert_eq!(suggestion.priority, Some(Priority::High));
+        assert_eq!(suggestion.due_in_days, Some(4));
+        assert_eq!(suggestion.based_on.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_suggestion() {
+        let mut list = TodoList::new();
+        let id = add(&mut list, "Inbox item", Priority::Medium, &["keep"], None);
+
+        let suggestion = Suggestion {
+            tags: vec!["work".to_string(), "keep".to_string()],
+            priority: Some(Priority::High),
+            due_in_days: Some(0),
+            based_on: vec![],
+        };
+        apply(&mut list, id, &suggestion).unwrap();
+
+        let task = list.find_task(id).unwrap();
+        assert_eq!(task.tags, vec!["keep", "work"]);
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(crate::dates::local_date(task.due_date.unwrap()), crate::dates::today());
+    }
+
+    #[test]
+    fn test_no_similar_tasks() {
+        let mut list = TodoList::new();
+        add(&mut list, "Buy groceries", Priority::Low, &["home"], None);
+        let new = add(&mut list, "Fix production bug", Priority::Medium, &[], None);
+
+        let task = list.find_task(new).unwrap();
+        assert!(suggest(&list, task).is_none());
+    }
+}
@@ -0,0 +1,267 @@
+use crate::error::TodoResult;
+use crate::storage::write_atomic;
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 最多保留的撤销步数
+pub const UNDO_LIMIT: usize = 10;
+
+/// 某次操作之前的列表内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// 操作的描述，例如 `add 写周报`
+    pub label: String,
+    /// 保存快照的时间
+    pub at: DateTime<Utc>,
+    list: TodoList,
+}
+
+/// 撤销和重做栈
+///
+/// 保存完整的列表快照而不是逆操作，任何修改（包括导入、合并等批量操作）都能撤销。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UndoStack {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// 最近一次 `checkpoint` 的快照，列表确实改变后才进入撤销栈
+    #[serde(skip)]
+    pending: Option<Box<Snapshot>>,
+}
+
+impl UndoStack {
+    /// 数据文件对应的撤销记录文件（同一目录下的隐藏文件，例如 `.todos.json.undo`）
+    pub fn path_for(data_path: &Path) -> PathBuf {
+        let file_name = data_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        data_path.with_file_name(format!(".{}.undo", file_name))
+    }
+
+    /// 从指定文件加载，文件不存在时返回空栈
+    pub fn load_from<P: AsRef<Path>>(path: P) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !path_ref.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path_ref)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存到指定文件
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> TodoResult<()> {
+        let content = serde_json::to_string(self)?;
+        write_atomic(path.as_ref(), content.as_bytes())
+    }
+
+    /// 从加密的文件加载（加密数据文件的撤销记录同样加密），文件未加密时按普通文件加载
+    #[cfg(feature = "encryption")]
+    pub fn load_encrypted<P: AsRef<Path>>(path: P, passphrase: &str) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !crate::storage::is_encrypted_file(path_ref)? {
+            return Self::load_from(path_ref);
+        }
+
+        let decrypted = crate::crypto::decrypt(&fs::read(path_ref)?, passphrase)?;
+        Ok(serde_json::from_slice(&decrypted)?)
+    }
+
+    /// 加密后保存到文件
+    #[cfg(feature = "encryption")]
+    pub fn save_encrypted<P: AsRef<Path>>(&self, path: P, passphrase: &str) -> TodoResult<()> {
+        let content = serde_json::to_vec(self)?;
+        write_atomic(path.as_ref(), &crate::crypto::encrypt(&content, passphrase)?)
+    }
+
+    /// 可以撤销的操作，最近的在最后
+    pub fn undo_labels(&self) -> Vec<&str> {
+        self.undo.iter().map(|snapshot| snapshot.label.as_str()).collect()
+    }
+
+    /// 可以重做的操作，最近撤销的在最后
+    pub fn redo_labels(&self) -> Vec<&str> {
+        self.redo.iter().map(|snapshot| snapshot.label.as_str()).collect()
+    }
+
+    /// 把待定的快照与当前列表比较：列表有变化时放入撤销栈并清空重做栈，否则丢弃
+    fn settle(&mut self, current: &TodoList) {
+        if let Some(pending) = self.pending.take() {
+            if !same_contents(&pending.list, current) {
+                push_limited(&mut self.undo, *pending);
+                self.redo.clear();
+            }
+        }
+    }
+}
+
+impl TodoList {
+    /// 在一次操作开始前调用，记下当前内容
+    ///
+    /// 操作没有改变列表时不会留下撤销记录，也不会清空重做栈。
+    ///
+    /// # 示例
+    /// ```
+    /// use rust_todo_cli::prelude::*;
+    ///
+    /// let mut list = TodoList::new();
+    /// list.checkpoint("add 写周报");
+    /// list.add_task("写周报".to_string(), Priority::High, None);
+    ///
+    /// assert_eq!(list.undo().as_deref(), Some("add 写周报"));
+    /// assert!(list.is_empty());
+    /// assert_eq!(list.redo().as_deref(), Some("add 写周报"));
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn checkpoint(&mut self, label: impl Into<String>) {
+        let snapshot = self.snapshot(label.into());
+        let mut stack = std::mem::take(self.undo_stack_mut());
+        stack.settle(self);
+        stack.pending = Some(Box::new(snapshot));
+        *self.undo_stack_mut() = stack;
+    }
+
+    /// 撤销最近一次操作，返回它的描述；没有可撤销的操作时返回 `None`
+    pub fn undo(&mut self) -> Option<String> {
+        let mut stack = std::mem::take(self.undo_stack_mut());
+        stack.settle(self);
+        let label = stack.undo.pop().map(|snapshot| {
+            let current = self.snapshot(snapshot.label.clone());
+            push_limited(&mut stack.redo, current);
+            self.restore_contents(snapshot.list);
+            snapshot.label
+        });
+        *self.undo_stack_mut() = stack;
+        label
+    }
+
+    /// 重做最近一次撤销的操作，返回它的描述；没有可重做的操作时返回 `None`
+    pub fn redo(&mut self) -> Option<String> {
+        let mut stack = std::mem::take(self.undo_stack_mut());
+        stack.settle(self);
+        let label = stack.redo.pop().map(|snapshot| {
+            let current = self.snapshot(snapshot.label.clone());
+            push_limited(&mut stack.undo, current);
+            self.restore_contents(snapshot.list);
+            snapshot.label
+        });
+        *self.undo_stack_mut() = stack;
+        label
+    }
+
+    /// 当前的撤销和重做栈（已处理待定的快照），用于保存到文件
+    pub fn undo_stack(&self) -> UndoStack {
+        let mut stack = self.undo_stack_ref().clone();
+        stack.settle(self);
+        stack
+    }
+
+    /// 替换撤销和重做栈，例如加载数据文件后恢复之前保存的记录
+    pub fn set_undo_stack(&mut self, stack: UndoStack) {
+        *self.undo_stack_mut() = stack;
+    }
+
+    fn snapshot(&self, label: String) -> Snapshot {
+        Snapshot {
+            label,
+            at: Utc::now(),
+            list: self.contents(),
+        }
+    }
+}
+
+/// 两份列表的内容是否相同（按序列化结果比较）
+fn same_contents(a: &TodoList, b: &TodoList) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 压入快照，超过上限时丢弃最旧的
+fn push_limited(stack: &mut Vec<Snapshot>, snapshot: Snapshot) {
+    stack.push(snapshot);
+    if stack.len() > UNDO_LIMIT {
+        stack.remove(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut list = TodoList::new();
+        assert!(list.undo().is_none());
+
+        list.checkpoint("add 写周报");
+        let id = list.add_task("写周报".to_string(), Priority::High, None);
+        list.checkpoint("done 1");
+        list.complete_task(id).unwrap();
+
+        assert_eq!(list.undo().as_deref(), Some("done 1"));
+        assert!(!list[id].completed);
+        assert_eq!(list.undo().as_deref(), Some("add 写周报"));
+        assert!(list.is_empty());
+        assert!(list.undo().is_none());
+
+        assert_eq!(list.redo().as_deref(), Some("add 写周报"));
+        assert_eq!(list.redo().as_deref(), Some("done 1"));
+        assert!(list[id].completed);
+        assert!(list.redo().is_none());
+        assert_eq!(list.undo_stack().undo_labels(), ["add 写周报", "done 1"]);
+    }
+
+    #[test]
+    fn test_unchanged_operation_keeps_redo() {
+        let mut list = TodoList::new();
+        list.checkpoint("add 写周报");
+        list.add_task("写周报".to_string(), Priority::High, None);
+        list.undo();
+
+        // 只读的操作不会清空重做栈
+        list.checkpoint("list");
+        assert_eq!(list.undo_stack().redo_labels(), ["add 写周报"]);
+
+        // 新的修改会清空重做栈
+        list.checkpoint("add 买牛奶");
+        list.add_task("买牛奶".to_string(), Priority::Low, None);
+        let stack = list.undo_stack();
+        assert_eq!(stack.undo_labels(), ["add 买牛奶"]);
+        assert!(stack.redo_labels().is_empty());
+    }
+
+    #[test]
+    fn test_undo_limit_and_persistence() {
+        let mut list = TodoList::new();
+        for i in 0..UNDO_LIMIT + 2 {
+            list.checkpoint(format!("add {}", i));
+            list.add_task(i.to_string(), Priority::Low, None);
+        }
+        let stack = list.undo_stack();
+        assert_eq!(stack.undo_labels().len(), UNDO_LIMIT);
+        assert_eq!(stack.undo_labels()[0], "add 2");
+
+        // 保存后在新加载的列表中继续撤销
+        let json = serde_json::to_string(&stack).unwrap();
+        let mut reloaded = list.clone();
+        reloaded.set_undo_stack(serde_json::from_str(&json).unwrap());
+        assert_eq!(reloaded.undo().as_deref(), Some("add 11"));
+        assert_eq!(reloaded.len(), UNDO_LIMIT + 1);
+    }
+
+    #[test]
+    fn test_path_for() {
+        assert_eq!(
+            UndoStack::path_for(Path::new("/data/todos.json")),
+            Path::new("/data/.todos.json.undo")
+        );
+    }
+}
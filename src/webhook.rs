@@ -0,0 +1,199 @@
+use crate::config::{data_dir_override, project_dirs, WebhookConfig, WebhookEvent};
+use crate::error::{TodoError, TodoResult};
+use crate::history::EventKind;
+use crate::storage::write_atomic;
+use crate::task::Task;
+use crate::todo_list::TodoList;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 已通知过期的任务记录文件名
+pub const WEBHOOK_STATE_FILE_NAME: &str = "webhooks.json";
+
+/// 发送请求的超时时间，Webhook 无响应时不会让命令一直卡住
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 发送给 Webhook 的消息
+///
+/// `text` 是一句可读的描述，Slack 等只认 `text` 字段的服务可以直接显示。
+#[derive(Debug, Clone, Serialize)]
+pub struct Payload {
+    pub event: WebhookEvent,
+    pub at: DateTime<Utc>,
+    pub text: String,
+    pub task: Task,
+}
+
+impl Payload {
+    pub fn new(event: WebhookEvent, task: &Task, at: DateTime<Utc>) -> Self {
+        let verb = match event {
+            WebhookEvent::Added => "added",
+            WebhookEvent::Completed => "completed",
+            WebhookEvent::Overdue => "is overdue",
+        };
+        Self {
+            event,
+            at,
+            text: format!("Task {} {}: {}", task.id, verb, task.title),
+            task: task.clone(),
+        }
+    }
+}
+
+/// 历史记录中第 `since` 条之后的新建和完成事件
+///
+/// 调用方在加载数据时记下历史记录的长度，保存时用它找出本次新产生的事件。
+/// 任务之后被删除时跳过对应的事件。
+pub fn history_payloads(todo_list: &TodoList, since: usize) -> Vec<Payload> {
+    todo_list
+        .history()
+        .iter()
+        .skip(since)
+        .filter_map(|event| {
+            let kind = match event.kind {
+                EventKind::Added => WebhookEvent::Added,
+                EventKind::Completed => WebhookEvent::Completed,
+                _ => return None,
+            };
+            let task = todo_list.find_task_by_uuid(&event.task_uuid)?;
+            Some(Payload::new(kind, task, event.at))
+        })
+        .collect()
+}
+
+/// 已经通知过的过期任务：UUID → 通知时的截止日期
+///
+/// 截止日期改变后再次过期会重新通知。
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OverdueState {
+    announced: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl OverdueState {
+    /// 默认路径（与数据文件在同一目录，Linux 下为 `~/.local/share/todo/webhooks.json`）
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(dir) = data_dir_override() {
+            return Some(dir.join(WEBHOOK_STATE_FILE_NAME));
+        }
+        project_dirs().map(|dirs| dirs.data_dir().join(WEBHOOK_STATE_FILE_NAME))
+    }
+
+    /// 从指定文件加载，文件不存在时返回空记录
+    pub fn load_from<P: AsRef<Path>>(path: P) -> TodoResult<Self> {
+        let path_ref = path.as_ref();
+
+        if !path_ref.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path_ref)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存到指定文件
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> TodoResult<()> {
+        let path_ref = path.as_ref();
+        if let Some(parent) = path_ref.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path_ref, content.as_bytes())
+    }
+
+    /// 找出在 `now` 之前过期、还没有通知过的任务并记下，同时忘掉已经不再过期的任务
+    pub fn newly_overdue(&mut self, todo_list: &TodoList, now: DateTime<Utc>) -> Vec<Payload> {
+        let overdue: BTreeMap<&str, (&Task, DateTime<Utc>)> = todo_list
+            .list_tasks()
+            .iter()
+            .filter(|task| !task.completed)
+            .filter_map(|task| task.due_date.filter(|due| *due < now).map(|due| (task.uuid.as_str(), (task, due))))
+            .collect();
+
+        self.announced.retain(|uuid, _| overdue.contains_key(uuid.as_str()));
+
+        let mut payloads = Vec::new();
+        for (uuid, (task, due)) in overdue {
+            if self.announced.get(uuid) != Some(&due) {
+                self.announced.insert(uuid.to_string(), due);
+                payloads.push(Payload::new(WebhookEvent::Overdue, task, now));
+            }
+        }
+        payloads
+    }
+}
+
+/// 把消息发送给订阅了对应事件的 Webhook，返回失败的原因（一个 Webhook 失败不影响其他的）
+pub fn deliver(webhooks: &[WebhookConfig], payloads: &[Payload]) -> Vec<TodoError> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .into();
+
+    let mut errors = Vec::new();
+    for payload in payloads {
+        for webhook in webhooks.iter().filter(|webhook| webhook.events.contains(&payload.event)) {
+            if let Err(err) = agent.post(&webhook.url).send_json(payload) {
+                errors.push(TodoError::Custom(format!("webhook {} failed: {}", webhook.url, err)));
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+    use chrono::Duration;
+
+    #[test]
+    fn test_history_payloads() {
+        let mut list = TodoList::new();
+        list.add_task("写周报".to_string(), Priority::Low, None);
+        let since = list.history().len();
+
+        let id = list.add_task("买牛奶".to_string(), Priority::Low, None);
+        list.complete_task(id).unwrap();
+        list.set_due_date(id, Some(Utc::now())).unwrap();
+        let removed = list.add_task("临时".to_string(), Priority::Low, None);
+        list.delete_task(removed).unwrap();
+
+        let payloads = history_payloads(&list, since);
+        let events: Vec<WebhookEvent> = payloads.iter().map(|payload| payload.event).collect();
+        assert_eq!(events, [WebhookEvent::Added, WebhookEvent::Completed]);
+        assert_eq!(payloads[1].text, "Task 2 completed: 买牛奶");
+
+        let value = serde_json::to_value(&payloads[0]).unwrap();
+        assert_eq!(value["event"], "added");
+        assert_eq!(value["task"]["title"], "买牛奶");
+    }
+
+    #[test]
+    fn test_overdue_is_announced_once_per_due_date() {
+        let now = Utc::now();
+        let mut list = TodoList::new();
+        let late = list.add_task("交房租".to_string(), Priority::High, Some(now - Duration::hours(1)));
+        list.add_task("以后再说".to_string(), Priority::Low, Some(now + Duration::days(3)));
+
+        let mut state = OverdueState::default();
+        let payloads = state.newly_overdue(&list, now);
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].text, "Task 1 is overdue: 交房租");
+        assert!(state.newly_overdue(&list, now).is_empty());
+
+        // 改期后再次过期会重新通知
+        list.set_due_date(late, Some(now + Duration::hours(1))).unwrap();
+        assert!(state.newly_overdue(&list, now).is_empty());
+        assert_eq!(state.newly_overdue(&list, now + Duration::hours(2)).len(), 1);
+
+        // 完成后不再记录
+        list.complete_task(late).unwrap();
+        assert!(state.newly_overdue(&list, now + Duration::hours(2)).is_empty());
+        assert!(state.announced.is_empty());
+    }
+}
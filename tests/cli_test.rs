@@ -0,0 +1,965 @@
+//! 端到端测试：在隔离的目录中运行编译好的 `todo` 程序
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rust_todo_cli::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+/// 每个测试独占的临时数据目录，测试结束时自动删除
+struct TestEnv {
+    dir: TempDir,
+}
+
+impl TestEnv {
+    fn new(name: &str) -> Self {
+        let dir = tempfile::Builder::new().prefix(&format!("rust-todo-cli-{}-", name)).tempdir().unwrap();
+        Self { dir }
+    }
+
+    /// 数据目录
+    fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::cargo_bin("rust-todo-cli").unwrap();
+        command
+            .arg("--data-dir")
+            .arg(self.path())
+            .args(args)
+            .env_remove("TODO_FILE")
+            .env("NO_COLOR", "1")
+            .env("TZ", "UTC")
+            .env_remove("LC_ALL")
+            .env_remove("LC_MESSAGES")
+            .env("LANG", "C");
+        command
+    }
+
+    /// 运行命令，不检查退出状态
+    fn run(&self, args: &[&str]) -> Output {
+        self.command(args).output().unwrap()
+    }
+
+    /// 运行命令并把 `input` 写入标准输入，要求成功，返回标准输出
+    fn ok_with_stdin(&self, args: &[&str], input: &str) -> String {
+        let assert = self.command(args).write_stdin(input).assert().success();
+        String::from_utf8(assert.get_output().stdout.clone()).unwrap()
+    }
+
+    /// 运行命令并要求成功，返回标准输出
+    fn ok(&self, args: &[&str]) -> String {
+        let assert = self.command(args).assert().success();
+        String::from_utf8(assert.get_output().stdout.clone()).unwrap()
+    }
+
+    /// 运行命令并要求失败，返回标准错误
+    fn fails(&self, args: &[&str]) -> String {
+        let assert = self.command(args).assert().failure();
+        String::from_utf8(assert.get_output().stderr.clone()).unwrap()
+    }
+
+    /// 直接读取数据文件
+    fn todo_list(&self) -> TodoList {
+        load_from_file(self.path().join("todos.json")).unwrap()
+    }
+}
+
+#[test]
+fn test_add_list_complete() {
+    let env = TestEnv::new("add-list-complete");
+
+    let output = env.ok(&["add", "写周报", "-p", "high", "-d", "2099-01-05", "-t", "work"]);
+    assert!(output.contains("(ID: 1)"));
+    env.ok(&["add", "买牛奶"]);
+
+    let output = env.ok(&["list"]);
+    assert!(output.contains("HIGH  写周报  2099-01-05  #work"));
+    assert!(output.contains("2 task(s)"));
+
+    env.ok(&["complete", "1"]);
+    let output = env.ok(&["list", "pending"]);
+    assert!(!output.contains("写周报"));
+    assert!(output.contains("买牛奶"));
+
+    let list = env.todo_list();
+    assert!(list.find_task(1).unwrap().completed);
+    assert_eq!(list.find_task(1).unwrap().priority, Priority::High);
+}
+
+#[test]
+fn test_delete_and_clear() {
+    let env = TestEnv::new("delete-clear");
+    env.ok(&["add", "父任务"]);
+    env.ok(&["add", "子任务", "--parent", "1"]);
+    env.ok(&["add", "已完成"]);
+    env.ok(&["complete", "3"]);
+
+    // 有子任务时用 --cascade 跳过确认提示
+    env.ok(&["delete", "1", "--cascade"]);
+    assert_eq!(env.todo_list().len(), 1);
+
+    env.ok(&["clear", "--force"]);
+    assert!(env.todo_list().is_empty());
+    assert!(env.ok(&["list"]).contains("No tasks found"));
+}
+
+#[test]
+fn test_trash_and_restore() {
+    let env = TestEnv::new("trash");
+    assert!(env.ok(&["trash", "list"]).contains("The trash is empty"));
+
+    env.ok(&["add", "写周报"]);
+    env.ok(&["add", "买牛奶"]);
+    assert!(env.ok(&["delete", "1"]).contains("todo restore 1"));
+    env.ok(&["delete", "2"]);
+
+    let output = env.ok(&["trash", "list"]);
+    assert!(output.contains("[1]  写周报  deleted "));
+    assert!(output.find("买牛奶").unwrap() < output.find("写周报").unwrap());
+
+    assert!(env.ok(&["restore", "1"]).contains("Task 1 '写周报' restored!"));
+    assert_eq!(env.todo_list().find_task(1).unwrap().title, "写周报");
+    assert!(env.fails(&["restore", "1"]).contains("not in the trash"));
+
+    // 刚删除的任务不会被按时间清理
+    assert!(env.ok(&["trash", "empty", "--older-than", "30d"]).contains("Nothing to remove"));
+    assert!(env.ok(&["trash", "empty"]).contains("Permanently deleted 1 task(s)"));
+    assert!(env.todo_list().trash().is_empty());
+    env.fails(&["restore", "2"]);
+}
+
+#[test]
+fn test_reset_requires_typed_confirmation() {
+    let env = TestEnv::new("reset");
+    assert!(env.ok(&["reset"]).contains("already empty"));
+
+    env.ok(&["add", "写周报"]);
+    env.ok(&["goal", "add", "发布 v1"]);
+    assert!(env.ok_with_stdin(&["reset"], "y\n").contains("Operation cancelled"));
+    assert_eq!(env.todo_list().len(), 1);
+
+    let output = env.ok_with_stdin(&["reset"], "reset\n");
+    assert!(output.contains("Deleted 1 task(s) and 1 goal(s)"));
+    assert!(output.contains("todo backup restore"));
+    let list = env.todo_list();
+    assert!(list.is_empty() && list.goals().is_empty());
+    assert!(env.path().join("backups").read_dir().unwrap().next().is_some());
+
+    env.ok(&["undo"]);
+    assert_eq!(env.todo_list().len(), 1);
+    env.ok(&["reset", "--force"]);
+    assert!(env.todo_list().is_empty());
+}
+
+#[test]
+fn test_configured_date_format() {
+    let env = TestEnv::new("date-format");
+    fs::write(env.path().join("config.toml"), "date_format = \"%d/%m/%Y\"\ntime_format = \"%H:%M\"\n").unwrap();
+    env.ok(&["add", "写周报", "-d", "2099-01-05"]);
+
+    assert!(env.ok(&["list"]).contains("写周报  05/01/2099"));
+    assert!(env.ok(&["show", "1"]).contains("Due Date: 05/01/2099 23:59\n"));
+
+    fs::write(env.path().join("config.toml"), "time_format = \"%H:%\"\n").unwrap();
+    assert!(env.fails(&["list"]).contains("invalid time_format"));
+}
+
+#[test]
+fn test_chinese_messages() {
+    let env = TestEnv::new("locale");
+    env.ok(&["add", "写周报", "-d", "2099-01-05"]);
+
+    let output = env.command(&["show", "1"]).env("LANG", "zh_CN.UTF-8").output().unwrap();
+    let output = String::from_utf8(output.stdout).unwrap();
+    assert!(output.contains("任务详情"));
+    assert!(output.contains("截止日期"));
+
+    env.command(&["show", "9"])
+        .env("LC_ALL", "zh_CN.UTF-8")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("未找到 ID 为 9 的任务"));
+
+    // LC_ALL 优先于 LANG
+    env.command(&["show", "9"])
+        .env("LANG", "zh_CN.UTF-8")
+        .env("LC_ALL", "en_US.UTF-8")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Task with ID 9 not found"));
+}
+
+#[test]
+fn test_dates_in_local_timezone() {
+    let env = TestEnv::new("local-timezone");
+    let in_tz = |args: &[&str]| {
+        let output = env.command(args).env("TZ", "XST-14").output().unwrap();
+        assert!(output.status.success(), "`todo {}` failed", args.join(" "));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // UTC+14 的 2099-01-05 结束时是 UTC 的 09:59:59
+    in_tz(&["add", "写周报", "--due", "2099-01-05"]);
+    let due = env.todo_list().find_task(1).unwrap().due_date.unwrap();
+    assert_eq!(due.to_rfc3339(), "2099-01-05T09:59:59+00:00");
+    assert!(in_tz(&["list"]).contains("写周报  2099-01-05"));
+
+    // 同一时刻在 UTC 下仍是 1 月 5 日，而 UTC-12 下是 1 月 4 日
+    assert!(env.ok(&["list"]).contains("写周报  2099-01-05"));
+    let output = env.command(&["list"]).env("TZ", "XST+12").output().unwrap();
+    assert!(String::from_utf8(output.stdout).unwrap().contains("写周报  2099-01-04"));
+}
+
+#[test]
+fn test_rrule_recurrence() {
+    let env = TestEnv::new("rrule");
+    // 2099-01-05 是当月第一个周一
+    env.ok(&["add", "月度复盘", "--due", "2099-01-05", "--every", "FREQ=MONTHLY;BYDAY=1MO"]);
+    env.ok(&["complete", "1"]);
+
+    let list = env.todo_list();
+    let next = list.find_task(2).unwrap();
+    assert_eq!(next.due_date.unwrap().format("%Y-%m-%d").to_string(), "2099-02-02");
+    assert_eq!(next.recurrence.unwrap().to_string(), "FREQ=MONTHLY;BYDAY=1MO");
+
+    let err = env.fails(&["add", "报表", "--due", "2099-01-05", "--every", "FREQ=WEEKLY;COUNT=3"]);
+    assert!(err.contains("Invalid RRULE: COUNT is not supported"));
+}
+
+#[test]
+fn test_gc_archives_completed_tasks() {
+    let env = TestEnv::new("gc");
+    env.ok(&["add", "写周报"]);
+    env.ok(&["add", "买牛奶"]);
+    env.ok(&["add", "交房租"]);
+    env.ok(&["complete", "1"]);
+    assert!(env.ok(&["gc"]).contains("No tasks completed before"));
+
+    let output = env.ok(&["gc", "--older-than", "2099-01-01", "--dry-run"]);
+    assert!(output.contains("写周报"));
+    assert_eq!(env.todo_list().len(), 3);
+
+    let output = env.ok(&["gc", "--older-than", "2099-01-01"]);
+    assert!(output.contains("Archived 1 task(s)"));
+    assert!(env.todo_list().find_task(1).is_none());
+
+    // 归档文件是普通的数据文件
+    let archive = env.path().join("todos.archive.json");
+    assert_eq!(load_from_file(&archive).unwrap()[1].title, "写周报");
+
+    // 配置了 archive_after_days 时，修改了数据的命令之后自动归档，只读的命令不改动数据文件
+    env.ok(&["complete", "2"]);
+    fs::write(env.path().join("config.toml"), "archive_after_days = 0\n").unwrap();
+    env.ok(&["list"]);
+    env.ok(&["count"]);
+    assert!(env.todo_list().find_task(2).is_some());
+
+    env.ok(&["complete", "3"]);
+    assert!(!env.ok(&["list"]).contains("买牛奶"));
+    let archived = load_from_file(&archive).unwrap();
+    assert_eq!(archived.list_tasks().iter().map(|task| task.id).collect::<Vec<_>>(), [1, 2, 3]);
+
+    // 自动归档与触发它的命令属于同一条撤销记录
+    assert!(env.ok(&["undo"]).contains("Undid `complete 3`"));
+    let list = env.todo_list();
+    assert!(list.find_task(2).unwrap().completed);
+    assert!(!list.find_task(3).unwrap().completed);
+}
+
+#[test]
+fn test_show_history() {
+    let env = TestEnv::new("show-history");
+    env.ok(&["add", "写周报", "-p", "low", "-t", "work"]);
+    env.ok(&["prioritize", "1", "high"]);
+    env.ok(&["postpone", "1", "2099-01-05"]);
+    env.ok(&["complete", "1"]);
+
+    let output = env.ok(&["show", "1", "--history"]);
+    let history = &output[output.find("History").unwrap()..];
+    let lines: Vec<&str> = history
+        .lines()
+        .filter_map(|line| line.split_once("  ").map(|(_, event)| event))
+        .collect();
+    assert_eq!(lines, ["created", "priority: low -> high", "due date: none -> 2099-01-05", "completed"]);
+    assert!(!env.ok(&["show", "1"]).contains("History"));
+}
+
+#[test]
+fn test_tag_defaults() {
+    let env = TestEnv::new("tag-defaults");
+    fs::write(env.path().join("config.toml"), "[tags.work]\npriority = \"high\"\ncontext = \"office\"\n").unwrap();
+
+    let output = env.ok(&["add", "写周报", "-t", "work"]);
+    assert!(output.contains("Applied defaults from #work: priority, context"));
+    env.ok(&["add", "开会", "-t", "work", "-c", "home"]);
+
+    let list = env.todo_list();
+    let report = list.find_task(1).unwrap();
+    assert_eq!(report.priority, Priority::High);
+    assert_eq!(report.context.as_deref(), Some("office"));
+    assert_eq!(list.find_task(2).unwrap().context.as_deref(), Some("home"));
+
+    let output = env.ok(&["show", "1", "--history"]);
+    assert!(output.contains("defaults from #work: priority, context"));
+    assert!(env.ok(&["show", "2", "--history"]).contains("defaults from #work: priority\n"));
+}
+
+#[test]
+fn test_done_lists_recently_completed_tasks() {
+    let env = TestEnv::new("done");
+    assert!(env.ok(&["done"]).contains("Nothing completed since today"));
+
+    env.ok(&["add", "写周报"]);
+    env.ok(&["add", "买牛奶"]);
+    env.ok(&["add", "交房租"]);
+    env.ok(&["complete", "2"]);
+    env.ok(&["complete", "1"]);
+
+    let output = env.ok(&["done"]);
+    assert!(output.contains("Done since today"));
+    assert!(output.contains("2 task(s)"));
+    assert!(output.find("买牛奶").unwrap() < output.find("写周报").unwrap());
+    assert!(!output.contains("交房租"));
+
+    assert!(env.ok(&["done", "--since", "yesterday"]).contains("2 task(s)"));
+    assert!(env.fails(&["done", "--since", "last week"]).contains("Invalid period"));
+}
+
+#[test]
+fn test_duplicate() {
+    let env = TestEnv::new("duplicate");
+    env.ok(&["add", "写周报", "-p", "high", "-t", "work", "-d", "2099-01-05"]);
+    env.ok(&["complete", "1"]);
+
+    let output = env.ok(&["duplicate", "1", "--due", "2099-01-12"]);
+    assert!(output.contains("Task 1 duplicated as task 2: '写周报'"));
+    assert!(output.contains("Due: 2099-01-12"));
+
+    let list = env.todo_list();
+    let copy = list.find_task(2).unwrap();
+    assert!(!copy.completed);
+    assert_eq!(copy.priority, Priority::High);
+    assert_eq!(copy.tags, ["work"]);
+
+    env.ok(&["dup", "1"]);
+    assert_eq!(env.todo_list().find_task(3).unwrap().due_date, None);
+    assert!(env.fails(&["duplicate", "9"]).contains("not found"));
+}
+
+#[test]
+fn test_undo_and_redo() {
+    let env = TestEnv::new("undo");
+    assert!(env.ok(&["undo"]).contains("Nothing to undo"));
+
+    env.ok(&["add", "写周报"]);
+    env.ok(&["add", "子任务", "--parent", "1"]);
+    env.ok(&["delete", "1", "--cascade"]);
+    env.ok(&["list"]);
+
+    assert!(env.ok(&["undo"]).contains("Undid `delete 1 --cascade`"));
+    assert_eq!(env.todo_list().len(), 2);
+    assert!(env.ok(&["redo"]).contains("Redid `delete 1 --cascade`"));
+    assert!(env.todo_list().is_empty());
+
+    env.ok(&["undo"]);
+    env.ok(&["undo"]);
+    assert_eq!(env.todo_list().len(), 1);
+    // 新的修改之后不能再重做
+    env.ok(&["add", "买牛奶"]);
+    assert!(env.ok(&["redo"]).contains("Nothing to redo"));
+}
+
+#[test]
+fn test_journal_mode() {
+    let env = TestEnv::new("journal");
+    fs::write(env.path().join("config.toml"), "journal = true\n").unwrap();
+    let journal = env.path().join(".todos.json.journal");
+
+    env.ok(&["add", "写周报"]);
+    env.ok(&["add", "买牛奶"]);
+    env.ok(&["complete", "1"]);
+
+    // 修改追加在日志中，数据文件还是空的
+    assert!(journal.exists());
+    assert!(env.todo_list().is_empty());
+    let output = env.ok(&["list", "all"]);
+    assert!(output.contains("写周报") && output.contains("买牛奶"));
+
+    // 直接读写数据文件的操作之前先合并日志
+    env.fails(&["backup", "restore", "20250101T120000.000"]);
+    assert!(!journal.exists());
+    let list = env.todo_list();
+    assert_eq!(list.len(), 2);
+    assert!(list[1].completed);
+}
+
+#[test]
+fn test_convert_to_binary() {
+    let env = TestEnv::new("convert");
+    env.ok(&["add", "写周报", "-t", "work"]);
+    env.ok(&["add", "买牛奶"]);
+
+    let output = env.ok(&["convert", "binary"]);
+    assert!(output.contains("Converted 2 task(s) to binary"));
+    assert!(env.path().join("todos.bin").exists());
+    assert!(env.path().join("todos.json").exists());
+
+    // 配置文件选择格式后使用二进制文件
+    fs::write(env.path().join("config.toml"), "format = \"binary\"\n").unwrap();
+    env.ok(&["complete", "1"]);
+    let output = env.ok(&["list", "completed"]);
+    assert!(output.contains("写周报"));
+    assert!(!env.todo_list().list_tasks()[0].completed);
+
+    // 转换回 JSON 时先备份已有的文件
+    env.ok(&["convert", "json"]);
+    assert!(env.path().join("todos.json.bak").exists());
+    assert!(env.todo_list().list_tasks()[0].completed);
+}
+
+#[test]
+fn test_compressed_data_file() {
+    let env = TestEnv::new("compress");
+    env.ok(&["add", "写周报"]);
+
+    // 开启压缩后已有的数据文件被压缩，原文件备份为 .bak
+    fs::write(env.path().join("config.toml"), "compress = true\n").unwrap();
+    let output = env.ok(&["add", "买牛奶"]);
+    assert!(output.contains("Compressed"));
+    let compressed = env.path().join("todos.json.gz");
+    assert!(fs::read(&compressed).unwrap().starts_with(&[0x1f, 0x8b]));
+    assert!(!env.path().join("todos.json").exists());
+    assert!(env.path().join("todos.json.bak").exists());
+
+    let list = load_from_file(&compressed).unwrap();
+    assert_eq!(list.len(), 2);
+    assert!(env.ok(&["list"]).contains("买牛奶"));
+}
+
+#[test]
+fn test_corrupted_data_file_falls_back_to_backup() {
+    let env = TestEnv::new("corrupt");
+    env.ok(&["add", "写周报"]);
+    env.ok(&["add", "买牛奶"]);
+    assert!(env.ok(&["doctor"]).contains("checksum verified"));
+
+    // 写到一半的数据文件
+    let path = env.path().join("todos.json");
+    fs::write(&path, "{\"version\": 6, \"tasks\": [").unwrap();
+
+    // 加载最近的备份（第二次保存前的内容），损坏的文件留作排查
+    let output = env.ok(&["list"]);
+    assert!(output.contains("could not be read") && output.contains("写周报"));
+    assert!(env.path().join("todos.json.corrupt").exists());
+
+    let output = env.ok(&["doctor"]);
+    assert!(output.contains("is damaged"));
+    assert!(output.contains("latest readable backup"));
+
+    env.ok(&["doctor", "--fix"]);
+    assert_eq!(env.todo_list().len(), 1);
+    assert!(env.ok(&["doctor"]).contains("No problems found"));
+}
+
+#[test]
+fn test_errors_exit_with_failure() {
+    let env = TestEnv::new("errors");
+
+    let stderr = env.fails(&["complete", "42"]);
+    assert!(stderr.contains("Task with ID 42 not found"));
+
+    let stderr = env.fails(&["add", "任务", "-d", "tomorrow-ish"]);
+    assert!(stderr.contains("Invalid date format"));
+}
+
+#[test]
+fn test_data_dir_isolates_session() {
+    let env = TestEnv::new("session");
+    env.ok(&["add", "写论文", "-t", "deepwork"]);
+    env.ok(&["add", "买牛奶"]);
+
+    env.ok(&["focus", "--tag", "deepwork"]);
+    assert!(env.path().join("session.json").exists());
+
+    let output = env.ok(&["list"]);
+    assert!(output.contains("写论文"));
+    assert!(!output.contains("买牛奶"));
+
+    env.ok(&["focus", "--off"]);
+    assert!(env.ok(&["list"]).contains("买牛奶"));
+}
+
+#[test]
+fn test_agenda_respects_focus() {
+    let env = TestEnv::new("agenda-focus");
+    // 测试命令在 UTC 下运行
+    let today = chrono::Utc::now().date_naive().to_string();
+    env.ok(&["add", "写论文", "-t", "deepwork", "-d", &today]);
+    env.ok(&["add", "买牛奶", "-d", &today]);
+
+    env.ok(&["focus", "--tag", "deepwork"]);
+    let output = env.ok(&["agenda"]);
+    assert!(output.contains("写论文"));
+    assert!(!output.contains("买牛奶"));
+    assert!(output.contains("Focusing on #deepwork"));
+
+    env.ok(&["focus", "--off"]);
+    assert!(env.ok(&["agenda"]).contains("买牛奶"));
+}
+
+#[test]
+fn test_context_filter() {
+    let env = TestEnv::new("context");
+    env.ok(&["add", "修水龙头", "-c", "@home"]);
+    env.ok(&["add", "写周报", "--context", "work"]);
+
+    let output = env.ok(&["list", "--context", "@home"]);
+    assert!(output.contains("修水龙头  @home"));
+    assert!(!output.contains("写周报"));
+
+    assert_eq!(env.todo_list().find_task(2).unwrap().context.as_deref(), Some("work"));
+}
+
+#[test]
+fn test_list_due_today_and_this_week() {
+    use chrono::{Datelike, Duration, Utc};
+
+    let env = TestEnv::new("today-week");
+    // 测试命令在 UTC 下运行
+    let today = Utc::now().date_naive();
+    let sunday = today + Duration::days(6 - i64::from(today.weekday().num_days_from_monday()));
+    let next_week = sunday + Duration::days(1);
+    env.ok(&["add", "交日报", "-d", &today.to_string()]);
+    env.ok(&["add", "周末大扫除", "-d", &sunday.to_string()]);
+    env.ok(&["add", "下周例会", "-d", &next_week.to_string()]);
+
+    let output = env.ok(&["list", "today"]);
+    assert!(output.contains("Due Today") && output.contains("交日报"));
+    assert!(!output.contains("下周例会"));
+    if sunday != today {
+        assert!(!output.contains("周末大扫除"));
+    }
+
+    let output = env.ok(&["list", "week"]);
+    assert!(output.contains("交日报") && output.contains("周末大扫除"));
+    assert!(!output.contains("下周例会"));
+
+    let output = env.ok(&["list", "-q", "week AND NOT today"]);
+    assert!(!output.contains("交日报"));
+    assert_eq!(output.contains("周末大扫除"), sunday != today);
+}
+
+#[test]
+fn test_search() {
+    let env = TestEnv::new("search");
+    env.ok(&["add", "Fix crash on prod"]);
+    env.ok(&["add", "Update prod runbook"]);
+    env.ok(&["add", "Fix typo"]);
+
+    let output = env.ok(&["search", "prod"]);
+    assert!(output.contains("Fix crash on prod") && output.contains("Update prod runbook"));
+    assert!(output.contains("2 task(s)"));
+
+    let output = env.ok(&["search", "--regex", "^Fix .*prod"]);
+    assert!(output.contains("Fix crash on prod"));
+    assert!(output.contains("1 task(s)"));
+
+    let err = env.fails(&["search", "--regex", "(prod"]);
+    assert!(err.contains("Invalid regular expression '(prod': unclosed group"));
+}
+
+#[test]
+fn test_count() {
+    let env = TestEnv::new("count");
+    assert_eq!(env.ok(&["count"]), "0\n");
+
+    env.ok(&["add", "写周报", "-t", "work", "-d", "2020-01-05"]);
+    env.ok(&["add", "开会", "-t", "work", "-p", "high"]);
+    env.ok(&["add", "买牛奶"]);
+    env.ok(&["complete", "2"]);
+    // 严重过期提醒不会混进输出
+    fs::write(env.path().join("config.toml"), "alert_bell = true\n").unwrap();
+
+    assert_eq!(env.ok(&["count"]), "3\n");
+    assert_eq!(env.ok(&["count", "pending"]), "2\n");
+    assert_eq!(env.ok(&["count", "completed"]), "1\n");
+    assert_eq!(env.ok(&["count", "overdue"]), "1\n");
+    assert_eq!(env.ok(&["count", "pending", "--tag", "#work"]), "1\n");
+    assert_eq!(env.ok(&["count", "-q", "priority:high OR title:牛奶"]), "2\n");
+}
+
+#[test]
+fn test_combined_list_filters() {
+    let env = TestEnv::new("combined-filters");
+    env.ok(&["add", "交季度报告", "-p", "high", "-d", "2025-06-30", "-t", "work"]);
+    env.ok(&["add", "整理邮箱", "-p", "low", "-d", "2025-06-30", "-t", "work"]);
+    env.ok(&["add", "准备年会", "-p", "high", "-d", "2025-07-15", "-t", "work"]);
+    env.ok(&["add", "买机票", "-p", "high", "-d", "2025-06-01", "-t", "home"]);
+
+    let output = env.ok(&["list", "pending", "--priority", "high", "--due-before", "2025-07-01", "--tag", "#work"]);
+    assert!(output.contains("交季度报告"));
+    for title in ["整理邮箱", "准备年会", "买机票"] {
+        assert!(!output.contains(title), "{} should be filtered out", title);
+    }
+
+    assert!(env.fails(&["list", "--priority", "urgent"]).contains("urgent"));
+
+    let output = env.ok(&["list", "--due-after", "2025-06-01", "--due-before", "2025-07-15"]);
+    assert!(output.contains("交季度报告") && output.contains("整理邮箱"));
+    assert!(!output.contains("准备年会") && !output.contains("买机票"));
+    assert!(env.fails(&["list", "--due-after", "friday"]).contains("Invalid date format"));
+}
+
+#[test]
+fn test_list_query() {
+    let env = TestEnv::new("query");
+    env.ok(&["add", "交季度报告", "-p", "high", "-t", "work"]);
+    env.ok(&["add", "交房租", "-p", "high", "-d", "2020-01-01"]);
+    env.ok(&["add", "整理邮箱", "-p", "low", "-t", "work"]);
+
+    let output = env.ok(&["list", "--query", "priority:high AND (tag:work OR overdue)"]);
+    assert!(output.contains("交季度报告"));
+    assert!(output.contains("交房租"));
+    assert!(!output.contains("整理邮箱"));
+
+    assert!(env.fails(&["list", "--query", "(tag:work"]).contains("Invalid query: missing ')'"));
+}
+
+#[test]
+fn test_demo_uses_sample_data() {
+    let env = TestEnv::new("demo");
+    env.ok(&["add", "买牛奶"]);
+
+    let output = env.ok(&["demo", "list", "overdue"]);
+    assert!(output.contains("Renew passport"));
+    assert!(!output.contains("买牛奶"));
+
+    // 演示中的修改不会影响自己的数据
+    env.ok(&["demo", "complete", "1"]);
+    let list = env.todo_list();
+    assert_eq!(list.len(), 1);
+    assert!(!list.find_task(1).unwrap().completed);
+}
+
+#[test]
+fn test_complete_and_delete_by_title() {
+    let env = TestEnv::new("match");
+    env.ok(&["add", "Buy groceries"]);
+    env.ok(&["add", "Buy milk"]);
+    env.ok(&["add", "Call the plumber"]);
+
+    let error = env.fails(&["complete", "--match", "buy"]);
+    assert!(error.contains("'buy' matches 2 tasks"));
+    assert!(error.contains("[1] Buy groceries"));
+    assert!(error.contains("[2] Buy milk"));
+
+    env.ok(&["complete", "--match", "GROCERIES"]);
+    env.ok(&["delete", "--match", "plumber"]);
+    assert!(env.fails(&["complete", "--match", "pizza"]).contains("No task matches 'pizza'"));
+
+    let list = env.todo_list();
+    assert!(list.find_task(1).unwrap().completed);
+    assert!(list.find_task(3).is_none());
+}
+
+#[test]
+fn test_completions() {
+    let env = TestEnv::new("completions");
+    let script = env.ok(&["completions", "bash"]);
+    assert!(script.contains("_todo()"));
+    assert!(script.contains("todo __ids"));
+    assert!(env.ok(&["completions", "fish"]).contains("__fish_seen_subcommand_from complete"));
+
+    env.ok(&["add", "Buy milk"]);
+    env.ok(&["add", "Call mom"]);
+    env.ok(&["complete", "1"]);
+    assert_eq!(env.ok(&["__ids"]), "2\tCall mom\n");
+}
+
+#[test]
+fn test_man_pages() {
+    let env = TestEnv::new("man");
+    assert!(env.ok(&["man"]).contains(".TH todo 1"));
+
+    let dir = env.path().join("man");
+    env.ok(&["man", "--out-dir", dir.to_str().unwrap()]);
+    assert!(dir.join("todo.1").exists());
+    assert!(fs::read_to_string(dir.join("todo-add.1")).unwrap().contains(".TH todo-add 1"));
+    // 隐藏的命令不生成页面
+    assert!(!dir.join("todo-man.1").exists());
+}
+
+#[test]
+fn test_redirected_output_is_plain() {
+    let env = TestEnv::new("plain-output");
+
+    let output = env.ok(&["add", "写周报", "-d", "2099-01-05"]);
+    assert!(output.contains("Task added"));
+
+    // 测试中标准输出是管道，不含颜色和 emoji
+    let output = env.ok(&["list", "--no-color"]);
+    assert!(output.contains("写周报  2099-01-05"));
+    assert!(!output.contains('\u{1b}'));
+    assert!(!output.contains('📅'));
+
+    // 纯文本同时只使用 ASCII 符号，子任务和完成状态也一样
+    env.ok(&["add", "Sub", "--parent", "1"]);
+    env.ok(&["complete", "2"]);
+    for args in [&["list", "all"][..], &["show", "1"], &["show", "2"]] {
+        let output = env.ok(args);
+        assert!(output.chars().all(|c| c.is_ascii() || "写周报".contains(c)), "{}", output);
+    }
+    assert!(env.ok(&["list", "all"]).contains("[x]"));
+}
+
+#[test]
+fn test_ascii_output() {
+    let env = TestEnv::new("ascii-output");
+    env.ok(&["add", "写周报", "-d", "2099-01-05", "-e", "1h30m"]);
+    env.ok(&["add", "买牛奶"]);
+
+    let output = env.ok(&["complete", "2", "--ascii"]);
+    assert!(output.contains("[ok] Task 2 marked as completed!"));
+
+    let output = env.ok(&["list", "--ascii"]);
+    assert!(output.contains("[ ]   1  MED  写周报 (1h30m)  2099-01-05"));
+    assert!(output.contains("[x]   2  MED  买牛奶"));
+
+    // 除了任务标题，所有字符都是 ASCII
+    let output = output.replace("写周报", "").replace("买牛奶", "");
+    assert!(output.is_ascii(), "{}", output);
+}
+
+#[test]
+fn test_list_group_by() {
+    let env = TestEnv::new("group-by");
+    env.ok(&["add", "写周报", "-p", "high", "-t", "work"]);
+    env.ok(&["add", "修水龙头", "-t", "home"]);
+    env.ok(&["add", "交房租", "-p", "high", "-t", "home"]);
+
+    let output = env.ok(&["list", "--group-by", "priority"]);
+    let high = output.find("High (2)").unwrap();
+    let medium = output.find("Medium (1)").unwrap();
+    assert!(high < output.find("写周报").unwrap() && output.find("交房租").unwrap() < medium);
+    assert!(medium < output.find("修水龙头").unwrap());
+    assert!(output.contains("3 task(s)"));
+
+    let output = env.ok(&["list", "-g", "tag"]);
+    assert!(output.find("#home (2)").unwrap() < output.find("#work (1)").unwrap());
+}
+
+#[test]
+fn test_list_sort_and_pagination() {
+    let env = TestEnv::new("pagination");
+    env.ok(&["add", "没有日期"]);
+    env.ok(&["add", "下周", "-d", "2099-01-12"]);
+    env.ok(&["add", "明天", "-d", "2099-01-06"]);
+
+    let output = env.ok(&["list", "--sort", "due", "--limit", "2"]);
+    assert!(output.find("明天").unwrap() < output.find("下周").unwrap());
+    assert!(!output.contains("没有日期"));
+    assert!(output.contains("Showing tasks 1-2 of 3"));
+
+    let output = env.ok(&["list", "--sort", "due", "--offset", "2"]);
+    assert!(output.contains("没有日期"));
+    assert!(!output.contains("明天"));
+}
+
+#[test]
+fn test_list_sort_by_urgency() {
+    let env = TestEnv::new("urgency");
+    env.ok(&["add", "不急", "-p", "low"]);
+    env.ok(&["add", "重要", "-p", "high"]);
+    env.ok(&["add", "已过期", "-p", "low", "-d", "2000-01-01"]);
+
+    let output = env.ok(&["list", "--sort", "urgency"]);
+    let position = |title| output.find(title).unwrap();
+    assert!(position("已过期") < position("重要"));
+    assert!(position("重要") < position("不急"));
+}
+
+#[test]
+fn test_reopen_clears_completion_time() {
+    let env = TestEnv::new("reopen");
+    env.ok(&["add", "写周报"]);
+    env.ok(&["complete", "1"]);
+    assert!(env.ok(&["show", "1"]).contains("Completed: "));
+
+    assert!(env.ok(&["reopen", "1"]).contains("Task 1 reopened"));
+    let task = env.todo_list().find_task(1).unwrap().clone();
+    assert!(!task.completed);
+    assert!(task.completed_at.is_none());
+    assert!(!env.ok(&["show", "1"]).contains("Completed: "));
+}
+
+#[test]
+fn test_add_from_stdin() {
+    let env = TestEnv::new("add-stdin");
+    let output = env.ok_with_stdin(&["add", "--stdin", "-t", "inbox"], "买牛奶\n\n写周报\n  开会  \n");
+    assert!(output.contains("3 tasks added successfully!  (IDs: 1, 2, 3)"));
+
+    let list = env.todo_list();
+    let titles: Vec<&str> = list.list_tasks().iter().map(|task| task.title.as_str()).collect();
+    assert_eq!(titles, ["买牛奶", "写周报", "开会"]);
+    assert!(list.list_tasks().iter().all(|task| task.tags == ["inbox"]));
+
+    assert!(env.ok_with_stdin(&["add", "--stdin"], "\n").contains("nothing added"));
+}
+
+#[test]
+fn test_add_from_template() {
+    let env = TestEnv::new("templates");
+    env.ok(&["add", "发布 v1", "-p", "high", "-t", "release", "-e", "2h"]);
+    env.ok(&["add", "更新日志", "--parent", "1"]);
+    env.ok(&["add", "打标签", "--parent", "1"]);
+    env.ok(&["template", "save", "release", "--from", "1"]);
+    assert!(env.ok(&["template", "list"]).contains("High, #release, 2h, 2 checklist item(s)"));
+
+    env.ok(&["add", "发布 v2", "--template", "release", "-t", "urgent"]);
+    let list = env.todo_list();
+    let task = list.find_task(4).unwrap();
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.tags, ["release", "urgent"]);
+    assert_eq!(task.estimate_minutes, Some(120));
+    let checklist: Vec<&str> = list.children(4).iter().map(|child| child.title.as_str()).collect();
+    assert_eq!(checklist, ["更新日志", "打标签"]);
+
+    assert!(env.fails(&["add", "x", "--template", "missing"]).contains("Template 'missing' not found"));
+    env.ok(&["template", "delete", "release"]);
+    assert!(env.ok(&["template", "list"]).contains("No templates yet"));
+}
+
+#[test]
+fn test_blocked_task_cannot_be_completed() {
+    let env = TestEnv::new("dependencies");
+    env.ok(&["add", "设计"]);
+    env.ok(&["add", "开发"]);
+    env.ok(&["block", "2", "--on", "1"]);
+
+    assert!(env.fails(&["block", "1", "--on", "2"]).contains("cycle"));
+    assert!(env.fails(&["complete", "2"]).contains("blocked by unfinished task(s) 1"));
+    assert!(env.ok(&["list"]).contains("blocked by 1"));
+    assert!(!env.ok(&["list", "--hide-blocked"]).contains("开发"));
+
+    env.ok(&["complete", "1"]);
+    env.ok(&["complete", "2"]);
+    assert!(env.todo_list().find_task(2).unwrap().completed);
+}
+
+#[test]
+fn test_sync_git_commits_locally_without_remote() {
+    let env = TestEnv::new("git-sync");
+    env.ok(&["add", "写周报"]);
+    assert!(env.fails(&["sync", "git"]).contains("not a git repository"));
+
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git").arg("-C").arg(env.path()).args(args).status().unwrap();
+        assert!(status.success(), "git {} failed", args.join(" "));
+    };
+    git(&["init", "--quiet"]);
+    git(&["config", "user.name", "todo"]);
+    git(&["config", "user.email", "todo@example.com"]);
+
+    let output = env.ok(&["sync", "git"]);
+    assert!(output.contains("local changes committed"));
+    assert!(output.contains("remote 'origin' not configured"));
+    assert!(env.ok(&["sync", "git"]).contains("nothing to commit"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_scripts_receive_task_json() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = TestEnv::new("hooks");
+    let hooks = env.path().join("hooks");
+    fs::create_dir_all(&hooks).unwrap();
+    for name in ["on-add", "on-complete", "on-delete"] {
+        let script = hooks.join(name);
+        fs::write(&script, format!("#!/bin/sh\ncat >> '{}'\n", env.path().join("events.log").display())).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    env.ok(&["add", "写周报"]);
+    env.ok(&["complete", "1"]);
+    env.ok(&["delete", "1"]);
+
+    let log = fs::read_to_string(env.path().join("events.log")).unwrap();
+    let events: Vec<serde_json::Value> = log.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(events.len(), 3);
+    assert!(events.iter().all(|task| task["title"] == "写周报"));
+    assert_eq!(events[1]["completed"], true);
+}
+
+#[test]
+fn test_merge_other_data_file() {
+    let env = TestEnv::new("merge");
+    env.ok(&["add", "写周报", "--tag", "work"]);
+    let base = env.path().join("base.json");
+    fs::copy(env.path().join("todos.json"), &base).unwrap();
+
+    // 另一份副本改了情境并加了标签，本地改了优先级
+    let mut other = env.todo_list();
+    other.set_context(1, Some("office".to_string())).unwrap();
+    other.set_tags(1, vec!["work".to_string(), "report".to_string()]).unwrap();
+    other.add_task("开会".to_string(), Priority::Low, None);
+    let other_path = env.path().join("other.json");
+    save_to_file(&other, &other_path).unwrap();
+    env.ok(&["prioritize", "1", "high"]);
+
+    let output = env.ok(&["merge", other_path.to_str().unwrap(), "--base", base.to_str().unwrap()]);
+    assert!(output.contains("1 added, 1 updated, 0 removed"));
+
+    let list = env.todo_list();
+    let task = list.find_task(1).unwrap();
+    assert_eq!(task.context.as_deref(), Some("office"));
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.tags, ["work", "report"]);
+    assert_eq!(list.len(), 2);
+    assert!(env.fails(&["merge", "missing.json"]).contains("No such file"));
+}
+
+#[test]
+fn test_edit_task() {
+    let env = TestEnv::new("edit");
+    env.ok(&["add", "写周报", "-d", "2099-01-05", "-t", "work", "-c", "office"]);
+
+    let output = env.ok(&["edit", "1", "--title", "写月报", "-p", "high", "-t", "#report", "-t", "team", "--no-due"]);
+    assert!(output.contains("Task 1 updated"));
+    let task = env.todo_list().find_task(1).unwrap().clone();
+    assert_eq!(task.title, "写月报");
+    assert_eq!(task.priority, Priority::High);
+    assert_eq!(task.tags, ["report", "team"]);
+    assert_eq!(task.due_date, None);
+    assert_eq!(task.context.as_deref(), Some("office"));
+
+    // 无效的值不修改任何字段
+    assert!(env.fails(&["edit", "1", "--title", "新标题", "-p", "urgent"]).contains("Invalid priority"));
+    assert_eq!(env.todo_list().find_task(1).unwrap().title, "写月报");
+    assert!(!env.run(&["edit", "1"]).status.success());
+
+    env.ok(&["edit", "1", "--no-tags", "--no-context"]);
+    let task = env.todo_list().find_task(1).unwrap().clone();
+    assert!(task.tags.is_empty());
+    assert_eq!(task.context, None);
+}
+
+#[test]
+fn test_shell_dispatches_commands() {
+    let env = TestEnv::new("shell");
+    let input = "add \"写 周报\" -p high\n\nadd 买牛奶\ndemo list\nbogus\ncomplete 9\ncomplete 1\nundo\nshell\nexit\nadd 不会执行\n";
+    let output = env.ok_with_stdin(&["shell"], input);
+
+    assert!(output.contains("Task added successfully!  (ID: 1)"));
+    assert!(output.contains("Task 1 marked as completed!"));
+    assert!(output.contains("Undid `complete 1`"));
+
+    let list = env.todo_list();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.find_task(1).unwrap().title, "写 周报");
+    assert!(!list.find_task(1).unwrap().completed);
+}
@@ -0,0 +1,26 @@
+use rust_todo_cli::prelude::*;
+
+#[test]
+fn test_prelude_round_trip() {
+    let path = "test_prelude_todos.json";
+
+    let mut list = TodoList::new();
+    let report = list.add_task("写周报".to_string(), Priority::High, None);
+    list.add_task("买牛奶".to_string(), Priority::Low, None);
+    list.complete_task(report).unwrap();
+    save_to_file(&list, path).unwrap();
+
+    let loaded = load_from_file(path).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert!(loaded.find_task(report).unwrap().completed);
+    assert!(matches!(loaded.history()[0].kind, EventKind::Added));
+
+    rust_todo_cli::storage::delete_file(path).unwrap();
+}
+
+#[test]
+fn test_prelude_errors() {
+    let mut list = TodoList::new();
+    let result: TodoResult<Option<u32>> = list.complete_task(42);
+    assert!(matches!(result, Err(TodoError::TaskNotFound(42))));
+}